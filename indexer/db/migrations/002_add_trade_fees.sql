@@ -0,0 +1,5 @@
+--- Trading fees reported by TradeExecuted
+--- buyer_fee is paid in the base asset, seller_fee in the quote asset
+ALTER TABLE trades ADD COLUMN IF NOT EXISTS taker_side TEXT NOT NULL DEFAULT 'Buy';
+ALTER TABLE trades ADD COLUMN IF NOT EXISTS buyer_fee NUMERIC(20, 6) NOT NULL DEFAULT 0;
+ALTER TABLE trades ADD COLUMN IF NOT EXISTS seller_fee NUMERIC(20, 6) NOT NULL DEFAULT 0;
@@ -0,0 +1,2 @@
+--- Rebate paid to the maker by the fee account, in the asset the maker received
+ALTER TABLE trades ADD COLUMN IF NOT EXISTS maker_rebate NUMERIC(20, 6) NOT NULL DEFAULT 0;
@@ -0,0 +1,12 @@
+--- SCALE bytes of each TradeExecuted event, the chain's archive commitment is built from them
+ALTER TABLE trades ADD COLUMN IF NOT EXISTS encoded BYTEA;
+
+--- One row per TradesPruned event, with whether our trades reproduce the chain's commitment
+CREATE TABLE IF NOT EXISTS trade_archive_commitments (
+    first_trade_id BIGINT NOT NULL,
+    last_trade_id BIGINT NOT NULL PRIMARY KEY,
+    commitment BYTEA NOT NULL,
+    block_number BIGINT NOT NULL,
+    verified BOOLEAN NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
@@ -203,7 +203,8 @@ async fn handle_unified_socket(config: UnifiedSocketConfig) {
                         break;
                     }
                     Some(Ok(Message::Ping(data))) => {
-                        if sender.send(Message::Pong(data)).await.is_err() {
+                        let pong = sender.send(Message::Pong(data)).await;
+                        if pong.is_err() {
                             break;
                         }
                     }
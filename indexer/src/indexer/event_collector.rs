@@ -4,6 +4,8 @@ use tokio::sync::Mutex;
 use crate::indexer::candle_aggregator::CandleAggregator;
 use crate::indexer::orderbook_reducer::{OrderInfo, OrderbookState};
 use crate::indexer::runtime;
+use crate::indexer::runtime::polkadot::runtime_types::pallet_orderbook::types::SelfTradePrevention;
+use crate::indexer::trade_archive::verify_pruned_trades;
 use crate::indexer::trade_mapper::{process_trade, TradeProcessingContext};
 use anyhow::Result;
 use rust_decimal::Decimal;
@@ -55,7 +57,14 @@ pub async fn start(
                                 candle_agg: &mut candle_agg,
                             };
 
-                            match process_trade(&mut ctx, block_number, &trade_event).await {
+                            match process_trade(
+                                &mut ctx,
+                                block_number,
+                                &trade_event,
+                                evt.field_bytes(),
+                            )
+                            .await
+                            {
                                 Ok(_) => {
                                     println!("✅ Trade inserted successfully!");
                                     info!("✅ Trade executed in block {}", block_number);
@@ -77,6 +86,7 @@ pub async fn start(
                     info!("📦 Order placed in block {}", block_number);
                     match evt.as_event::<runtime::OrderPlaced>() {
                         Ok(Some(place_order_event)) => {
+                            // an iceberg only announces its first slice
                             // Convert u128 to Decimal by dividing by 10^6
                             let price =
                                 Decimal::from(place_order_event.price) / Decimal::from(1_000_000);
@@ -120,6 +130,114 @@ pub async fn start(
                         Err(e) => debug!("❌ Failed to parse orderCancelled: {}", e),
                     }
                 }
+                ("Orderbook", "OrderRemainderCancelled") => {
+                    match evt.as_event::<runtime::OrderRemainderCancelled>() {
+                        Ok(Some(data)) => {
+                            println!(
+                                "❌ OrderRemainderCancelled: id={}, trader={}",
+                                data.order_id, data.trader
+                            );
+
+                            // IOC leftovers never rest, drop the order from the book
+                            let mut state = orderbook_state.lock().await;
+                            let _ = state.cancel_order(data.order_id);
+                            info!("✅ Order #{} remainder cancelled", data.order_id);
+                        }
+                        Ok(None) => debug!("❌ OrderRemainderCancelled event is None (filtered?)"),
+                        Err(e) => debug!("❌ Failed to parse orderRemainderCancelled: {}", e),
+                    }
+                }
+                ("Orderbook", "OrderKilled") => match evt.as_event::<runtime::OrderKilled>() {
+                    Ok(Some(data)) => {
+                        println!(
+                            "❌ OrderKilled: id={}, trader={}",
+                            data.order_id, data.trader
+                        );
+
+                        let mut state = orderbook_state.lock().await;
+                        let _ = state.cancel_order(data.order_id);
+                        info!("✅ Order #{} killed", data.order_id);
+                    }
+                    Ok(None) => debug!("❌ OrderKilled event is None (filtered?)"),
+                    Err(e) => debug!("❌ Failed to parse orderKilled: {}", e),
+                },
+                ("Orderbook", "OrderRejected") => match evt.as_event::<runtime::OrderRejected>() {
+                    Ok(Some(data)) => {
+                        println!(
+                            "🚫 OrderRejected: id={}, trader={}, reason={}",
+                            data.order_id, data.trader, data.reason
+                        );
+
+                        let mut state = orderbook_state.lock().await;
+                        let _ = state.reject_order(data.order_id, &data.reason.to_string());
+                        info!("✅ Order #{} rejected ({})", data.order_id, data.reason);
+                    }
+                    Ok(None) => debug!("❌ OrderRejected event is None (filtered?)"),
+                    Err(e) => debug!("❌ Failed to parse orderRejected: {}", e),
+                },
+                ("Orderbook", "OrderRepriced") => {
+                    match evt.as_event::<runtime::OrderRepriced>() {
+                        Ok(Some(data)) => {
+                            // Convert u128 to Decimal by dividing by 10^6
+                            let new_price =
+                                Decimal::from(data.new_price) / Decimal::from(1_000_000);
+
+                            println!(
+                                "🔁 OrderRepriced: id={}, new_price={}",
+                                data.order_id, new_price
+                            );
+
+                            let mut state = orderbook_state.lock().await;
+                            let _ = state.reprice_order(data.order_id, new_price);
+                            info!("✅ Order #{} repriced to {}", data.order_id, new_price);
+                        }
+                        Ok(None) => debug!("❌ OrderRepriced event is None (filtered?)"),
+                        Err(e) => debug!("❌ Failed to parse orderRepriced: {}", e),
+                    }
+                }
+                ("Orderbook", "OrderExpired") => match evt.as_event::<runtime::OrderExpired>() {
+                    Ok(Some(data)) => {
+                        println!(
+                            "⌛ OrderExpired: id={}, trader={}",
+                            data.order_id, data.trader
+                        );
+
+                        let mut state = orderbook_state.lock().await;
+                        let _ = state.expire_order(data.order_id);
+                        info!("✅ Order #{} expired", data.order_id);
+                    }
+                    Ok(None) => debug!("❌ OrderExpired event is None (filtered?)"),
+                    Err(e) => debug!("❌ Failed to parse orderExpired: {}", e),
+                },
+                ("Orderbook", "StopOrderTriggered") => {
+                    match evt.as_event::<runtime::StopOrderTriggered>() {
+                        Ok(Some(data)) => {
+                            // Convert u128 to Decimal by dividing by 10^6
+                            let price = Decimal::from(data.price) / Decimal::from(1_000_000);
+                            let quantity = Decimal::from(data.quantity) / Decimal::from(1_000_000);
+
+                            println!(
+                                "🎯 StopOrderTriggered: id={}, side={}, price={}, qty={}",
+                                data.order_id, data.side, price, quantity
+                            );
+
+                            // untriggered stops are kept out of the book until now
+                            let mut state = orderbook_state.lock().await;
+                            let order = OrderInfo {
+                                order_id: data.order_id,
+                                side: data.side.to_string(),
+                                price,
+                                quantity,
+                                filled_quantity: Decimal::ZERO,
+                                status: "Open".to_string(),
+                            };
+                            state.add_order(order);
+                            info!("✅ Stop order #{} added to state", data.order_id);
+                        }
+                        Ok(None) => debug!("❌ StopOrderTriggered event is None (filtered?)"),
+                        Err(e) => debug!("❌ Failed to parse stopOrderTriggered: {}", e),
+                    }
+                }
                 ("Orderbook", "OrderFilled") => {
                     info!("✅ Order filled in block {}", block_number);
                     match evt.as_event::<runtime::OrderFilled>() {
@@ -157,10 +275,10 @@ pub async fn start(
                             );
 
                             let mut state = orderbook_state.lock().await;
-                            let _ = state.update_order(
+                            let _ = state.partially_fill_order(
                                 data.order_id,
                                 filled_quantity,
-                                "PartiallyFilled",
+                                remaining_quantity,
                             );
                             info!(
                                 "✅ Order #{} partially filled ({}/{})",
@@ -173,6 +291,101 @@ pub async fn start(
                         Err(e) => debug!("❌ Failed: {}", e),
                     }
                 }
+                ("Orderbook", "SelfTradePrevented") => {
+                    match evt.as_event::<runtime::SelfTradePrevented>() {
+                        Ok(Some(data)) => {
+                            // Convert u128 to Decimal by dividing by 10^6
+                            let quantity = Decimal::from(data.quantity) / Decimal::from(1_000_000);
+
+                            println!(
+                                "🪞 SelfTradePrevented: taker={}, maker={}, mode={}",
+                                data.taker_order_id, data.maker_order_id, data.mode
+                            );
+
+                            // the newest of the two orders is the one placed last
+                            let newest = data.taker_order_id.max(data.maker_order_id);
+                            let oldest = data.taker_order_id.min(data.maker_order_id);
+                            let mut state = orderbook_state.lock().await;
+                            match data.mode {
+                                SelfTradePrevention::CancelNewest => {
+                                    let _ = state.cancel_order(newest);
+                                }
+                                SelfTradePrevention::CancelOldest => {
+                                    let _ = state.cancel_order(oldest);
+                                }
+                                SelfTradePrevention::CancelBoth => {
+                                    let _ = state.cancel_order(newest);
+                                    let _ = state.cancel_order(oldest);
+                                }
+                                SelfTradePrevention::DecrementAndCancel => {
+                                    let _ = state.decrement_order(newest, quantity);
+                                    let _ = state.decrement_order(oldest, quantity);
+                                }
+                            }
+                            info!(
+                                "✅ Self-trade between #{} and #{} prevented",
+                                oldest, newest
+                            );
+                        }
+                        Ok(None) => debug!("❌ SelfTradePrevented event is None (filtered?)"),
+                        Err(e) => debug!("❌ Failed to parse selfTradePrevented: {}", e),
+                    }
+                }
+                ("Orderbook", "TradeRolledBack") => {
+                    match evt.as_event::<runtime::TradeRolledBack>() {
+                        Ok(Some(data)) => {
+                            // nothing was stored for this trade, the fill events that follow
+                            // already reflect the rollback
+                            println!(
+                                "↩️ TradeRolledBack: buy={}, sell={}, price={}, quantity={}, error={:?}",
+                                data.buy_order_id,
+                                data.sell_order_id,
+                                data.price,
+                                data.quantity,
+                                data.error
+                            );
+                        }
+                        Ok(None) => debug!("❌ TradeRolledBack event is None (filtered?)"),
+                        Err(e) => debug!("❌ Failed to parse tradeRolledBack: {}", e),
+                    }
+                }
+                ("Orderbook", "MatchingFailed") => {
+                    match evt.as_event::<runtime::MatchingFailed>() {
+                        Ok(Some(data)) => {
+                            println!(
+                                "⛔ MatchingFailed: market={}, error={:?}",
+                                data.market_id, data.error
+                            );
+                        }
+                        Ok(None) => debug!("❌ MatchingFailed event is None (filtered?)"),
+                        Err(e) => debug!("❌ Failed to parse matchingFailed: {}", e),
+                    }
+                }
+                ("Orderbook", "UnlockFailed") => match evt.as_event::<runtime::UnlockFailed>() {
+                    Ok(Some(data)) => {
+                        println!(
+                            "⚠️ UnlockFailed: order={}, trader={}, asset={}, amount={}, error={:?}",
+                            data.order_id, data.trader, data.asset_id, data.amount, data.error
+                        );
+                    }
+                    Ok(None) => debug!("❌ UnlockFailed event is None (filtered?)"),
+                    Err(e) => debug!("❌ Failed to parse unlockFailed: {}", e),
+                },
+                ("Orderbook", "TradesPruned") => match evt.as_event::<runtime::TradesPruned>() {
+                    Ok(Some(data)) => {
+                        println!(
+                            "🗄️ TradesPruned: trades #{}..=#{}",
+                            data.first_trade_id, data.last_trade_id
+                        );
+
+                        // the chain dropped these trades, check our copy still adds up
+                        if let Err(e) = verify_pruned_trades(&pool, block_number, &data).await {
+                            debug!("❌ Failed to verify pruned trades: {}", e);
+                        }
+                    }
+                    Ok(None) => debug!("❌ TradesPruned event is None (filtered?)"),
+                    Err(e) => debug!("❌ Failed to parse tradesPruned: {}", e),
+                },
                 _ => {
                     // Ignore events from other pallets
                 }
@@ -2,4 +2,5 @@ pub mod candle_aggregator;
 pub mod event_collector;
 pub mod orderbook_reducer;
 pub mod runtime;
+pub mod trade_archive;
 pub mod trade_mapper;
@@ -209,6 +209,20 @@ impl OrderbookState {
         Ok(())
     }
 
+    /// Records a partial fill. `remaining_quantity` is what the order still shows, only the
+    /// current slice for an iceberg, so the shown size is rebuilt from the two.
+    pub fn partially_fill_order(
+        &mut self,
+        order_id: u64,
+        filled_quantity: Decimal,
+        remaining_quantity: Decimal,
+    ) -> Result<()> {
+        if let Some(order) = self.orders.get_mut(&order_id) {
+            order.quantity = filled_quantity + remaining_quantity;
+        }
+        self.update_order(order_id, filled_quantity, "PartiallyFilled")
+    }
+
     pub fn remove_order_from_level(&mut self, order_id: u64, side: &str, price: Decimal) {
         match side {
             "Buy" => {
@@ -246,6 +260,75 @@ impl OrderbookState {
         Ok(())
     }
 
+    pub fn reject_order(&mut self, order_id: u64, reason: &str) -> Result<()> {
+        let (side, price) = if let Some(order) = self.orders.get_mut(&order_id) {
+            order.status = format!("Rejected ({})", reason);
+            (order.side.clone(), order.price)
+        } else {
+            return Err(anyhow::anyhow!("Order #{} not found", order_id));
+        };
+
+        self.remove_order_from_level(order_id, &side, price);
+        info!(" Order #{} rejected: {}", order_id, reason);
+        self.notify();
+
+        Ok(())
+    }
+
+    pub fn expire_order(&mut self, order_id: u64) -> Result<()> {
+        let (side, price) = if let Some(order) = self.orders.get_mut(&order_id) {
+            order.status = "Expired".to_string();
+            (order.side.clone(), order.price)
+        } else {
+            return Err(anyhow::anyhow!("Order #{} not found", order_id));
+        };
+
+        self.remove_order_from_level(order_id, &side, price);
+        info!(" Order #{} expired", order_id);
+        self.notify();
+
+        Ok(())
+    }
+
+    /// Takes `quantity` off an order that self-trade prevention shrank, it leaves the book
+    /// once nothing is left of it
+    pub fn decrement_order(&mut self, order_id: u64, quantity: Decimal) -> Result<()> {
+        let remaining = if let Some(order) = self.orders.get_mut(&order_id) {
+            order.quantity -= quantity.min(order.quantity);
+            order.quantity - order.filled_quantity
+        } else {
+            return Err(anyhow::anyhow!("Order #{} not found", order_id));
+        };
+
+        if remaining <= Decimal::ZERO {
+            return self.cancel_order(order_id);
+        }
+        self.notify();
+
+        Ok(())
+    }
+
+    /// Moves an order to a new price level, it goes to the back of the queue there
+    pub fn reprice_order(&mut self, order_id: u64, new_price: Decimal) -> Result<()> {
+        let (side, price) = if let Some(order) = self.orders.get_mut(&order_id) {
+            let old = (order.side.clone(), order.price);
+            order.price = new_price;
+            old
+        } else {
+            return Err(anyhow::anyhow!("Order #{} not found", order_id));
+        };
+
+        self.remove_order_from_level(order_id, &side, price);
+        match side.as_str() {
+            "Buy" => self.bids.entry(new_price).or_default().push(order_id),
+            "Sell" => self.asks.entry(new_price).or_default().push(order_id),
+            _ => {}
+        }
+        self.notify();
+
+        Ok(())
+    }
+
     pub fn get_bid_depth(&self, depth: usize) -> Vec<(Decimal, usize)> {
         self.bids
             .iter()
@@ -2,11 +2,22 @@
 #[subxt::subxt(runtime_metadata_path = "../metadata.scale")]
 pub mod polkadot {}
 
+pub use polkadot::orderbook::events::MatchingFailed;
 pub use polkadot::orderbook::events::OrderCancelled;
+pub use polkadot::orderbook::events::OrderExpired;
 pub use polkadot::orderbook::events::OrderFilled;
+pub use polkadot::orderbook::events::OrderKilled;
 pub use polkadot::orderbook::events::OrderPartiallyFilled;
 pub use polkadot::orderbook::events::OrderPlaced;
+pub use polkadot::orderbook::events::OrderRejected;
+pub use polkadot::orderbook::events::OrderRemainderCancelled;
+pub use polkadot::orderbook::events::OrderRepriced;
+pub use polkadot::orderbook::events::SelfTradePrevented;
+pub use polkadot::orderbook::events::StopOrderTriggered;
 pub use polkadot::orderbook::events::TradeExecuted;
+pub use polkadot::orderbook::events::TradeRolledBack;
+pub use polkadot::orderbook::events::TradesPruned;
+pub use polkadot::orderbook::events::UnlockFailed;
 impl std::fmt::Display for polkadot::runtime_types::pallet_orderbook::types::OrderSide {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let side_str = match self {
@@ -16,3 +27,37 @@ impl std::fmt::Display for polkadot::runtime_types::pallet_orderbook::types::Ord
         write!(f, "{}", side_str)
     }
 }
+
+impl std::fmt::Display for polkadot::runtime_types::pallet_orderbook::types::RejectReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let reason_str = match self {
+            polkadot::runtime_types::pallet_orderbook::types::RejectReason::PostOnlyWouldCross => {
+                "PostOnlyWouldCross"
+            }
+            polkadot::runtime_types::pallet_orderbook::types::RejectReason::TooManyPriceLevels => {
+                "TooManyPriceLevels"
+            }
+        };
+        write!(f, "{}", reason_str)
+    }
+}
+
+impl std::fmt::Display for polkadot::runtime_types::pallet_orderbook::types::SelfTradePrevention {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mode_str = match self {
+            polkadot::runtime_types::pallet_orderbook::types::SelfTradePrevention::CancelNewest => {
+                "CancelNewest"
+            }
+            polkadot::runtime_types::pallet_orderbook::types::SelfTradePrevention::CancelOldest => {
+                "CancelOldest"
+            }
+            polkadot::runtime_types::pallet_orderbook::types::SelfTradePrevention::CancelBoth => {
+                "CancelBoth"
+            }
+            polkadot::runtime_types::pallet_orderbook::types::SelfTradePrevention::DecrementAndCancel => {
+                "DecrementAndCancel"
+            }
+        };
+        write!(f, "{}", mode_str)
+    }
+}
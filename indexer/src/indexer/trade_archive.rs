@@ -0,0 +1,93 @@
+use crate::indexer::runtime::TradesPruned;
+use anyhow::Result;
+use sqlx::PgPool;
+use subxt::config::substrate::BlakeTwo256;
+use subxt::config::Hasher;
+use subxt::utils::H256;
+use tracing::{info, warn};
+
+/// Rebuild the commitment of a TradesPruned event from the stored trades and record it
+/// Each pruned trade extends the chain as blake2_256(commitment ++ TradeExecuted field bytes)
+/// Returns whether our archive holds exactly the trades the chain pruned
+pub async fn verify_pruned_trades(
+    pool: &PgPool,
+    block_number: u32,
+    event: &TradesPruned,
+) -> Result<bool> {
+    let first = event.first_trade_id as i64;
+    let last = event.last_trade_id as i64;
+
+    // the chain starts from a zero hash, later prunings continue the last commitment
+    let previous: Option<Vec<u8>> = sqlx::query_scalar(
+        "SELECT commitment FROM trade_archive_commitments
+        WHERE last_trade_id < $1 ORDER BY last_trade_id DESC LIMIT 1",
+    )
+    .bind(first)
+    .fetch_optional(pool)
+    .await?;
+    let mut commitment = match previous {
+        Some(bytes) if bytes.len() == 32 => H256::from_slice(&bytes),
+        None if first == 0 => H256::zero(),
+        _ => {
+            warn!(
+                "⚠️ No commitment before trade #{}, pruned trades can't be verified",
+                first
+            );
+            return record(pool, block_number, event, false).await;
+        }
+    };
+
+    let trades: Vec<Option<Vec<u8>>> = sqlx::query_scalar(
+        "SELECT encoded FROM trades WHERE trade_id BETWEEN $1 AND $2 ORDER BY trade_id",
+    )
+    .bind(first)
+    .bind(last)
+    .fetch_all(pool)
+    .await?;
+
+    let complete = trades.len() as i64 == last - first + 1 && trades.iter().all(Option::is_some);
+    for encoded in trades.iter().flatten() {
+        commitment = BlakeTwo256.hash(&[commitment.as_bytes(), encoded].concat());
+    }
+
+    let verified = complete && commitment == event.commitment;
+    if verified {
+        info!(
+            "✅ Archive matches the chain for trades #{}..=#{}",
+            first, last
+        );
+    } else {
+        warn!(
+            "⚠️ Archive does not match the chain for trades #{}..=#{} ({} of {} stored)",
+            first,
+            last,
+            trades.len(),
+            last - first + 1
+        );
+    }
+
+    record(pool, block_number, event, verified).await
+}
+
+async fn record(
+    pool: &PgPool,
+    block_number: u32,
+    event: &TradesPruned,
+    verified: bool,
+) -> Result<bool> {
+    sqlx::query(
+        "INSERT INTO trade_archive_commitments
+        (first_trade_id, last_trade_id, commitment, block_number, verified)
+        VALUES ($1, $2, $3, $4, $5)
+        ON CONFLICT (last_trade_id) DO NOTHING",
+    )
+    .bind(event.first_trade_id as i64)
+    .bind(event.last_trade_id as i64)
+    .bind(event.commitment.as_bytes())
+    .bind(block_number as i64)
+    .bind(verified)
+    .execute(pool)
+    .await?;
+
+    Ok(verified)
+}
@@ -25,15 +25,27 @@ pub struct TradeData {
     pub seller: String,
     pub price: Decimal,
     pub quantity: Decimal,
+    pub taker_side: String,
+    /// Paid by the buyer in the base asset
+    pub buyer_fee: Decimal,
+    /// Paid by the seller in the quote asset
+    pub seller_fee: Decimal,
+    /// Paid to the maker out of the fee account
+    pub maker_rebate: Decimal,
+    /// SCALE bytes of the event fields, what the chain hashes once the trade is pruned
+    pub encoded: Vec<u8>,
 }
 
 impl TradeData {
     /// Parse trade data from a TradeExecuted event using generated types
     /// Converts u128 values (in 10^6 representation) to Decimal
-    pub fn from_typed_event(event: &TradeExecuted, block_number: u32) -> Self {
+    pub fn from_typed_event(event: &TradeExecuted, block_number: u32, encoded: &[u8]) -> Self {
         // Convert u128 to Decimal by dividing by 10^6
         let price = Decimal::from(event.price) / Decimal::from(1_000_000);
         let quantity = Decimal::from(event.quantity) / Decimal::from(1_000_000);
+        let buyer_fee = Decimal::from(event.buyer_fee) / Decimal::from(1_000_000);
+        let seller_fee = Decimal::from(event.seller_fee) / Decimal::from(1_000_000);
+        let maker_rebate = Decimal::from(event.maker_rebate) / Decimal::from(1_000_000);
 
         Self {
             trade_id: event.trade_id as u128,
@@ -44,6 +56,11 @@ impl TradeData {
             seller: format!("0x{}", hex::encode(event.seller.0)),
             price,
             quantity,
+            taker_side: event.taker_side.to_string(),
+            buyer_fee,
+            seller_fee,
+            maker_rebate,
+            encoded: encoded.to_vec(),
         }
     }
 
@@ -58,8 +75,9 @@ pub async fn process_trade(
     ctx: &mut TradeProcessingContext<'_>,
     block_number: u32,
     event: &TradeExecuted,
+    encoded: &[u8],
 ) -> Result<()> {
-    let trade = TradeData::from_typed_event(event, block_number);
+    let trade = TradeData::from_typed_event(event, block_number, encoded);
 
     info!(
         "🎯 TradeExecuted parsed: trade_id={}, buy={}, sell={}, price={}, qty={}, value={}",
@@ -76,8 +94,8 @@ pub async fn process_trade(
     // Insert into trades table
     sqlx::query(
         "INSERT INTO trades
-        (trade_id, block_number, buy_order_id, sell_order_id, buyer, seller, price, quantity, value, symbol)
-        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)",
+        (trade_id, block_number, buy_order_id, sell_order_id, buyer, seller, price, quantity, value, symbol, taker_side, buyer_fee, seller_fee, maker_rebate, encoded)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15)",
     )
     .bind(trade.trade_id as i64)
     .bind(trade.block_number as i64)
@@ -89,6 +107,11 @@ pub async fn process_trade(
     .bind(trade.quantity)
     .bind(value)
     .bind(SYMBOL)
+    .bind(&trade.taker_side)
+    .bind(trade.buyer_fee)
+    .bind(trade.seller_fee)
+    .bind(trade.maker_rebate)
+    .bind(&trade.encoded)
     .execute(ctx.pool)
     .await?;
 
@@ -1,6 +1,8 @@
 //ensures it compiles to wasm
 #![cfg_attr(not(feature = "std"), no_std)]
 
+extern crate alloc;
+
 pub use pallet::*;
 
 #[cfg(test)]
@@ -11,6 +13,7 @@ mod tests;
 
 #[cfg(feature = "runtime-benchmarks")]
 mod benchmarking;
+pub mod migrations;
 pub mod weights;
 pub use weights::*;
 
@@ -20,10 +23,15 @@ pub const ETH: u32 = 1;
 #[frame_support::pallet]
 pub mod pallet {
     use super::*;
+    use alloc::collections::btree_map::BTreeMap;
     use frame_support::{dispatch::DispatchResult, pallet_prelude::*};
     use frame_system::pallet_prelude::{OriginFor, *};
 
+    /// v1 tracks the total issuance of every asset
+    const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+
     #[pallet::pallet]
+    #[pallet::storage_version(STORAGE_VERSION)]
     pub struct Pallet<T>(_);
 
     // UserID -> Token -> Value
@@ -51,6 +59,10 @@ pub mod pallet {
         ValueQuery,
     >;
 
+    // everything deposited less everything withdrawn, per asset
+    #[pallet::storage]
+    pub type TotalIssuance<T: Config> = StorageMap<_, Blake2_128Concat, u32, u128, ValueQuery>;
+
     #[pallet::config]
     pub trait Config: frame_system::Config {
         type WeightInfo: WeightInfo;
@@ -93,6 +105,20 @@ pub mod pallet {
         InsufficientLockedBalance,
         InvalidAsset,
         AmountZero,
+        // a locked balance is not what the caller accounted for
+        LockedBalanceMismatch,
+        // the balances of an asset don't add up to its issuance
+        BalanceNotConserved,
+    }
+
+    #[pallet::hooks]
+    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+        #[cfg(feature = "try-runtime")]
+        fn try_state(
+            _n: BlockNumberFor<T>,
+        ) -> Result<(), frame_support::sp_runtime::TryRuntimeError> {
+            Self::ensure_conservation()
+        }
     }
 
     //Now we write the extrinsincs deposit, withdraw, lock and unlock & also transfer
@@ -109,6 +135,9 @@ pub mod pallet {
             FreeBalance::<T>::mutate(who.clone(), asset_id, |balance| {
                 *balance = balance.saturating_add(amount);
             });
+            TotalIssuance::<T>::mutate(asset_id, |issuance| {
+                *issuance = issuance.saturating_add(amount);
+            });
 
             Self::deposit_event(Event::Deposited {
                 user: who,
@@ -134,6 +163,9 @@ pub mod pallet {
                 *balance = balance.saturating_sub(amount);
                 Ok::<_, DispatchError>(())
             })?;
+            TotalIssuance::<T>::mutate(asset_id, |issuance| {
+                *issuance = issuance.saturating_sub(amount);
+            });
 
             Self::deposit_event(Event::Withdrawn {
                 user: who,
@@ -224,5 +256,74 @@ pub mod pallet {
 
             Ok(())
         }
+
+        pub fn transfer_free(
+            from: &T::AccountId,
+            to: &T::AccountId,
+            asset_id: u32,
+            amount: u128,
+        ) -> DispatchResult {
+            FreeBalance::<T>::try_mutate(from, asset_id, |balance| {
+                ensure!(*balance >= amount, Error::<T>::InsufficientFreeBalance);
+                *balance = balance.saturating_sub(amount);
+                Ok::<_, DispatchError>(())
+            })?;
+
+            FreeBalance::<T>::mutate(to, asset_id, |balance| {
+                *balance = balance.saturating_add(amount)
+            });
+
+            Self::deposit_event(Event::Transferred {
+                from: from.clone(),
+                to: to.clone(),
+                asset_id,
+                amount,
+            });
+
+            Ok(())
+        }
+
+        // Invariant check: every locked balance must be exactly what `expected` says, anything
+        // missing from it must not be locked at all. Reads every lock, so tests/try-runtime only.
+        pub fn ensure_locked_balances(
+            expected: &BTreeMap<(T::AccountId, u32), u128>,
+        ) -> DispatchResult {
+            for (user, asset_id, locked) in LockedBalance::<T>::iter() {
+                let needed = expected.get(&(user, asset_id)).copied().unwrap_or_default();
+                ensure!(locked == needed, Error::<T>::LockedBalanceMismatch);
+            }
+
+            for ((user, asset_id), needed) in expected.iter() {
+                ensure!(
+                    LockedBalance::<T>::get(user, asset_id) == *needed,
+                    Error::<T>::LockedBalanceMismatch
+                );
+            }
+
+            Ok(())
+        }
+
+        // Invariant check: the free and locked balances of every asset add up to its issuance,
+        // funds only ever move between accounts. Reads every balance, so tests/try-runtime only.
+        pub fn ensure_conservation() -> DispatchResult {
+            let mut held: BTreeMap<u32, u128> = BTreeMap::new();
+            for (_, asset_id, amount) in FreeBalance::<T>::iter().chain(LockedBalance::<T>::iter())
+            {
+                let total = held.entry(asset_id).or_insert(0);
+                *total = total.saturating_add(amount);
+            }
+
+            for (asset_id, issuance) in TotalIssuance::<T>::iter() {
+                let total = held.remove(&asset_id).unwrap_or_default();
+                ensure!(total == issuance, Error::<T>::BalanceNotConserved);
+            }
+            // held without ever being deposited
+            ensure!(
+                held.values().all(|total| *total == 0),
+                Error::<T>::BalanceNotConserved
+            );
+
+            Ok(())
+        }
     }
 }
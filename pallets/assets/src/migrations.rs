@@ -0,0 +1,54 @@
+//! Storage migrations of the assets pallet
+
+use crate::{Config, FreeBalance, LockedBalance, Pallet, TotalIssuance};
+use alloc::collections::btree_map::BTreeMap;
+use frame_support::{
+    migrations::VersionedMigration,
+    traits::{Get, UncheckedOnRuntimeUpgrade},
+    weights::Weight,
+};
+
+pub mod v1 {
+    use super::*;
+
+    /// Before v1 nothing recorded how much of an asset was deposited. Everything held at the
+    /// upgrade, free or locked, becomes the starting issuance.
+    pub struct InnerMigrateToV1<T>(core::marker::PhantomData<T>);
+
+    impl<T: Config> UncheckedOnRuntimeUpgrade for InnerMigrateToV1<T> {
+        fn on_runtime_upgrade() -> Weight {
+            let mut reads = 0u64;
+            let mut issuance: BTreeMap<u32, u128> = BTreeMap::new();
+
+            for (_, asset_id, amount) in FreeBalance::<T>::iter().chain(LockedBalance::<T>::iter())
+            {
+                let total = issuance.entry(asset_id).or_insert(0);
+                *total = total.saturating_add(amount);
+                reads = reads.saturating_add(1);
+            }
+
+            let writes = issuance.len() as u64;
+            for (asset_id, total) in issuance {
+                TotalIssuance::<T>::insert(asset_id, total);
+            }
+
+            T::DbWeight::get().reads_writes(reads, writes)
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn post_upgrade(
+            _state: alloc::vec::Vec<u8>,
+        ) -> Result<(), frame_support::sp_runtime::TryRuntimeError> {
+            Pallet::<T>::ensure_conservation()
+        }
+    }
+
+    /// Runs [`InnerMigrateToV1`] once, when the on-chain version is 0
+    pub type MigrateToV1<T> = VersionedMigration<
+        0,
+        1,
+        InnerMigrateToV1<T>,
+        Pallet<T>,
+        <T as frame_system::Config>::DbWeight,
+    >;
+}
@@ -42,3 +42,11 @@ pub fn new_test_ext() -> sp_io::TestExternalities {
         .unwrap()
         .into()
 }
+
+// Runs a test and checks the pallet invariants on whatever state it leaves behind
+pub fn build_and_execute(test: impl FnOnce()) {
+    new_test_ext().execute_with(|| {
+        test();
+        frame_support::assert_ok!(Assets::ensure_conservation());
+    });
+}
@@ -2,10 +2,11 @@
 
 use crate::{ETH, Error, Event, USDT, mock::*};
 use frame_support::{assert_noop, assert_ok};
+use std::collections::BTreeMap;
 
 #[test]
 fn deposit_works() {
-    new_test_ext().execute_with(|| {
+    build_and_execute(|| {
         // Go to block 1 (for events)
         System::set_block_number(1);
 
@@ -29,7 +30,7 @@ fn deposit_works() {
 
 #[test]
 fn deposit_zero_fails() {
-    new_test_ext().execute_with(|| {
+    build_and_execute(|| {
         assert_noop!(
             Assets::deposit(RuntimeOrigin::signed(1), USDT, 0),
             Error::<Test>::AmountZero
@@ -39,7 +40,7 @@ fn deposit_zero_fails() {
 
 #[test]
 fn deposit_invalid_asset_fails() {
-    new_test_ext().execute_with(|| {
+    build_and_execute(|| {
         assert_noop!(
             Assets::deposit(RuntimeOrigin::signed(1), 999, 100),
             Error::<Test>::InvalidAsset
@@ -49,7 +50,7 @@ fn deposit_invalid_asset_fails() {
 
 #[test]
 fn withdraw_works() {
-    new_test_ext().execute_with(|| {
+    build_and_execute(|| {
         System::set_block_number(1);
 
         // Setup: deposit first
@@ -65,7 +66,7 @@ fn withdraw_works() {
 
 #[test]
 fn withdraw_insufficient_balance_fails() {
-    new_test_ext().execute_with(|| {
+    build_and_execute(|| {
         // No deposit, try to withdraw
         assert_noop!(
             Assets::withdraw(RuntimeOrigin::signed(1), USDT, 100),
@@ -76,7 +77,7 @@ fn withdraw_insufficient_balance_fails() {
 
 #[test]
 fn lock_and_unlock_works() {
-    new_test_ext().execute_with(|| {
+    build_and_execute(|| {
         System::set_block_number(1);
 
         // Deposit 1000
@@ -96,7 +97,7 @@ fn lock_and_unlock_works() {
 
 #[test]
 fn transfer_locked_works() {
-    new_test_ext().execute_with(|| {
+    build_and_execute(|| {
         System::set_block_number(1);
 
         // User 1 deposits and locks
@@ -108,6 +109,95 @@ fn transfer_locked_works() {
 
         // Check balances
         assert_eq!(Assets::get_locked_balance(&1, USDT), 200); // 500 - 300
-        assert_eq!(Assets::get_locked_balance(&2, USDT), 300); // Received as free
+        assert_eq!(Assets::get_free_balance(&2, USDT), 300); // Received as free
+    });
+}
+
+#[test]
+fn transfer_free_works() {
+    build_and_execute(|| {
+        assert_ok!(Assets::deposit(RuntimeOrigin::signed(1), USDT, 1000));
+        assert_ok!(Assets::lock_funds(&1, USDT, 600));
+
+        // only the free part can be moved
+        assert_noop!(
+            Assets::transfer_free(&1, &2, USDT, 500),
+            Error::<Test>::InsufficientFreeBalance
+        );
+        assert_ok!(Assets::transfer_free(&1, &2, USDT, 400));
+
+        assert_eq!(Assets::get_free_balance(&1, USDT), 0);
+        assert_eq!(Assets::get_locked_balance(&1, USDT), 600);
+        assert_eq!(Assets::get_free_balance(&2, USDT), 400);
+    });
+}
+
+#[test]
+fn ensure_locked_balances_works() {
+    build_and_execute(|| {
+        assert_ok!(Assets::deposit(RuntimeOrigin::signed(1), USDT, 1000));
+        assert_ok!(Assets::deposit(RuntimeOrigin::signed(2), ETH, 10));
+        assert_ok!(Assets::lock_funds(&1, USDT, 500));
+        assert_ok!(Assets::lock_funds(&2, ETH, 4));
+
+        let mut expected = BTreeMap::new();
+        expected.insert((1, USDT), 500);
+        expected.insert((2, ETH), 4);
+        assert_ok!(Assets::ensure_locked_balances(&expected));
+
+        // a lock nobody accounted for
+        expected.remove(&(2, ETH));
+        assert_noop!(
+            Assets::ensure_locked_balances(&expected),
+            Error::<Test>::LockedBalanceMismatch
+        );
+
+        // an expected lock that isn't there
+        expected.insert((2, ETH), 4);
+        expected.insert((2, USDT), 1);
+        assert_noop!(
+            Assets::ensure_locked_balances(&expected),
+            Error::<Test>::LockedBalanceMismatch
+        );
+    });
+}
+
+#[test]
+fn ensure_conservation_works() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Assets::deposit(RuntimeOrigin::signed(1), USDT, 1000));
+        assert_ok!(Assets::deposit(RuntimeOrigin::signed(2), ETH, 10));
+        assert_ok!(Assets::lock_funds(&1, USDT, 500));
+        assert_ok!(Assets::transfer_locked(&1, &2, USDT, 200));
+        assert_ok!(Assets::withdraw(RuntimeOrigin::signed(2), USDT, 50));
+        assert_eq!(crate::TotalIssuance::<Test>::get(USDT), 950);
+        assert_ok!(Assets::ensure_conservation());
+
+        // a balance that appeared out of nowhere
+        crate::FreeBalance::<Test>::insert(3, ETH, 1);
+        assert_noop!(
+            Assets::ensure_conservation(),
+            Error::<Test>::BalanceNotConserved
+        );
+    });
+}
+
+#[test]
+fn migration_to_v1_sets_issuance() {
+    use frame_support::traits::{GetStorageVersion, OnRuntimeUpgrade, StorageVersion};
+
+    build_and_execute(|| {
+        // balances from before issuance was tracked
+        crate::FreeBalance::<Test>::insert(1, USDT, 700);
+        crate::LockedBalance::<Test>::insert(1, USDT, 300);
+        crate::FreeBalance::<Test>::insert(2, ETH, 10);
+        StorageVersion::new(0).put::<Assets>();
+
+        crate::migrations::v1::MigrateToV1::<Test>::on_runtime_upgrade();
+
+        assert_eq!(Assets::on_chain_storage_version(), StorageVersion::new(1));
+        assert_eq!(crate::TotalIssuance::<Test>::get(USDT), 1000);
+        assert_eq!(crate::TotalIssuance::<Test>::get(ETH), 10);
+        assert_ok!(Assets::ensure_conservation());
     });
 }
@@ -5,12 +5,22 @@ use frame_benchmarking::v2::*;
 mod benchmarks {
     use super::*;
     use crate::Pallet as Orderbook;
-    use crate::types::{OrderSide, OrderType};
-    use crate::{Call, Config, Pallet};
-    use frame_support::assert_ok;
+    use crate::engine::MAX_AUCTION_ROUNDS;
+    use crate::types::{
+        ClearingMode, FeeTier, MakerFee, MarketId, MarketPair, MarketStatus, MatchingMode,
+        OrderRequest, OrderSide, OrderType, OrderingPolicy, SelfTradePrevention, StopKind,
+        TimeInForce, Trade, TradingRules,
+    };
+    use crate::{
+        AccountSelfTradePrevention, Call, Config, MarketClearingMode, MarketMatchingMode,
+        MarketOrderingPolicy, MarketsByPair, NextTradeId, Pallet, PrunedTradesUpTo, Trades,
+    };
     use frame_support::traits::Hooks;
+    use frame_support::{BoundedVec, assert_ok, traits::Get, weights::Weight};
     use frame_system::RawOrigin;
     use pallet_assets::{ETH, USDT};
+    use sp_runtime::{Permill, SaturatedConversion};
+    use sp_std::vec::Vec;
 
     // Type alias for cleaner code
     type AccountIdOf<T> = <T as frame_system::Config>::AccountId;
@@ -36,16 +46,54 @@ mod benchmarks {
         caller
     }
 
+    /// Helper to get the ETH/USDT market, listing it if genesis didn't
+    fn eth_usdt<T: Config>() -> MarketId {
+        let pair = MarketPair {
+            base_asset: ETH,
+            quote_asset: USDT,
+        };
+        let market_id = match MarketsByPair::<T>::get(pair) {
+            Some(market_id) => market_id,
+            None => {
+                assert_ok!(Orderbook::<T>::create_market(
+                    RawOrigin::Root.into(),
+                    ETH,
+                    USDT
+                ));
+                MarketsByPair::<T>::get(pair).expect("market was just created")
+            }
+        };
+        // unit ticks and lots so the benchmarks can use any price and size, also on a
+        // market genesis listed with the runtime's default rules
+        assert_ok!(Orderbook::<T>::set_market_rules(
+            RawOrigin::Root.into(),
+            market_id,
+            TradingRules {
+                tick_size: 1,
+                lot_size: 1,
+                min_notional: 0,
+            }
+        ));
+        market_id
+    }
+
     /// Helper to setup matching orders
     fn setup_matching_orders<T: Config>(num_bids: u32, num_asks: u32, price: u128) {
         for i in 0..num_bids {
             let buyer = funded_account::<T>("buyer", i);
             assert_ok!(Orderbook::<T>::place_order(
                 RawOrigin::Signed(buyer).into(),
+                eth_usdt::<T>(),
                 OrderSide::Buy,
                 price,
                 10u128,
-                OrderType::Limit
+                OrderType::Limit,
+                TimeInForce::GTC,
+                None,
+                None,
+                None,
+                None,
+                None
             ));
         }
 
@@ -53,10 +101,17 @@ mod benchmarks {
             let seller = funded_account::<T>("seller", i);
             assert_ok!(Orderbook::<T>::place_order(
                 RawOrigin::Signed(seller).into(),
+                eth_usdt::<T>(),
                 OrderSide::Sell,
                 price,
                 10u128,
-                OrderType::Limit
+                OrderType::Limit,
+                TimeInForce::GTC,
+                None,
+                None,
+                None,
+                None,
+                None
             ));
         }
     }
@@ -67,10 +122,17 @@ mod benchmarks {
             let buyer = funded_account::<T>("buyer", i);
             assert_ok!(Orderbook::<T>::place_order(
                 RawOrigin::Signed(buyer).into(),
+                eth_usdt::<T>(),
                 OrderSide::Buy,
                 90u128,
                 10u128,
-                OrderType::Limit
+                OrderType::Limit,
+                TimeInForce::GTC,
+                None,
+                None,
+                None,
+                None,
+                None
             ));
         }
 
@@ -78,10 +140,17 @@ mod benchmarks {
             let seller = funded_account::<T>("seller", i);
             assert_ok!(Orderbook::<T>::place_order(
                 RawOrigin::Signed(seller).into(),
+                eth_usdt::<T>(),
                 OrderSide::Sell,
                 110u128,
                 10u128,
-                OrderType::Limit
+                OrderType::Limit,
+                TimeInForce::GTC,
+                None,
+                None,
+                None,
+                None,
+                None
             ));
         }
     }
@@ -97,16 +166,24 @@ mod benchmarks {
             // Place order
             assert_ok!(Pallet::<T>::place_order(
                 RawOrigin::Signed(user.clone()).into(),
+                eth_usdt::<T>(),
                 OrderSide::Buy,
                 100u128,
                 10u128,
-                OrderType::Limit
+                OrderType::Limit,
+                TimeInForce::GTC,
+                None,
+                None,
+                None,
+                None,
+                None
             ));
 
             // The order_id that was just created is order_id_before
             // (because NextOrderId was incremented AFTER the order was placed)
             assert_ok!(Pallet::<T>::cancel_order(
                 RawOrigin::Signed(user).into(),
+                eth_usdt::<T>(),
                 order_id_before, // Use the captured order_id
             ));
         }
@@ -123,10 +200,17 @@ mod benchmarks {
         #[extrinsic_call]
         place_order(
             RawOrigin::Signed(caller.clone()),
+            eth_usdt::<T>(),
             OrderSide::Buy,
             100u128,
             10u128,
             OrderType::Limit,
+            TimeInForce::GTC,
+            None,
+            None,
+            None,
+            None,
+            None,
         );
 
         assert_eq!(Orderbook::<T>::next_order_id(), 1);
@@ -138,18 +222,139 @@ mod benchmarks {
 
         assert_ok!(Orderbook::<T>::place_order(
             RawOrigin::Signed(caller.clone()).into(),
+            eth_usdt::<T>(),
             OrderSide::Buy,
             100u128,
             10u128,
-            OrderType::Limit
+            OrderType::Limit,
+            TimeInForce::GTC,
+            None,
+            None,
+            None,
+            None,
+            None
         ));
 
         let order_id = 0;
 
         #[extrinsic_call]
-        cancel_order(RawOrigin::Signed(caller.clone()), order_id);
+        cancel_order(RawOrigin::Signed(caller.clone()), eth_usdt::<T>(), order_id);
+
+        assert_eq!(
+            Orderbook::<T>::get_pending_cancellations(eth_usdt::<T>()).len(),
+            1
+        );
+    }
+
+    #[benchmark]
+    fn place_stop_order() {
+        let caller = funded_account::<T>("caller", 0);
+
+        #[extrinsic_call]
+        place_stop_order(
+            RawOrigin::Signed(caller.clone()),
+            eth_usdt::<T>(),
+            OrderSide::Sell,
+            StopKind::StopLoss,
+            90u128,
+            85u128,
+            10u128,
+            OrderType::Limit,
+        );
+
+        assert_eq!(Orderbook::<T>::get_stop_orders(eth_usdt::<T>()).len(), 1);
+    }
+
+    #[benchmark]
+    fn cancel_stop_order() {
+        let caller = funded_account::<T>("caller", 0);
+
+        assert_ok!(Orderbook::<T>::place_stop_order(
+            RawOrigin::Signed(caller.clone()).into(),
+            eth_usdt::<T>(),
+            OrderSide::Buy,
+            StopKind::StopLoss,
+            110u128,
+            115u128,
+            10u128,
+            OrderType::Limit
+        ));
+
+        #[extrinsic_call]
+        cancel_order(RawOrigin::Signed(caller.clone()), eth_usdt::<T>(), 0);
+
+        assert!(Orderbook::<T>::get_stop_orders(eth_usdt::<T>()).is_empty());
+    }
+
+    #[benchmark]
+    fn create_market() {
+        #[extrinsic_call]
+        _(RawOrigin::Root, 100u32, USDT);
+
+        assert!(
+            MarketsByPair::<T>::get(MarketPair {
+                base_asset: 100,
+                quote_asset: USDT,
+            })
+            .is_some()
+        );
+    }
+
+    #[benchmark]
+    fn set_market_status() {
+        let market_id = eth_usdt::<T>();
+
+        #[extrinsic_call]
+        pause_market(RawOrigin::Root, market_id);
+
+        assert_eq!(
+            Orderbook::<T>::get_market(market_id).map(|m| m.status),
+            Some(MarketStatus::Paused)
+        );
+    }
+
+    #[benchmark]
+    fn set_market_fees() {
+        let market_id = eth_usdt::<T>();
+        let maker = Permill::from_parts(1_000);
+        let taker = Permill::from_parts(2_000);
+
+        #[extrinsic_call]
+        set_market_fees(RawOrigin::Root, market_id, maker, taker);
+
+        assert_eq!(Orderbook::<T>::market_fees(market_id).taker, taker);
+    }
+
+    #[benchmark]
+    fn set_market_rules() {
+        let market_id = eth_usdt::<T>();
+        let rules = TradingRules {
+            tick_size: 10_000,
+            lot_size: 1_000,
+            min_notional: 1_000_000,
+        };
+
+        #[extrinsic_call]
+        set_market_rules(RawOrigin::Root, market_id, rules);
+
+        assert_eq!(Orderbook::<T>::market_rules(market_id), rules);
+    }
+
+    #[benchmark]
+    fn set_fee_tiers(t: Linear<0, { T::MaxFeeTiers::get() }>) {
+        let tiers: Vec<FeeTier> = (0..t)
+            .map(|i| FeeTier {
+                min_volume: (i as u128 + 1) * 1_000_000,
+                maker: MakerFee::Rebate(Permill::from_parts(100)),
+                taker: Permill::from_parts(2_000 - i * 100),
+            })
+            .collect();
+        let tiers: BoundedVec<_, _> = tiers.try_into().unwrap();
+
+        #[extrinsic_call]
+        set_fee_tiers(RawOrigin::Root, tiers);
 
-        assert_eq!(Orderbook::<T>::get_pending_cancellations().len(), 1);
+        assert_eq!(crate::FeeTiers::<T>::get().len() as u32, t);
     }
 
     // ========================================
@@ -185,8 +390,8 @@ mod benchmarks {
             Orderbook::<T>::on_finalize(1u32.into());
         }
 
-        assert!(Orderbook::<T>::get_bids_at_price(90).len() > 0);
-        assert!(Orderbook::<T>::get_asks_at_price(110).len() > 0);
+        assert!(!Orderbook::<T>::get_bids_at_price(eth_usdt::<T>(), 90).is_empty());
+        assert!(!Orderbook::<T>::get_asks_at_price(eth_usdt::<T>(), 110).is_empty());
     }
 
     #[benchmark]
@@ -198,7 +403,10 @@ mod benchmarks {
             Orderbook::<T>::on_finalize(1u32.into());
         }
 
-        assert_eq!(Orderbook::<T>::get_pending_cancellations().len(), 0);
+        assert_eq!(
+            Orderbook::<T>::get_pending_cancellations(eth_usdt::<T>()).len(),
+            0
+        );
     }
 
     #[benchmark]
@@ -207,10 +415,17 @@ mod benchmarks {
             let seller = funded_account::<T>("persistent_seller", i);
             assert_ok!(Orderbook::<T>::place_order(
                 RawOrigin::Signed(seller).into(),
+                eth_usdt::<T>(),
                 OrderSide::Sell,
                 100u128,
                 10u128,
-                OrderType::Limit
+                OrderType::Limit,
+                TimeInForce::GTC,
+                None,
+                None,
+                None,
+                None,
+                None
             ));
         }
 
@@ -220,10 +435,17 @@ mod benchmarks {
             let buyer = funded_account::<T>("new_buyer", i);
             assert_ok!(Orderbook::<T>::place_order(
                 RawOrigin::Signed(buyer).into(),
+                eth_usdt::<T>(),
                 OrderSide::Buy,
                 100u128,
                 10u128,
-                OrderType::Limit
+                OrderType::Limit,
+                TimeInForce::GTC,
+                None,
+                None,
+                None,
+                None,
+                None
             ));
         }
 
@@ -243,10 +465,17 @@ mod benchmarks {
             let buyer = funded_account::<T>("buyer_high", i);
             assert_ok!(Orderbook::<T>::place_order(
                 RawOrigin::Signed(buyer).into(),
+                eth_usdt::<T>(),
                 OrderSide::Buy,
                 90u128,
                 10u128,
-                OrderType::Limit
+                OrderType::Limit,
+                TimeInForce::GTC,
+                None,
+                None,
+                None,
+                None,
+                None
             ));
         }
 
@@ -258,5 +487,426 @@ mod benchmarks {
         }
     }
 
+    #[benchmark]
+    fn on_finalize_market(p: Linear<1, 50>, r: Linear<0, 50>, c: Linear<0, 20>) {
+        // resting asks all on one level, every one of them has to be loaded
+        for i in 0..r {
+            let seller = funded_account::<T>("resting_seller", i);
+            assert_ok!(Orderbook::<T>::place_order(
+                RawOrigin::Signed(seller).into(),
+                eth_usdt::<T>(),
+                OrderSide::Sell,
+                100u128,
+                10u128,
+                OrderType::Limit,
+                TimeInForce::GTC,
+                None,
+                None,
+                None,
+                None,
+                None
+            ));
+        }
+
+        Orderbook::<T>::on_finalize(1u32.into());
+
+        for i in 0..p {
+            let buyer = funded_account::<T>("pending_buyer", i);
+            assert_ok!(Orderbook::<T>::place_order(
+                RawOrigin::Signed(buyer).into(),
+                eth_usdt::<T>(),
+                OrderSide::Buy,
+                100u128,
+                10u128,
+                OrderType::Limit,
+                TimeInForce::GTC,
+                None,
+                None,
+                None,
+                None,
+                None
+            ));
+        }
+
+        setup_cancellations::<T>(c);
+
+        #[block]
+        {
+            Orderbook::<T>::on_finalize(2u32.into());
+        }
+
+        assert_eq!(Orderbook::<T>::next_trade_id(), p.min(r) as u64);
+    }
+
+    #[benchmark]
+    fn on_finalize_auction(p: Linear<1, 50>) {
+        let market_id = eth_usdt::<T>();
+        assert_ok!(Orderbook::<T>::set_clearing_mode(
+            RawOrigin::Root.into(),
+            market_id,
+            ClearingMode::BatchAuction
+        ));
+
+        // one trader's bids and asks all cross each other and are checked for self-trades
+        let trader = funded_account::<T>("own", 0);
+        for i in 0..p {
+            let (side, price) = if i % 2 == 0 {
+                (OrderSide::Buy, 101u128)
+            } else {
+                (OrderSide::Sell, 100u128)
+            };
+            assert_ok!(Orderbook::<T>::place_order(
+                RawOrigin::Signed(trader.clone()).into(),
+                market_id,
+                side,
+                price,
+                10u128,
+                OrderType::Limit,
+                TimeInForce::GTC,
+                None,
+                None,
+                Some(SelfTradePrevention::DecrementAndCancel),
+                None,
+                None
+            ));
+        }
+
+        // every round the best FOK bid gets all there is, comes up short and is killed
+        let seller = funded_account::<T>("seller", 0);
+        assert_ok!(Orderbook::<T>::place_order(
+            RawOrigin::Signed(seller).into(),
+            market_id,
+            OrderSide::Sell,
+            100u128,
+            10u128,
+            OrderType::Limit,
+            TimeInForce::GTC,
+            None,
+            None,
+            None,
+            None,
+            None
+        ));
+        for i in 0..MAX_AUCTION_ROUNDS {
+            let buyer = funded_account::<T>("fok_buyer", i);
+            assert_ok!(Orderbook::<T>::place_order(
+                RawOrigin::Signed(buyer).into(),
+                market_id,
+                OrderSide::Buy,
+                102u128 + i as u128,
+                11u128,
+                OrderType::Limit,
+                TimeInForce::FOK,
+                None,
+                None,
+                None,
+                None,
+                None
+            ));
+        }
+
+        #[block]
+        {
+            Orderbook::<T>::on_finalize(1u32.into());
+        }
+
+        // only an own bid left over takes the seller's ask
+        assert_eq!(Orderbook::<T>::next_trade_id(), (p % 2) as u64);
+    }
+
+    #[benchmark]
+    fn trigger_stop_orders(s: Linear<1, 50>) {
+        let market_id = eth_usdt::<T>();
+
+        // every stop sits on its own price level
+        for i in 0..s {
+            let seller = funded_account::<T>("stop_seller", i);
+            assert_ok!(Orderbook::<T>::place_stop_order(
+                RawOrigin::Signed(seller).into(),
+                market_id,
+                OrderSide::Sell,
+                StopKind::StopLoss,
+                90u128,
+                80u128 - i as u128,
+                10u128,
+                OrderType::Limit
+            ));
+        }
+
+        #[block]
+        {
+            Pallet::<T>::trigger_stop_orders(market_id, 90u128);
+        }
+
+        assert!(Orderbook::<T>::get_stop_orders(market_id).is_empty());
+    }
+
+    #[benchmark]
+    fn expire_orders(e: Linear<1, 100>) {
+        let market_id = eth_usdt::<T>();
+        let now = frame_system::Pallet::<T>::block_number();
+        let expiry: u32 = now.saturated_into::<u32>() + 1;
+
+        // resting bids spread over as many price levels as the book allows
+        for i in 0..e {
+            let buyer = funded_account::<T>("expiring_buyer", i);
+            assert_ok!(Orderbook::<T>::place_order(
+                RawOrigin::Signed(buyer).into(),
+                market_id,
+                OrderSide::Buy,
+                50u128 + (i % 40) as u128,
+                10u128,
+                OrderType::Limit,
+                TimeInForce::GTC,
+                None,
+                Some(expiry),
+                None,
+                None,
+                None
+            ));
+        }
+        Orderbook::<T>::on_finalize(now);
+
+        #[block]
+        {
+            Pallet::<T>::expire_orders(expiry);
+        }
+
+        assert!(Orderbook::<T>::get_expiring_orders(expiry).is_empty());
+        assert_eq!(Orderbook::<T>::get_price_levels(market_id).0.len(), 0);
+    }
+
+    /// Bids of one trader spread over the price levels the book allows
+    fn ladder<T: Config>(
+        market_id: MarketId,
+        count: u32,
+    ) -> BoundedVec<OrderRequest, T::MaxPendingOrders> {
+        let orders: Vec<OrderRequest> = (0..count)
+            .map(|i| OrderRequest {
+                market_id,
+                side: OrderSide::Buy,
+                price: 50u128 + (i % 40) as u128,
+                quantity: 10u128,
+                order_type: OrderType::Limit,
+                time_in_force: TimeInForce::GTC,
+                post_only: None,
+                expires_at: None,
+                self_trade: None,
+                display_quantity: None,
+                reduce_only: None,
+            })
+            .collect();
+        orders.try_into().unwrap()
+    }
+
+    #[benchmark]
+    fn place_orders(n: Linear<1, { T::MaxPendingOrders::get() }>) {
+        let caller = funded_account::<T>("caller", 0);
+        let orders = ladder::<T>(eth_usdt::<T>(), n);
+
+        #[extrinsic_call]
+        place_orders(RawOrigin::Signed(caller.clone()), orders);
+
+        assert_eq!(Orderbook::<T>::next_order_id(), n as u64);
+    }
+
+    #[benchmark]
+    fn cancel_orders(n: Linear<1, { T::MaxCancellationOrders::get() }>) {
+        let caller = funded_account::<T>("caller", 0);
+        let market_id = eth_usdt::<T>();
+        assert_ok!(Orderbook::<T>::place_orders(
+            RawOrigin::Signed(caller.clone()).into(),
+            ladder::<T>(market_id, n)
+        ));
+        let order_ids: BoundedVec<_, _> = (0..n as u64).collect::<Vec<_>>().try_into().unwrap();
+
+        #[extrinsic_call]
+        cancel_orders(RawOrigin::Signed(caller.clone()), order_ids);
+
+        assert_eq!(
+            Orderbook::<T>::get_pending_cancellations(market_id).len() as u32,
+            n
+        );
+    }
+
+    #[benchmark]
+    fn replace_orders(
+        c: Linear<0, { T::MaxCancellationOrders::get() }>,
+        p: Linear<
+            1,
+            {
+                T::MaxPendingOrders::get()
+                    .min(T::MaxUserOrders::get() - T::MaxCancellationOrders::get())
+            },
+        >,
+    ) {
+        let caller = funded_account::<T>("caller", 0);
+        let market_id = eth_usdt::<T>();
+        if c > 0 {
+            assert_ok!(Orderbook::<T>::place_orders(
+                RawOrigin::Signed(caller.clone()).into(),
+                ladder::<T>(market_id, c)
+            ));
+        }
+        let cancels: BoundedVec<_, _> = (0..c as u64).collect::<Vec<_>>().try_into().unwrap();
+        let orders = ladder::<T>(market_id, p);
+
+        #[extrinsic_call]
+        replace_orders(RawOrigin::Signed(caller.clone()), cancels, orders);
+
+        assert_eq!(Orderbook::<T>::next_order_id(), (c + p) as u64);
+        assert_eq!(
+            Orderbook::<T>::get_pending_cancellations(market_id).len() as u32,
+            c
+        );
+    }
+
+    #[benchmark]
+    fn amend_order() {
+        let caller = funded_account::<T>("caller", 0);
+        let market_id = eth_usdt::<T>();
+        assert_ok!(Orderbook::<T>::place_order(
+            RawOrigin::Signed(caller.clone()).into(),
+            market_id,
+            OrderSide::Buy,
+            100u128,
+            10u128,
+            OrderType::Limit,
+            TimeInForce::GTC,
+            None,
+            None,
+            None,
+            None,
+            None
+        ));
+        // worst case: a resting order that grows and goes back to the queue
+        Orderbook::<T>::on_finalize(1u32.into());
+
+        #[extrinsic_call]
+        amend_order(RawOrigin::Signed(caller.clone()), 0, 101u128, 20u128);
+
+        let order = Orderbook::<T>::get_order(0).unwrap();
+        assert_eq!((order.price, order.quantity), (101, 20));
+    }
+
+    #[benchmark]
+    fn cancel_all_orders(n: Linear<1, { T::MaxUserOrders::get() }>) {
+        let caller = funded_account::<T>("caller", 0);
+        let market_id = eth_usdt::<T>();
+        for i in 0..n {
+            assert_ok!(Orderbook::<T>::place_order(
+                RawOrigin::Signed(caller.clone()).into(),
+                market_id,
+                OrderSide::Buy,
+                50u128 + (i % 40) as u128,
+                10u128,
+                OrderType::Limit,
+                TimeInForce::GTC,
+                None,
+                None,
+                None,
+                None,
+                None
+            ));
+        }
+
+        #[extrinsic_call]
+        cancel_all_orders(RawOrigin::Signed(caller.clone()), None);
+
+        assert_eq!(
+            Orderbook::<T>::get_pending_cancellations(market_id).len() as u32,
+            n.min(T::MaxCancellationOrders::get())
+        );
+    }
+
+    #[benchmark]
+    fn prune_trades(n: Linear<0, 100>) {
+        let buyer: AccountIdOf<T> = account("buyer", 0, 0);
+        let seller: AccountIdOf<T> = account("seller", 0, 0);
+        for trade_id in 0..n as u64 {
+            Trades::<T>::insert(
+                trade_id,
+                Trade::<T> {
+                    trade_id,
+                    market_id: 0,
+                    buyer: buyer.clone(),
+                    seller: seller.clone(),
+                    buy_order_id: trade_id * 2,
+                    sell_order_id: trade_id * 2 + 1,
+                    price: 100,
+                    quantity: 10,
+                    taker_side: OrderSide::Buy,
+                    buyer_fee: 1,
+                    seller_fee: 1,
+                    maker_rebate: 0,
+                },
+            );
+        }
+        // everything benchmarked is past the retention window
+        NextTradeId::<T>::put(n as u64 + T::TradeRetention::get());
+
+        #[block]
+        {
+            Orderbook::<T>::prune_trades(Weight::MAX);
+        }
+
+        assert_eq!(PrunedTradesUpTo::<T>::get(), n as u64);
+    }
+
+    #[benchmark]
+    fn set_self_trade_prevention() {
+        let caller: AccountIdOf<T> = account("caller", 0, 0);
+
+        #[extrinsic_call]
+        set_self_trade_prevention(
+            RawOrigin::Signed(caller.clone()),
+            Some(SelfTradePrevention::CancelBoth),
+        );
+
+        assert_eq!(
+            AccountSelfTradePrevention::<T>::get(&caller),
+            Some(SelfTradePrevention::CancelBoth)
+        );
+    }
+
+    #[benchmark]
+    fn set_matching_mode() {
+        let market_id = eth_usdt::<T>();
+        let mode = MatchingMode::ProRata {
+            fifo_slice: Permill::from_percent(20),
+        };
+
+        #[extrinsic_call]
+        set_matching_mode(RawOrigin::Root, market_id, mode);
+
+        assert_eq!(MarketMatchingMode::<T>::get(market_id), mode);
+    }
+
+    #[benchmark]
+    fn set_clearing_mode() {
+        let market_id = eth_usdt::<T>();
+
+        #[extrinsic_call]
+        set_clearing_mode(RawOrigin::Root, market_id, ClearingMode::BatchAuction);
+
+        assert_eq!(
+            MarketClearingMode::<T>::get(market_id),
+            ClearingMode::BatchAuction
+        );
+    }
+
+    #[benchmark]
+    fn set_ordering_policy() {
+        let market_id = eth_usdt::<T>();
+
+        #[extrinsic_call]
+        set_ordering_policy(RawOrigin::Root, market_id, OrderingPolicy::ParentHash);
+
+        assert_eq!(
+            MarketOrderingPolicy::<T>::get(market_id),
+            OrderingPolicy::ParentHash
+        );
+    }
+
     impl_benchmark_test_suite!(Orderbook, crate::mock::new_test_ext(), crate::mock::Test);
 }
@@ -1,21 +1,201 @@
 use frame_support::pallet_prelude::*;
-use sp_std::{collections::btree_map::BTreeMap, vec::Vec};
+use sp_core::hashing::blake2_256;
+use sp_runtime::{Permill, Rounding, helpers_128bit::multiply_by_rational_with_rounding};
+use sp_std::{boxed::Box, collections::btree_map::BTreeMap, vec, vec::Vec};
 
 // Import our types
 use crate::types::*;
 use frame_system::Config;
 
-// This will match with the cache structure
+// How many times a batch auction is run before whatever could still be killed is
+pub const MAX_AUCTION_ROUNDS: u32 = 8;
+
+// What matching the cache came up with
+pub struct PendingMatch<T: Config> {
+    pub trades: Vec<Trade<T>>,
+    // orders left over for the persistent book
+    pub unmatched: Vec<OrderId>,
+    // FOK orders that could not be filled completely, market orders that found nothing
+    // within their quote limit
+    pub killed: Vec<OrderId>,
+    // market orders that traded, anything they did not fill is cancelled
+    pub market: Vec<OrderId>,
+    // post-only orders that would have crossed
+    pub rejected: Vec<OrderId>,
+    // post-only orders moved inside the spread: (order, old price, quantity left at that point)
+    pub repriced: Vec<(OrderId, Amount, Amount)>,
+    // matches between two orders of the same trader that were prevented instead
+    pub prevented: Vec<SelfTrade>,
+}
+
+// What matching the leftovers against the persistent book came up with
+pub struct PersistentMatch<T: Config> {
+    pub trades: Vec<Trade<T>>,
+    // IOC orders whose remainder got cancelled
+    pub dropped: Vec<OrderId>,
+    pub prevented: Vec<SelfTrade>,
+}
+
+impl<T: Config> Default for PersistentMatch<T> {
+    fn default() -> Self {
+        Self {
+            trades: Vec::new(),
+            dropped: Vec::new(),
+            prevented: Vec::new(),
+        }
+    }
+}
+
+// A match that self-trade prevention stopped, `quantity` is what was taken off both
+// orders by decrement-and-cancel
+pub struct SelfTrade {
+    pub taker: OrderId,
+    pub maker: OrderId,
+    pub mode: SelfTradePrevention,
+    pub quantity: Amount,
+}
+
+// Splits a taker's quantity among the makers it can trade with at one price
+pub trait Allocation {
+    // `open` is what each maker has left, in queue order. Returns each maker's share, together
+    // they are `quantity` or everything open if that is less.
+    fn allocate(&self, quantity: Amount, open: &[Amount]) -> Vec<Amount>;
+}
+
+// price-time priority, each maker is filled completely before the next one gets anything
+pub struct Fifo;
+
+impl Allocation for Fifo {
+    fn allocate(&self, quantity: Amount, open: &[Amount]) -> Vec<Amount> {
+        let mut left = quantity;
+        open.iter()
+            .map(|open| {
+                let share = left.min(*open);
+                left -= share;
+                share
+            })
+            .collect()
+    }
+}
+
+// The first `fifo_slice` of the quantity goes out FIFO, the rest in proportion to what each
+// maker still has open. Shares are rounded down to whole lots, the lots that rounding leaves
+// over go one at a time to the makers in queue order.
+pub struct ProRata {
+    pub fifo_slice: Permill,
+    pub lot: Amount,
+}
+
+impl Allocation for ProRata {
+    fn allocate(&self, quantity: Amount, open: &[Amount]) -> Vec<Amount> {
+        let lot = self.lot.max(1);
+        let quantity = quantity.min(
+            open.iter()
+                .fold(0, |total: Amount, o| total.saturating_add(*o)),
+        );
+
+        let slice = self.fifo_slice.mul_floor(quantity);
+        let mut shares = Fifo.allocate(slice - slice % lot, open);
+        let allocated = |shares: &[Amount]| shares.iter().sum::<Amount>();
+
+        let mut left = quantity - allocated(&shares);
+        let room: Vec<Amount> = open.iter().zip(shares.iter()).map(|(o, s)| o - s).collect();
+        let room_total = room.iter().sum::<Amount>();
+        if left > 0 {
+            for (share, room) in shares.iter_mut().zip(room.iter()) {
+                let part =
+                    multiply_by_rational_with_rounding(left, *room, room_total, Rounding::Down)
+                        .unwrap_or_default();
+                *share += part - part % lot;
+            }
+            left = quantity - allocated(&shares);
+        }
+
+        while left > 0 {
+            let before = left;
+            for (share, open) in shares.iter_mut().zip(open.iter()) {
+                let part = lot.min(open - *share).min(left);
+                *share += part;
+                left -= part;
+                if left == 0 {
+                    break;
+                }
+            }
+            if left == before {
+                break;
+            }
+        }
+
+        shares
+    }
+}
+
+// The allocation a market's matching mode stands for, `lot` is the market lot size
+pub fn allocation(mode: MatchingMode, lot: Amount) -> Box<dyn Allocation> {
+    match mode {
+        MatchingMode::Fifo => Box::new(Fifo),
+        MatchingMode::ProRata { fifo_slice } => Box::new(ProRata { fifo_slice, lot }),
+    }
+}
+
+impl<T: Config> Default for PendingMatch<T> {
+    fn default() -> Self {
+        Self {
+            trades: Vec::new(),
+            unmatched: Vec::new(),
+            killed: Vec::new(),
+            market: Vec::new(),
+            rejected: Vec::new(),
+            repriced: Vec::new(),
+            prevented: Vec::new(),
+        }
+    }
+}
+
+// The order new orders are matched in. Without a seed that is by id, the order the block
+// author included them in. With one it is by `blake2_256((seed, order_id))`, a shuffle anyone
+// can redo from the seed.
+pub fn arrival_order(order_ids: &mut [OrderId], seed: Option<[u8; 32]>) {
+    match seed {
+        None => order_ids.sort(),
+        Some(seed) => order_ids.sort_by_cached_key(|id| blake2_256(&(seed, id).encode())),
+    }
+}
+
+// This will match with the cache structure, `tick` is how far a post-only order is moved
+// inside the spread, `allocation` shares each price level among its makers and `seed` picks
+// the order the cache is matched in
+#[allow(clippy::too_many_arguments)]
 pub fn match_pending_internal<T: Config>(
     pending_bids: BTreeMap<Amount, Vec<OrderId>>,
     pending_asks: BTreeMap<Amount, Vec<OrderId>>,
+    persistent_bids: &mut BTreeMap<Amount, Vec<OrderId>>,
+    persistent_asks: &mut BTreeMap<Amount, Vec<OrderId>>,
     orders_map: &mut BTreeMap<OrderId, Order<T>>,
-) -> Result<(Vec<Trade<T>>, Vec<OrderId>), DispatchError> {
+    tick: Amount,
+    lot: Amount,
+    allocation: &dyn Allocation,
+    seed: Option<[u8; 32]>,
+) -> Result<PendingMatch<T>, DispatchError> {
     let mut bid_book = pending_bids;
     let mut ask_book = pending_asks;
-    let mut trades = Vec::new();
+    let mut result = PendingMatch::<T>::default();
+
+    // FOK orders never sit in the cache as makers, a partial fill there could not be undone.
+    // Market orders have no price to be taken at, they only take.
+    let taker_only: Vec<Order<T>> = bid_book
+        .values()
+        .chain(ask_book.values())
+        .flatten()
+        .filter_map(|id| orders_map.get(id))
+        .filter(|order| order.time_in_force == TimeInForce::FOK || order.order_type.is_market())
+        .cloned()
+        .collect();
+    for order in taker_only.iter() {
+        remove_from_orderbook(order.order_id, order, &mut bid_book, &mut ask_book);
+    }
 
-    let mut all_pending_ids = Vec::new();
+    let mut all_pending_ids: Vec<OrderId> = taker_only.iter().map(|o| o.order_id).collect();
 
     for (_price, order_ids) in bid_book.iter() {
         all_pending_ids.extend(order_ids.clone());
@@ -25,7 +205,7 @@ pub fn match_pending_internal<T: Config>(
         all_pending_ids.extend(order_ids.clone());
     }
 
-    all_pending_ids.sort();
+    arrival_order(&mut all_pending_ids, seed);
 
     for order_id in all_pending_ids {
         let mut order = match orders_map.get(&order_id) {
@@ -35,38 +215,773 @@ pub fn match_pending_internal<T: Config>(
 
         remove_from_orderbook(order_id, &order, &mut bid_book, &mut ask_book);
 
+        // cancelled by self-trade prevention before its turn came
+        if order.status.is_finished() {
+            continue;
+        }
+
+        if let OrderType::Market { quote_limit }
+        | OrderType::Spend {
+            quote_amount: quote_limit,
+        } = order.order_type
+        {
+            let (pending_book, persistent_book) = match order.side {
+                OrderSide::Buy => (&mut ask_book, &mut *persistent_asks),
+                OrderSide::Sell => (&mut bid_book, &mut *persistent_bids),
+            };
+
+            // self-trade prevention can stop or shrink it part way, so with its own orders in
+            // reach it is matched on a copy and only the outcome is checked
+            let own = meets_own_order(&order, pending_book, orders_map)
+                || meets_own_order(&order, persistent_book, orders_map);
+            if !own {
+                let (quantity, quote) = market_reach(
+                    &order,
+                    quote_limit,
+                    lot,
+                    pending_book,
+                    persistent_book,
+                    orders_map,
+                );
+                let short = order.time_in_force == TimeInForce::FOK
+                    && quantity < remaining_quantity(&mut order);
+                let below_floor = order.side == OrderSide::Sell && quote < quote_limit;
+                if quantity == 0 || short || below_floor {
+                    order.status = OrderStatus::Cancelled;
+                    orders_map.insert(order_id, order);
+                    result.killed.push(order_id);
+                    continue;
+                }
+            }
+
+            let trial = own.then(|| {
+                Trial::take(
+                    &order,
+                    pending_book,
+                    persistent_book,
+                    orders_map,
+                    &result.prevented,
+                )
+            });
+            let trades = match_market_order(
+                &mut order,
+                quote_limit,
+                lot,
+                pending_book,
+                persistent_book,
+                orders_map,
+                &mut result.prevented,
+                allocation,
+            )?;
+            if let Some(trial) = trial
+                && !keeps_outcome(&order, &trades)
+            {
+                order = trial.restore(
+                    pending_book,
+                    persistent_book,
+                    orders_map,
+                    &mut result.prevented,
+                );
+                order.status = OrderStatus::Cancelled;
+                orders_map.insert(order_id, order);
+                result.killed.push(order_id);
+                continue;
+            }
+            result.trades.extend(trades);
+
+            close_market_order(&mut order);
+            orders_map.insert(order_id, order);
+            result.market.push(order_id);
+            continue;
+        }
+
+        if let Some(mode) = order.post_only {
+            let best = match order.side {
+                OrderSide::Buy => best_ask(&ask_book, persistent_asks),
+                OrderSide::Sell => best_bid(&bid_book, persistent_bids),
+            };
+
+            // never takes, it goes straight to the book unless it is rejected
+            let rests = apply_post_only(&mut order, mode, best, tick, &mut result);
+            orders_map.insert(order_id, order.clone());
+            if rests {
+                add_order_to_book(&order, &mut bid_book, &mut ask_book);
+            }
+            continue;
+        }
+
+        if order.time_in_force == TimeInForce::FOK {
+            let (pending_book, persistent_book) = match order.side {
+                OrderSide::Buy => (&mut ask_book, &mut *persistent_asks),
+                OrderSide::Sell => (&mut bid_book, &mut *persistent_bids),
+            };
+
+            // like a market order, with its own orders in reach it is matched on a copy
+            let own = meets_own_order(&order, pending_book, orders_map)
+                || meets_own_order(&order, persistent_book, orders_map);
+            if !own {
+                let available = fillable_quantity(&order, pending_book, orders_map)
+                    .saturating_add(fillable_quantity(&order, persistent_book, orders_map));
+                if available < remaining_quantity(&mut order) {
+                    order.status = OrderStatus::Cancelled;
+                    orders_map.insert(order_id, order);
+                    result.killed.push(order_id);
+                    continue;
+                }
+            }
+
+            let trial = own.then(|| {
+                Trial::take(
+                    &order,
+                    pending_book,
+                    persistent_book,
+                    orders_map,
+                    &result.prevented,
+                )
+            });
+            // fill it right away, the persistent liquidity it counted on is still there
+            let (pending_trades, persistent_trades) = match order.side {
+                OrderSide::Buy => (
+                    match_buy_order(
+                        &mut order,
+                        pending_book,
+                        orders_map,
+                        &mut result.prevented,
+                        allocation,
+                    )?,
+                    match_buy_order(
+                        &mut order,
+                        persistent_book,
+                        orders_map,
+                        &mut result.prevented,
+                        allocation,
+                    )?,
+                ),
+                OrderSide::Sell => (
+                    match_sell_order(
+                        &mut order,
+                        pending_book,
+                        orders_map,
+                        &mut result.prevented,
+                        allocation,
+                    )?,
+                    match_sell_order(
+                        &mut order,
+                        persistent_book,
+                        orders_map,
+                        &mut result.prevented,
+                        allocation,
+                    )?,
+                ),
+            };
+            let mut trades = pending_trades;
+            trades.extend(persistent_trades);
+            if let Some(trial) = trial
+                && !keeps_outcome(&order, &trades)
+            {
+                order = trial.restore(
+                    pending_book,
+                    persistent_book,
+                    orders_map,
+                    &mut result.prevented,
+                );
+                order.status = OrderStatus::Cancelled;
+                orders_map.insert(order_id, order);
+                result.killed.push(order_id);
+                continue;
+            }
+            result.trades.extend(trades);
+
+            orders_map.insert(order_id, order);
+            continue;
+        }
+
         let order_trades = match order.side {
-            OrderSide::Buy => match_buy_order(&mut order, &mut ask_book, orders_map)?,
-            OrderSide::Sell => match_sell_order(&mut order, &mut bid_book, orders_map)?,
+            OrderSide::Buy => match_buy_order(
+                &mut order,
+                &mut ask_book,
+                orders_map,
+                &mut result.prevented,
+                allocation,
+            )?,
+            OrderSide::Sell => match_sell_order(
+                &mut order,
+                &mut bid_book,
+                orders_map,
+                &mut result.prevented,
+                allocation,
+            )?,
         };
 
-        trades.extend(order_trades);
+        result.trades.extend(order_trades);
 
         orders_map.insert(order_id, order.clone());
 
-        if order.status != OrderStatus::Filled {
+        if !order.status.is_finished() {
             add_order_to_book(&order, &mut bid_book, &mut ask_book);
         }
     }
 
-    let mut unmatched = Vec::new();
     for (_price, ids) in bid_book.iter() {
-        unmatched.extend(ids.clone());
+        result.unmatched.extend(ids.clone());
     }
     for (_price, ids) in ask_book.iter() {
-        unmatched.extend(ids.clone());
+        result.unmatched.extend(ids.clone());
+    }
+
+    Ok(result)
+}
+
+// Checks a post-only order against the best opposite price, returns whether it can rest.
+// One that would cross is moved `tick` inside the spread or rejected, as its mode says.
+fn apply_post_only<T: Config>(
+    order: &mut Order<T>,
+    mode: PostOnly,
+    best_opposite: Option<Amount>,
+    tick: Amount,
+    result: &mut PendingMatch<T>,
+) -> bool {
+    let crossing = best_opposite.filter(|best| match order.side {
+        OrderSide::Buy => order.price >= *best,
+        OrderSide::Sell => order.price <= *best,
+    });
+    let Some(best) = crossing else {
+        return true;
+    };
+
+    let inside = match order.side {
+        OrderSide::Buy => best.checked_sub(tick).filter(|p| *p > 0),
+        OrderSide::Sell => best.checked_add(tick),
+    };
+    match (mode, inside) {
+        (PostOnly::Reprice, Some(new_price)) => {
+            let remaining = remaining_quantity(order);
+            result
+                .repriced
+                .push((order.order_id, order.price, remaining));
+            order.price = new_price;
+            true
+        }
+        _ => {
+            order.status = OrderStatus::Cancelled;
+            result.rejected.push(order.order_id);
+            false
+        }
+    }
+}
+
+// lowest ask across the cache and the loaded persistent levels
+fn best_ask(
+    pending_asks: &BTreeMap<Amount, Vec<OrderId>>,
+    persistent_asks: &BTreeMap<Amount, Vec<OrderId>>,
+) -> Option<Amount> {
+    let pending = pending_asks.keys().next().copied();
+    let persistent = persistent_asks
+        .iter()
+        .find(|(_, ids)| !ids.is_empty())
+        .map(|(price, _)| *price);
+    match (pending, persistent) {
+        (Some(a), Some(b)) => Some(a.min(b)),
+        (a, b) => a.or(b),
+    }
+}
+
+// highest bid across the cache and the loaded persistent levels
+fn best_bid(
+    pending_bids: &BTreeMap<Amount, Vec<OrderId>>,
+    persistent_bids: &BTreeMap<Amount, Vec<OrderId>>,
+) -> Option<Amount> {
+    let pending = pending_bids.keys().next_back().copied();
+    let persistent = persistent_bids
+        .iter()
+        .rev()
+        .find(|(_, ids)| !ids.is_empty())
+        .map(|(price, _)| *price);
+    pending.max(persistent)
+}
+
+// Batch auction over the cache and the resting levels it reaches. One clearing price is picked
+// where the most quantity trades and every trade happens at it, so the order orders arrived in
+// within the block doesn't matter. Ties go to the smaller imbalance between the two sides,
+// then to the price closest to `reference` (the last trade price), then to the lower price.
+// The side with more quantity is filled by price, a level that can't be filled completely is
+// shared by `allocation`, pending orders at one price in the order `seed` gives them.
+// Post-only orders don't take part, they go to the book afterwards.
+#[allow(clippy::too_many_arguments)]
+pub fn match_batch_auction<T: Config>(
+    pending_bids: BTreeMap<Amount, Vec<OrderId>>,
+    pending_asks: BTreeMap<Amount, Vec<OrderId>>,
+    persistent_bids: &mut BTreeMap<Amount, Vec<OrderId>>,
+    persistent_asks: &mut BTreeMap<Amount, Vec<OrderId>>,
+    orders_map: &mut BTreeMap<OrderId, Order<T>>,
+    tick: Amount,
+    lot: Amount,
+    allocation: &dyn Allocation,
+    reference: Option<Amount>,
+    seed: Option<[u8; 32]>,
+) -> Result<(PendingMatch<T>, PersistentMatch<T>), DispatchError> {
+    let mut result = PendingMatch::<T>::default();
+    let mut dropped = Vec::new();
+
+    // own orders that cross each other never trade, so they must not count towards the
+    // clearing price or be left resting across each other either
+    let bids = auction_queue(
+        OrderSide::Buy,
+        &pending_bids,
+        persistent_bids,
+        orders_map,
+        seed,
+    );
+    let asks = auction_queue(
+        OrderSide::Sell,
+        &pending_asks,
+        persistent_asks,
+        orders_map,
+        seed,
+    );
+    prevent_auction_self_trades(&bids, &asks, orders_map, &mut result.prevented);
+
+    // FOK orders that can't be filled completely and market sells that would get less than
+    // their floor are killed, then the auction is run again without them. The last round
+    // kills every one of them that is left, so the one after it is final.
+    let killable = |order: &Order<T>| match order.order_type {
+        OrderType::Market { .. } => order.side == OrderSide::Sell,
+        OrderType::Spend { .. } => false,
+        OrderType::Limit => order.time_in_force == TimeInForce::FOK,
+    };
+    let mut rounds = 0u32;
+    let (price, pairs) = loop {
+        rounds = rounds.saturating_add(1);
+        let bids = auction_queue(
+            OrderSide::Buy,
+            &pending_bids,
+            persistent_bids,
+            orders_map,
+            seed,
+        );
+        let asks = auction_queue(
+            OrderSide::Sell,
+            &pending_asks,
+            persistent_asks,
+            orders_map,
+            seed,
+        );
+        let Some((price, volume)) = clearing_price(&bids, &asks, orders_map, reference, lot) else {
+            break (0, Vec::new());
+        };
+
+        let bid_fills = auction_fills(&bids, price, volume, lot, orders_map, allocation);
+        let ask_fills = auction_fills(&asks, price, volume, lot, orders_map, allocation);
+        let pairs = auction_pairs(bid_fills, ask_fills);
+
+        let mut paired: BTreeMap<OrderId, Amount> = BTreeMap::new();
+        for (bid_id, ask_id, quantity) in pairs.iter() {
+            *paired.entry(*bid_id).or_insert(0) += quantity;
+            *paired.entry(*ask_id).or_insert(0) += quantity;
+        }
+        let killed: Vec<OrderId> = paired
+            .iter()
+            .filter_map(|(id, quantity)| orders_map.get(id).map(|order| (order, *quantity)))
+            .filter(|(order, quantity)| match order.order_type {
+                OrderType::Market { quote_limit } => {
+                    order.side == OrderSide::Sell && price.saturating_mul(*quantity) < quote_limit
+                }
+                OrderType::Spend { .. } => false,
+                OrderType::Limit => {
+                    order.time_in_force == TimeInForce::FOK && *quantity < order.open_quantity()
+                }
+            })
+            .map(|(order, _)| order.order_id)
+            .collect();
+        if killed.is_empty() {
+            break (price, pairs);
+        }
+        let killed = if rounds < MAX_AUCTION_ROUNDS {
+            killed
+        } else {
+            bids.iter()
+                .chain(asks.iter())
+                .flatten()
+                .filter(|id| orders_map.get(id).is_some_and(killable))
+                .copied()
+                .collect()
+        };
+
+        for order_id in killed {
+            if let Some(order) = orders_map.get_mut(&order_id) {
+                order.status = OrderStatus::Cancelled;
+                result.killed.push(order_id);
+            }
+        }
+    };
+
+    for (bid_id, ask_id, quantity) in pairs {
+        let (Some(mut buy), Some(mut sell)) = (
+            orders_map.get(&bid_id).cloned(),
+            orders_map.get(&ask_id).cloned(),
+        ) else {
+            continue;
+        };
+        // the order placed last is the one that took liquidity
+        let taker_side = if buy.order_id > sell.order_id {
+            OrderSide::Buy
+        } else {
+            OrderSide::Sell
+        };
+        result.trades.push(execute_trade(
+            &mut buy, &mut sell, price, quantity, taker_side,
+        )?);
+        orders_map.insert(bid_id, buy);
+        orders_map.insert(ask_id, sell);
+    }
+
+    // resting orders that were filled or cancelled leave the book, partly filled ones keep
+    // their place
+    let resting: Vec<OrderId> = persistent_bids
+        .values()
+        .chain(persistent_asks.values())
+        .flatten()
+        .copied()
+        .collect();
+    for order_id in resting {
+        if let Some(order) = orders_map.get(&order_id)
+            && order.status.is_finished()
+        {
+            remove_from_orderbook(order_id, order, persistent_bids, persistent_asks);
+        }
+    }
+
+    let mut pending_ids: Vec<OrderId> = pending_bids
+        .values()
+        .chain(pending_asks.values())
+        .flatten()
+        .copied()
+        .collect();
+    arrival_order(&mut pending_ids, seed);
+
+    let mut post_only = Vec::new();
+    for order_id in pending_ids {
+        let Some(mut order) = orders_map.get(&order_id).cloned() else {
+            continue;
+        };
+        if result.killed.contains(&order_id) {
+            continue;
+        }
+
+        if order.order_type.is_market() {
+            // never rests, one that found nothing is killed. One that traded or that
+            // self-trade prevention cancelled is refunded with the other market orders.
+            if order.filled_quantity == 0 && order.status != OrderStatus::Cancelled {
+                result.killed.push(order_id);
+            } else {
+                result.market.push(order_id);
+            }
+            close_market_order(&mut order);
+            orders_map.insert(order_id, order);
+            continue;
+        }
+        if order.status.is_finished() {
+            continue;
+        }
+
+        if let Some(mode) = order.post_only {
+            post_only.push((order_id, mode));
+            continue;
+        }
+
+        if order.time_in_force == TimeInForce::FOK {
+            // one that got anything was filled completely, so this one got nothing
+            order.status = OrderStatus::Cancelled;
+            result.killed.push(order_id);
+        } else if order.time_in_force == TimeInForce::IOC {
+            order.status = OrderStatus::Cancelled;
+            dropped.push(order_id);
+        } else {
+            add_order_to_book(&order, persistent_bids, persistent_asks);
+        }
+        orders_map.insert(order_id, order);
+    }
+
+    // after the auction the book is no longer crossed, what would still cross it is taking
+    for (order_id, mode) in post_only {
+        let Some(mut order) = orders_map.get(&order_id).cloned() else {
+            continue;
+        };
+        let best = match order.side {
+            OrderSide::Buy => best_ask(&BTreeMap::new(), persistent_asks),
+            OrderSide::Sell => best_bid(&BTreeMap::new(), persistent_bids),
+        };
+        if apply_post_only(&mut order, mode, best, tick, &mut result) {
+            add_order_to_book(&order, persistent_bids, persistent_asks);
+        }
+        orders_map.insert(order_id, order);
+    }
+
+    Ok((
+        result,
+        PersistentMatch {
+            dropped,
+            ..Default::default()
+        },
+    ))
+}
+
+// The orders of one side that take part in an auction, as price levels in priority order:
+// market orders first, then the best price. Resting orders are ahead of pending ones at the
+// same price, pending ones are in arrival order.
+fn auction_queue<T: Config>(
+    side: OrderSide,
+    pending: &BTreeMap<Amount, Vec<OrderId>>,
+    persistent: &BTreeMap<Amount, Vec<OrderId>>,
+    orders_map: &BTreeMap<OrderId, Order<T>>,
+    seed: Option<[u8; 32]>,
+) -> Vec<Vec<OrderId>> {
+    let taking = |id: &OrderId| {
+        orders_map
+            .get(id)
+            .is_some_and(|order| !order.status.is_finished() && order.post_only.is_none())
+    };
+    let is_market = |id: &OrderId| orders_map.get(id).is_some_and(|o| o.order_type.is_market());
+
+    let mut market: Vec<OrderId> = pending
+        .values()
+        .flatten()
+        .copied()
+        .filter(|id| taking(id) && is_market(id))
+        .collect();
+    arrival_order(&mut market, seed);
+
+    let mut prices: Vec<Amount> = pending.keys().chain(persistent.keys()).copied().collect();
+    prices.sort();
+    prices.dedup();
+    if side == OrderSide::Buy {
+        prices.reverse();
+    }
+
+    let mut levels = vec![market];
+    for price in prices {
+        let mut arrived: Vec<OrderId> = pending.get(&price).cloned().unwrap_or_default();
+        arrival_order(&mut arrived, seed);
+        let level: Vec<OrderId> = persistent
+            .get(&price)
+            .into_iter()
+            .flatten()
+            .chain(arrived.iter())
+            .copied()
+            .filter(|id| taking(id) && !is_market(id))
+            .collect();
+        levels.push(level);
+    }
+    levels.retain(|level| !level.is_empty());
+    levels
+}
+
+// What an order could trade in an auction clearing at `price`, a market buy as many lots as
+// its quote limit pays for
+fn auction_depth<T: Config>(order: &Order<T>, price: Amount, lot: Amount) -> Amount {
+    let open = order.open_quantity();
+    match (order.order_type, order.side) {
+        (
+            OrderType::Market { quote_limit }
+            | OrderType::Spend {
+                quote_amount: quote_limit,
+            },
+            OrderSide::Buy,
+        ) => open.min(affordable(quote_limit, price, lot)),
+        (OrderType::Market { .. } | OrderType::Spend { .. }, OrderSide::Sell) => open,
+        (OrderType::Limit, OrderSide::Buy) if order.price >= price => open,
+        (OrderType::Limit, OrderSide::Sell) if order.price <= price => open,
+        _ => 0,
+    }
+}
+
+// How much `quote` buys at `price` in whole lots, so a budget never fills part of a lot
+fn affordable(quote: Amount, price: Amount, lot: Amount) -> Amount {
+    let quantity = quote / price.max(1);
+    quantity - quantity % lot.max(1)
+}
+
+// The price the most quantity trades at and that quantity, `None` if nothing crosses
+fn clearing_price<T: Config>(
+    bids: &[Vec<OrderId>],
+    asks: &[Vec<OrderId>],
+    orders_map: &BTreeMap<OrderId, Order<T>>,
+    reference: Option<Amount>,
+    lot: Amount,
+) -> Option<(Amount, Amount)> {
+    let orders = |levels: &[Vec<OrderId>]| -> Vec<Order<T>> {
+        levels
+            .iter()
+            .flatten()
+            .filter_map(|id| orders_map.get(id))
+            .cloned()
+            .collect()
+    };
+    let (bids, asks) = (orders(bids), orders(asks));
+
+    let mut candidates: Vec<Amount> = bids
+        .iter()
+        .chain(asks.iter())
+        .filter(|order| !order.order_type.is_market())
+        .map(|order| order.price)
+        .chain(reference)
+        .filter(|price| *price > 0)
+        .collect();
+    candidates.sort();
+    candidates.dedup();
+
+    let depth = |orders: &[Order<T>], price| {
+        orders.iter().fold(0, |total: Amount, order| {
+            total.saturating_add(auction_depth(order, price, lot))
+        })
+    };
+
+    candidates
+        .into_iter()
+        .map(|price| {
+            let (demand, supply) = (depth(&bids, price), depth(&asks, price));
+            let distance = reference.map_or(0, |reference| reference.abs_diff(price));
+            (price, demand.min(supply), demand.abs_diff(supply), distance)
+        })
+        .filter(|(_, volume, _, _)| *volume > 0)
+        // most volume, smallest imbalance, closest to the reference, lowest price
+        .min_by_key(|(price, volume, imbalance, distance)| {
+            (Amount::MAX - volume, *imbalance, *distance, *price)
+        })
+        .map(|(price, volume, _, _)| (price, volume))
+}
+
+// How much of `volume` each order of one side gets at `price`, level by level in priority
+// order. The level that can't be filled completely is shared by `allocation`.
+fn auction_fills<T: Config>(
+    levels: &[Vec<OrderId>],
+    price: Amount,
+    volume: Amount,
+    lot: Amount,
+    orders_map: &BTreeMap<OrderId, Order<T>>,
+    allocation: &dyn Allocation,
+) -> Vec<(OrderId, Amount)> {
+    let mut fills = Vec::new();
+    let mut left = volume;
+
+    for level in levels {
+        if left == 0 {
+            break;
+        }
+        let open: Vec<Amount> = level
+            .iter()
+            .map(|id| {
+                orders_map
+                    .get(id)
+                    .map_or(0, |order| auction_depth(order, price, lot))
+            })
+            .collect();
+        let shares = allocation.allocate(left, &open);
+        for (order_id, share) in level.iter().zip(shares) {
+            if share > 0 {
+                left -= share;
+                fills.push((*order_id, share));
+            }
+        }
+    }
+
+    fills
+}
+
+// Self-trade prevention between orders of one trader that cross, met in priority order with
+// the order placed last as the taker. Whichever of the two the mode cancels is out of the
+// auction, decrement-and-cancel takes the smaller remainder off both.
+fn prevent_auction_self_trades<T: Config>(
+    bids: &[Vec<OrderId>],
+    asks: &[Vec<OrderId>],
+    orders_map: &mut BTreeMap<OrderId, Order<T>>,
+    prevented: &mut Vec<SelfTrade>,
+) {
+    // only orders of one trader are prevented from trading, each bid is only compared to the
+    // asks of its own trader
+    let mut own_asks: BTreeMap<T::AccountId, Vec<OrderId>> = BTreeMap::new();
+    for ask_id in asks.iter().flatten() {
+        if let Some(sell) = orders_map.get(ask_id) {
+            own_asks
+                .entry(sell.trader.clone())
+                .or_default()
+                .push(*ask_id);
+        }
     }
 
-    Ok((trades, unmatched))
+    for bid_id in bids.iter().flatten() {
+        let Some(ask_ids) = orders_map
+            .get(bid_id)
+            .and_then(|buy| own_asks.get(&buy.trader))
+        else {
+            continue;
+        };
+        for ask_id in ask_ids {
+            let (Some(buy), Some(sell)) = (orders_map.get(bid_id), orders_map.get(ask_id)) else {
+                continue;
+            };
+            let crosses = buy.order_type.is_market()
+                || sell.order_type.is_market()
+                || buy.price >= sell.price;
+            // market asks come first, the limit ones after it only get dearer
+            if buy.status.is_finished() || !crosses {
+                break;
+            }
+            if sell.status.is_finished() {
+                continue;
+            }
+            let Some(mode) = self_trade_mode(buy, sell) else {
+                continue;
+            };
+
+            let (mut buy, mut sell) = (buy.clone(), sell.clone());
+            let stp = if buy.order_id > sell.order_id {
+                prevent_self_trade(&mut buy, &mut sell, mode)
+            } else {
+                prevent_self_trade(&mut sell, &mut buy, mode)
+            };
+            prevented.push(stp);
+            orders_map.insert(*bid_id, buy);
+            orders_map.insert(*ask_id, sell);
+        }
+    }
+}
+
+// Pairs the bid fills with the ask fills into (bid, ask, quantity) trades, both in priority
+// order. Own orders that would self-trade were already kept apart, so no pair is one.
+fn auction_pairs(
+    mut bid_fills: Vec<(OrderId, Amount)>,
+    mut ask_fills: Vec<(OrderId, Amount)>,
+) -> Vec<(OrderId, OrderId, Amount)> {
+    let mut pairs = Vec::new();
+
+    for (bid_id, bid_left) in bid_fills.iter_mut() {
+        for (ask_id, ask_left) in ask_fills.iter_mut() {
+            if *bid_left == 0 {
+                break;
+            }
+            if *ask_left == 0 {
+                continue;
+            }
+            let quantity = (*bid_left).min(*ask_left);
+            *bid_left -= quantity;
+            *ask_left -= quantity;
+            pairs.push((*bid_id, *ask_id, quantity));
+        }
+    }
+
+    pairs
 }
 
+// the leftovers of the cache against the loaded persistent book
 pub fn match_persistent_storage<T: Config>(
     persistent_bids: &mut BTreeMap<Amount, Vec<OrderId>>,
     persistent_asks: &mut BTreeMap<Amount, Vec<OrderId>>,
     unmatched: Vec<OrderId>,
     orders_map: &mut BTreeMap<OrderId, Order<T>>,
-) -> Result<Vec<Trade<T>>, DispatchError> {
-    let mut trades = Vec::new();
+    allocation: &dyn Allocation,
+) -> Result<PersistentMatch<T>, DispatchError> {
+    let mut result = PersistentMatch::<T>::default();
 
     for order_id in unmatched.iter() {
         let mut order = match orders_map.get(order_id) {
@@ -75,19 +990,172 @@ pub fn match_persistent_storage<T: Config>(
         };
 
         let order_trades = match order.side {
-            OrderSide::Buy => match_buy_order(&mut order, persistent_asks, orders_map),
-            OrderSide::Sell => match_sell_order(&mut order, persistent_bids, orders_map),
+            OrderSide::Buy => match_buy_order(
+                &mut order,
+                persistent_asks,
+                orders_map,
+                &mut result.prevented,
+                allocation,
+            ),
+            OrderSide::Sell => match_sell_order(
+                &mut order,
+                persistent_bids,
+                orders_map,
+                &mut result.prevented,
+                allocation,
+            ),
         };
 
-        trades.extend(order_trades.unwrap());
+        result.trades.extend(order_trades?);
+
+        // IOC never rests, whatever is left now is cancelled
+        if !order.status.is_finished() && order.time_in_force == TimeInForce::IOC {
+            order.status = OrderStatus::Cancelled;
+            result.dropped.push(*order_id);
+        }
 
         orders_map.insert(*order_id, order.clone());
 
-        if order.status != OrderStatus::Filled {
+        if order.status != OrderStatus::Filled && order.status != OrderStatus::Cancelled {
             add_order_to_book(&order, persistent_bids, persistent_asks);
         }
     }
-    Ok(trades)
+    Ok(result)
+}
+
+// whether the order would meet one of its trader's own orders at acceptable prices in a way
+// that cancels or shrinks it
+fn meets_own_order<T: Config>(
+    order: &Order<T>,
+    book: &BTreeMap<Amount, Vec<OrderId>>,
+    orders_map: &BTreeMap<OrderId, Order<T>>,
+) -> bool {
+    book.iter()
+        .filter(|(price, _)| crosses(order, **price))
+        .flat_map(|(_, ids)| ids.iter())
+        .filter_map(|id| orders_map.get(id))
+        .any(|maker| {
+            let newest = order.order_id > maker.order_id;
+            match self_trade_mode(order, maker) {
+                None => false,
+                Some(SelfTradePrevention::CancelNewest) => newest,
+                Some(SelfTradePrevention::CancelOldest) => !newest,
+                Some(_) => true,
+            }
+        })
+}
+
+// whether an opposite level at `price` is one the order accepts
+fn crosses<T: Config>(order: &Order<T>, price: Amount) -> bool {
+    match (order.order_type, order.side) {
+        (OrderType::Market { .. } | OrderType::Spend { .. }, _) => true,
+        (OrderType::Limit, OrderSide::Buy) => order.price >= price,
+        (OrderType::Limit, OrderSide::Sell) => order.price <= price,
+    }
+}
+
+// What a market or FOK order can change while it matches, kept to undo its match: the levels
+// it crosses and the makers resting there, nothing else is touched
+struct Trial<T: Config> {
+    order: Order<T>,
+    pending_levels: Vec<(Amount, Vec<OrderId>)>,
+    persistent_levels: Vec<(Amount, Vec<OrderId>)>,
+    makers: Vec<Order<T>>,
+    prevented: usize,
+}
+
+impl<T: Config> Trial<T> {
+    fn take(
+        order: &Order<T>,
+        pending_book: &BTreeMap<Amount, Vec<OrderId>>,
+        persistent_book: &BTreeMap<Amount, Vec<OrderId>>,
+        orders_map: &BTreeMap<OrderId, Order<T>>,
+        prevented: &[SelfTrade],
+    ) -> Self {
+        let crossing = |book: &BTreeMap<Amount, Vec<OrderId>>| -> Vec<(Amount, Vec<OrderId>)> {
+            book.iter()
+                .filter(|(price, _)| crosses(order, **price))
+                .map(|(price, ids)| (*price, ids.clone()))
+                .collect()
+        };
+        let (pending_levels, persistent_levels) =
+            (crossing(pending_book), crossing(persistent_book));
+        let makers = pending_levels
+            .iter()
+            .chain(persistent_levels.iter())
+            .flat_map(|(_, ids)| ids.iter())
+            .filter_map(|id| orders_map.get(id))
+            .cloned()
+            .collect();
+
+        Self {
+            order: order.clone(),
+            pending_levels,
+            persistent_levels,
+            makers,
+            prevented: prevented.len(),
+        }
+    }
+
+    // puts everything back and hands the order back as it was
+    fn restore(
+        self,
+        pending_book: &mut BTreeMap<Amount, Vec<OrderId>>,
+        persistent_book: &mut BTreeMap<Amount, Vec<OrderId>>,
+        orders_map: &mut BTreeMap<OrderId, Order<T>>,
+        prevented: &mut Vec<SelfTrade>,
+    ) -> Order<T> {
+        for (book, levels) in [
+            (pending_book, self.pending_levels),
+            (persistent_book, self.persistent_levels),
+        ] {
+            book.retain(|price, _| !crosses(&self.order, *price));
+            book.extend(levels);
+        }
+        for maker in self.makers {
+            orders_map.insert(maker.order_id, maker);
+        }
+        prevented.truncate(self.prevented);
+        self.order
+    }
+}
+
+// Whether a market or FOK order that met its own orders keeps what it did. Having traded it
+// must be filled if it is FOK and a market sell must have got its floor, having traded nothing
+// it must have been stopped by self-trade prevention rather than found nothing.
+fn keeps_outcome<T: Config>(order: &Order<T>, trades: &[Trade<T>]) -> bool {
+    if trades.is_empty() {
+        return order.status == OrderStatus::Cancelled;
+    }
+    if order.time_in_force == TimeInForce::FOK && order.status != OrderStatus::Filled {
+        return false;
+    }
+    match order.order_type {
+        OrderType::Market { quote_limit } if order.side == OrderSide::Sell => {
+            let quote = trades.iter().fold(0, |total: Amount, trade| {
+                total.saturating_add(trade.price.saturating_mul(trade.quantity))
+            });
+            quote >= quote_limit
+        }
+        _ => true,
+    }
+}
+
+// how much of the order the book could fill at acceptable prices
+fn fillable_quantity<T: Config>(
+    order: &Order<T>,
+    book: &BTreeMap<Amount, Vec<OrderId>>,
+    orders_map: &BTreeMap<OrderId, Order<T>>,
+) -> Amount {
+    book.iter()
+        .filter(|(price, _)| crosses(order, **price))
+        .flat_map(|(_, ids)| ids.iter())
+        .filter_map(|id| orders_map.get(id))
+        // a self-trade would be prevented, not filled
+        .filter(|maker| self_trade_mode(order, maker).is_none())
+        .fold(0, |total: Amount, maker| {
+            total.saturating_add(maker.open_quantity())
+        })
 }
 
 fn remove_from_orderbook<T: Config>(
@@ -128,6 +1196,8 @@ fn match_buy_order<T: Config>(
     buy_order: &mut Order<T>,
     ask_book: &mut BTreeMap<Amount, Vec<OrderId>>,
     orders_map: &mut BTreeMap<OrderId, Order<T>>,
+    prevented: &mut Vec<SelfTrade>,
+    allocation: &dyn Allocation,
 ) -> Result<Vec<Trade<T>>, DispatchError> {
     let mut trades = Vec::new();
     let mut prices_to_remove = Vec::new();
@@ -138,7 +1208,7 @@ fn match_buy_order<T: Config>(
     for price in ask_prices.iter() {
         // Check if we can match at this price
         match buy_order.order_type {
-            OrderType::Market => {
+            OrderType::Market { .. } | OrderType::Spend { .. } => {
                 // Market orders match at any price
             }
             OrderType::Limit => {
@@ -149,44 +1219,22 @@ fn match_buy_order<T: Config>(
         }
 
         // Check if buy order still needs filling
-        if remaining_quantity(buy_order) == 0 {
+        if remaining_quantity(buy_order) == 0 || buy_order.status == OrderStatus::Cancelled {
             break;
         }
 
         // Get sell orders at this price level
         if let Some(sell_order_ids) = ask_book.get_mut(price) {
-            let mut indices_to_remove = Vec::new();
-
-            // Match with each sell order (FIFO - price-time priority)
-            for (idx, sell_order_id) in sell_order_ids.iter().enumerate() {
-                // Get the sell order
-                let mut sell_order = match orders_map.get(sell_order_id) {
-                    Some(o) => o.clone(),
-                    None => continue,
-                };
-
-                // Execute trade at this price level (maker's price)
-                let trade = execute_trade(buy_order, &mut sell_order, *price)?;
-                trades.push(trade);
-
-                // Update sell order in orders_map
-                orders_map.insert(*sell_order_id, sell_order.clone());
-
-                // If sell order is filled, mark for removal
-                if sell_order.status == OrderStatus::Filled {
-                    indices_to_remove.push(idx);
-                }
-
-                // If buy order is filled, stop matching
-                if buy_order.status == OrderStatus::Filled {
-                    break;
-                }
-            }
-
-            // Remove filled orders (reverse to maintain indices)
-            for idx in indices_to_remove.iter().rev() {
-                sell_order_ids.remove(*idx);
-            }
+            // Execute trades at this price level (maker's price)
+            trades.extend(fill_level(
+                buy_order,
+                *price,
+                sell_order_ids,
+                Amount::MAX,
+                orders_map,
+                prevented,
+                allocation,
+            )?);
 
             // If no orders left at this price, mark for removal
             if sell_order_ids.is_empty() {
@@ -207,6 +1255,8 @@ fn match_sell_order<T: Config>(
     sell_order: &mut Order<T>,
     bid_book: &mut BTreeMap<Amount, Vec<OrderId>>,
     orders_map: &mut BTreeMap<OrderId, Order<T>>,
+    prevented: &mut Vec<SelfTrade>,
+    allocation: &dyn Allocation,
 ) -> Result<Vec<Trade<T>>, DispatchError> {
     let mut trades = Vec::new();
     let mut prices_to_remove = Vec::new();
@@ -218,7 +1268,7 @@ fn match_sell_order<T: Config>(
     for price in bid_prices.iter() {
         // Check if we can match at this price
         match sell_order.order_type {
-            OrderType::Market => {
+            OrderType::Market { .. } | OrderType::Spend { .. } => {
                 // Market orders match at any price
             }
             OrderType::Limit => {
@@ -229,44 +1279,22 @@ fn match_sell_order<T: Config>(
         }
 
         // Check if sell order still needs filling
-        if remaining_quantity(sell_order) == 0 {
+        if remaining_quantity(sell_order) == 0 || sell_order.status == OrderStatus::Cancelled {
             break;
         }
 
         // Get buy orders at this price level
         if let Some(buy_order_ids) = bid_book.get_mut(price) {
-            let mut indices_to_remove = Vec::new();
-
-            // Match with each buy order (FIFO - price-time priority)
-            for (idx, buy_order_id) in buy_order_ids.iter().enumerate() {
-                // Get the buy order
-                let mut buy_order = match orders_map.get(buy_order_id) {
-                    Some(o) => o.clone(),
-                    None => continue,
-                };
-
-                // Execute trade at this price level (maker's price)
-                let trade = execute_trade(&mut buy_order, sell_order, *price)?;
-                trades.push(trade);
-
-                // Update buy order in orders_map
-                orders_map.insert(*buy_order_id, buy_order.clone());
-
-                // If buy order is filled, mark for removal
-                if buy_order.status == OrderStatus::Filled {
-                    indices_to_remove.push(idx);
-                }
-
-                // If sell order is filled, stop matching
-                if sell_order.status == OrderStatus::Filled {
-                    break;
-                }
-            }
-
-            // Remove filled orders (reverse to maintain indices)
-            for idx in indices_to_remove.iter().rev() {
-                buy_order_ids.remove(*idx);
-            }
+            // Execute trades at this price level (maker's price)
+            trades.extend(fill_level(
+                sell_order,
+                *price,
+                buy_order_ids,
+                Amount::MAX,
+                orders_map,
+                prevented,
+                allocation,
+            )?);
 
             // If no orders left at this price, mark for removal
             if buy_order_ids.is_empty() {
@@ -283,18 +1311,359 @@ fn match_sell_order<T: Config>(
     Ok(trades)
 }
 
+// Fills the taker against the makers queued at one price, at most `max_quantity` of it.
+// Makers of the same trader go through self-trade prevention in queue order, the others
+// share the fill as `allocation` says. Filled and cancelled makers leave the queue. Icebergs
+// that used up their slice show the next one from the back of the queue, and the level is
+// shared again for what the taker has left.
+fn fill_level<T: Config>(
+    taker: &mut Order<T>,
+    price: Amount,
+    maker_ids: &mut Vec<OrderId>,
+    max_quantity: Amount,
+    orders_map: &mut BTreeMap<OrderId, Order<T>>,
+    prevented: &mut Vec<SelfTrade>,
+    allocation: &dyn Allocation,
+) -> Result<Vec<Trade<T>>, DispatchError> {
+    let mut trades = Vec::new();
+    let mut left = max_quantity;
+
+    loop {
+        let (round, refilled) = fill_round(
+            taker, price, maker_ids, left, orders_map, prevented, allocation,
+        )?;
+        let filled = round.iter().fold(0, |total: Amount, trade| {
+            total.saturating_add(trade.quantity)
+        });
+        left = left.saturating_sub(filled);
+        trades.extend(round);
+
+        // every refill came from a trade, so the taker shrinks each time round
+        if !refilled
+            || left == 0
+            || remaining_quantity(taker) == 0
+            || taker.status == OrderStatus::Cancelled
+        {
+            return Ok(trades);
+        }
+    }
+}
+
+// One pass over the queue at a price, returns the trades and whether an iceberg showed a new
+// slice
+fn fill_round<T: Config>(
+    taker: &mut Order<T>,
+    price: Amount,
+    maker_ids: &mut Vec<OrderId>,
+    max_quantity: Amount,
+    orders_map: &mut BTreeMap<OrderId, Order<T>>,
+    prevented: &mut Vec<SelfTrade>,
+    allocation: &dyn Allocation,
+) -> Result<(Vec<Trade<T>>, bool), DispatchError> {
+    let mut trades = Vec::new();
+
+    let makers: Vec<&Order<T>> = maker_ids
+        .iter()
+        .filter_map(|id| orders_map.get(id))
+        .filter(|maker| self_trade_mode(taker, maker).is_none())
+        .collect();
+    let open: Vec<Amount> = makers.iter().map(|maker| maker.shown_quantity()).collect();
+    let quantity = remaining_quantity(taker).min(max_quantity);
+    let shares: BTreeMap<OrderId, Amount> = makers
+        .iter()
+        .map(|maker| maker.order_id)
+        .zip(allocation.allocate(quantity, &open))
+        .collect();
+
+    let mut done = Vec::new();
+    let mut refilled = Vec::new();
+    for maker_id in maker_ids.iter() {
+        if remaining_quantity(taker) == 0 || taker.status == OrderStatus::Cancelled {
+            break;
+        }
+
+        let Some(mut maker) = orders_map.get(maker_id).cloned() else {
+            done.push(*maker_id);
+            continue;
+        };
+
+        // the same trader on both sides, the mode decides what is cancelled
+        if let Some(mode) = self_trade_mode(taker, &maker) {
+            prevented.push(prevent_self_trade(taker, &mut maker, mode));
+            if maker.status == OrderStatus::Cancelled {
+                done.push(*maker_id);
+            }
+            orders_map.insert(*maker_id, maker);
+            continue;
+        }
+
+        let share = shares.get(maker_id).copied().unwrap_or_default();
+        if share == 0 {
+            continue;
+        }
+
+        let trade = match taker.side {
+            OrderSide::Buy => execute_trade(taker, &mut maker, price, share, OrderSide::Buy)?,
+            OrderSide::Sell => execute_trade(&mut maker, taker, price, share, OrderSide::Sell)?,
+        };
+        if maker.status.is_finished() {
+            done.push(*maker_id);
+        } else if use_slice(&mut maker, trade.quantity) {
+            refilled.push(*maker_id);
+        }
+        orders_map.insert(*maker_id, maker);
+        trades.push(trade);
+    }
+
+    maker_ids.retain(|id| !done.contains(id) && !refilled.contains(id));
+    let refilled_any = !refilled.is_empty();
+    maker_ids.extend(refilled);
+    Ok((trades, refilled_any))
+}
+
+// Takes a fill off an iceberg's slice. Returns whether the slice ran out and the next one,
+// at most the display quantity, is shown.
+fn use_slice<T: Config>(maker: &mut Order<T>, quantity: Amount) -> bool {
+    let remaining = remaining_quantity(maker);
+    let Some(iceberg) = maker.iceberg.as_mut() else {
+        return false;
+    };
+
+    iceberg.visible_quantity = iceberg.visible_quantity.saturating_sub(quantity);
+    if iceberg.visible_quantity > 0 || remaining == 0 {
+        return false;
+    }
+    iceberg.visible_quantity = iceberg.display_quantity.min(remaining);
+    true
+}
+
+// Opposite side levels across the cache and the loaded persistent book, best price first,
+// with the open quantity at each price the taker could trade with
+fn merged_levels<T: Config>(
+    taker: &Order<T>,
+    pending_book: &BTreeMap<Amount, Vec<OrderId>>,
+    persistent_book: &BTreeMap<Amount, Vec<OrderId>>,
+    orders_map: &BTreeMap<OrderId, Order<T>>,
+) -> Vec<(Amount, Amount)> {
+    let mut levels: BTreeMap<Amount, Amount> = BTreeMap::new();
+    for (price, ids) in pending_book.iter().chain(persistent_book.iter()) {
+        let open = ids
+            .iter()
+            .filter_map(|id| orders_map.get(id))
+            .filter(|maker| self_trade_mode(taker, maker).is_none())
+            .fold(0, |total: Amount, maker| {
+                total.saturating_add(maker.open_quantity())
+            });
+        if open > 0 {
+            let level = levels.entry(*price).or_insert(0);
+            *level = level.saturating_add(open);
+        }
+    }
+
+    let mut levels: Vec<(Amount, Amount)> = levels.into_iter().collect();
+    // a sell takes the highest bids first
+    if taker.side == OrderSide::Sell {
+        levels.reverse();
+    }
+    levels
+}
+
+// How much a market order would fill and the quote it would pay or get for it,
+// a buy stops at the last whole lot its quote limit pays for
+fn market_reach<T: Config>(
+    order: &Order<T>,
+    quote_limit: Amount,
+    lot: Amount,
+    pending_book: &BTreeMap<Amount, Vec<OrderId>>,
+    persistent_book: &BTreeMap<Amount, Vec<OrderId>>,
+    orders_map: &BTreeMap<OrderId, Order<T>>,
+) -> (Amount, Amount) {
+    let mut left = order.open_quantity();
+    let (mut quantity, mut quote): (Amount, Amount) = (0, 0);
+
+    for (price, open) in merged_levels(order, pending_book, persistent_book, orders_map) {
+        let mut take = open.min(left);
+        if order.side == OrderSide::Buy {
+            take = take.min(affordable(quote_limit.saturating_sub(quote), price, lot));
+        }
+        if take == 0 {
+            break;
+        }
+        quantity = quantity.saturating_add(take);
+        quote = quote.saturating_add(price.saturating_mul(take));
+        left = left.saturating_sub(take);
+    }
+
+    (quantity, quote)
+}
+
+// Fills a market order against the cache and the loaded persistent book, best price first.
+// Within a price the persistent orders came first, so they are taken first.
+#[allow(clippy::too_many_arguments)]
+fn match_market_order<T: Config>(
+    order: &mut Order<T>,
+    quote_limit: Amount,
+    lot: Amount,
+    pending_book: &mut BTreeMap<Amount, Vec<OrderId>>,
+    persistent_book: &mut BTreeMap<Amount, Vec<OrderId>>,
+    orders_map: &mut BTreeMap<OrderId, Order<T>>,
+    prevented: &mut Vec<SelfTrade>,
+    allocation: &dyn Allocation,
+) -> Result<Vec<Trade<T>>, DispatchError> {
+    let mut trades = Vec::new();
+    let mut spent: Amount = 0;
+
+    while remaining_quantity(order) > 0 {
+        let best = |book: &BTreeMap<Amount, Vec<OrderId>>| match order.side {
+            OrderSide::Buy => book
+                .iter()
+                .find(|(_, ids)| !ids.is_empty())
+                .map(|(p, _)| *p),
+            OrderSide::Sell => book
+                .iter()
+                .rev()
+                .find(|(_, ids)| !ids.is_empty())
+                .map(|(p, _)| *p),
+        };
+        let (price, book) = match (best(persistent_book), best(pending_book), order.side) {
+            (Some(a), Some(b), OrderSide::Buy) if b < a => (b, &mut *pending_book),
+            (Some(a), Some(b), OrderSide::Sell) if b > a => (b, &mut *pending_book),
+            (Some(a), _, _) => (a, &mut *persistent_book),
+            (None, Some(b), _) => (b, &mut *pending_book),
+            (None, None, _) => break,
+        };
+
+        let max_quantity = match order.side {
+            OrderSide::Buy => affordable(quote_limit.saturating_sub(spent), price, lot),
+            OrderSide::Sell => Amount::MAX,
+        };
+        if max_quantity == 0 {
+            break;
+        }
+
+        let Some(ids) = book.get_mut(&price) else {
+            break;
+        };
+        let level_trades = fill_level(
+            order,
+            price,
+            ids,
+            max_quantity,
+            orders_map,
+            prevented,
+            allocation,
+        )?;
+        let level_left = !ids.is_empty();
+        if !level_left {
+            book.remove(&price);
+        }
+
+        // nothing traded and nothing left the level, it has nothing more to give
+        if level_trades.is_empty() && level_left {
+            break;
+        }
+        for trade in level_trades {
+            spent = spent.saturating_add(price.saturating_mul(trade.quantity));
+            trades.push(trade);
+        }
+        if order.status == OrderStatus::Cancelled {
+            break;
+        }
+    }
+
+    Ok(trades)
+}
+
+// Market orders never rest. A spend that bought anything is filled with what its quote paid
+// for, any other market order has the rest cancelled.
+fn close_market_order<T: Config>(order: &mut Order<T>) {
+    if matches!(order.order_type, OrderType::Spend { .. }) && order.filled_quantity > 0 {
+        order.quantity = order.filled_quantity;
+        order.status = OrderStatus::Filled;
+    } else if order.status != OrderStatus::Filled {
+        order.status = OrderStatus::Cancelled;
+    }
+}
+
 fn remaining_quantity<T: Config>(order: &mut Order<T>) -> Amount {
-    order.quantity.saturating_sub(order.filled_quantity)
+    order.open_quantity()
+}
+
+// Only orders of the same trader can self-trade. The newest order, the one placed last, has
+// its own mode win over the other one's.
+fn self_trade_mode<T: Config>(taker: &Order<T>, maker: &Order<T>) -> Option<SelfTradePrevention> {
+    if taker.trader != maker.trader {
+        return None;
+    }
+    if taker.order_id > maker.order_id {
+        taker.self_trade.or(maker.self_trade)
+    } else {
+        maker.self_trade.or(taker.self_trade)
+    }
 }
 
+// Applies `mode` in place of the trade. In the cache the taker can be the older order.
+fn prevent_self_trade<T: Config>(
+    taker: &mut Order<T>,
+    maker: &mut Order<T>,
+    mode: SelfTradePrevention,
+) -> SelfTrade {
+    let (taker_id, maker_id) = (taker.order_id, maker.order_id);
+    let (newest, oldest) = if taker_id > maker_id {
+        (taker, maker)
+    } else {
+        (maker, taker)
+    };
+
+    let mut quantity = 0;
+    match mode {
+        SelfTradePrevention::CancelNewest => newest.status = OrderStatus::Cancelled,
+        SelfTradePrevention::CancelOldest => oldest.status = OrderStatus::Cancelled,
+        SelfTradePrevention::CancelBoth => {
+            newest.status = OrderStatus::Cancelled;
+            oldest.status = OrderStatus::Cancelled;
+        }
+        // both shrink by the smaller remainder, whichever has nothing left is cancelled
+        SelfTradePrevention::DecrementAndCancel => {
+            quantity = remaining_quantity(newest).min(remaining_quantity(oldest));
+            for order in [newest, oldest] {
+                order.quantity = order.quantity.saturating_sub(quantity);
+                // what was taken off is unlocked, a reduce-only sell can sell that much less
+                if let Some(locked) = order.reduce_only.as_mut() {
+                    *locked = locked.saturating_sub(quantity);
+                }
+                if remaining_quantity(order) == 0 {
+                    order.status = OrderStatus::Cancelled;
+                }
+            }
+        }
+    }
+
+    SelfTrade {
+        taker: taker_id,
+        maker: maker_id,
+        mode,
+        quantity,
+    }
+}
+
+// `max_quantity` caps the trade below what both orders have left. A cap that comes from a
+// quote budget is already rounded down to whole lots by `affordable`, so every trade stays on
+// the lot size.
 fn execute_trade<T: Config>(
     buy_order: &mut Order<T>,
     sell_order: &mut Order<T>,
     match_price: Amount,
+    max_quantity: Amount,
+    taker_side: OrderSide,
 ) -> Result<Trade<T>, DispatchError> {
     let buy_remaining = remaining_quantity(buy_order);
     let sell_remaining = remaining_quantity(sell_order);
-    let trade_qty = buy_remaining.min(sell_remaining);
+    let trade_qty = buy_remaining.min(sell_remaining).min(max_quantity);
+    // settlement moves the quote value of the trade, it has to fit
+    match_price
+        .checked_mul(trade_qty)
+        .ok_or("ArithmeticOverFlow")?;
 
     // update buy order
     buy_order.filled_quantity = buy_order
@@ -302,11 +1671,7 @@ fn execute_trade<T: Config>(
         .checked_add(trade_qty)
         .ok_or("ArithmeticOverFlow")?;
 
-    if buy_order.filled_quantity == buy_order.quantity {
-        buy_order.status = OrderStatus::Filled;
-    } else {
-        buy_order.status = OrderStatus::PartiallyFilled;
-    }
+    buy_order.status = fill_status(buy_order);
 
     //update sell order
     sell_order.filled_quantity = sell_order
@@ -314,39 +1679,76 @@ fn execute_trade<T: Config>(
         .checked_add(trade_qty)
         .ok_or("ArithmeticOverFlow")?;
 
-    if sell_order.filled_quantity == sell_order.quantity {
-        sell_order.status = OrderStatus::Filled;
-    } else {
-        sell_order.status = OrderStatus::PartiallyFilled;
-    }
+    sell_order.status = fill_status(sell_order);
 
     //Everything updated, now to emit the trades
     Ok(Trade {
         trade_id: 0, // placeholder
+        market_id: buy_order.market_id,
         buyer: buy_order.trader.clone(),
         seller: sell_order.trader.clone(),
         buy_order_id: buy_order.order_id,
         sell_order_id: sell_order.order_id,
         price: match_price,
         quantity: trade_qty,
+        taker_side,
+        // fees are worked out at settlement
+        buyer_fee: 0,
+        seller_fee: 0,
+        maker_rebate: 0,
     })
 }
 
-// now for cancellation
+// A reduce-only sell that sold all the base it locked has no position left, the rest of it
+// is cancelled
+fn fill_status<T: Config>(order: &Order<T>) -> OrderStatus {
+    if order.filled_quantity == order.quantity {
+        OrderStatus::Filled
+    } else if order.open_quantity() == 0 {
+        OrderStatus::Cancelled
+    } else {
+        OrderStatus::PartiallyFilled
+    }
+}
+
+// whether the last trade price reached a stop order's trigger
+pub fn stop_triggered(side: OrderSide, trigger: &StopTrigger, last_price: Amount) -> bool {
+    match (side, trigger.kind) {
+        (OrderSide::Sell, StopKind::StopLoss) | (OrderSide::Buy, StopKind::TakeProfit) => {
+            last_price <= trigger.trigger_price
+        }
+        (OrderSide::Buy, StopKind::StopLoss) | (OrderSide::Sell, StopKind::TakeProfit) => {
+            last_price >= trigger.trigger_price
+        }
+    }
+}
+
+// now for cancellation, returns the orders that actually got cancelled
 pub fn process_cancellations<T: Config>(
     order_ids: Vec<OrderId>,
+    pending_bids: &mut BTreeMap<Amount, Vec<OrderId>>,
+    pending_asks: &mut BTreeMap<Amount, Vec<OrderId>>,
     bid_book: &mut BTreeMap<Amount, Vec<OrderId>>,
     ask_book: &mut BTreeMap<Amount, Vec<OrderId>>,
     orders_map: &mut BTreeMap<OrderId, Order<T>>,
-) -> Result<(), DispatchError> {
+) -> Vec<OrderId> {
+    let mut cancelled = Vec::new();
+
     for order_id in order_ids {
         if let Some(mut order) = orders_map.get(&order_id).cloned() {
+            // already filled or cancelled (e.g. requested twice), nothing to unlock
+            if order.status != OrderStatus::Open && order.status != OrderStatus::PartiallyFilled {
+                continue;
+            }
             order.status = OrderStatus::Cancelled;
 
+            // it could still be waiting in the cache or already resting in the book
+            remove_from_orderbook(order_id, &order, pending_bids, pending_asks);
             remove_from_orderbook(order_id, &order, bid_book, ask_book);
 
             orders_map.insert(order_id, order.clone());
+            cancelled.push(order_id);
         }
     }
-    Ok(())
+    cancelled
 }
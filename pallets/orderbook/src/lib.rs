@@ -1,6 +1,8 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 #![allow(ambiguous_glob_reexports)]
 mod engine;
+pub mod migrations;
+pub mod runtime_api;
 pub mod types;
 pub use pallet::*;
 //pub use crate::types;
@@ -17,29 +19,48 @@ mod benchmarking;
 pub mod weights;
 pub use weights::WeightInfo;
 
+// extrinsics take every order option as its own argument
+#[allow(clippy::too_many_arguments)]
 #[frame_support::pallet]
 pub mod pallet {
     //use std::intrinsics::saturating_add;
 
-    use core::u32;
-
     //use super::*;
     use crate::{
         engine::*,
-        types::{Amount, Order, OrderId, OrderSide, OrderStatus, OrderType, Trade, TradeId},
+        types::{
+            Amount, AssetId, ClearingMode, EffectiveFees, FeeRates, FeeTier, Iceberg, MakerFee,
+            Market, MarketId, MarketPair, MarketStatus, MatchingMode, Order, OrderId, OrderRequest,
+            OrderSide, OrderStatus, OrderType, OrderingPolicy, PostOnly, ReduceOnly, RejectReason,
+            SelfTradePrevention, StopEntry, StopKind, StopTrigger, TimeInForce, Trade, TradeId,
+            TradingRules,
+        },
         weights::WeightInfo,
     };
 
-    use assets::{ETH, USDT};
-    use frame_support::{Blake2_128Concat, pallet_prelude::*};
+    use frame_support::{
+        Blake2_128Concat,
+        pallet_prelude::*,
+        storage::{TransactionOutcome, with_transaction},
+        traits::Randomness,
+    };
     use frame_system::pallet_prelude::{OriginFor, *};
     use pallet_assets as assets;
     use sp_core::Get;
-    use sp_std::{collections::btree_map::BTreeMap, vec::Vec};
+    use sp_runtime::{Permill, SaturatedConversion, traits::Hash};
+    use sp_std::{
+        collections::{btree_map::BTreeMap, btree_set::BTreeSet},
+        vec::Vec,
+    };
     //use assets::*;
     //use sp_runtime::legacy::byte_sized_error::DispatchError;
 
+    /// v1 drops finished orders from `Orders` and `UserOrders`, v2 gives orders a self-trade
+    /// prevention mode, v3 lets them hide size as icebergs, v4 makes sells reduce-only
+    const STORAGE_VERSION: StorageVersion = StorageVersion::new(4);
+
     #[pallet::pallet]
+    #[pallet::storage_version(STORAGE_VERSION)]
     pub struct Pallet<T>(_);
 
     #[pallet::config]
@@ -58,6 +79,65 @@ pub mod pallet {
         #[pallet::constant]
         type MaxUserOrders: Get<u32>;
 
+        // finished orders kept per trader, the oldest is dropped first, 0 keeps no history
+        #[pallet::constant]
+        type MaxOrderHistory: Get<u32>;
+
+        // distinct price levels per side of a market, bounds the sorted price index
+        #[pallet::constant]
+        type MaxPriceLevels: Get<u32>;
+
+        // untriggered stop orders per market, the whole trigger book is checked after trades
+        #[pallet::constant]
+        type MaxStopOrders: Get<u32>;
+
+        // orders that can expire in the same block
+        #[pallet::constant]
+        type MaxExpiringOrders: Get<u32>;
+
+        // cancel-all requests that did not fit in one block and wait for the next
+        #[pallet::constant]
+        type MaxCancelAllRequests: Get<u32>;
+
+        // most recent trades kept in `Trades`, older ones are pruned in on_idle
+        #[pallet::constant]
+        type TradeRetention: Get<u64>;
+
+        // account trading fees are paid to
+        #[pallet::constant]
+        type FeeAccount: Get<Self::AccountId>;
+
+        // fee rates of markets governance has not set its own rates for
+        #[pallet::constant]
+        type DefaultMakerFee: Get<Permill>;
+
+        #[pallet::constant]
+        type DefaultTakerFee: Get<Permill>;
+
+        // trading volume is kept in buckets of this many blocks
+        #[pallet::constant]
+        type VolumeBucketBlocks: Get<u32>;
+
+        // buckets the rolling volume that picks a fee tier is summed over
+        #[pallet::constant]
+        type VolumeBuckets: Get<u32>;
+
+        #[pallet::constant]
+        type MaxFeeTiers: Get<u32>;
+
+        // order size rules of markets governance has not set its own rules for
+        #[pallet::constant]
+        type DefaultTickSize: Get<Amount>;
+
+        #[pallet::constant]
+        type DefaultLotSize: Get<Amount>;
+
+        #[pallet::constant]
+        type DefaultMinNotional: Get<Amount>;
+
+        // what the `Randomness` ordering policy seeds its shuffle with
+        type Randomness: Randomness<Self::Hash, BlockNumberFor<Self>>;
+
         type WeightInfo: WeightInfo;
     }
 
@@ -65,6 +145,17 @@ pub mod pallet {
     // Persisten storage
     // ===========================
 
+    #[pallet::storage]
+    pub type Markets<T: Config> = StorageMap<_, Blake2_128Concat, MarketId, Market, OptionQuery>;
+
+    // reverse lookup so the same pair can't be listed twice
+    #[pallet::storage]
+    pub type MarketsByPair<T: Config> =
+        StorageMap<_, Blake2_128Concat, MarketPair, MarketId, OptionQuery>;
+
+    #[pallet::storage]
+    pub type NextMarketId<T: Config> = StorageValue<_, MarketId, ValueQuery>;
+
     // not sure if this needed yet, so just keeping it
     #[pallet::storage]
     pub type Orders<T: Config> = StorageMap<_, Blake2_128Concat, OrderId, Order<T>, OptionQuery>;
@@ -73,38 +164,160 @@ pub mod pallet {
     pub type Trades<T: Config> = StorageMap<_, Blake2_128Concat, TradeId, Trade<T>, OptionQuery>;
 
     #[pallet::storage]
-    pub type Bids<T: Config> =
-        StorageMap<_, Blake2_128Concat, Amount, BoundedVec<OrderId, T::MaxOrders>, ValueQuery>;
+    pub type Bids<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        MarketId,
+        Blake2_128Concat,
+        Amount,
+        BoundedVec<OrderId, T::MaxOrders>,
+        ValueQuery,
+    >;
+
+    #[pallet::storage]
+    pub type Asks<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        MarketId,
+        Blake2_128Concat,
+        Amount,
+        BoundedVec<OrderId, T::MaxOrders>,
+        ValueQuery,
+    >;
+
+    // Sorted price index per side (ascending), so matching only reads the levels it needs.
+    // It also holds the prices of pending orders until they have been matched or rested.
+    #[pallet::storage]
+    pub type BidPrices<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        MarketId,
+        BoundedVec<Amount, T::MaxPriceLevels>,
+        ValueQuery,
+    >;
+
+    #[pallet::storage]
+    pub type AskPrices<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        MarketId,
+        BoundedVec<Amount, T::MaxPriceLevels>,
+        ValueQuery,
+    >;
+
+    // best prices after the last matching round, lets us skip the other side when nothing crosses
+    #[pallet::storage]
+    pub type BestBid<T: Config> = StorageMap<_, Blake2_128Concat, MarketId, Amount, OptionQuery>;
+
+    #[pallet::storage]
+    pub type BestAsk<T: Config> = StorageMap<_, Blake2_128Concat, MarketId, Amount, OptionQuery>;
+
+    // Stop orders waiting for the last trade price to reach their trigger
+    #[pallet::storage]
+    pub type StopOrders<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        MarketId,
+        BoundedVec<StopEntry, T::MaxStopOrders>,
+        ValueQuery,
+    >;
+
+    // price of the last trade in each market, what stop orders are checked against
+    #[pallet::storage]
+    pub type LastTradePrice<T: Config> =
+        StorageMap<_, Blake2_128Concat, MarketId, Amount, OptionQuery>;
+
+    // traders whose cancel-all did not fit under MaxCancellationOrders, continued in on_initialize
+    #[pallet::storage]
+    pub type CancelAllQueue<T: Config> = StorageValue<
+        _,
+        BoundedVec<(T::AccountId, Option<OrderSide>), T::MaxCancelAllRequests>,
+        ValueQuery,
+    >;
+
+    // fee rates set by governance, markets without an entry use the Config defaults
+    #[pallet::storage]
+    pub type MarketFees<T: Config> =
+        StorageMap<_, Blake2_128Concat, MarketId, FeeRates, OptionQuery>;
+
+    // tick, lot and min notional set by governance, markets without an entry use the Config defaults
+    #[pallet::storage]
+    pub type MarketRules<T: Config> =
+        StorageMap<_, Blake2_128Concat, MarketId, TradingRules, OptionQuery>;
+
+    // volume tiers sorted by `min_volume`, accounts below the first tier pay the market rates
+    #[pallet::storage]
+    pub type FeeTiers<T: Config> = StorageValue<_, BoundedVec<FeeTier, T::MaxFeeTiers>, ValueQuery>;
+
+    // how each price level is shared among its makers, FIFO unless governance set otherwise
+    #[pallet::storage]
+    pub type MarketMatchingMode<T: Config> =
+        StorageMap<_, Blake2_128Concat, MarketId, MatchingMode, ValueQuery>;
+
+    // continuous matching or one batch auction per block, continuous unless governance set otherwise
+    #[pallet::storage]
+    pub type MarketClearingMode<T: Config> =
+        StorageMap<_, Blake2_128Concat, MarketId, ClearingMode, ValueQuery>;
 
+    // the order each block's new orders are matched in, by id unless governance set otherwise
     #[pallet::storage]
-    pub type Asks<T: Config> =
-        StorageMap<_, Blake2_128Concat, Amount, BoundedVec<OrderId, T::MaxOrders>, ValueQuery>;
+    pub type MarketOrderingPolicy<T: Config> =
+        StorageMap<_, Blake2_128Concat, MarketId, OrderingPolicy, ValueQuery>;
+
+    // self-trade prevention of orders placed without a mode of their own
+    #[pallet::storage]
+    pub type AccountSelfTradePrevention<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, SelfTradePrevention, OptionQuery>;
+
+    // quote volume each account traded, as (bucket, volume) with the oldest bucket first
+    #[pallet::storage]
+    pub type TradingVolume<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        T::AccountId,
+        BoundedVec<(u32, Amount), T::VolumeBuckets>,
+        ValueQuery,
+    >;
+
+    // orders to expire, keyed by the block number they expire at
+    #[pallet::storage]
+    pub type ExpiringOrders<T: Config> =
+        StorageMap<_, Blake2_128Concat, u32, BoundedVec<OrderId, T::MaxExpiringOrders>, ValueQuery>;
 
     // ===========================
     // Cache
     // ===========================
 
     #[pallet::storage]
-    pub type PendingAsks<T: Config> = StorageMap<
+    pub type PendingAsks<T: Config> = StorageDoubleMap<
         _,
         Blake2_128Concat,
+        MarketId,
+        Blake2_128Concat,
         Amount,
         BoundedVec<OrderId, T::MaxPendingOrders>,
         ValueQuery,
     >;
 
     #[pallet::storage]
-    pub type PendingBids<T: Config> = StorageMap<
+    pub type PendingBids<T: Config> = StorageDoubleMap<
         _,
         Blake2_128Concat,
+        MarketId,
+        Blake2_128Concat,
         Amount,
         BoundedVec<OrderId, T::MaxPendingOrders>,
         ValueQuery,
     >;
 
     #[pallet::storage]
-    pub type PendingCancellations<T: Config> =
-        StorageValue<_, BoundedVec<OrderId, T::MaxCancellationOrders>, ValueQuery>;
+    pub type PendingCancellations<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        MarketId,
+        BoundedVec<OrderId, T::MaxCancellationOrders>,
+        ValueQuery,
+    >;
 
     //Keeping this so that users can easily access their orders
     #[pallet::storage]
@@ -116,16 +329,93 @@ pub mod pallet {
         ValueQuery,
     >;
 
+    // last filled, cancelled or expired orders of a trader, oldest first
+    #[pallet::storage]
+    pub type OrderHistory<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        T::AccountId,
+        BoundedVec<Order<T>, T::MaxOrderHistory>,
+        ValueQuery,
+    >;
+
     #[pallet::storage]
     pub type NextOrderId<T: Config> = StorageValue<_, OrderId, ValueQuery>;
 
     #[pallet::storage]
     pub type NextTradeId<T: Config> = StorageValue<_, TradeId, ValueQuery>;
 
+    // trades below this id are pruned from `Trades`
+    #[pallet::storage]
+    pub type PrunedTradesUpTo<T: Config> = StorageValue<_, TradeId, ValueQuery>;
+
+    // hash chain over every pruned trade, in trade id order
+    #[pallet::storage]
+    pub type TradeArchiveCommitment<T: Config> = StorageValue<_, T::Hash, ValueQuery>;
+    // ===========================
+    // Genesis
+    // ===========================
+
+    #[pallet::genesis_config]
+    #[derive(frame_support::DefaultNoBound)]
+    pub struct GenesisConfig<T: Config> {
+        /// (base_asset, quote_asset) pairs to list at genesis, ids are handed out in order
+        pub markets: Vec<(AssetId, AssetId)>,
+        #[serde(skip)]
+        pub _config: core::marker::PhantomData<T>,
+    }
+
+    #[pallet::genesis_build]
+    impl<T: Config> BuildGenesisConfig for GenesisConfig<T> {
+        fn build(&self) {
+            for (base_asset, quote_asset) in self.markets.iter() {
+                let pair = MarketPair {
+                    base_asset: *base_asset,
+                    quote_asset: *quote_asset,
+                };
+                Pallet::<T>::do_create_market(pair).expect("genesis markets must be valid pairs");
+            }
+        }
+    }
+
     #[pallet::event]
     #[pallet::generate_deposit(pub(super) fn deposit_event)]
     pub enum Event<T: Config> {
+        MarketCreated {
+            market_id: MarketId,
+            base_asset: AssetId,
+            quote_asset: AssetId,
+        },
+        MarketStatusChanged {
+            market_id: MarketId,
+            status: MarketStatus,
+        },
+        MarketFeesSet {
+            market_id: MarketId,
+            maker_fee: Permill,
+            taker_fee: Permill,
+        },
+        FeeTiersSet {
+            tiers: u32,
+        },
+        MarketRulesSet {
+            market_id: MarketId,
+            rules: TradingRules,
+        },
+        MatchingModeSet {
+            market_id: MarketId,
+            mode: MatchingMode,
+        },
+        ClearingModeSet {
+            market_id: MarketId,
+            mode: ClearingMode,
+        },
+        OrderingPolicySet {
+            market_id: MarketId,
+            policy: OrderingPolicy,
+        },
         OrderPlaced {
+            market_id: MarketId,
             order_id: OrderId,
             side: OrderSide,
             asset_id: u32,
@@ -133,6 +423,7 @@ pub mod pallet {
             quantity: Amount,
         },
         TradeExecuted {
+            market_id: MarketId,
             trade_id: TradeId,
             buy_order_id: OrderId,
             sell_order_id: OrderId,
@@ -140,27 +431,162 @@ pub mod pallet {
             seller: T::AccountId,
             price: Amount,
             quantity: Amount,
+            taker_side: OrderSide,
+            // paid by the buyer in the base asset
+            buyer_fee: Amount,
+            // paid by the seller in the quote asset
+            seller_fee: Amount,
+            // paid to the maker out of the fee account in the quote asset
+            maker_rebate: Amount,
         },
         OrderCancelled {
+            market_id: MarketId,
             order_id: OrderId,
             trader: T::AccountId,
         },
         OrderFilled {
+            market_id: MarketId,
             order_id: OrderId,
             trader: T::AccountId,
         },
         OrderPartiallyFilled {
+            market_id: MarketId,
             order_id: OrderId,
             trader: T::AccountId,
             filled_quantity: Amount,
             remaining_quantity: Amount,
         },
+        // IOC or market order that did not fill completely, or reduce-only order that sold
+        // all the base it locked, the rest was cancelled and unlocked
+        OrderRemainderCancelled {
+            market_id: MarketId,
+            order_id: OrderId,
+            trader: T::AccountId,
+            filled_quantity: Amount,
+            cancelled_quantity: Amount,
+        },
+        // FOK order that could not be filled completely or market order that found nothing
+        // within its quote limit, nothing was traded
+        OrderKilled {
+            market_id: MarketId,
+            order_id: OrderId,
+            trader: T::AccountId,
+            quantity: Amount,
+        },
+        // order dropped by the engine before it could rest, funds are unlocked
+        OrderRejected {
+            market_id: MarketId,
+            order_id: OrderId,
+            trader: T::AccountId,
+            reason: RejectReason,
+        },
+        StopOrderPlaced {
+            market_id: MarketId,
+            order_id: OrderId,
+            trader: T::AccountId,
+            side: OrderSide,
+            kind: StopKind,
+            trigger_price: Amount,
+            price: Amount,
+            quantity: Amount,
+        },
+        // the stop order went into the pending cache and is matched next block
+        StopOrderTriggered {
+            market_id: MarketId,
+            order_id: OrderId,
+            trader: T::AccountId,
+            side: OrderSide,
+            price: Amount,
+            quantity: Amount,
+            last_price: Amount,
+        },
+        // post-only order moved inside the spread instead of crossing it
+        OrderRepriced {
+            market_id: MarketId,
+            order_id: OrderId,
+            trader: T::AccountId,
+            old_price: Amount,
+            new_price: Amount,
+        },
+        // order reached its expiry block, it left the book and the rest was unlocked
+        OrderExpired {
+            market_id: MarketId,
+            order_id: OrderId,
+            trader: T::AccountId,
+            filled_quantity: Amount,
+            expired_quantity: Amount,
+        },
         // we are putting this event, so that we know its requested but it could not be processed perhaps
         CancellationRequested {
+            market_id: MarketId,
+            order_id: OrderId,
+            trader: T::AccountId,
+        },
+        // trades `first_trade_id..=last_trade_id` left `Trades`, `commitment` covers all pruned so far
+        TradesPruned {
+            first_trade_id: TradeId,
+            last_trade_id: TradeId,
+            commitment: T::Hash,
+        },
+        // default self-trade prevention of a trader, `None` lets own orders trade
+        SelfTradePreventionSet {
+            trader: T::AccountId,
+            mode: Option<SelfTradePrevention>,
+        },
+        // two orders of `trader` met and did not trade, `quantity` is what
+        // decrement-and-cancel took off both
+        SelfTradePrevented {
+            market_id: MarketId,
+            trader: T::AccountId,
+            taker_order_id: OrderId,
+            maker_order_id: OrderId,
+            mode: SelfTradePrevention,
+            quantity: Amount,
+        },
+        // matching a market failed, its books and cache are left as they were
+        MatchingFailed {
+            market_id: MarketId,
+            error: DispatchError,
+        },
+        // a matched trade could not be settled, nothing moved and both orders got the
+        // quantity back
+        TradeRolledBack {
+            market_id: MarketId,
+            buy_order_id: OrderId,
+            sell_order_id: OrderId,
+            price: Amount,
+            quantity: Amount,
+            error: DispatchError,
+        },
+        // funds an order no longer needed could not be unlocked
+        UnlockFailed {
+            market_id: MarketId,
+            order_id: OrderId,
+            trader: T::AccountId,
+            asset_id: AssetId,
+            amount: Amount,
+            error: DispatchError,
+        },
+        // `kept_priority` is false when the order went to the back of the queue at its price
+        OrderAmended {
+            market_id: MarketId,
             order_id: OrderId,
             trader: T::AccountId,
+            old_price: Amount,
+            old_quantity: Amount,
+            new_price: Amount,
+            new_quantity: Amount,
+            kept_priority: bool,
+        },
+        // a cancel-all pass, `remaining` orders did not fit and are queued in a later block
+        CancelAllQueued {
+            trader: T::AccountId,
+            side: Option<OrderSide>,
+            queued: u32,
+            remaining: u32,
         },
         MatchingCompleted {
+            market_id: MarketId,
             total_trades: u32,
             total_volume: Amount,
         },
@@ -206,268 +632,198 @@ pub mod pallet {
 
         /// No matching orders
         NoMatchingOrders,
-    }
 
-    // ========================================
-    // HOOKS FOR MATCHING
-    // ========================================
+        /// Market does not exist
+        MarketNotFound,
 
-    #[pallet::hooks]
-    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
-        /// Calculate weight based on pending work
-        fn on_initialize(_n: BlockNumberFor<T>) -> Weight {
-            // Count pending orders quickly
-            let mut total_pending = 0u32;
+        /// Market is paused or delisted
+        MarketNotActive,
 
-            // Quick count of pending bids/asks
-            for (_, orders) in PendingBids::<T>::iter() {
-                total_pending = total_pending.saturating_add(orders.len() as u32);
-            }
-            for (_, orders) in PendingAsks::<T>::iter() {
-                total_pending = total_pending.saturating_add(orders.len() as u32);
-            }
+        /// Market for this pair is already listed
+        MarketAlreadyExists,
 
-            let cancellations = PendingCancellations::<T>::get().len() as u32;
+        /// Base and quote asset must differ
+        InvalidMarketPair,
 
-            // Return worst-case weight for safety
-            if total_pending > 0 || cancellations > 0 {
-                // Use complex scenario as upper bound
-                <T as Config>::WeightInfo::on_finalize_complex(
-                    total_pending.min(20),
-                    total_pending.min(20),
-                    cancellations.min(10),
-                )
-            } else {
-                <T as Config>::WeightInfo::on_finalize_empty()
-            }
-        }
+        /// Market cannot move to the requested status
+        InvalidMarketStatus,
 
-        // on finalize
-        fn on_finalize(_n: BlockNumberFor<T>) {
-            let mut orders_map = BTreeMap::new();
+        /// Order belongs to another market
+        MarketMismatch,
 
-            //load all orders, will need to modify for sure
-            for (order_id, order) in Orders::<T>::iter() {
-                orders_map.insert(order_id, order);
-            }
+        /// No room for another price level on this side of the book
+        TooManyPriceLevels,
 
-            //================================
-            // These will load the temp caches
-            //================================
+        /// Post-only only works for good-till-cancelled limit orders
+        InvalidPostOnly,
 
-            // Load pending bids
-            let mut pending_bids = BTreeMap::new();
-            for (price, order_ids) in PendingBids::<T>::iter() {
-                pending_bids.insert(price, order_ids.into_inner());
-            }
+        /// Trigger price must be > 0
+        InvalidTriggerPrice,
 
-            // Load pending asks
-            let mut pending_asks = BTreeMap::new();
-            for (price, order_ids) in PendingAsks::<T>::iter() {
-                pending_asks.insert(price, order_ids.into_inner());
-            }
+        /// The last trade price already reached the trigger
+        StopAlreadyTriggered,
 
-            // Load persistent bids
-            let mut persistent_bids = BTreeMap::new();
-            for (price, order_ids) in Bids::<T>::iter() {
-                persistent_bids.insert(price, order_ids.into_inner());
-            }
+        /// Too many untriggered stop orders in this market
+        TooManyStopOrders,
 
-            // Load persistent asks
-            let mut persistent_asks = BTreeMap::new();
-            for (price, order_ids) in Asks::<T>::iter() {
-                persistent_asks.insert(price, order_ids.into_inner());
-            }
+        /// Expiry block must be after the current block
+        InvalidExpiry,
 
-            let cancellations = PendingCancellations::<T>::get();
-            if !cancellations.is_empty() {
-                let _ = process_cancellations::<T>(
-                    cancellations.into_inner(),
-                    &mut persistent_bids,
-                    &mut persistent_asks,
-                    &mut orders_map,
-                );
-            }
+        /// Too many orders expire in that block
+        TooManyExpiringOrders,
 
-            let mut all_trades: Vec<Trade<T>> = Vec::new();
+        /// A market buy needs a quote limit > 0
+        InvalidQuoteLimit,
 
-            // here we are matching first only from the temp cache
-            let (pending_trades, unmatched) =
-                match_pending_internal(pending_bids, pending_asks, &mut orders_map)
-                    .unwrap_or_default();
+        /// Fee tiers must be sorted by volume and rebates can't exceed any tier's taker fee
+        InvalidFeeTiers,
 
-            all_trades.extend(pending_trades);
+        /// Price is not a multiple of the market tick size
+        PriceNotOnTick,
 
-            if !unmatched.is_empty() {
-                let persistent_trades = match_persistent_storage(
-                    &mut persistent_bids,
-                    &mut persistent_asks,
-                    unmatched,
-                    &mut orders_map,
-                )
-                .unwrap_or_default();
+        /// Quantity is not a multiple of the market lot size
+        QuantityNotOnLot,
 
-                all_trades.extend(persistent_trades);
-            }
+        /// Order value is below the market minimum notional
+        BelowMinNotional,
 
-            // At this point, we have in memory done all necessary transactions
-            // Now we need to adjust order/money management
-            let mut total_volume = 0u128;
+        /// Tick and lot size must be above zero
+        InvalidTradingRules,
 
-            for trade in all_trades.iter_mut() {
-                // Set trade_id
-                let trade_id = NextTradeId::<T>::get();
-                trade.trade_id = trade_id;
+        /// A batch call needs at least one order
+        EmptyBatch,
 
-                // Transfer USDT from buyer to seller
-                let usdt_amount = trade.price.saturating_mul(trade.quantity);
-                let _ = assets::Pallet::<T>::transfer_locked(
-                    &trade.buyer,
-                    &trade.seller,
-                    USDT,
-                    usdt_amount,
-                );
+        /// Too many cancel-all requests are already waiting for the next block
+        TooManyCancelAllRequests,
 
-                // Transfer ETH from seller to buyer
-                let _ = assets::Pallet::<T>::transfer_locked(
-                    &trade.seller,
-                    &trade.buyer,
-                    ETH,
-                    trade.quantity,
-                );
+        /// Only open limit orders can be amended, and the amendment has to change something
+        InvalidAmendment,
 
-                // Unlock funds for both parties(NOt required i realized that transfer_locked alredy transfer
-                //to free balance, unlocking might unlock some other things not in the trade)
-                //let _ = assets::Pallet::<T>::unlock_funds(&trade.seller, USDT, usdt_amount);
-                //let _ = assets::Pallet::<T>::unlock_funds(&trade.buyer, ETH, trade.quantity);
+        /// Only GTC limit orders can hide size, and the display quantity must be above zero,
+        /// below the order quantity and on the lot size
+        InvalidDisplayQuantity,
 
-                // Store trade
-                Trades::<T>::insert(trade_id, trade.clone());
-                NextTradeId::<T>::put(trade_id + 1);
+        /// Only sell orders can be reduce-only
+        ReduceOnlyBuy,
 
-                // Emit event
-                Self::deposit_event(Event::TradeExecuted {
-                    trade_id,
-                    buy_order_id: trade.buy_order_id,
-                    sell_order_id: trade.sell_order_id,
-                    buyer: trade.buyer.clone(),
-                    seller: trade.seller.clone(),
-                    price: trade.price,
-                    quantity: trade.quantity,
-                });
+        /// A reduce-only sell needs at least one lot of free base balance to sell
+        NoPositionToReduce,
 
-                total_volume = total_volume.saturating_add(usdt_amount);
-            }
+        /// Only a buy that isn't fill-or-kill can spend a quote amount
+        InvalidSpend,
 
-            // Now we need to unlock funds which are cancelled
-            for (order_id, order) in orders_map.iter() {
-                if order.status == OrderStatus::Cancelled {
-                    let remaining = order.quantity.saturating_sub(order.filled_quantity);
+        /// A resting book entry doesn't point at an open order of that side and price
+        InvalidBookEntry,
 
-                    if remaining > 0 {
-                        let (asset, amount) = match order.side {
-                            OrderSide::Buy => {
-                                let total = order.price.saturating_mul(remaining);
-                                (USDT, total)
-                            }
-                            OrderSide::Sell => (ETH, remaining),
-                        };
+        /// The best bid of a market is at or above its best ask
+        BookCrossed,
 
-                        let _ = assets::Pallet::<T>::unlock_funds(&order.trader, asset, amount);
+        /// An order or trade id is at or above the next id to hand out
+        IdCounterBehind,
+    }
 
-                        Self::deposit_event(Event::OrderCancelled {
-                            order_id: *order_id,
-                            trader: order.trader.clone(),
-                        });
-                    }
-                }
-            }
+    // ========================================
+    // HOOKS FOR MATCHING
+    // ========================================
 
-            // Emit events for filled/partially filled:
-            for (order_id, order) in orders_map.iter() {
-                Orders::<T>::insert(order_id, order);
+    #[pallet::hooks]
+    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+        /// Expire orders, then calculate weight based on pending work
+        fn on_initialize(n: BlockNumberFor<T>) -> Weight {
+            let expired = Self::expire_orders(n.saturated_into());
+            let expiry_weight = if expired > 0 {
+                <T as Config>::WeightInfo::expire_orders(expired)
+            } else {
+                Weight::zero()
+            };
 
-                // Emit events for filled/partially filled orders
-                if order.status == OrderStatus::Filled {
-                    Self::deposit_event(Event::OrderFilled {
-                        order_id: *order_id,
-                        trader: order.trader.clone(),
-                    });
-                    Orders::<T>::remove(order_id);
-                } else if order.status == OrderStatus::PartiallyFilled {
-                    let remaining = order.quantity.saturating_sub(order.filled_quantity);
-                    Self::deposit_event(Event::OrderPartiallyFilled {
-                        order_id: *order_id,
-                        trader: order.trader.clone(),
-                        filled_quantity: order.filled_quantity,
-                        remaining_quantity: remaining,
-                    });
+            // before counting cancellations, so the ones queued here are paid for
+            let continued = Self::continue_cancel_all();
+            let cancel_all_weight =
+                <T as Config>::WeightInfo::cancel_all_orders(T::MaxUserOrders::get())
+                    .saturating_mul(continued.into());
+
+            // Count pending orders quickly
+            let mut total_pending = 0u32;
+            // orders going into a batch auction are weighed for it on their own
+            let mut auction_pending = 0u32;
+            let mut auctions: BTreeMap<MarketId, bool> = BTreeMap::new();
+
+            // Quick count of pending bids/asks across all markets
+            for (market_id, _, orders) in PendingBids::<T>::iter().chain(PendingAsks::<T>::iter()) {
+                let auction = *auctions.entry(market_id).or_insert_with(|| {
+                    MarketClearingMode::<T>::get(market_id) == ClearingMode::BatchAuction
+                });
+                if auction {
+                    auction_pending = auction_pending.saturating_add(orders.len() as u32);
+                } else {
+                    total_pending = total_pending.saturating_add(orders.len() as u32);
                 }
             }
 
-            // Here we modify the StorageDoubleMap
-            for (price, order_ids) in persistent_bids.iter() {
-                if !order_ids.is_empty() {
-                    match BoundedVec::<OrderId, T::MaxOrders>::try_from(order_ids.clone()) {
-                        Ok(bounded) => {
-                            Bids::<T>::insert(price, bounded);
-                        }
-                        Err(_) => {
-                            // Doing this so that its save and bounded(altho this is mostly guaranteed because its from pending asks/bids and also pendingcancellations)
-                            let truncated: Vec<OrderId> = order_ids
-                                .iter()
-                                .take(T::MaxOrders::get() as usize)
-                                .cloned()
-                                .collect();
-
-                            if let Ok(bounded) =
-                                BoundedVec::<OrderId, T::MaxOrders>::try_from(truncated.clone())
-                            {
-                                Bids::<T>::insert(price, bounded);
-                            }
-                        }
-                    }
-                }
+            let mut cancellations = 0u32;
+            for ids in PendingCancellations::<T>::iter_values() {
+                cancellations = cancellations.saturating_add(ids.len() as u32);
             }
 
-            for (price, order_ids) in persistent_asks.iter() {
-                if !order_ids.is_empty() {
-                    match BoundedVec::<OrderId, T::MaxOrders>::try_from(order_ids.clone()) {
-                        Ok(bounded) => {
-                            Asks::<T>::insert(price, bounded);
-                        }
-                        Err(_) => {
-                            // Doing this so that its save and bounded(altho this is mostly guaranteed because its from pending asks/bids and also pendingcancellations)
-                            let truncated: Vec<OrderId> = order_ids
-                                .iter()
-                                .take(T::MaxOrders::get() as usize)
-                                .cloned()
-                                .collect();
-
-                            if let Ok(bounded) =
-                                BoundedVec::<OrderId, T::MaxOrders>::try_from(truncated.clone())
-                            {
-                                Asks::<T>::insert(price, bounded);
-                            }
-                        }
-                    }
-                }
+            // Resting orders pulled in by matching are only known in on_finalize,
+            // that part is registered there once we know how many were loaded
+            let mut matching_weight = if total_pending > 0 || cancellations > 0 {
+                <T as Config>::WeightInfo::on_finalize_market(total_pending, 0, cancellations)
+            } else {
+                <T as Config>::WeightInfo::on_finalize_empty()
+            };
+            if auction_pending > 0 {
+                matching_weight = matching_weight.saturating_add(
+                    <T as Config>::WeightInfo::on_finalize_auction(auction_pending),
+                );
             }
 
-            // Clear Pending Bids and Asks
-            let _ = PendingBids::<T>::clear(u32::MAX, None);
-            let _ = PendingAsks::<T>::clear(u32::MAX, None);
+            matching_weight
+                .saturating_add(expiry_weight)
+                .saturating_add(cancel_all_weight)
+        }
 
-            //remove the cancellation storage
-            PendingCancellations::<T>::kill();
+        // on finalize, every market is matched on its own
+        fn on_finalize(_n: BlockNumberFor<T>) {
+            let mut resting_loaded = 0u32;
+            let mut stops_checked = 0u32;
+            for (market_id, market) in Markets::<T>::iter() {
+                let (loaded, checked) = Self::finalize_market(market_id, market);
+                resting_loaded = resting_loaded.saturating_add(loaded);
+                stops_checked = stops_checked.saturating_add(checked);
+            }
 
-            //EMIT event about complete trades
-            Self::deposit_event(Event::MatchingCompleted {
-                total_trades: all_trades.len() as u32,
-                total_volume,
-            });
+            let mut extra = Weight::zero();
+            if resting_loaded > 0 {
+                extra = extra.saturating_add(
+                    <T as Config>::WeightInfo::on_finalize_market(0, resting_loaded, 0)
+                        .saturating_sub(<T as Config>::WeightInfo::on_finalize_market(0, 0, 0)),
+                );
+            }
+            if stops_checked > 0 {
+                extra = extra.saturating_add(<T as Config>::WeightInfo::trigger_stop_orders(
+                    stops_checked,
+                ));
+            }
+            if extra != Weight::zero() {
+                frame_system::Pallet::<T>::register_extra_weight_unchecked(
+                    extra,
+                    DispatchClass::Mandatory,
+                );
+            }
+        }
+
+        /// Prune trades past the retention window with whatever weight is left in the block
+        fn on_idle(_n: BlockNumberFor<T>, remaining_weight: Weight) -> Weight {
+            Self::prune_trades(remaining_weight)
+        }
 
-            //Ok(())
+        #[cfg(feature = "try-runtime")]
+        fn try_state(
+            _n: BlockNumberFor<T>,
+        ) -> Result<(), frame_support::sp_runtime::TryRuntimeError> {
+            Self::do_try_state()
         }
     }
 
@@ -477,58 +833,202 @@ pub mod pallet {
 
     #[pallet::call]
     impl<T: Config> Pallet<T> {
-        /// Place a limit order
+        /// Place a limit or market order. A market order ignores `price`, it takes what the
+        /// book offers within its quote limit and whatever it can't fill is cancelled.
+        /// `self_trade` overrides the trader's default self-trade prevention for this order.
+        /// With `display_quantity` a GTC limit order only shows that much of its size at a
+        /// time, the next slice goes to the back of the price level once one is filled.
+        /// A `reduce_only` sell locks no more than the trader's free base balance and is
+        /// cancelled once that is sold, `ClosePosition` sells all of it whatever `quantity` is.
+        /// A `Spend` buy ignores `price` and `quantity` and buys as much as its quote amount
+        /// pays for in whole lots, the quote it couldn't use is refunded.
         #[pallet::call_index(0)]
         #[pallet::weight(<T as Config>::WeightInfo::place_order())]
         pub fn place_order(
             origin: OriginFor<T>,
+            market_id: MarketId,
             side: OrderSide,
             price: Amount,
             quantity: Amount,
             order_type: OrderType,
+            time_in_force: TimeInForce,
+            post_only: Option<PostOnly>,
+            expires_at: Option<u32>,
+            self_trade: Option<SelfTradePrevention>,
+            display_quantity: Option<Amount>,
+            reduce_only: Option<ReduceOnly>,
         ) -> DispatchResult {
             let trader = ensure_signed(origin)?;
-            ensure!(price > 0, Error::<T>::InvalidPrice);
-            ensure!(quantity > 0, Error::<T>::InvalidQuantity);
+            Self::do_place_order(
+                trader,
+                OrderRequest {
+                    market_id,
+                    side,
+                    price,
+                    quantity,
+                    order_type,
+                    time_in_force,
+                    post_only,
+                    expires_at,
+                    self_trade,
+                    display_quantity,
+                    reduce_only,
+                },
+            )
+        }
 
-            let (asset, amount_to_lock) = match side {
-                OrderSide::Buy => {
-                    let total_amount = price
-                        .checked_mul(quantity)
-                        .ok_or(Error::<T>::ArithmeticOverflow)?;
-                    (USDT, total_amount)
+        #[pallet::call_index(1)]
+        #[pallet::weight(
+            <T as Config>::WeightInfo::cancel_order()
+                .max(<T as Config>::WeightInfo::cancel_stop_order())
+        )]
+        pub fn cancel_order(
+            origin: OriginFor<T>,
+            market_id: MarketId,
+            order_id: OrderId,
+        ) -> DispatchResult {
+            let trader = ensure_signed(origin)?;
+            Self::do_cancel_order(trader, market_id, order_id)
+        }
+
+        /// List a new market, root only
+        #[pallet::call_index(2)]
+        #[pallet::weight(<T as Config>::WeightInfo::create_market())]
+        pub fn create_market(
+            origin: OriginFor<T>,
+            base_asset: AssetId,
+            quote_asset: AssetId,
+        ) -> DispatchResult {
+            ensure_root(origin)?;
+
+            Self::do_create_market(MarketPair {
+                base_asset,
+                quote_asset,
+            })?;
+
+            Ok(())
+        }
+
+        /// Halt trading on a market, resting orders can still be cancelled
+        #[pallet::call_index(3)]
+        #[pallet::weight(<T as Config>::WeightInfo::set_market_status())]
+        pub fn pause_market(origin: OriginFor<T>, market_id: MarketId) -> DispatchResult {
+            ensure_root(origin)?;
+            Self::do_set_market_status(market_id, MarketStatus::Active, MarketStatus::Paused)
+        }
+
+        /// Resume trading on a paused market
+        #[pallet::call_index(4)]
+        #[pallet::weight(<T as Config>::WeightInfo::set_market_status())]
+        pub fn resume_market(origin: OriginFor<T>, market_id: MarketId) -> DispatchResult {
+            ensure_root(origin)?;
+            Self::do_set_market_status(market_id, MarketStatus::Paused, MarketStatus::Active)
+        }
+
+        /// Permanently delist a market, traders have to cancel what is left
+        #[pallet::call_index(5)]
+        #[pallet::weight(<T as Config>::WeightInfo::set_market_status())]
+        pub fn delist_market(origin: OriginFor<T>, market_id: MarketId) -> DispatchResult {
+            ensure_root(origin)?;
+
+            Markets::<T>::try_mutate(market_id, |maybe_market| {
+                let market = maybe_market.as_mut().ok_or(Error::<T>::MarketNotFound)?;
+                ensure!(
+                    market.status != MarketStatus::Delisted,
+                    Error::<T>::InvalidMarketStatus
+                );
+                market.status = MarketStatus::Delisted;
+                Ok::<_, DispatchError>(())
+            })?;
+
+            Self::deposit_event(Event::MarketStatusChanged {
+                market_id,
+                status: MarketStatus::Delisted,
+            });
+
+            Ok(())
+        }
+
+        /// Place a stop-market or stop-limit order. It waits in the trigger book and goes to
+        /// the pending cache once the last trade price reaches `trigger_price`.
+        /// Funds are locked right away, a stop-market order is bounded by its quote limit
+        /// like any market order and `price` is ignored for it.
+        #[pallet::call_index(6)]
+        #[pallet::weight(<T as Config>::WeightInfo::place_stop_order())]
+        pub fn place_stop_order(
+            origin: OriginFor<T>,
+            market_id: MarketId,
+            side: OrderSide,
+            kind: StopKind,
+            trigger_price: Amount,
+            price: Amount,
+            quantity: Amount,
+            order_type: OrderType,
+        ) -> DispatchResult {
+            let trader = ensure_signed(origin)?;
+            let market = Markets::<T>::get(market_id).ok_or(Error::<T>::MarketNotFound)?;
+            ensure!(
+                market.status == MarketStatus::Active,
+                Error::<T>::MarketNotActive
+            );
+            let price = Self::order_price(side, price, order_type)?;
+            let quantity = match order_type {
+                OrderType::Spend { quote_amount } => {
+                    Self::spend_size(market_id, side, TimeInForce::GTC, quote_amount)?
                 }
-                OrderSide::Sell => (ETH, quantity),
+                _ => quantity,
+            };
+            ensure!(quantity > 0, Error::<T>::InvalidQuantity);
+            ensure!(trigger_price > 0, Error::<T>::InvalidTriggerPrice);
+            Self::ensure_trading_rules(market_id, side, price, quantity, order_type)?;
+            ensure!(
+                trigger_price.is_multiple_of(Self::market_rules(market_id).tick_size),
+                Error::<T>::PriceNotOnTick
+            );
+
+            let trigger = StopTrigger {
+                kind,
+                trigger_price,
             };
-            assets::Pallet::<T>::lock_funds(&trader, asset, amount_to_lock)?;
+            if let Some(last_price) = LastTradePrice::<T>::get(market_id) {
+                ensure!(
+                    !stop_triggered(side, &trigger, last_price),
+                    Error::<T>::StopAlreadyTriggered
+                );
+            }
+
+            Self::lock_order_funds(&trader, &market, side, price, quantity, order_type)?;
 
             let order_id = NextOrderId::<T>::get();
             let order = Order {
                 order_id,
+                market_id,
                 trader: trader.clone(),
                 side,
-                status: OrderStatus::Open,
+                status: OrderStatus::Untriggered,
                 order_type,
+                time_in_force: TimeInForce::GTC,
+                post_only: None,
+                stop: Some(trigger),
                 price,
                 quantity,
                 filled_quantity: 0,
                 ttl: None,
+                self_trade: AccountSelfTradePrevention::<T>::get(&trader),
+                iceberg: None,
+                reduce_only: None,
             };
 
             Orders::<T>::insert(order_id, order);
-            if side == OrderSide::Buy {
-                PendingBids::<T>::try_mutate(price, |orders| {
-                    orders
-                        .try_push(order_id)
-                        .map_err(|_| Error::<T>::TooManyPendingOrders)
-                })?;
-            } else {
-                PendingAsks::<T>::try_mutate(price, |orders| {
-                    orders
-                        .try_push(order_id)
-                        .map_err(|_| Error::<T>::TooManyPendingOrders)
-                })?;
-            }
+            StopOrders::<T>::try_mutate(market_id, |stops| {
+                stops
+                    .try_push(StopEntry {
+                        order_id,
+                        side,
+                        trigger,
+                    })
+                    .map_err(|_| Error::<T>::TooManyStopOrders)
+            })?;
 
             UserOrders::<T>::try_mutate(trader.clone(), |orders| {
                 orders
@@ -538,96 +1038,2431 @@ pub mod pallet {
 
             NextOrderId::<T>::put(order_id + 1);
 
-            Self::deposit_event(Event::OrderPlaced {
+            Self::deposit_event(Event::StopOrderPlaced {
+                market_id,
                 order_id,
+                trader,
                 side,
+                kind,
+                trigger_price,
                 price,
-                asset_id: if side == OrderSide::Buy { USDT } else { ETH },
                 quantity,
             });
 
             Ok(())
         }
 
-        #[pallet::call_index(1)]
-        #[pallet::weight(<T as Config>::WeightInfo::cancel_order())]
-        pub fn cancel_order(origin: OriginFor<T>, order_id: OrderId) -> DispatchResult {
-            let trader = ensure_signed(origin)?;
-
-            let order = Orders::<T>::get(order_id).ok_or(Error::<T>::OrderNotFound)?;
-
-            ensure!(trader == order.trader, Error::<T>::NotOrderOwner);
+        /// Set the maker and taker fee rates of a market, they apply from the next match
+        #[pallet::call_index(7)]
+        #[pallet::weight(<T as Config>::WeightInfo::set_market_fees())]
+        pub fn set_market_fees(
+            origin: OriginFor<T>,
+            market_id: MarketId,
+            maker_fee: Permill,
+            taker_fee: Permill,
+        ) -> DispatchResult {
+            ensure_root(origin)?;
             ensure!(
-                order.status != OrderStatus::Filled,
-                Error::<T>::OrderNotActive
+                Markets::<T>::contains_key(market_id),
+                Error::<T>::MarketNotFound
             );
 
-            PendingCancellations::<T>::try_mutate(|cancellations| {
-                cancellations
-                    .try_push(order_id)
-                    .map_err(|_| Error::<T>::TooManyPendingCancellations)
-            })?;
+            MarketFees::<T>::insert(
+                market_id,
+                FeeRates {
+                    maker: maker_fee,
+                    taker: taker_fee,
+                },
+            );
 
-            Self::deposit_event(Event::CancellationRequested {
-                order_id: order.order_id,
-                trader,
+            Self::deposit_event(Event::MarketFeesSet {
+                market_id,
+                maker_fee,
+                taker_fee,
             });
 
             Ok(())
         }
-    }
 
-    // ======================================
-    // Getter functions for storage/for some reason, directly acccessing them doesnt work
-    // =======================================
-    impl<T: Config> Pallet<T> {
-        pub fn next_order_id() -> OrderId {
-            NextOrderId::<T>::get()
+        /// Replace the volume tier table. Each tier applies to accounts whose rolling volume is
+        /// at least its `min_volume`, an empty table leaves everyone on the market rates.
+        #[pallet::call_index(8)]
+        #[pallet::weight(<T as Config>::WeightInfo::set_fee_tiers(tiers.len() as u32))]
+        pub fn set_fee_tiers(
+            origin: OriginFor<T>,
+            tiers: BoundedVec<FeeTier, T::MaxFeeTiers>,
+        ) -> DispatchResult {
+            ensure_root(origin)?;
+            ensure!(
+                tiers
+                    .windows(2)
+                    .all(|pair| pair[0].min_volume < pair[1].min_volume),
+                Error::<T>::InvalidFeeTiers
+            );
+            // makers are paid out of what takers leave in the fee account, and the taker can be
+            // on any tier
+            let lowest_taker = tiers.iter().map(|tier| tier.taker).min();
+            ensure!(
+                tiers.iter().all(|tier| match tier.maker {
+                    MakerFee::Fee(_) => true,
+                    MakerFee::Rebate(rebate) => lowest_taker.is_some_and(|taker| rebate <= taker),
+                }),
+                Error::<T>::InvalidFeeTiers
+            );
+
+            let count = tiers.len() as u32;
+            FeeTiers::<T>::put(tiers);
+            Self::deposit_event(Event::FeeTiersSet { tiers: count });
+
+            Ok(())
         }
 
-        /// Get the next trade ID
-        pub fn next_trade_id() -> TradeId {
-            NextTradeId::<T>::get()
+        /// Set the tick size, lot size and minimum notional of a market. Orders already in
+        /// the book are left as they are.
+        #[pallet::call_index(9)]
+        #[pallet::weight(<T as Config>::WeightInfo::set_market_rules())]
+        pub fn set_market_rules(
+            origin: OriginFor<T>,
+            market_id: MarketId,
+            rules: TradingRules,
+        ) -> DispatchResult {
+            ensure_root(origin)?;
+            ensure!(
+                Markets::<T>::contains_key(market_id),
+                Error::<T>::MarketNotFound
+            );
+            ensure!(
+                rules.tick_size > 0 && rules.lot_size > 0,
+                Error::<T>::InvalidTradingRules
+            );
+
+            MarketRules::<T>::insert(market_id, rules);
+            Self::deposit_event(Event::MarketRulesSet { market_id, rules });
+
+            Ok(())
         }
 
-        /// Get an order by ID
-        pub fn get_order(order_id: OrderId) -> Option<Order<T>> {
-            Orders::<T>::get(order_id)
+        /// Place several orders in one call, e.g. a quote ladder. Either all of them are
+        /// placed or, if one fails, none is.
+        #[pallet::call_index(10)]
+        #[pallet::weight(<T as Config>::WeightInfo::place_orders(orders.len() as u32))]
+        pub fn place_orders(
+            origin: OriginFor<T>,
+            orders: BoundedVec<OrderRequest, T::MaxPendingOrders>,
+        ) -> DispatchResult {
+            let trader = ensure_signed(origin)?;
+            ensure!(!orders.is_empty(), Error::<T>::EmptyBatch);
+
+            for request in orders {
+                Self::do_place_order(trader.clone(), request)?;
+            }
+
+            Ok(())
         }
 
-        /// Get a trade by ID
-        pub fn get_trade(trade_id: TradeId) -> Option<Trade<T>> {
-            Trades::<T>::get(trade_id)
+        /// Cancel several orders in one call, each in the market it was placed in.
+        /// Either all of them are cancelled or none is.
+        #[pallet::call_index(11)]
+        #[pallet::weight(<T as Config>::WeightInfo::cancel_orders(order_ids.len() as u32))]
+        pub fn cancel_orders(
+            origin: OriginFor<T>,
+            order_ids: BoundedVec<OrderId, T::MaxCancellationOrders>,
+        ) -> DispatchResult {
+            let trader = ensure_signed(origin)?;
+            ensure!(!order_ids.is_empty(), Error::<T>::EmptyBatch);
+
+            for order_id in order_ids {
+                Self::cancel_in_order_market(trader.clone(), order_id)?;
+            }
+
+            Ok(())
         }
 
-        /// Get bids at a specific price level
-        pub fn get_bids_at_price(price: Amount) -> Vec<OrderId> {
-            Bids::<T>::get(price).into_inner()
+        /// Cancel `cancels` and place `orders` atomically, if any of them fails nothing
+        /// changes. Cancellations still settle at the end of the block like `cancel_order`,
+        /// so the new orders need funds of their own.
+        #[pallet::call_index(12)]
+        #[pallet::weight(
+            <T as Config>::WeightInfo::replace_orders(cancels.len() as u32, orders.len() as u32)
+        )]
+        pub fn replace_orders(
+            origin: OriginFor<T>,
+            cancels: BoundedVec<OrderId, T::MaxCancellationOrders>,
+            orders: BoundedVec<OrderRequest, T::MaxPendingOrders>,
+        ) -> DispatchResult {
+            let trader = ensure_signed(origin)?;
+            ensure!(
+                !cancels.is_empty() || !orders.is_empty(),
+                Error::<T>::EmptyBatch
+            );
+
+            for order_id in cancels {
+                Self::cancel_in_order_market(trader.clone(), order_id)?;
+            }
+            for request in orders {
+                Self::do_place_order(trader.clone(), request)?;
+            }
+
+            Ok(())
         }
 
-        /// Get asks at a specific price level
-        pub fn get_asks_at_price(price: Amount) -> Vec<OrderId> {
-            Asks::<T>::get(price).into_inner()
+        /// Change the price and/or size of an open limit order. `new_quantity` is the new total
+        /// size, what already filled included. Cutting the size at the same price keeps the
+        /// order's place in the queue, any other change sends it to the back as if it was
+        /// placed again. The locked funds are topped up or released right away.
+        #[pallet::call_index(14)]
+        #[pallet::weight(<T as Config>::WeightInfo::amend_order())]
+        pub fn amend_order(
+            origin: OriginFor<T>,
+            order_id: OrderId,
+            new_price: Amount,
+            new_quantity: Amount,
+        ) -> DispatchResult {
+            let trader = ensure_signed(origin)?;
+
+            let mut order = Orders::<T>::get(order_id).ok_or(Error::<T>::OrderNotFound)?;
+            ensure!(trader == order.trader, Error::<T>::NotOrderOwner);
+            ensure!(
+                order.status == OrderStatus::Open || order.status == OrderStatus::PartiallyFilled,
+                Error::<T>::OrderNotActive
+            );
+            ensure!(
+                !PendingCancellations::<T>::get(order.market_id).contains(&order_id),
+                Error::<T>::OrderNotActive
+            );
+            ensure!(
+                order.order_type == OrderType::Limit,
+                Error::<T>::InvalidAmendment
+            );
+            let market_id = order.market_id;
+            let market = Markets::<T>::get(market_id).ok_or(Error::<T>::MarketNotFound)?;
+            ensure!(
+                market.status == MarketStatus::Active,
+                Error::<T>::MarketNotActive
+            );
+            ensure!(new_price > 0, Error::<T>::InvalidPrice);
+            ensure!(
+                new_quantity > order.filled_quantity,
+                Error::<T>::InvalidQuantity
+            );
+            ensure!(
+                new_price != order.price || new_quantity != order.quantity,
+                Error::<T>::InvalidAmendment
+            );
+            Self::ensure_trading_rules(
+                market_id,
+                order.side,
+                new_price,
+                new_quantity.saturating_sub(order.filled_quantity),
+                OrderType::Limit,
+            )?;
+
+            let kept_priority = new_price == order.price && new_quantity < order.quantity;
+            if !kept_priority {
+                Self::remove_from_book(&order);
+            }
+
+            let (asset, old_lock) = Self::remaining_lock(&order, &market.pair);
+            let (old_price, old_quantity) = (order.price, order.quantity);
+            order.price = new_price;
+            order.quantity = new_quantity;
+            // a reduce-only order never locks more than it did at placement
+            if let Some(cap) = order.reduce_only.as_mut() {
+                *cap = (*cap).min(new_quantity);
+            }
+            let (_, new_lock) = Self::remaining_lock(&order, &market.pair);
+            if new_lock > old_lock {
+                assets::Pallet::<T>::lock_funds(&trader, asset, new_lock - old_lock)?;
+            } else if old_lock > new_lock {
+                assets::Pallet::<T>::unlock_funds(&trader, asset, old_lock - new_lock)?;
+            }
+
+            // back of the queue: matched again at the end of the block like a new order,
+            // an iceberg shows a full slice again
+            if !kept_priority {
+                if let Some(iceberg) = order.iceberg.as_mut() {
+                    iceberg.visible_quantity = iceberg.display_quantity;
+                }
+                Self::insert_price_level(market_id, order.side, new_price)?;
+                Self::push_pending(market_id, order.side, new_price, order_id)?;
+            }
+            Orders::<T>::insert(order_id, order);
+
+            Self::deposit_event(Event::OrderAmended {
+                market_id,
+                order_id,
+                trader,
+                old_price,
+                old_quantity,
+                new_price,
+                new_quantity,
+                kept_priority,
+            });
+
+            Ok(())
         }
 
-        /// Get pending bids at a specific price level
-        pub fn get_pending_bids_at_price(price: Amount) -> Vec<OrderId> {
-            PendingBids::<T>::get(price).into_inner()
+        /// Cancel every open order of the caller, or only those on `side`. Untriggered stops
+        /// are cancelled right away, the rest is queued like `cancel_order`. Orders that don't
+        /// fit under `MaxCancellationOrders` are queued in the following blocks.
+        #[pallet::call_index(13)]
+        #[pallet::weight(<T as Config>::WeightInfo::cancel_all_orders(T::MaxUserOrders::get()))]
+        pub fn cancel_all_orders(origin: OriginFor<T>, side: Option<OrderSide>) -> DispatchResult {
+            let trader = ensure_signed(origin)?;
+
+            let remaining = Self::queue_cancel_all(&trader, side);
+            // a newer request replaces whatever was still waiting
+            CancelAllQueue::<T>::try_mutate(|queue| {
+                queue.retain(|(who, _)| *who != trader);
+                if remaining > 0 {
+                    queue
+                        .try_push((trader, side))
+                        .map_err(|_| Error::<T>::TooManyCancelAllRequests)?;
+                }
+                Ok::<_, DispatchError>(())
+            })
         }
 
-        /// Get pending asks at a specific price level
-        pub fn get_pending_asks_at_price(price: Amount) -> Vec<OrderId> {
-            PendingAsks::<T>::get(price).into_inner()
+        /// Set what happens when the caller's orders would trade with each other, `None`
+        /// lets them trade. Applies to orders placed afterwards that don't set their own.
+        #[pallet::call_index(15)]
+        #[pallet::weight(<T as Config>::WeightInfo::set_self_trade_prevention())]
+        pub fn set_self_trade_prevention(
+            origin: OriginFor<T>,
+            mode: Option<SelfTradePrevention>,
+        ) -> DispatchResult {
+            let trader = ensure_signed(origin)?;
+
+            AccountSelfTradePrevention::<T>::set(&trader, mode);
+            Self::deposit_event(Event::SelfTradePreventionSet { trader, mode });
+
+            Ok(())
         }
 
-        /// Get pending cancellations
-        pub fn get_pending_cancellations() -> Vec<OrderId> {
-            PendingCancellations::<T>::get().into_inner()
+        /// Set how a market shares each price level among the makers resting there, from the
+        /// next block on. Pro-rata can give the first part of every fill to the oldest makers.
+        #[pallet::call_index(16)]
+        #[pallet::weight(<T as Config>::WeightInfo::set_matching_mode())]
+        pub fn set_matching_mode(
+            origin: OriginFor<T>,
+            market_id: MarketId,
+            mode: MatchingMode,
+        ) -> DispatchResult {
+            ensure_root(origin)?;
+            ensure!(
+                Markets::<T>::contains_key(market_id),
+                Error::<T>::MarketNotFound
+            );
+
+            MarketMatchingMode::<T>::insert(market_id, mode);
+            Self::deposit_event(Event::MatchingModeSet { market_id, mode });
+
+            Ok(())
+        }
+
+        /// Switch a market between continuous matching and one batch auction per block, from
+        /// the next block on. In an auction everything trades at one clearing price.
+        #[pallet::call_index(17)]
+        #[pallet::weight(<T as Config>::WeightInfo::set_clearing_mode())]
+        pub fn set_clearing_mode(
+            origin: OriginFor<T>,
+            market_id: MarketId,
+            mode: ClearingMode,
+        ) -> DispatchResult {
+            ensure_root(origin)?;
+            ensure!(
+                Markets::<T>::contains_key(market_id),
+                Error::<T>::MarketNotFound
+            );
+
+            MarketClearingMode::<T>::insert(market_id, mode);
+            Self::deposit_event(Event::ClearingModeSet { market_id, mode });
+
+            Ok(())
+        }
+
+        /// Set the order a market matches each block's new orders in: by id, or shuffled with
+        /// a seed from the parent hash or the runtime's randomness, from the next block on
+        #[pallet::call_index(18)]
+        #[pallet::weight(<T as Config>::WeightInfo::set_ordering_policy())]
+        pub fn set_ordering_policy(
+            origin: OriginFor<T>,
+            market_id: MarketId,
+            policy: OrderingPolicy,
+        ) -> DispatchResult {
+            ensure_root(origin)?;
+            ensure!(
+                Markets::<T>::contains_key(market_id),
+                Error::<T>::MarketNotFound
+            );
+
+            MarketOrderingPolicy::<T>::insert(market_id, policy);
+            Self::deposit_event(Event::OrderingPolicySet { market_id, policy });
+
+            Ok(())
+        }
+    }
+
+    // ======================================
+    // Internal helpers
+    // =======================================
+    impl<T: Config> Pallet<T> {
+        pub(crate) fn do_place_order(
+            trader: T::AccountId,
+            request: OrderRequest,
+        ) -> DispatchResult {
+            let OrderRequest {
+                market_id,
+                side,
+                price,
+                quantity,
+                order_type,
+                time_in_force,
+                post_only,
+                expires_at,
+                self_trade,
+                display_quantity,
+                reduce_only,
+            } = request;
+            let market = Markets::<T>::get(market_id).ok_or(Error::<T>::MarketNotFound)?;
+            ensure!(
+                market.status == MarketStatus::Active,
+                Error::<T>::MarketNotActive
+            );
+            let price = Self::order_price(side, price, order_type)?;
+            let quantity = match order_type {
+                OrderType::Spend { quote_amount } => {
+                    Self::spend_size(market_id, side, time_in_force, quote_amount)?
+                }
+                _ => quantity,
+            };
+            let (quantity, locked_base) = match reduce_only {
+                Some(mode) => {
+                    Self::reduce_only_size(&trader, market_id, &market, side, quantity, mode)?
+                }
+                None => (quantity, None),
+            };
+            ensure!(quantity > 0, Error::<T>::InvalidQuantity);
+            Self::ensure_trading_rules(market_id, side, price, quantity, order_type)?;
+            // a reduce-only sell can't sell more than it locked, so that has to pass as well
+            if let Some(locked) = locked_base {
+                Self::ensure_trading_rules(market_id, side, price, locked, order_type)?;
+            }
+            ensure!(
+                post_only.is_none()
+                    || (order_type == OrderType::Limit && time_in_force == TimeInForce::GTC),
+                Error::<T>::InvalidPostOnly
+            );
+            if let Some(expiry) = expires_at {
+                let now: u32 = frame_system::Pallet::<T>::block_number().saturated_into();
+                ensure!(expiry > now, Error::<T>::InvalidExpiry);
+            }
+            if let Some(display) = display_quantity {
+                ensure!(
+                    order_type == OrderType::Limit
+                        && time_in_force == TimeInForce::GTC
+                        && display > 0
+                        && display < quantity
+                        && display.is_multiple_of(Self::market_rules(market_id).lot_size),
+                    Error::<T>::InvalidDisplayQuantity
+                );
+            }
+
+            let asset = Self::lock_order_funds(
+                &trader,
+                &market,
+                side,
+                price,
+                locked_base.unwrap_or(quantity),
+                order_type,
+            )?;
+            // market orders never rest, so they don't get a price level
+            if !order_type.is_market() {
+                Self::insert_price_level(market_id, side, price)?;
+            }
+
+            let order_id = NextOrderId::<T>::get();
+            if let Some(expiry) = expires_at {
+                ExpiringOrders::<T>::try_mutate(expiry, |orders| {
+                    orders
+                        .try_push(order_id)
+                        .map_err(|_| Error::<T>::TooManyExpiringOrders)
+                })?;
+            }
+
+            let order = Order {
+                order_id,
+                market_id,
+                trader: trader.clone(),
+                side,
+                status: OrderStatus::Open,
+                order_type,
+                time_in_force,
+                post_only,
+                stop: None,
+                price,
+                quantity,
+                filled_quantity: 0,
+                ttl: expires_at,
+                self_trade: self_trade.or_else(|| AccountSelfTradePrevention::<T>::get(&trader)),
+                iceberg: display_quantity.map(|display| Iceberg {
+                    display_quantity: display,
+                    visible_quantity: display,
+                }),
+                reduce_only: locked_base,
+            };
+            let shown = order.shown_quantity();
+
+            Orders::<T>::insert(order_id, order);
+            Self::push_pending(market_id, side, price, order_id)?;
+
+            UserOrders::<T>::try_mutate(trader.clone(), |orders| {
+                orders
+                    .try_push(order_id)
+                    .map_err(|_| Error::<T>::TooManyUserOrders)
+            })?;
+
+            NextOrderId::<T>::put(order_id + 1);
+
+            // an iceberg only shows its first slice, a reduce-only sell what it could lock
+            Self::deposit_event(Event::OrderPlaced {
+                market_id,
+                order_id,
+                side,
+                price,
+                asset_id: asset,
+                quantity: shown,
+            });
+
+            Ok(())
+        }
+
+        pub(crate) fn do_cancel_order(
+            trader: T::AccountId,
+            market_id: MarketId,
+            order_id: OrderId,
+        ) -> DispatchResult {
+            let mut order = Orders::<T>::get(order_id).ok_or(Error::<T>::OrderNotFound)?;
+
+            ensure!(trader == order.trader, Error::<T>::NotOrderOwner);
+            ensure!(order.market_id == market_id, Error::<T>::MarketMismatch);
+
+            // untriggered stops are not in any book, they can go right away
+            if order.status == OrderStatus::Untriggered {
+                let market = Markets::<T>::get(market_id).ok_or(Error::<T>::MarketNotFound)?;
+                StopOrders::<T>::mutate(market_id, |stops| {
+                    stops.retain(|stop| stop.order_id != order_id)
+                });
+                Self::unlock_remaining(&order, &market.pair);
+
+                order.status = OrderStatus::Cancelled;
+                Self::retire_order(&order);
+
+                Self::deposit_event(Event::OrderCancelled {
+                    market_id,
+                    order_id,
+                    trader,
+                });
+                return Ok(());
+            }
+
+            ensure!(
+                order.status == OrderStatus::Open || order.status == OrderStatus::PartiallyFilled,
+                Error::<T>::OrderNotActive
+            );
+
+            PendingCancellations::<T>::try_mutate(market_id, |cancellations| {
+                cancellations
+                    .try_push(order_id)
+                    .map_err(|_| Error::<T>::TooManyPendingCancellations)
+            })?;
+
+            Self::deposit_event(Event::CancellationRequested {
+                market_id,
+                order_id: order.order_id,
+                trader,
+            });
+
+            Ok(())
+        }
+
+        // queues the trader's open orders on `side` for cancellation, returns how many did not fit
+        fn queue_cancel_all(trader: &T::AccountId, side: Option<OrderSide>) -> u32 {
+            let mut queued = 0u32;
+            let mut remaining = 0u32;
+
+            for order_id in UserOrders::<T>::get(trader) {
+                let Some(order) = Orders::<T>::get(order_id) else {
+                    continue;
+                };
+                if side.is_some_and(|side| side != order.side) {
+                    continue;
+                }
+                match order.status {
+                    OrderStatus::Open | OrderStatus::PartiallyFilled => {
+                        // queued by an earlier pass in this block
+                        if PendingCancellations::<T>::get(order.market_id).contains(&order_id) {
+                            continue;
+                        }
+                    }
+                    OrderStatus::Untriggered => {}
+                    _ => continue,
+                }
+
+                match Self::do_cancel_order(trader.clone(), order.market_id, order_id) {
+                    Ok(()) => queued = queued.saturating_add(1),
+                    Err(e) if e == Error::<T>::TooManyPendingCancellations.into() => {
+                        remaining = remaining.saturating_add(1)
+                    }
+                    Err(_) => {}
+                }
+            }
+
+            Self::deposit_event(Event::CancelAllQueued {
+                trader: trader.clone(),
+                side,
+                queued,
+                remaining,
+            });
+
+            remaining
+        }
+
+        // carries on with cancel-all requests from earlier blocks, returns how many were looked at
+        pub(crate) fn continue_cancel_all() -> u32 {
+            let queue = CancelAllQueue::<T>::get();
+            if queue.is_empty() {
+                return 0;
+            }
+
+            let processed = queue.len() as u32;
+            let mut waiting = queue;
+            waiting.retain(|(trader, side)| Self::queue_cancel_all(trader, *side) > 0);
+            CancelAllQueue::<T>::put(waiting);
+
+            processed
+        }
+
+        // batch cancels only carry the order id, the market comes from the order
+        fn cancel_in_order_market(trader: T::AccountId, order_id: OrderId) -> DispatchResult {
+            let market_id = Orders::<T>::get(order_id)
+                .map(|order| order.market_id)
+                .ok_or(Error::<T>::OrderNotFound)?;
+            Self::do_cancel_order(trader, market_id, order_id)
+        }
+
+        pub(crate) fn do_create_market(pair: MarketPair) -> Result<MarketId, DispatchError> {
+            ensure!(
+                pair.base_asset != pair.quote_asset,
+                Error::<T>::InvalidMarketPair
+            );
+            ensure!(
+                !MarketsByPair::<T>::contains_key(pair),
+                Error::<T>::MarketAlreadyExists
+            );
+
+            let market_id = NextMarketId::<T>::get();
+            Markets::<T>::insert(
+                market_id,
+                Market {
+                    pair,
+                    status: MarketStatus::Active,
+                },
+            );
+            MarketsByPair::<T>::insert(pair, market_id);
+            NextMarketId::<T>::put(market_id + 1);
+
+            Self::deposit_event(Event::MarketCreated {
+                market_id,
+                base_asset: pair.base_asset,
+                quote_asset: pair.quote_asset,
+            });
+
+            Ok(market_id)
+        }
+
+        fn do_set_market_status(
+            market_id: MarketId,
+            from: MarketStatus,
+            to: MarketStatus,
+        ) -> DispatchResult {
+            Markets::<T>::try_mutate(market_id, |maybe_market| {
+                let market = maybe_market.as_mut().ok_or(Error::<T>::MarketNotFound)?;
+                ensure!(market.status == from, Error::<T>::InvalidMarketStatus);
+                market.status = to;
+                Ok::<_, DispatchError>(())
+            })?;
+
+            Self::deposit_event(Event::MarketStatusChanged {
+                market_id,
+                status: to,
+            });
+
+            Ok(())
+        }
+
+        /// Run cancellations, matching and settlement for a single market.
+        /// Returns how many resting orders had to be loaded for it and how many
+        /// stop orders were checked against its last trade price.
+        fn finalize_market(market_id: MarketId, market: Market) -> (u32, u32) {
+            //================================
+            // These will load the temp caches
+            //================================
+
+            // Load pending bids
+            let mut pending_bids = BTreeMap::new();
+            for (price, order_ids) in PendingBids::<T>::iter_prefix(market_id) {
+                pending_bids.insert(price, order_ids.into_inner());
+            }
+
+            // Load pending asks
+            let mut pending_asks = BTreeMap::new();
+            for (price, order_ids) in PendingAsks::<T>::iter_prefix(market_id) {
+                pending_asks.insert(price, order_ids.into_inner());
+            }
+
+            let cancellations = PendingCancellations::<T>::take(market_id);
+
+            // nothing new for this market, the book can't have changed
+            if pending_bids.is_empty() && pending_asks.is_empty() && cancellations.is_empty() {
+                return (0, 0);
+            }
+
+            // only pending orders, cancellations and the resting levels they touch are loaded
+            let mut orders_map = BTreeMap::new();
+            let new_ids = pending_bids
+                .values()
+                .chain(pending_asks.values())
+                .flatten()
+                .chain(cancellations.iter());
+            for order_id in new_ids {
+                if let Some(order) = Orders::<T>::get(order_id) {
+                    orders_map.insert(*order_id, order);
+                }
+            }
+
+            // Loaded slices of the persistent book, every price in the level sets is written back
+            let mut persistent_bids: BTreeMap<Amount, Vec<OrderId>> = BTreeMap::new();
+            let mut persistent_asks: BTreeMap<Amount, Vec<OrderId>> = BTreeMap::new();
+            let mut bid_levels: BTreeSet<Amount> = BTreeSet::new();
+            let mut ask_levels: BTreeSet<Amount> = BTreeSet::new();
+            let mut loaded = 0u32;
+
+            // levels of resting orders being cancelled
+            for order_id in cancellations.iter() {
+                let Some((side, price)) = orders_map.get(order_id).map(|o| (o.side, o.price))
+                else {
+                    continue;
+                };
+                let (book, levels) = match side {
+                    OrderSide::Buy => (&mut persistent_bids, &mut bid_levels),
+                    OrderSide::Sell => (&mut persistent_asks, &mut ask_levels),
+                };
+                loaded += Self::load_level(market_id, side, price, book, levels, &mut orders_map);
+            }
+
+            // own side levels at the pending prices, unmatched orders get appended there
+            for price in pending_bids.keys() {
+                loaded += Self::load_level(
+                    market_id,
+                    OrderSide::Buy,
+                    *price,
+                    &mut persistent_bids,
+                    &mut bid_levels,
+                    &mut orders_map,
+                );
+            }
+            for price in pending_asks.keys() {
+                loaded += Self::load_level(
+                    market_id,
+                    OrderSide::Sell,
+                    *price,
+                    &mut persistent_asks,
+                    &mut ask_levels,
+                    &mut orders_map,
+                );
+            }
+
+            let cancelled = process_cancellations::<T>(
+                cancellations.into_inner(),
+                &mut pending_bids,
+                &mut pending_asks,
+                &mut persistent_bids,
+                &mut persistent_asks,
+                &mut orders_map,
+            );
+
+            let mut all_trades: Vec<Trade<T>> = Vec::new();
+            // IOC remainders and killed FOK and market orders
+            let mut dropped: Vec<OrderId> = Vec::new();
+            // market orders that traded, they are done after this round
+            let mut market_orders: Vec<OrderId> = Vec::new();
+            let mut rejected: Vec<(OrderId, RejectReason)> = Vec::new();
+            let mut prevented: Vec<SelfTrade> = Vec::new();
+            // post-only orders are repriced by the smallest price step
+            let rules = Self::market_rules(market_id);
+            let tick = rules.tick_size;
+            let allocation = allocation(MarketMatchingMode::<T>::get(market_id), rules.lot_size);
+            let seed = Self::ordering_seed(market_id);
+
+            // a paused/delisted market keeps its pending orders untouched until it trades again
+            let mut trading = market.status == MarketStatus::Active;
+
+            if trading {
+                // opposite side levels the pending orders can reach, from the best price inwards
+                let (best_bid, best_ask) =
+                    (BestBid::<T>::get(market_id), BestAsk::<T>::get(market_id));
+                let (bid_limit, bid_demand) = Self::pending_reach(
+                    OrderSide::Buy,
+                    &pending_bids,
+                    &orders_map,
+                    best_ask.unwrap_or_default(),
+                );
+                let (ask_limit, ask_demand) = Self::pending_reach(
+                    OrderSide::Sell,
+                    &pending_asks,
+                    &orders_map,
+                    best_bid.unwrap_or_default(),
+                );
+
+                // their own resting orders may be prevented from trading instead of filling
+                let traders = |pending: &BTreeMap<Amount, Vec<OrderId>>| -> BTreeSet<T::AccountId> {
+                    pending
+                        .values()
+                        .flatten()
+                        .filter_map(|id| orders_map.get(id))
+                        .map(|order| order.trader.clone())
+                        .collect()
+                };
+                let (bid_traders, ask_traders) = (traders(&pending_bids), traders(&pending_asks));
+
+                if bid_demand > 0 && best_ask.is_some_and(|p| p <= bid_limit) {
+                    loaded += Self::load_crossing_levels(
+                        market_id,
+                        OrderSide::Sell,
+                        bid_limit,
+                        bid_demand,
+                        &bid_traders,
+                        &mut persistent_asks,
+                        &mut ask_levels,
+                        &mut orders_map,
+                    );
+                }
+                if ask_demand > 0 && best_bid.is_some_and(|p| p >= ask_limit) {
+                    loaded += Self::load_crossing_levels(
+                        market_id,
+                        OrderSide::Buy,
+                        ask_limit,
+                        ask_demand,
+                        &ask_traders,
+                        &mut persistent_bids,
+                        &mut bid_levels,
+                        &mut orders_map,
+                    );
+                }
+
+                // a repriced post-only order lands one tick off an opposite price in memory,
+                // those levels have to be loaded before the engine appends to them
+                let reprices = |pending: &BTreeMap<Amount, Vec<OrderId>>| {
+                    pending
+                        .values()
+                        .flatten()
+                        .filter_map(|id| orders_map.get(id))
+                        .any(|order| order.post_only == Some(PostOnly::Reprice))
+                };
+                let (reprice_bids, reprice_asks) =
+                    (reprices(&pending_bids), reprices(&pending_asks));
+
+                if reprice_bids {
+                    let targets: Vec<Amount> = pending_asks
+                        .keys()
+                        .chain(persistent_asks.keys())
+                        .filter_map(|&price| price.checked_sub(tick))
+                        .filter(|price| *price > 0)
+                        .collect();
+                    for price in targets {
+                        loaded += Self::load_level(
+                            market_id,
+                            OrderSide::Buy,
+                            price,
+                            &mut persistent_bids,
+                            &mut bid_levels,
+                            &mut orders_map,
+                        );
+                    }
+                }
+                if reprice_asks {
+                    let targets: Vec<Amount> = pending_bids
+                        .keys()
+                        .chain(persistent_bids.keys())
+                        .filter_map(|&price| price.checked_add(tick))
+                        .collect();
+                    for price in targets {
+                        loaded += Self::load_level(
+                            market_id,
+                            OrderSide::Sell,
+                            price,
+                            &mut persistent_asks,
+                            &mut ask_levels,
+                            &mut orders_map,
+                        );
+                    }
+                }
+
+                // here we are matching first only from the temp cache, then what is left of it
+                // against the persistent book, or everything in one auction. An error undoes
+                // both, the cache waits for the next block.
+                let snapshot = (
+                    orders_map.clone(),
+                    persistent_bids.clone(),
+                    persistent_asks.clone(),
+                );
+                let result = match MarketClearingMode::<T>::get(market_id) {
+                    ClearingMode::Continuous => match_pending_internal(
+                        pending_bids.clone(),
+                        pending_asks.clone(),
+                        &mut persistent_bids,
+                        &mut persistent_asks,
+                        &mut orders_map,
+                        tick,
+                        rules.lot_size,
+                        &*allocation,
+                        seed,
+                    )
+                    .and_then(|mut matched| {
+                        let persistent = match_persistent_storage(
+                            &mut persistent_bids,
+                            &mut persistent_asks,
+                            core::mem::take(&mut matched.unmatched),
+                            &mut orders_map,
+                            &*allocation,
+                        )?;
+                        Ok((matched, persistent))
+                    }),
+                    ClearingMode::BatchAuction => match_batch_auction(
+                        pending_bids.clone(),
+                        pending_asks.clone(),
+                        &mut persistent_bids,
+                        &mut persistent_asks,
+                        &mut orders_map,
+                        tick,
+                        rules.lot_size,
+                        &*allocation,
+                        LastTradePrice::<T>::get(market_id),
+                        seed,
+                    ),
+                };
+                let (matched, persistent) = match result {
+                    Ok(result) => {
+                        pending_bids.clear();
+                        pending_asks.clear();
+                        result
+                    }
+                    Err(error) => {
+                        (orders_map, persistent_bids, persistent_asks) = snapshot;
+                        trading = false;
+                        Self::deposit_event(Event::MatchingFailed { market_id, error });
+                        Default::default()
+                    }
+                };
+
+                all_trades.extend(matched.trades);
+                dropped.extend(matched.killed);
+                market_orders.extend(matched.market);
+                prevented.extend(matched.prevented);
+                rejected.extend(
+                    matched
+                        .rejected
+                        .into_iter()
+                        .map(|order_id| (order_id, RejectReason::PostOnlyWouldCross)),
+                );
+
+                all_trades.extend(persistent.trades);
+                dropped.extend(persistent.dropped);
+                prevented.extend(persistent.prevented);
+
+                for (order_id, old_price, remaining) in matched.repriced {
+                    let Some(mut order) = orders_map.get(&order_id).cloned() else {
+                        continue;
+                    };
+
+                    // a bid needs less quote at its new price
+                    if order.side == OrderSide::Buy {
+                        let excess = old_price
+                            .saturating_sub(order.price)
+                            .saturating_mul(remaining);
+                        Self::unlock_or_report(&order, market.pair.quote_asset, excess);
+                    }
+
+                    let open = order.status == OrderStatus::Open
+                        || order.status == OrderStatus::PartiallyFilled;
+                    if Self::insert_price_level(market_id, order.side, order.price).is_err() && open
+                    {
+                        let book = match order.side {
+                            OrderSide::Buy => &mut persistent_bids,
+                            OrderSide::Sell => &mut persistent_asks,
+                        };
+                        if let Some(ids) = book.get_mut(&order.price) {
+                            ids.retain(|id| *id != order_id);
+                        }
+                        order.status = OrderStatus::Cancelled;
+                        orders_map.insert(order_id, order);
+                        rejected.push((order_id, RejectReason::TooManyPriceLevels));
+                        continue;
+                    }
+
+                    Self::deposit_event(Event::OrderRepriced {
+                        market_id,
+                        order_id,
+                        trader: order.trader.clone(),
+                        old_price,
+                        new_price: order.price,
+                    });
+                }
+            }
+
+            // At this point, we have in memory done all necessary transactions
+            // Now we need to adjust order/money management
+            let MarketPair {
+                base_asset,
+                quote_asset,
+            } = market.pair;
+            let mut total_volume = 0u128;
+            // quote each market buy paid, the rest of its limit is refunded
+            let mut market_spent: BTreeMap<OrderId, Amount> = BTreeMap::new();
+            let fees = Self::market_fees(market_id);
+            let tiers = FeeTiers::<T>::get();
+            let fee_account = T::FeeAccount::get();
+            let now: u32 = frame_system::Pallet::<T>::block_number().saturated_into();
+
+            // each trade settles completely or not at all, one that can't is undone in the books
+            let mut settled: Vec<Trade<T>> = Vec::new();
+            for mut trade in all_trades.into_iter() {
+                // each side pays its fee out of the asset it receives, at its own tier
+                let quote_amount = trade.price.saturating_mul(trade.quantity);
+                let (maker, taker) = match trade.taker_side {
+                    OrderSide::Buy => (&trade.seller, &trade.buyer),
+                    OrderSide::Sell => (&trade.buyer, &trade.seller),
+                };
+                let (maker_received, taker_received) = match trade.taker_side {
+                    OrderSide::Buy => (quote_amount, trade.quantity),
+                    OrderSide::Sell => (trade.quantity, quote_amount),
+                };
+                let (maker_fee, _) =
+                    Self::tier_fees(fees, &tiers, Self::rolling_volume(maker, now));
+                let (_, taker_rate) =
+                    Self::tier_fees(fees, &tiers, Self::rolling_volume(taker, now));
+
+                let taker_fee = taker_rate.mul_floor(taker_received);
+                // the rebate is paid in quote on the quote volume, like the tiers count it. The
+                // fee account pays it, a trade whose rebate it can't fund is rolled back.
+                let (maker_fee, maker_rebate) = match maker_fee {
+                    MakerFee::Fee(rate) => (rate.mul_floor(maker_received), 0),
+                    MakerFee::Rebate(rate) => (0, rate.mul_floor(quote_amount)),
+                };
+                (trade.buyer_fee, trade.seller_fee) = match trade.taker_side {
+                    OrderSide::Buy => (taker_fee, maker_fee),
+                    OrderSide::Sell => (maker_fee, taker_fee),
+                };
+                trade.maker_rebate = maker_rebate;
+
+                let buy_order = orders_map
+                    .get(&trade.buy_order_id)
+                    .map(|order| (order.order_type, order.price));
+                // a bid filled below its limit locked more than it paid, the surplus goes back
+                let surplus = match buy_order {
+                    Some((OrderType::Limit, limit)) if limit > trade.price => {
+                        (limit - trade.price).saturating_mul(trade.quantity)
+                    }
+                    _ => 0,
+                };
+
+                if let Err(error) = Self::settle_trade(&trade, &market.pair, &fee_account, surplus)
+                {
+                    Self::unwind_trade(
+                        &trade,
+                        &mut orders_map,
+                        &mut persistent_bids,
+                        &mut persistent_asks,
+                        &mut dropped,
+                    );
+                    Self::deposit_event(Event::TradeRolledBack {
+                        market_id,
+                        buy_order_id: trade.buy_order_id,
+                        sell_order_id: trade.sell_order_id,
+                        price: trade.price,
+                        quantity: trade.quantity,
+                        error,
+                    });
+                    continue;
+                }
+
+                // Set trade_id
+                let trade_id = NextTradeId::<T>::get();
+                trade.trade_id = trade_id;
+
+                Self::record_volume(&trade.buyer, quote_amount, now);
+                Self::record_volume(&trade.seller, quote_amount, now);
+
+                // Store trade
+                Trades::<T>::insert(trade_id, trade.clone());
+                NextTradeId::<T>::put(trade_id + 1);
+
+                // Emit event
+                Self::deposit_event(Event::TradeExecuted {
+                    market_id,
+                    trade_id,
+                    buy_order_id: trade.buy_order_id,
+                    sell_order_id: trade.sell_order_id,
+                    buyer: trade.buyer.clone(),
+                    seller: trade.seller.clone(),
+                    price: trade.price,
+                    quantity: trade.quantity,
+                    taker_side: trade.taker_side,
+                    buyer_fee: trade.buyer_fee,
+                    seller_fee: trade.seller_fee,
+                    maker_rebate: trade.maker_rebate,
+                });
+
+                total_volume = total_volume.saturating_add(quote_amount);
+
+                if buy_order.is_some_and(|(order_type, _)| order_type.is_market()) {
+                    let spent = market_spent.entry(trade.buy_order_id).or_insert(0);
+                    *spent = spent.saturating_add(quote_amount);
+                }
+                settled.push(trade);
+            }
+            let all_trades = settled;
+
+            // self-trades that did not happen: what decrement-and-cancel took off and the
+            // orders the mode cancelled go back to the trader
+            let mut stp_cancelled: BTreeSet<OrderId> = BTreeSet::new();
+            for stp in prevented.iter() {
+                let Some(taker) = orders_map.get(&stp.taker) else {
+                    continue;
+                };
+                for order_id in [stp.taker, stp.maker] {
+                    let Some(order) = orders_map.get(&order_id) else {
+                        continue;
+                    };
+                    if stp.quantity > 0 {
+                        let (asset, amount) = match order.side {
+                            OrderSide::Buy => {
+                                (quote_asset, order.price.saturating_mul(stp.quantity))
+                            }
+                            OrderSide::Sell => (base_asset, stp.quantity),
+                        };
+                        Self::unlock_or_report(order, asset, amount);
+                    }
+                    // market orders are refunded with the other market orders
+                    if order.status == OrderStatus::Cancelled
+                        && !order.order_type.is_market()
+                        && stp_cancelled.insert(order_id)
+                    {
+                        Self::unlock_remaining(order, &market.pair);
+                    }
+                }
+
+                Self::deposit_event(Event::SelfTradePrevented {
+                    market_id,
+                    trader: taker.trader.clone(),
+                    taker_order_id: stp.taker,
+                    maker_order_id: stp.maker,
+                    mode: stp.mode,
+                    quantity: stp.quantity,
+                });
+            }
+
+            // Now we need to unlock funds which are cancelled
+            for order_id in cancelled.iter() {
+                let Some(order) = orders_map.get(order_id) else {
+                    continue;
+                };
+                Self::unlock_remaining(order, &market.pair);
+
+                Self::deposit_event(Event::OrderCancelled {
+                    market_id,
+                    order_id: *order_id,
+                    trader: order.trader.clone(),
+                });
+            }
+
+            for (order_id, reason) in rejected.iter() {
+                let Some(order) = orders_map.get(order_id) else {
+                    continue;
+                };
+                Self::unlock_remaining(order, &market.pair);
+
+                Self::deposit_event(Event::OrderRejected {
+                    market_id,
+                    order_id: *order_id,
+                    trader: order.trader.clone(),
+                    reason: *reason,
+                });
+            }
+
+            // same for IOC/FOK/market orders that could not be filled
+            for order_id in dropped.iter() {
+                let Some(order) = orders_map.get(order_id) else {
+                    continue;
+                };
+                Self::unlock_remaining(order, &market.pair);
+
+                let remaining = order.quantity.saturating_sub(order.filled_quantity);
+                if order.time_in_force == TimeInForce::FOK || order.order_type.is_market() {
+                    Self::deposit_event(Event::OrderKilled {
+                        market_id,
+                        order_id: *order_id,
+                        trader: order.trader.clone(),
+                        quantity: remaining,
+                    });
+                } else {
+                    Self::deposit_event(Event::OrderRemainderCancelled {
+                        market_id,
+                        order_id: *order_id,
+                        trader: order.trader.clone(),
+                        filled_quantity: order.filled_quantity,
+                        cancelled_quantity: remaining,
+                    });
+                }
+            }
+
+            // reduce-only sells that sold all the base they locked, the position is gone and
+            // the rest of the order with it
+            for (order_id, order) in orders_map.iter() {
+                let position_gone = order.reduce_only.is_some()
+                    && order.status == OrderStatus::Cancelled
+                    && !order.order_type.is_market()
+                    && !cancelled.contains(order_id)
+                    && !stp_cancelled.contains(order_id)
+                    && !dropped.contains(order_id)
+                    && !rejected.iter().any(|(id, _)| id == order_id);
+                if !position_gone {
+                    continue;
+                }
+                // nothing is left locked unless a trade of it was rolled back
+                Self::unlock_remaining(order, &market.pair);
+
+                Self::deposit_event(Event::OrderRemainderCancelled {
+                    market_id,
+                    order_id: *order_id,
+                    trader: order.trader.clone(),
+                    filled_quantity: order.filled_quantity,
+                    cancelled_quantity: order.quantity.saturating_sub(order.filled_quantity),
+                });
+            }
+
+            // market orders never rest: unspent quote and unfilled base go back
+            for order_id in market_orders.iter() {
+                let Some(order) = orders_map.get(order_id) else {
+                    continue;
+                };
+                let remaining = order.quantity.saturating_sub(order.filled_quantity);
+
+                let (asset, refund) = match (order.side, order.order_type) {
+                    (
+                        OrderSide::Buy,
+                        OrderType::Market { quote_limit }
+                        | OrderType::Spend {
+                            quote_amount: quote_limit,
+                        },
+                    ) => {
+                        let spent = market_spent.get(order_id).copied().unwrap_or_default();
+                        (quote_asset, quote_limit.saturating_sub(spent))
+                    }
+                    _ => (base_asset, order.open_quantity()),
+                };
+                if refund > 0 {
+                    Self::unlock_or_report(order, asset, refund);
+                }
+
+                if remaining > 0 {
+                    Self::deposit_event(Event::OrderRemainderCancelled {
+                        market_id,
+                        order_id: *order_id,
+                        trader: order.trader.clone(),
+                        filled_quantity: order.filled_quantity,
+                        cancelled_quantity: remaining,
+                    });
+                }
+            }
+
+            // Emit events for filled/partially filled:
+            for (order_id, order) in orders_map.iter() {
+                if order.status.is_finished() {
+                    Self::retire_order(order);
+                } else {
+                    Orders::<T>::insert(order_id, order);
+                }
+
+                if !trading {
+                    continue;
+                }
+
+                // Emit events for filled/partially filled orders
+                if order.status == OrderStatus::Filled {
+                    Self::deposit_event(Event::OrderFilled {
+                        market_id,
+                        order_id: *order_id,
+                        trader: order.trader.clone(),
+                    });
+                } else if order.status == OrderStatus::PartiallyFilled {
+                    // an iceberg only reports its current slice
+                    Self::deposit_event(Event::OrderPartiallyFilled {
+                        market_id,
+                        order_id: *order_id,
+                        trader: order.trader.clone(),
+                        filled_quantity: order.filled_quantity,
+                        remaining_quantity: order.shown_quantity(),
+                    });
+                }
+            }
+
+            // Clear Pending Bids and Asks, whatever is left only happens when the market is halted
+            let _ = PendingBids::<T>::clear_prefix(market_id, u32::MAX, None);
+            let _ = PendingAsks::<T>::clear_prefix(market_id, u32::MAX, None);
+
+            for (price, order_ids) in pending_bids.iter() {
+                if let Some(bounded) = Self::bounded_level::<T::MaxPendingOrders>(order_ids) {
+                    PendingBids::<T>::insert(market_id, price, bounded);
+                }
+            }
+
+            for (price, order_ids) in pending_asks.iter() {
+                if let Some(bounded) = Self::bounded_level::<T::MaxPendingOrders>(order_ids) {
+                    PendingAsks::<T>::insert(market_id, price, bounded);
+                }
+            }
+
+            // Here we modify the StorageDoubleMap, only the loaded levels are written back
+            bid_levels.extend(persistent_bids.keys());
+            ask_levels.extend(persistent_asks.keys());
+            Self::write_levels(
+                market_id,
+                OrderSide::Buy,
+                &bid_levels,
+                &persistent_bids,
+                &pending_bids,
+            );
+            Self::write_levels(
+                market_id,
+                OrderSide::Sell,
+                &ask_levels,
+                &persistent_asks,
+                &pending_asks,
+            );
+
+            //EMIT event about complete trades
+            if trading {
+                Self::deposit_event(Event::MatchingCompleted {
+                    market_id,
+                    total_trades: all_trades.len() as u32,
+                    total_volume,
+                });
+            }
+
+            // the price moved, stops that it reached go to the cache for the next block
+            let mut stops_checked = 0u32;
+            if let Some(last_trade) = all_trades.last() {
+                LastTradePrice::<T>::insert(market_id, last_trade.price);
+                stops_checked = Self::trigger_stop_orders(market_id, last_trade.price);
+            }
+
+            (loaded, stops_checked)
+        }
+
+        /// Moves every stop order the last trade price reached into the pending cache.
+        /// One that doesn't fit there yet stays in the trigger book for the next trade.
+        /// Returns the size of the trigger book that was checked.
+        pub(crate) fn trigger_stop_orders(market_id: MarketId, last_price: Amount) -> u32 {
+            let stops = StopOrders::<T>::get(market_id);
+            let checked = stops.len() as u32;
+            if stops.is_empty() {
+                return 0;
+            }
+
+            let mut waiting = Vec::new();
+            for stop in stops.into_iter() {
+                if !stop_triggered(stop.side, &stop.trigger, last_price) {
+                    waiting.push(stop);
+                    continue;
+                }
+
+                let Some(mut order) = Orders::<T>::get(stop.order_id) else {
+                    continue;
+                };
+
+                let queued = if order.order_type.is_market() {
+                    Self::push_pending(market_id, order.side, order.price, order.order_id)
+                } else {
+                    Self::insert_price_level(market_id, order.side, order.price).and_then(|_| {
+                        Self::push_pending(market_id, order.side, order.price, order.order_id)
+                    })
+                };
+                if queued.is_err() {
+                    waiting.push(stop);
+                    continue;
+                }
+
+                order.status = OrderStatus::Open;
+                Orders::<T>::insert(order.order_id, &order);
+
+                Self::deposit_event(Event::StopOrderTriggered {
+                    market_id,
+                    order_id: order.order_id,
+                    trader: order.trader,
+                    side: order.side,
+                    price: order.price,
+                    quantity: order.quantity,
+                    last_price,
+                });
+            }
+
+            // nothing was added, it can only have shrunk
+            if waiting.is_empty() {
+                StopOrders::<T>::remove(market_id);
+            } else {
+                StopOrders::<T>::insert(
+                    market_id,
+                    BoundedVec::<StopEntry, T::MaxStopOrders>::truncate_from(waiting),
+                );
+            }
+
+            checked
+        }
+
+        /// Drops every order that expires at block `now` from the book or the pending
+        /// cache and unlocks what is left of it. Orders that already finished are skipped.
+        /// Returns how many entries of the expiry index were read.
+        pub(crate) fn expire_orders(now: u32) -> u32 {
+            let order_ids = ExpiringOrders::<T>::take(now);
+            let read = order_ids.len() as u32;
+
+            for order_id in order_ids {
+                let Some(mut order) = Orders::<T>::get(order_id) else {
+                    continue;
+                };
+                if order.status != OrderStatus::Open && order.status != OrderStatus::PartiallyFilled
+                {
+                    continue;
+                }
+                let Some(market) = Markets::<T>::get(order.market_id) else {
+                    continue;
+                };
+
+                Self::remove_from_book(&order);
+                Self::unlock_remaining(&order, &market.pair);
+
+                order.status = OrderStatus::Expired;
+                Self::retire_order(&order);
+
+                Self::deposit_event(Event::OrderExpired {
+                    market_id: order.market_id,
+                    order_id,
+                    trader: order.trader,
+                    filled_quantity: order.filled_quantity,
+                    expired_quantity: order.quantity.saturating_sub(order.filled_quantity),
+                });
+            }
+
+            read
+        }
+
+        /// Removes the oldest trades beyond `TradeRetention` as far as `limit` allows and
+        /// folds them into `TradeArchiveCommitment`. Trades are stored in on_finalize along
+        /// with their `TradeExecuted` event and on_idle runs before it, so only trades that
+        /// were emitted in an earlier block are pruned.
+        pub(crate) fn prune_trades(limit: Weight) -> Weight {
+            let base = <T as Config>::WeightInfo::prune_trades(0);
+            let per_trade = <T as Config>::WeightInfo::prune_trades(1).saturating_sub(base);
+            if !base.saturating_add(per_trade).all_lte(limit) {
+                return Weight::zero();
+            }
+
+            let first_trade_id = PrunedTradesUpTo::<T>::get();
+            let keep_from = NextTradeId::<T>::get().saturating_sub(T::TradeRetention::get());
+            if first_trade_id >= keep_from {
+                return T::DbWeight::get().reads(2);
+            }
+
+            let mut commitment = TradeArchiveCommitment::<T>::get();
+            let mut trade_id = first_trade_id;
+            let mut used = base;
+            while trade_id < keep_from && used.saturating_add(per_trade).all_lte(limit) {
+                if let Some(trade) = Trades::<T>::take(trade_id) {
+                    commitment = Self::archive_trade(commitment, &trade);
+                }
+                trade_id = trade_id.saturating_add(1);
+                used = used.saturating_add(per_trade);
+            }
+
+            PrunedTradesUpTo::<T>::put(trade_id);
+            TradeArchiveCommitment::<T>::put(commitment);
+
+            Self::deposit_event(Event::TradesPruned {
+                first_trade_id,
+                last_trade_id: trade_id.saturating_sub(1),
+                commitment,
+            });
+
+            used
+        }
+
+        /// Next link of the archive hash chain: `hash(commitment ++ trade)`, with the trade
+        /// encoded like the fields of its `TradeExecuted` event so an archive built from
+        /// events can recompute it
+        pub(crate) fn archive_trade(commitment: T::Hash, trade: &Trade<T>) -> T::Hash {
+            let mut data = commitment.encode();
+            (
+                trade.market_id,
+                trade.trade_id,
+                trade.buy_order_id,
+                trade.sell_order_id,
+                &trade.buyer,
+                &trade.seller,
+                trade.price,
+                trade.quantity,
+                trade.taker_side,
+                trade.buyer_fee,
+                trade.seller_fee,
+                trade.maker_rebate,
+            )
+                .encode_to(&mut data);
+            T::Hashing::hash(&data)
+        }
+
+        /// Drops a filled, cancelled or expired order from `Orders` and `UserOrders`, a copy
+        /// goes to the trader's history when it is enabled
+        pub(crate) fn retire_order(order: &Order<T>) {
+            Orders::<T>::remove(order.order_id);
+            UserOrders::<T>::mutate(&order.trader, |order_ids| {
+                order_ids.retain(|id| *id != order.order_id)
+            });
+
+            if T::MaxOrderHistory::get() > 0 {
+                OrderHistory::<T>::mutate(&order.trader, |history| {
+                    let _ = history.force_insert_keep_right(history.len(), order.clone());
+                });
+            }
+        }
+
+        /// Takes one order out of its resting level or the pending cache (a halted market
+        /// keeps orders pending) and updates the price index of its side
+        fn remove_from_book(order: &Order<T>) {
+            let (market_id, side, price) = (order.market_id, order.side, order.price);
+
+            let mut level = match side {
+                OrderSide::Buy => Bids::<T>::get(market_id, price),
+                OrderSide::Sell => Asks::<T>::get(market_id, price),
+            }
+            .into_inner();
+            level.retain(|id| *id != order.order_id);
+
+            let mut pending = match side {
+                OrderSide::Buy => PendingBids::<T>::get(market_id, price),
+                OrderSide::Sell => PendingAsks::<T>::get(market_id, price),
+            }
+            .into_inner();
+            let pending_len = pending.len();
+            pending.retain(|id| *id != order.order_id);
+
+            if pending.len() != pending_len {
+                match (Self::bounded_level::<T::MaxPendingOrders>(&pending), side) {
+                    (Some(bounded), OrderSide::Buy) => {
+                        PendingBids::<T>::insert(market_id, price, bounded)
+                    }
+                    (Some(bounded), OrderSide::Sell) => {
+                        PendingAsks::<T>::insert(market_id, price, bounded)
+                    }
+                    (None, OrderSide::Buy) => PendingBids::<T>::remove(market_id, price),
+                    (None, OrderSide::Sell) => PendingAsks::<T>::remove(market_id, price),
+                }
+            }
+
+            let book = BTreeMap::from([(price, level)]);
+            let mut pending_levels = BTreeMap::new();
+            if !pending.is_empty() {
+                pending_levels.insert(price, pending);
+            }
+            Self::write_levels(
+                market_id,
+                side,
+                &BTreeSet::from([price]),
+                &book,
+                &pending_levels,
+            );
+        }
+
+        /// Price an order is kept at. Market orders have none, their quote limit bounds them
+        /// instead, a buy needs one above zero.
+        fn order_price(
+            side: OrderSide,
+            price: Amount,
+            order_type: OrderType,
+        ) -> Result<Amount, DispatchError> {
+            match order_type {
+                OrderType::Market { quote_limit }
+                | OrderType::Spend {
+                    quote_amount: quote_limit,
+                } => {
+                    ensure!(
+                        side == OrderSide::Sell || quote_limit > 0,
+                        Error::<T>::InvalidQuoteLimit
+                    );
+                    Ok(0)
+                }
+                OrderType::Limit => {
+                    ensure!(price > 0, Error::<T>::InvalidPrice);
+                    Ok(price)
+                }
+            }
+        }
+
+        /// Checks an order against the market tick, lot and min notional. A market buy is
+        /// held to the notional through its quote limit, a market sell has no price to check.
+        fn ensure_trading_rules(
+            market_id: MarketId,
+            side: OrderSide,
+            price: Amount,
+            quantity: Amount,
+            order_type: OrderType,
+        ) -> DispatchResult {
+            let rules = Self::market_rules(market_id);
+            ensure!(
+                quantity.is_multiple_of(rules.lot_size),
+                Error::<T>::QuantityNotOnLot
+            );
+
+            match order_type {
+                OrderType::Limit => {
+                    ensure!(
+                        price.is_multiple_of(rules.tick_size),
+                        Error::<T>::PriceNotOnTick
+                    );
+                    ensure!(
+                        price.saturating_mul(quantity) >= rules.min_notional,
+                        Error::<T>::BelowMinNotional
+                    );
+                }
+                OrderType::Market { quote_limit }
+                | OrderType::Spend {
+                    quote_amount: quote_limit,
+                } => {
+                    ensure!(
+                        side == OrderSide::Sell || quote_limit >= rules.min_notional,
+                        Error::<T>::BelowMinNotional
+                    );
+                }
+            }
+
+            Ok(())
+        }
+
+        /// Size of a spend before it matches: the most its quote amount could buy, a lot at a
+        /// time at one tick. It ends up as what the quote really bought. Only a buy that isn't
+        /// fill-or-kill can spend.
+        fn spend_size(
+            market_id: MarketId,
+            side: OrderSide,
+            time_in_force: TimeInForce,
+            quote_amount: Amount,
+        ) -> Result<Amount, DispatchError> {
+            ensure!(
+                side == OrderSide::Buy && time_in_force != TimeInForce::FOK,
+                Error::<T>::InvalidSpend
+            );
+            let rules = Self::market_rules(market_id);
+            let quantity = quote_amount / rules.tick_size;
+            Ok(quantity - quantity % rules.lot_size)
+        }
+
+        /// Size of a reduce-only sell and the base it locks: the free base balance, rounded
+        /// down to the lot size, or less if the order asks for less. A position close sells
+        /// all of it.
+        fn reduce_only_size(
+            trader: &T::AccountId,
+            market_id: MarketId,
+            market: &Market,
+            side: OrderSide,
+            quantity: Amount,
+            mode: ReduceOnly,
+        ) -> Result<(Amount, Option<Amount>), DispatchError> {
+            ensure!(side == OrderSide::Sell, Error::<T>::ReduceOnlyBuy);
+            let lot = Self::market_rules(market_id).lot_size;
+            let free = assets::Pallet::<T>::get_free_balance(trader, market.pair.base_asset);
+            let position = free.saturating_sub(free % lot);
+            ensure!(position > 0, Error::<T>::NoPositionToReduce);
+
+            Ok(match mode {
+                ReduceOnly::Reduce => (quantity, Some(quantity.min(position))),
+                ReduceOnly::ClosePosition => (position, Some(position)),
+            })
+        }
+
+        /// Locks what an order needs: quote for a bid (all of its quote limit for a market
+        /// buy), base for an ask. Returns the asset that was locked.
+        fn lock_order_funds(
+            trader: &T::AccountId,
+            market: &Market,
+            side: OrderSide,
+            price: Amount,
+            quantity: Amount,
+            order_type: OrderType,
+        ) -> Result<AssetId, DispatchError> {
+            let (asset, amount_to_lock) = match (side, order_type) {
+                (
+                    OrderSide::Buy,
+                    OrderType::Market { quote_limit }
+                    | OrderType::Spend {
+                        quote_amount: quote_limit,
+                    },
+                ) => (market.pair.quote_asset, quote_limit),
+                (OrderSide::Buy, OrderType::Limit) => {
+                    let total_amount = price
+                        .checked_mul(quantity)
+                        .ok_or(Error::<T>::ArithmeticOverflow)?;
+                    (market.pair.quote_asset, total_amount)
+                }
+                (OrderSide::Sell, _) => (market.pair.base_asset, quantity),
+            };
+            assets::Pallet::<T>::lock_funds(trader, asset, amount_to_lock)?;
+
+            Ok(asset)
+        }
+
+        /// Queues an order in the pending cache of its side
+        fn push_pending(
+            market_id: MarketId,
+            side: OrderSide,
+            price: Amount,
+            order_id: OrderId,
+        ) -> DispatchResult {
+            let push = |orders: &mut BoundedVec<OrderId, T::MaxPendingOrders>| {
+                orders
+                    .try_push(order_id)
+                    .map_err(|_| Error::<T>::TooManyPendingOrders)
+            };
+
+            match side {
+                OrderSide::Buy => PendingBids::<T>::try_mutate(market_id, price, push)?,
+                OrderSide::Sell => PendingAsks::<T>::try_mutate(market_id, price, push)?,
+            }
+
+            Ok(())
+        }
+
+        /// Unlocks what is still locked for the unfilled part of an order
+        fn unlock_remaining(order: &Order<T>, pair: &MarketPair) {
+            let (asset, amount) = Self::remaining_lock(order, pair);
+            if amount == 0 {
+                return;
+            }
+
+            Self::unlock_or_report(order, asset, amount);
+        }
+
+        /// Gives an order's trader back funds it no longer needs. Failing means the books and
+        /// the balances disagree, that is reported rather than left unnoticed.
+        fn unlock_or_report(order: &Order<T>, asset: AssetId, amount: Amount) {
+            if let Err(error) = assets::Pallet::<T>::unlock_funds(&order.trader, asset, amount) {
+                Self::deposit_event(Event::UnlockFailed {
+                    market_id: order.market_id,
+                    order_id: order.order_id,
+                    trader: order.trader.clone(),
+                    asset_id: asset,
+                    amount,
+                    error,
+                });
+            }
+        }
+
+        /// Pays out one trade: quote less the seller's fee to the seller, base less the buyer's
+        /// fee to the buyer, both fees to the fee account, the rebate to the maker and a bid's
+        /// price improvement back to the buyer. Any failure undoes all of it.
+        fn settle_trade(
+            trade: &Trade<T>,
+            pair: &MarketPair,
+            fee_account: &T::AccountId,
+            surplus: Amount,
+        ) -> DispatchResult {
+            with_transaction(|| {
+                let result = Self::transfer_trade(trade, pair, fee_account, surplus);
+                match result {
+                    Ok(()) => TransactionOutcome::Commit(Ok(())),
+                    Err(error) => TransactionOutcome::Rollback(Err(error)),
+                }
+            })
+        }
+
+        fn transfer_trade(
+            trade: &Trade<T>,
+            pair: &MarketPair,
+            fee_account: &T::AccountId,
+            surplus: Amount,
+        ) -> DispatchResult {
+            let quote_amount = trade.price.saturating_mul(trade.quantity);
+
+            // Transfer quote from buyer to seller
+            assets::Pallet::<T>::transfer_locked(
+                &trade.buyer,
+                &trade.seller,
+                pair.quote_asset,
+                quote_amount.saturating_sub(trade.seller_fee),
+            )?;
+            if trade.seller_fee > 0 {
+                assets::Pallet::<T>::transfer_locked(
+                    &trade.buyer,
+                    fee_account,
+                    pair.quote_asset,
+                    trade.seller_fee,
+                )?;
+            }
+
+            // Transfer base from seller to buyer
+            assets::Pallet::<T>::transfer_locked(
+                &trade.seller,
+                &trade.buyer,
+                pair.base_asset,
+                trade.quantity.saturating_sub(trade.buyer_fee),
+            )?;
+            if trade.buyer_fee > 0 {
+                assets::Pallet::<T>::transfer_locked(
+                    &trade.seller,
+                    fee_account,
+                    pair.base_asset,
+                    trade.buyer_fee,
+                )?;
+            }
+
+            if trade.maker_rebate > 0 {
+                let maker = match trade.taker_side {
+                    OrderSide::Buy => &trade.seller,
+                    OrderSide::Sell => &trade.buyer,
+                };
+                assets::Pallet::<T>::transfer_free(
+                    fee_account,
+                    maker,
+                    pair.quote_asset,
+                    trade.maker_rebate,
+                )?;
+            }
+
+            if surplus > 0 {
+                assets::Pallet::<T>::unlock_funds(&trade.buyer, pair.quote_asset, surplus)?;
+            }
+
+            Ok(())
+        }
+
+        /// Takes back a trade the engine made but settlement could not pay out. The maker gets
+        /// the quantity back and, if it had filled, rests again at the end of its price level.
+        /// The taker could only rest crossing it, so the rest of the taker is cancelled with
+        /// the other leftovers.
+        fn unwind_trade(
+            trade: &Trade<T>,
+            orders_map: &mut BTreeMap<OrderId, Order<T>>,
+            bids: &mut BTreeMap<Amount, Vec<OrderId>>,
+            asks: &mut BTreeMap<Amount, Vec<OrderId>>,
+            dropped: &mut Vec<OrderId>,
+        ) {
+            let (taker_id, maker_id) = match trade.taker_side {
+                OrderSide::Buy => (trade.buy_order_id, trade.sell_order_id),
+                OrderSide::Sell => (trade.sell_order_id, trade.buy_order_id),
+            };
+
+            for order_id in [taker_id, maker_id] {
+                let Some(order) = orders_map.get_mut(&order_id) else {
+                    continue;
+                };
+                order.filled_quantity = order.filled_quantity.saturating_sub(trade.quantity);
+                // already cancelled, its remainder is unlocked with the quantity given back
+                if order.status == OrderStatus::Cancelled {
+                    continue;
+                }
+
+                let book = match order.side {
+                    OrderSide::Buy => &mut *bids,
+                    OrderSide::Sell => &mut *asks,
+                };
+                let resting = order_id == maker_id
+                    && order.time_in_force == TimeInForce::GTC
+                    && !order.order_type.is_market();
+                if resting {
+                    if order.status == OrderStatus::Filled {
+                        book.entry(order.price).or_default().push(order_id);
+                    }
+                    // the fill comes back to the iceberg's slice
+                    if let Some(iceberg) = order.iceberg.as_mut() {
+                        iceberg.visible_quantity = iceberg
+                            .visible_quantity
+                            .saturating_add(trade.quantity)
+                            .min(iceberg.display_quantity);
+                    }
+                    order.status = if order.filled_quantity == 0 {
+                        OrderStatus::Open
+                    } else {
+                        OrderStatus::PartiallyFilled
+                    };
+                    continue;
+                }
+
+                if let Some(ids) = book.get_mut(&order.price) {
+                    ids.retain(|id| *id != order_id);
+                }
+                order.status = OrderStatus::Cancelled;
+                // market orders are refunded with the other market orders
+                if !order.order_type.is_market() && !dropped.contains(&order_id) {
+                    dropped.push(order_id);
+                }
+            }
+        }
+
+        /// What an order still has locked for its unfilled part. A market buy is only asked
+        /// before it traded, so that is all of its quote limit.
+        fn remaining_lock(order: &Order<T>, pair: &MarketPair) -> (AssetId, Amount) {
+            let remaining = order.open_quantity();
+            if remaining == 0 {
+                return (pair.quote_asset, 0);
+            }
+
+            match (order.side, order.order_type) {
+                (
+                    OrderSide::Buy,
+                    OrderType::Market { quote_limit }
+                    | OrderType::Spend {
+                        quote_amount: quote_limit,
+                    },
+                ) => (pair.quote_asset, quote_limit),
+                (OrderSide::Buy, OrderType::Limit) => {
+                    (pair.quote_asset, order.price.saturating_mul(remaining))
+                }
+                (OrderSide::Sell, _) => (pair.base_asset, remaining),
+            }
+        }
+
+        // highest tier the volume reaches, the market rates below the first one
+        fn tier_fees(fees: FeeRates, tiers: &[FeeTier], volume: Amount) -> (MakerFee, Permill) {
+            tiers
+                .iter()
+                .rev()
+                .find(|tier| volume >= tier.min_volume)
+                .map(|tier| (tier.maker, tier.taker))
+                .unwrap_or((MakerFee::Fee(fees.maker), fees.taker))
+        }
+
+        fn volume_bucket(now: u32) -> u32 {
+            now / T::VolumeBucketBlocks::get().max(1)
+        }
+
+        // quote volume over the current bucket and the ones before it that are still in the window
+        pub(crate) fn rolling_volume(who: &T::AccountId, now: u32) -> Amount {
+            let oldest = Self::volume_bucket(now)
+                .saturating_add(1)
+                .saturating_sub(T::VolumeBuckets::get());
+            TradingVolume::<T>::get(who)
+                .iter()
+                .filter(|(bucket, _)| *bucket >= oldest)
+                .fold(0, |total, (_, volume)| total.saturating_add(*volume))
+        }
+
+        fn record_volume(who: &T::AccountId, amount: Amount, now: u32) {
+            let current = Self::volume_bucket(now);
+            let oldest = current
+                .saturating_add(1)
+                .saturating_sub(T::VolumeBuckets::get());
+            TradingVolume::<T>::mutate(who, |buckets| {
+                buckets.retain(|(bucket, _)| *bucket >= oldest);
+                match buckets.last_mut() {
+                    Some((bucket, volume)) if *bucket == current => {
+                        *volume = volume.saturating_add(amount)
+                    }
+                    // everything left is inside the window, so there is room for one more
+                    _ => {
+                        let _ = buckets.try_push((current, amount));
+                    }
+                }
+            });
+        }
+
+        /// Invariant check: the assets pallet has exactly what the open, pending and
+        /// untriggered orders still need locked, nothing more. Reads every order and lock,
+        /// so it is meant for tests and try-runtime.
+        pub fn ensure_locked_funds() -> DispatchResult {
+            let mut expected: BTreeMap<(T::AccountId, AssetId), Amount> = BTreeMap::new();
+
+            for order in Orders::<T>::iter_values() {
+                if !matches!(
+                    order.status,
+                    OrderStatus::Open | OrderStatus::PartiallyFilled | OrderStatus::Untriggered
+                ) {
+                    continue;
+                }
+                let market =
+                    Markets::<T>::get(order.market_id).ok_or(Error::<T>::MarketNotFound)?;
+                let (asset, amount) = Self::remaining_lock(&order, &market.pair);
+
+                let locked = expected.entry((order.trader, asset)).or_insert(0);
+                *locked = locked.saturating_add(amount);
+            }
+
+            assets::Pallet::<T>::ensure_locked_balances(&expected)
+        }
+
+        /// All invariants of the pallet, what `try_state` runs after every block.
+        /// Reads the whole book, so it is meant for tests and try-runtime.
+        pub fn do_try_state() -> DispatchResult {
+            Self::ensure_book_entries()?;
+            Self::ensure_book_uncrossed()?;
+            Self::ensure_id_counters()?;
+            Self::ensure_locked_funds()
+        }
+
+        /// Invariant check: every id resting in `Bids`/`Asks` is an open order of that
+        /// market, side and price
+        pub fn ensure_book_entries() -> DispatchResult {
+            let bids = Bids::<T>::iter()
+                .map(|(market_id, price, ids)| (market_id, price, ids, OrderSide::Buy));
+            let asks = Asks::<T>::iter()
+                .map(|(market_id, price, ids)| (market_id, price, ids, OrderSide::Sell));
+
+            for (market_id, price, order_ids, side) in bids.chain(asks) {
+                for order_id in order_ids {
+                    let order = Orders::<T>::get(order_id).ok_or(Error::<T>::InvalidBookEntry)?;
+                    ensure!(
+                        matches!(
+                            order.status,
+                            OrderStatus::Open | OrderStatus::PartiallyFilled
+                        ) && order.market_id == market_id
+                            && order.side == side
+                            && order.price == price,
+                        Error::<T>::InvalidBookEntry
+                    );
+                }
+            }
+
+            Ok(())
+        }
+
+        /// Invariant check: no market has its best bid at or above its best ask,
+        /// matching leaves nothing crossed behind after `on_finalize`
+        pub fn ensure_book_uncrossed() -> DispatchResult {
+            for market_id in Markets::<T>::iter_keys() {
+                let best_bid = Bids::<T>::iter_prefix(market_id)
+                    .filter(|(_, ids)| !ids.is_empty())
+                    .map(|(price, _)| price)
+                    .max();
+                let best_ask = Asks::<T>::iter_prefix(market_id)
+                    .filter(|(_, ids)| !ids.is_empty())
+                    .map(|(price, _)| price)
+                    .min();
+
+                if let (Some(bid), Some(ask)) = (best_bid, best_ask) {
+                    ensure!(bid < ask, Error::<T>::BookCrossed);
+                }
+            }
+
+            Ok(())
+        }
+
+        /// Invariant check: `NextOrderId`/`NextTradeId` are above every order and trade
+        /// still in storage, ids are never handed out twice
+        pub fn ensure_id_counters() -> DispatchResult {
+            let next_order_id = NextOrderId::<T>::get();
+            let history = OrderHistory::<T>::iter_values()
+                .flat_map(|orders| orders.into_iter().map(|order| order.order_id));
+            for order_id in Orders::<T>::iter_keys().chain(history) {
+                ensure!(order_id < next_order_id, Error::<T>::IdCounterBehind);
+            }
+
+            let next_trade_id = NextTradeId::<T>::get();
+            for trade_id in Trades::<T>::iter_keys() {
+                ensure!(trade_id < next_trade_id, Error::<T>::IdCounterBehind);
+            }
+
+            Ok(())
+        }
+
+        /// Loads one resting price level and its orders unless it is already in memory,
+        /// returns the number of orders read
+        fn load_level(
+            market_id: MarketId,
+            side: OrderSide,
+            price: Amount,
+            book: &mut BTreeMap<Amount, Vec<OrderId>>,
+            levels: &mut BTreeSet<Amount>,
+            orders_map: &mut BTreeMap<OrderId, Order<T>>,
+        ) -> u32 {
+            if !levels.insert(price) {
+                return 0;
+            }
+
+            let order_ids = match side {
+                OrderSide::Buy => Bids::<T>::get(market_id, price),
+                OrderSide::Sell => Asks::<T>::get(market_id, price),
+            }
+            .into_inner();
+
+            let mut read = 0u32;
+            for order_id in order_ids.iter() {
+                if orders_map.contains_key(order_id) {
+                    continue;
+                }
+                if let Some(order) = Orders::<T>::get(order_id) {
+                    orders_map.insert(*order_id, order);
+                    read += 1;
+                }
+            }
+
+            book.insert(price, order_ids);
+            read
+        }
+
+        /// Worst price and total open quantity of one side of the pending cache.
+        /// Market orders can go all the way through the book, a market or spend buy for no
+        /// more than its quote budget pays for at the best opposite price.
+        fn pending_reach(
+            side: OrderSide,
+            pending: &BTreeMap<Amount, Vec<OrderId>>,
+            orders_map: &BTreeMap<OrderId, Order<T>>,
+            best_opposite: Amount,
+        ) -> (Amount, Amount) {
+            let mut limit = match side {
+                OrderSide::Buy => 0,
+                OrderSide::Sell => Amount::MAX,
+            };
+            let mut demand: Amount = 0;
+
+            for order in pending
+                .values()
+                .flatten()
+                .filter_map(|id| orders_map.get(id))
+            {
+                if order.status != OrderStatus::Open && order.status != OrderStatus::PartiallyFilled
+                {
+                    continue;
+                }
+                let reach = match (order.order_type, side) {
+                    (OrderType::Market { .. } | OrderType::Spend { .. }, OrderSide::Buy) => {
+                        Amount::MAX
+                    }
+                    (OrderType::Market { .. } | OrderType::Spend { .. }, OrderSide::Sell) => 0,
+                    (OrderType::Limit, _) => order.price,
+                };
+                limit = match side {
+                    OrderSide::Buy => limit.max(reach),
+                    OrderSide::Sell => limit.min(reach),
+                };
+                // every price further in is worse, so this is the most it can buy
+                let quantity = match order.order_type {
+                    OrderType::Market { quote_limit }
+                    | OrderType::Spend {
+                        quote_amount: quote_limit,
+                    } if side == OrderSide::Buy => order
+                        .open_quantity()
+                        .min(quote_limit / best_opposite.max(1)),
+                    _ => order.open_quantity(),
+                };
+                demand = demand.saturating_add(quantity);
+            }
+
+            (limit, demand)
+        }
+
+        /// Walks the price index of `side` from the best price and loads levels until they
+        /// stop crossing `limit` or hold enough quantity to cover `demand`.
+        /// Every level the engine can reach is therefore in memory, with no gaps.
+        /// Orders of `takers` don't count towards `demand`, they might not trade with them.
+        fn load_crossing_levels(
+            market_id: MarketId,
+            side: OrderSide,
+            limit: Amount,
+            demand: Amount,
+            takers: &BTreeSet<T::AccountId>,
+            book: &mut BTreeMap<Amount, Vec<OrderId>>,
+            levels: &mut BTreeSet<Amount>,
+            orders_map: &mut BTreeMap<OrderId, Order<T>>,
+        ) -> u32 {
+            let mut prices = match side {
+                OrderSide::Buy => BidPrices::<T>::get(market_id).into_inner(),
+                OrderSide::Sell => AskPrices::<T>::get(market_id).into_inner(),
+            };
+            // best bid is the highest one
+            if side == OrderSide::Buy {
+                prices.reverse();
+            }
+
+            let mut read = 0u32;
+            let mut depth: Amount = 0;
+
+            for price in prices {
+                let crosses = match side {
+                    OrderSide::Buy => price >= limit,
+                    OrderSide::Sell => price <= limit,
+                };
+                if !crosses || depth >= demand {
+                    break;
+                }
+
+                read += Self::load_level(market_id, side, price, book, levels, orders_map);
+
+                for order in book
+                    .get(&price)
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|id| orders_map.get(id))
+                {
+                    if (order.status == OrderStatus::Open
+                        || order.status == OrderStatus::PartiallyFilled)
+                        && !takers.contains(&order.trader)
+                    {
+                        depth = depth.saturating_add(order.open_quantity());
+                    }
+                }
+            }
+
+            read
+        }
+
+        /// Writes the loaded levels of one side back, drops emptied ones from the price index
+        /// and moves the best price pointer.
+        fn write_levels(
+            market_id: MarketId,
+            side: OrderSide,
+            levels: &BTreeSet<Amount>,
+            book: &BTreeMap<Amount, Vec<OrderId>>,
+            pending: &BTreeMap<Amount, Vec<OrderId>>,
+        ) {
+            let mut emptied = Vec::new();
+
+            for price in levels.iter() {
+                let order_ids = book.get(price).map(Vec::as_slice).unwrap_or_default();
+                match Self::bounded_level::<T::MaxOrders>(order_ids) {
+                    Some(bounded) => match side {
+                        OrderSide::Buy => Bids::<T>::insert(market_id, price, bounded),
+                        OrderSide::Sell => Asks::<T>::insert(market_id, price, bounded),
+                    },
+                    None => {
+                        match side {
+                            OrderSide::Buy => Bids::<T>::remove(market_id, price),
+                            OrderSide::Sell => Asks::<T>::remove(market_id, price),
+                        }
+                        // a halted market can still have orders waiting at this price
+                        if !pending.contains_key(price) {
+                            emptied.push(*price);
+                        }
+                    }
+                }
+            }
+
+            let mutate_index = |prices: &mut BoundedVec<Amount, T::MaxPriceLevels>| {
+                for price in emptied.iter() {
+                    if let Ok(pos) = prices.binary_search(price) {
+                        prices.remove(pos);
+                    }
+                }
+                match side {
+                    OrderSide::Buy => prices.last().copied(),
+                    OrderSide::Sell => prices.first().copied(),
+                }
+            };
+
+            match side {
+                OrderSide::Buy => {
+                    let best = BidPrices::<T>::mutate(market_id, mutate_index);
+                    BestBid::<T>::set(market_id, best);
+                }
+                OrderSide::Sell => {
+                    let best = AskPrices::<T>::mutate(market_id, mutate_index);
+                    BestAsk::<T>::set(market_id, best);
+                }
+            }
+        }
+
+        /// Adds a price to the sorted index of its side, no-op if it is already there
+        fn insert_price_level(
+            market_id: MarketId,
+            side: OrderSide,
+            price: Amount,
+        ) -> DispatchResult {
+            let insert = |prices: &mut BoundedVec<Amount, T::MaxPriceLevels>| {
+                if let Err(pos) = prices.binary_search(&price) {
+                    prices
+                        .try_insert(pos, price)
+                        .map_err(|_| Error::<T>::TooManyPriceLevels)?;
+                }
+                Ok::<_, DispatchError>(())
+            };
+
+            match side {
+                OrderSide::Buy => BidPrices::<T>::try_mutate(market_id, insert),
+                OrderSide::Sell => AskPrices::<T>::try_mutate(market_id, insert),
+            }
+        }
+
+        // Doing this so that its save and bounded(altho this is mostly guaranteed because its from pending asks/bids and also pendingcancellations)
+        fn bounded_level<S: Get<u32>>(order_ids: &[OrderId]) -> Option<BoundedVec<OrderId, S>> {
+            if order_ids.is_empty() {
+                return None;
+            }
+
+            let truncated: Vec<OrderId> =
+                order_ids.iter().take(S::get() as usize).cloned().collect();
+
+            BoundedVec::<OrderId, S>::try_from(truncated).ok()
+        }
+    }
+
+    // ======================================
+    // Getter functions for storage/for some reason, directly acccessing them doesnt work
+    // =======================================
+    impl<T: Config> Pallet<T> {
+        pub fn next_order_id() -> OrderId {
+            NextOrderId::<T>::get()
+        }
+
+        /// Get the next trade ID
+        pub fn next_trade_id() -> TradeId {
+            NextTradeId::<T>::get()
+        }
+
+        /// Get a market by ID
+        pub fn get_market(market_id: MarketId) -> Option<Market> {
+            Markets::<T>::get(market_id)
+        }
+
+        /// Fee rates that apply to a market
+        pub fn market_fees(market_id: MarketId) -> FeeRates {
+            MarketFees::<T>::get(market_id).unwrap_or(FeeRates {
+                maker: T::DefaultMakerFee::get(),
+                taker: T::DefaultTakerFee::get(),
+            })
+        }
+
+        /// Seed the market's new orders are shuffled with this block, `None` when they are
+        /// matched by id. It only depends on chain data, so anyone can redo the shuffle.
+        pub fn ordering_seed(market_id: MarketId) -> Option<[u8; 32]> {
+            let source = match MarketOrderingPolicy::<T>::get(market_id) {
+                OrderingPolicy::ById => return None,
+                OrderingPolicy::ParentHash => frame_system::Pallet::<T>::parent_hash(),
+                OrderingPolicy::Randomness => {
+                    T::Randomness::random(&(b"orderbook/ordering", market_id).encode()).0
+                }
+            };
+            Some(sp_core::hashing::blake2_256(&(source, market_id).encode()))
+        }
+
+        /// Tick size, lot size and min notional that apply to a market
+        pub fn market_rules(market_id: MarketId) -> TradingRules {
+            MarketRules::<T>::get(market_id).unwrap_or(TradingRules {
+                tick_size: T::DefaultTickSize::get(),
+                lot_size: T::DefaultLotSize::get(),
+                min_notional: T::DefaultMinNotional::get(),
+            })
+        }
+
+        /// Rolling volume of an account and the rates it pays in a market
+        pub fn effective_fees(market_id: MarketId, who: &T::AccountId) -> EffectiveFees {
+            let now: u32 = frame_system::Pallet::<T>::block_number().saturated_into();
+            let volume = Self::rolling_volume(who, now);
+            let (maker, taker) =
+                Self::tier_fees(Self::market_fees(market_id), &FeeTiers::<T>::get(), volume);
+            EffectiveFees {
+                volume,
+                maker,
+                taker,
+            }
+        }
+
+        /// Get an order by ID
+        pub fn get_order(order_id: OrderId) -> Option<Order<T>> {
+            Orders::<T>::get(order_id)
+        }
+
+        /// Get a trade by ID
+        pub fn get_trade(trade_id: TradeId) -> Option<Trade<T>> {
+            Trades::<T>::get(trade_id)
+        }
+
+        /// Get bids at a specific price level
+        pub fn get_bids_at_price(market_id: MarketId, price: Amount) -> Vec<OrderId> {
+            Bids::<T>::get(market_id, price).into_inner()
+        }
+
+        /// Get asks at a specific price level
+        pub fn get_asks_at_price(market_id: MarketId, price: Amount) -> Vec<OrderId> {
+            Asks::<T>::get(market_id, price).into_inner()
+        }
+
+        /// Get the orders that expire at a block
+        pub fn get_expiring_orders(block: u32) -> Vec<OrderId> {
+            ExpiringOrders::<T>::get(block).into_inner()
+        }
+
+        /// Get the untriggered stop orders of a market
+        pub fn get_stop_orders(market_id: MarketId) -> Vec<StopEntry> {
+            StopOrders::<T>::get(market_id).into_inner()
+        }
+
+        /// Get the price of the last trade in a market
+        pub fn get_last_trade_price(market_id: MarketId) -> Option<Amount> {
+            LastTradePrice::<T>::get(market_id)
+        }
+
+        /// Get the best bid/ask of a market after the last matching round
+        pub fn get_best_prices(market_id: MarketId) -> (Option<Amount>, Option<Amount>) {
+            (BestBid::<T>::get(market_id), BestAsk::<T>::get(market_id))
+        }
+
+        /// Get the sorted bid/ask price levels of a market
+        pub fn get_price_levels(market_id: MarketId) -> (Vec<Amount>, Vec<Amount>) {
+            (
+                BidPrices::<T>::get(market_id).into_inner(),
+                AskPrices::<T>::get(market_id).into_inner(),
+            )
+        }
+
+        /// Get pending bids at a specific price level
+        pub fn get_pending_bids_at_price(market_id: MarketId, price: Amount) -> Vec<OrderId> {
+            PendingBids::<T>::get(market_id, price).into_inner()
+        }
+
+        /// Get pending asks at a specific price level
+        pub fn get_pending_asks_at_price(market_id: MarketId, price: Amount) -> Vec<OrderId> {
+            PendingAsks::<T>::get(market_id, price).into_inner()
+        }
+
+        /// Get pending cancellations
+        pub fn get_pending_cancellations(market_id: MarketId) -> Vec<OrderId> {
+            PendingCancellations::<T>::get(market_id).into_inner()
         }
 
         /// Get user's orders
         pub fn get_user_orders(user: &T::AccountId) -> Vec<OrderId> {
             UserOrders::<T>::get(user).into_inner()
         }
+
+        /// Get user's finished orders, oldest first
+        pub fn get_order_history(user: &T::AccountId) -> Vec<Order<T>> {
+            OrderHistory::<T>::get(user).into_inner()
+        }
+    }
+}
+
+/// Randomness for runtimes without a source of their own: the parent block hash hashed with
+/// the subject. The author of the parent block knew it, so this only keeps a block's order
+/// out of its own author's hands.
+pub struct ParentHashRandomness<T>(core::marker::PhantomData<T>);
+
+impl<T: frame_system::Config>
+    frame_support::traits::Randomness<T::Hash, frame_system::pallet_prelude::BlockNumberFor<T>>
+    for ParentHashRandomness<T>
+{
+    fn random(subject: &[u8]) -> (T::Hash, frame_system::pallet_prelude::BlockNumberFor<T>) {
+        use codec::Encode;
+        use sp_runtime::traits::{Hash, One, Saturating};
+
+        let parent = frame_system::Pallet::<T>::parent_hash();
+        let known_since = frame_system::Pallet::<T>::block_number().saturating_sub(One::one());
+        (T::Hashing::hash(&(parent, subject).encode()), known_since)
     }
 }
@@ -0,0 +1,716 @@
+//! Storage migrations of the orderbook pallet
+
+use crate::{
+    Config, OrderHistory, Orders, Pallet, UserOrders,
+    types::{
+        Amount, AssetId, Iceberg, MarketId, MarketPair, Order, OrderId, OrderSide, OrderStatus,
+        OrderType, PostOnly, SelfTradePrevention, StopTrigger, TimeInForce, Trade, TradeId,
+    },
+};
+use codec::{Decode, Encode};
+use frame_support::{
+    Blake2_128Concat, BoundedVec,
+    migrations::VersionedMigration,
+    pallet_prelude::{OptionQuery, ValueQuery},
+    traits::{Get, UncheckedOnRuntimeUpgrade},
+    weights::Weight,
+};
+use sp_runtime::RuntimeDebug;
+use sp_std::{
+    collections::{btree_map::BTreeMap, btree_set::BTreeSet},
+    vec::Vec,
+};
+
+/// Order types before v1. A market order had a price and locked for it like a limit order.
+#[derive(Encode, Decode, Clone, Copy, RuntimeDebug, PartialEq, Eq)]
+pub enum V0OrderType {
+    Market,
+    Limit,
+}
+
+/// [`Order`] as stored before v1, when ETH/USDT was the only market
+#[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq)]
+pub struct V0Order<T: Config> {
+    pub order_id: OrderId,
+    pub trader: T::AccountId,
+    pub side: OrderSide,
+    pub status: OrderStatus,
+    pub order_type: V0OrderType,
+    pub price: Amount,
+    pub quantity: Amount,
+    pub filled_quantity: Amount,
+    pub ttl: Option<u32>,
+}
+
+impl<T: Config> V0Order<T> {
+    // a v0 market order was booked at its price and locked like a limit order, so it carries
+    // on as one
+    fn upgrade(self, market_id: MarketId) -> V1Order<T> {
+        V1Order {
+            order_id: self.order_id,
+            market_id,
+            trader: self.trader,
+            side: self.side,
+            status: self.status,
+            order_type: OrderType::Limit,
+            time_in_force: TimeInForce::GTC,
+            post_only: None,
+            stop: None,
+            price: self.price,
+            quantity: self.quantity,
+            filled_quantity: self.filled_quantity,
+            ttl: self.ttl,
+        }
+    }
+}
+
+/// [`Trade`] as stored before v1, without a market, a taker side or fees
+#[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq)]
+pub struct V0Trade<T: Config> {
+    pub trade_id: TradeId,
+    pub buyer: T::AccountId,
+    pub seller: T::AccountId,
+    pub buy_order_id: OrderId,
+    pub sell_order_id: OrderId,
+    pub price: Amount,
+    pub quantity: Amount,
+}
+
+impl<T: Config> V0Trade<T> {
+    // v0 charged no fees, the order placed last is the one that took liquidity
+    fn upgrade(self, market_id: MarketId) -> Trade<T> {
+        Trade {
+            trade_id: self.trade_id,
+            market_id,
+            buyer: self.buyer,
+            seller: self.seller,
+            buy_order_id: self.buy_order_id,
+            sell_order_id: self.sell_order_id,
+            price: self.price,
+            quantity: self.quantity,
+            taker_side: if self.buy_order_id > self.sell_order_id {
+                OrderSide::Buy
+            } else {
+                OrderSide::Sell
+            },
+            buyer_fee: 0,
+            seller_fee: 0,
+            maker_rebate: 0,
+        }
+    }
+}
+
+/// [`Order`] as stored in v1, without a self-trade prevention mode
+#[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq)]
+pub struct V1Order<T: Config> {
+    pub order_id: OrderId,
+    pub market_id: MarketId,
+    pub trader: T::AccountId,
+    pub side: OrderSide,
+    pub status: OrderStatus,
+    pub order_type: OrderType,
+    pub time_in_force: TimeInForce,
+    pub post_only: Option<PostOnly>,
+    pub stop: Option<StopTrigger>,
+    pub price: Amount,
+    pub quantity: Amount,
+    pub filled_quantity: Amount,
+    pub ttl: Option<u32>,
+}
+
+impl<T: Config> V1Order<T> {
+    fn upgrade(self) -> V2Order<T> {
+        V2Order {
+            order_id: self.order_id,
+            market_id: self.market_id,
+            trader: self.trader,
+            side: self.side,
+            status: self.status,
+            order_type: self.order_type,
+            time_in_force: self.time_in_force,
+            post_only: self.post_only,
+            stop: self.stop,
+            price: self.price,
+            quantity: self.quantity,
+            filled_quantity: self.filled_quantity,
+            ttl: self.ttl,
+            self_trade: None,
+        }
+    }
+}
+
+/// [`Order`] as stored in v2, with a self-trade prevention mode but no iceberg
+#[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq)]
+pub struct V2Order<T: Config> {
+    pub order_id: OrderId,
+    pub market_id: MarketId,
+    pub trader: T::AccountId,
+    pub side: OrderSide,
+    pub status: OrderStatus,
+    pub order_type: OrderType,
+    pub time_in_force: TimeInForce,
+    pub post_only: Option<PostOnly>,
+    pub stop: Option<StopTrigger>,
+    pub price: Amount,
+    pub quantity: Amount,
+    pub filled_quantity: Amount,
+    pub ttl: Option<u32>,
+    pub self_trade: Option<SelfTradePrevention>,
+}
+
+impl<T: Config> V2Order<T> {
+    fn upgrade(self) -> V3Order<T> {
+        V3Order {
+            order_id: self.order_id,
+            market_id: self.market_id,
+            trader: self.trader,
+            side: self.side,
+            status: self.status,
+            order_type: self.order_type,
+            time_in_force: self.time_in_force,
+            post_only: self.post_only,
+            stop: self.stop,
+            price: self.price,
+            quantity: self.quantity,
+            filled_quantity: self.filled_quantity,
+            ttl: self.ttl,
+            self_trade: self.self_trade,
+            iceberg: None,
+        }
+    }
+}
+
+/// [`Order`] as stored in v3, with an iceberg but not reduce-only
+#[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq)]
+pub struct V3Order<T: Config> {
+    pub order_id: OrderId,
+    pub market_id: MarketId,
+    pub trader: T::AccountId,
+    pub side: OrderSide,
+    pub status: OrderStatus,
+    pub order_type: OrderType,
+    pub time_in_force: TimeInForce,
+    pub post_only: Option<PostOnly>,
+    pub stop: Option<StopTrigger>,
+    pub price: Amount,
+    pub quantity: Amount,
+    pub filled_quantity: Amount,
+    pub ttl: Option<u32>,
+    pub self_trade: Option<SelfTradePrevention>,
+    pub iceberg: Option<Iceberg>,
+}
+
+impl<T: Config> V3Order<T> {
+    fn upgrade(self) -> Order<T> {
+        Order {
+            order_id: self.order_id,
+            market_id: self.market_id,
+            trader: self.trader,
+            side: self.side,
+            status: self.status,
+            order_type: self.order_type,
+            time_in_force: self.time_in_force,
+            post_only: self.post_only,
+            stop: self.stop,
+            price: self.price,
+            quantity: self.quantity,
+            filled_quantity: self.filled_quantity,
+            ttl: self.ttl,
+            self_trade: self.self_trade,
+            iceberg: self.iceberg,
+            reduce_only: None,
+        }
+    }
+}
+
+pub mod v0 {
+    use super::*;
+
+    /// `Orders` in the layout v0 used
+    #[frame_support::storage_alias]
+    pub type Orders<T: Config> =
+        StorageMap<Pallet<T>, Blake2_128Concat, OrderId, V0Order<T>, OptionQuery>;
+
+    /// `Trades` in the layout v0 used
+    #[frame_support::storage_alias]
+    pub type Trades<T: Config> =
+        StorageMap<Pallet<T>, Blake2_128Concat, TradeId, V0Trade<T>, OptionQuery>;
+
+    /// `Bids` of the one market, keyed by price alone
+    #[frame_support::storage_alias]
+    pub type Bids<T: Config> = StorageMap<
+        Pallet<T>,
+        Blake2_128Concat,
+        Amount,
+        BoundedVec<OrderId, <T as Config>::MaxOrders>,
+        ValueQuery,
+    >;
+
+    /// `Asks` of the one market, keyed by price alone
+    #[frame_support::storage_alias]
+    pub type Asks<T: Config> = StorageMap<
+        Pallet<T>,
+        Blake2_128Concat,
+        Amount,
+        BoundedVec<OrderId, <T as Config>::MaxOrders>,
+        ValueQuery,
+    >;
+
+    /// `PendingBids` of the one market, keyed by price alone
+    #[frame_support::storage_alias]
+    pub type PendingBids<T: Config> = StorageMap<
+        Pallet<T>,
+        Blake2_128Concat,
+        Amount,
+        BoundedVec<OrderId, <T as Config>::MaxPendingOrders>,
+        ValueQuery,
+    >;
+
+    /// `PendingAsks` of the one market, keyed by price alone
+    #[frame_support::storage_alias]
+    pub type PendingAsks<T: Config> = StorageMap<
+        Pallet<T>,
+        Blake2_128Concat,
+        Amount,
+        BoundedVec<OrderId, <T as Config>::MaxPendingOrders>,
+        ValueQuery,
+    >;
+
+    /// `PendingCancellations` of the one market
+    #[frame_support::storage_alias]
+    pub type PendingCancellations<T: Config> = StorageValue<
+        Pallet<T>,
+        BoundedVec<OrderId, <T as Config>::MaxCancellationOrders>,
+        ValueQuery,
+    >;
+}
+
+pub mod v1 {
+    use super::*;
+
+    /// `Orders` in the layout v1 still used
+    #[frame_support::storage_alias]
+    pub type Orders<T: Config> =
+        StorageMap<Pallet<T>, Blake2_128Concat, OrderId, V1Order<T>, OptionQuery>;
+
+    /// `OrderHistory` in the layout v1 still used
+    #[frame_support::storage_alias]
+    pub type OrderHistory<T: Config> = StorageMap<
+        Pallet<T>,
+        Blake2_128Concat,
+        <T as frame_system::Config>::AccountId,
+        BoundedVec<V1Order<T>, <T as Config>::MaxOrderHistory>,
+        ValueQuery,
+    >;
+
+    /// v0 had one market, ETH/USDT, with its books keyed by price alone, and no fees. It is
+    /// listed as a market if it isn't yet, and orders, trades and books move into it: market
+    /// orders carry on as the limit orders they were booked as, trades were charged nothing.
+    ///
+    /// Before v1 cancelled and expired orders also stayed in `Orders` and no id ever left
+    /// `UserOrders`, so traders ran into `TooManyUserOrders` with nothing open. Finished
+    /// orders still stored go to the trader's history, ids of orders that are gone are dropped
+    /// from `UserOrders` and the books.
+    ///
+    /// v0 also never refunded a buy that traded below its price, the difference stayed locked.
+    /// Whatever a trader has locked beyond what their open orders need is released.
+    pub struct InnerMigrateToV1<T>(core::marker::PhantomData<T>);
+
+    impl<T: Config> UncheckedOnRuntimeUpgrade for InnerMigrateToV1<T> {
+        fn on_runtime_upgrade() -> Weight {
+            let mut reads = 1u64;
+            let mut writes = 0u64;
+
+            let pair = MarketPair {
+                base_asset: pallet_assets::ETH,
+                quote_asset: pallet_assets::USDT,
+            };
+            let market_id = match crate::MarketsByPair::<T>::get(pair) {
+                Some(market_id) => market_id,
+                None => match Pallet::<T>::do_create_market(pair) {
+                    Ok(market_id) => {
+                        reads = reads.saturating_add(1);
+                        writes = writes.saturating_add(3);
+                        market_id
+                    }
+                    Err(_) => return T::DbWeight::get().reads(reads),
+                },
+            };
+
+            Orders::<T>::translate::<V0Order<T>, _>(|_, old| {
+                reads = reads.saturating_add(1);
+                writes = writes.saturating_add(1);
+                Some(old.upgrade(market_id))
+            });
+            crate::Trades::<T>::translate::<V0Trade<T>, _>(|_, old| {
+                reads = reads.saturating_add(1);
+                writes = writes.saturating_add(1);
+                Some(old.upgrade(market_id))
+            });
+
+            let traders: Vec<T::AccountId> = UserOrders::<T>::iter_keys().collect();
+            for trader in traders {
+                let order_ids = UserOrders::<T>::get(&trader);
+                let mut history = OrderHistory::<T>::get(&trader);
+                let mut open = Vec::new();
+                let mut retired = 0u64;
+                reads = reads.saturating_add(2 + order_ids.len() as u64);
+
+                // ids are in placement order, so the history stays oldest first
+                for order_id in order_ids {
+                    match Orders::<T>::get(order_id) {
+                        Some(order) if order.status.is_finished() => {
+                            Orders::<T>::remove(order_id);
+                            let _ = history.force_insert_keep_right(history.len(), order);
+                            retired = retired.saturating_add(1);
+                        }
+                        Some(_) => open.push(order_id),
+                        None => {}
+                    }
+                }
+
+                if retired > 0 {
+                    OrderHistory::<T>::insert(&trader, history);
+                    writes = writes.saturating_add(retired + 1);
+                }
+                UserOrders::<T>::insert(&trader, BoundedVec::truncate_from(open));
+                writes = writes.saturating_add(1);
+            }
+
+            // only open orders are left in `Orders` now
+            let needed = open_locks::<T>();
+            let locks: Vec<_> = pallet_assets::LockedBalance::<T>::iter().collect();
+            reads = reads.saturating_add(needed.len() as u64 + locks.len() as u64);
+            for (trader, asset_id, locked) in locks {
+                let needed = needed
+                    .get(&(trader.clone(), asset_id))
+                    .copied()
+                    .unwrap_or_default();
+                let excess = locked.saturating_sub(needed);
+                if excess > 0
+                    && pallet_assets::Pallet::<T>::unlock_funds(&trader, asset_id, excess).is_ok()
+                {
+                    writes = writes.saturating_add(2);
+                }
+            }
+
+            // the books are drained before anything is written under the new keys, they share
+            // the storage prefix
+            let (mut bid_prices, mut ask_prices) = (BTreeSet::new(), BTreeSet::new());
+            let bids: Vec<_> = v0::Bids::<T>::drain().collect();
+            let asks: Vec<_> = v0::Asks::<T>::drain().collect();
+            let pending_bids: Vec<_> = v0::PendingBids::<T>::drain().collect();
+            let pending_asks: Vec<_> = v0::PendingAsks::<T>::drain().collect();
+            reads = reads.saturating_add(
+                (bids.len() + asks.len() + pending_bids.len() + pending_asks.len()) as u64,
+            );
+            writes = writes.saturating_add(
+                (bids.len() + asks.len() + pending_bids.len() + pending_asks.len()) as u64,
+            );
+            writes =
+                writes.saturating_add(move_levels::<T, _>(bids, &mut bid_prices, |price, ids| {
+                    crate::Bids::<T>::insert(market_id, price, ids)
+                }));
+            writes =
+                writes.saturating_add(move_levels::<T, _>(asks, &mut ask_prices, |price, ids| {
+                    crate::Asks::<T>::insert(market_id, price, ids)
+                }));
+            writes = writes.saturating_add(move_levels::<T, _>(
+                pending_bids,
+                &mut bid_prices,
+                |price, ids| crate::PendingBids::<T>::insert(market_id, price, ids),
+            ));
+            writes = writes.saturating_add(move_levels::<T, _>(
+                pending_asks,
+                &mut ask_prices,
+                |price, ids| crate::PendingAsks::<T>::insert(market_id, price, ids),
+            ));
+
+            // the sorted price index, best bid last and best ask first
+            crate::BestBid::<T>::set(market_id, bid_prices.last().copied());
+            crate::BestAsk::<T>::set(market_id, ask_prices.first().copied());
+            crate::BidPrices::<T>::insert(
+                market_id,
+                BoundedVec::truncate_from(bid_prices.into_iter().collect::<Vec<_>>()),
+            );
+            crate::AskPrices::<T>::insert(
+                market_id,
+                BoundedVec::truncate_from(ask_prices.into_iter().collect::<Vec<_>>()),
+            );
+
+            let cancellations = v0::PendingCancellations::<T>::take();
+            if !cancellations.is_empty() {
+                crate::PendingCancellations::<T>::insert(market_id, cancellations);
+            }
+            reads = reads.saturating_add(1);
+            writes = writes.saturating_add(6);
+
+            T::DbWeight::get().reads_writes(reads, writes)
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn pre_upgrade() -> Result<Vec<u8>, sp_runtime::TryRuntimeError> {
+            Ok((v0::Trades::<T>::iter_keys().count() as u64).encode())
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn post_upgrade(state: Vec<u8>) -> Result<(), sp_runtime::TryRuntimeError> {
+            let trades = u64::decode(&mut &state[..]).map_err(|_| "invalid pre-upgrade state")?;
+            frame_support::ensure!(
+                crate::Trades::<T>::iter_values().count() as u64 == trades,
+                "a trade was lost in the translation"
+            );
+            frame_support::ensure!(
+                Orders::<T>::iter_values().all(|order| !order.status.is_finished()),
+                "a finished order is still stored"
+            );
+            for order_ids in UserOrders::<T>::iter_values() {
+                frame_support::ensure!(
+                    order_ids.iter().all(Orders::<T>::contains_key),
+                    "UserOrders lists an order that is gone"
+                );
+            }
+            for (_, _, order_ids) in crate::Bids::<T>::iter().chain(crate::Asks::<T>::iter()) {
+                frame_support::ensure!(
+                    order_ids.iter().all(Orders::<T>::contains_key),
+                    "a book lists an order that is gone"
+                );
+            }
+            // `ensure_locked_funds` reads orders in the current layout, not yet the one here
+            pallet_assets::Pallet::<T>::ensure_locked_balances(&open_locks::<T>())?;
+            Ok(())
+        }
+    }
+
+    /// What the open orders lock, per trader and asset. Every v1 order is a limit order of
+    /// ETH/USDT.
+    fn open_locks<T: Config>() -> BTreeMap<(T::AccountId, AssetId), Amount> {
+        let mut locks: BTreeMap<(T::AccountId, AssetId), Amount> = BTreeMap::new();
+        for order in Orders::<T>::iter_values() {
+            if order.status.is_finished() {
+                continue;
+            }
+            let remaining = order.quantity.saturating_sub(order.filled_quantity);
+            let (asset_id, amount) = match order.side {
+                OrderSide::Buy => (pallet_assets::USDT, order.price.saturating_mul(remaining)),
+                OrderSide::Sell => (pallet_assets::ETH, remaining),
+            };
+            let locked = locks.entry((order.trader, asset_id)).or_insert(0);
+            *locked = locked.saturating_add(amount);
+        }
+        locks
+    }
+
+    /// Moves price levels of a v0 book into the market, without the orders that are gone.
+    /// Returns how many levels were written.
+    fn move_levels<T: Config, S: Get<u32>>(
+        levels: Vec<(Amount, BoundedVec<OrderId, S>)>,
+        prices: &mut BTreeSet<Amount>,
+        insert: impl Fn(Amount, BoundedVec<OrderId, S>),
+    ) -> u64 {
+        let mut written = 0u64;
+        for (price, mut order_ids) in levels {
+            order_ids.retain(|order_id| Orders::<T>::contains_key(order_id));
+            if order_ids.is_empty() {
+                continue;
+            }
+            insert(price, order_ids);
+            prices.insert(price);
+            written = written.saturating_add(1);
+        }
+        written
+    }
+
+    /// Runs [`InnerMigrateToV1`] once, when the on-chain version is 0
+    pub type MigrateToV1<T> = VersionedMigration<
+        0,
+        1,
+        InnerMigrateToV1<T>,
+        Pallet<T>,
+        <T as frame_system::Config>::DbWeight,
+    >;
+}
+
+pub mod v2 {
+    use super::*;
+
+    /// `Orders` in the layout v2 used
+    #[frame_support::storage_alias]
+    pub type Orders<T: Config> =
+        StorageMap<Pallet<T>, Blake2_128Concat, OrderId, V2Order<T>, OptionQuery>;
+
+    /// `OrderHistory` in the layout v2 used
+    #[frame_support::storage_alias]
+    pub type OrderHistory<T: Config> = StorageMap<
+        Pallet<T>,
+        Blake2_128Concat,
+        <T as frame_system::Config>::AccountId,
+        BoundedVec<V2Order<T>, <T as Config>::MaxOrderHistory>,
+        ValueQuery,
+    >;
+
+    /// v2 gave every order a self-trade prevention mode. Orders already stored, open or in a
+    /// history, get none and keep trading with their trader's other orders as before.
+    pub struct InnerMigrateToV2<T>(core::marker::PhantomData<T>);
+
+    impl<T: Config> UncheckedOnRuntimeUpgrade for InnerMigrateToV2<T> {
+        fn on_runtime_upgrade() -> Weight {
+            let mut translated = 0u64;
+
+            Orders::<T>::translate::<V1Order<T>, _>(|_, old| {
+                translated = translated.saturating_add(1);
+                Some(old.upgrade())
+            });
+            OrderHistory::<T>::translate::<BoundedVec<V1Order<T>, T::MaxOrderHistory>, _>(
+                |_, old| {
+                    translated = translated.saturating_add(1);
+                    let orders: Vec<V2Order<T>> = old.into_iter().map(V1Order::upgrade).collect();
+                    Some(BoundedVec::truncate_from(orders))
+                },
+            );
+
+            T::DbWeight::get().reads_writes(translated, translated)
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn pre_upgrade() -> Result<Vec<u8>, sp_runtime::TryRuntimeError> {
+            Ok((v1::Orders::<T>::iter_keys().count() as u64).encode())
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn post_upgrade(state: Vec<u8>) -> Result<(), sp_runtime::TryRuntimeError> {
+            let before = u64::decode(&mut &state[..]).map_err(|_| "invalid pre-upgrade state")?;
+            frame_support::ensure!(
+                Orders::<T>::iter_values().count() as u64 == before,
+                "an order was lost in the translation"
+            );
+            Ok(())
+        }
+    }
+
+    /// Runs [`InnerMigrateToV2`] once, when the on-chain version is 1
+    pub type MigrateToV2<T> = VersionedMigration<
+        1,
+        2,
+        InnerMigrateToV2<T>,
+        Pallet<T>,
+        <T as frame_system::Config>::DbWeight,
+    >;
+}
+
+pub mod v3 {
+    use super::*;
+
+    /// `Orders` in the layout v3 used
+    #[frame_support::storage_alias]
+    pub type Orders<T: Config> =
+        StorageMap<Pallet<T>, Blake2_128Concat, OrderId, V3Order<T>, OptionQuery>;
+
+    /// `OrderHistory` in the layout v3 used
+    #[frame_support::storage_alias]
+    pub type OrderHistory<T: Config> = StorageMap<
+        Pallet<T>,
+        Blake2_128Concat,
+        <T as frame_system::Config>::AccountId,
+        BoundedVec<V3Order<T>, <T as Config>::MaxOrderHistory>,
+        ValueQuery,
+    >;
+
+    /// v3 lets orders hide size as icebergs. Orders already stored show all of it.
+    pub struct InnerMigrateToV3<T>(core::marker::PhantomData<T>);
+
+    impl<T: Config> UncheckedOnRuntimeUpgrade for InnerMigrateToV3<T> {
+        fn on_runtime_upgrade() -> Weight {
+            let mut translated = 0u64;
+
+            Orders::<T>::translate::<V2Order<T>, _>(|_, old| {
+                translated = translated.saturating_add(1);
+                Some(old.upgrade())
+            });
+            OrderHistory::<T>::translate::<BoundedVec<V2Order<T>, T::MaxOrderHistory>, _>(
+                |_, old| {
+                    translated = translated.saturating_add(1);
+                    let orders: Vec<V3Order<T>> = old.into_iter().map(V2Order::upgrade).collect();
+                    Some(BoundedVec::truncate_from(orders))
+                },
+            );
+
+            T::DbWeight::get().reads_writes(translated, translated)
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn pre_upgrade() -> Result<Vec<u8>, sp_runtime::TryRuntimeError> {
+            Ok((v2::Orders::<T>::iter_keys().count() as u64).encode())
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn post_upgrade(state: Vec<u8>) -> Result<(), sp_runtime::TryRuntimeError> {
+            let before = u64::decode(&mut &state[..]).map_err(|_| "invalid pre-upgrade state")?;
+            frame_support::ensure!(
+                Orders::<T>::iter_values().count() as u64 == before,
+                "an order was lost in the translation"
+            );
+            Ok(())
+        }
+    }
+
+    /// Runs [`InnerMigrateToV3`] once, when the on-chain version is 2
+    pub type MigrateToV3<T> = VersionedMigration<
+        2,
+        3,
+        InnerMigrateToV3<T>,
+        Pallet<T>,
+        <T as frame_system::Config>::DbWeight,
+    >;
+}
+
+pub mod v4 {
+    use super::*;
+
+    /// v4 lets sells be reduce-only. Orders already stored lock and sell their whole size.
+    pub struct InnerMigrateToV4<T>(core::marker::PhantomData<T>);
+
+    impl<T: Config> UncheckedOnRuntimeUpgrade for InnerMigrateToV4<T> {
+        fn on_runtime_upgrade() -> Weight {
+            let mut translated = 0u64;
+
+            Orders::<T>::translate::<V3Order<T>, _>(|_, old| {
+                translated = translated.saturating_add(1);
+                Some(old.upgrade())
+            });
+            OrderHistory::<T>::translate::<BoundedVec<V3Order<T>, T::MaxOrderHistory>, _>(
+                |_, old| {
+                    translated = translated.saturating_add(1);
+                    let orders: Vec<Order<T>> = old.into_iter().map(V3Order::upgrade).collect();
+                    Some(BoundedVec::truncate_from(orders))
+                },
+            );
+
+            T::DbWeight::get().reads_writes(translated, translated)
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn pre_upgrade() -> Result<Vec<u8>, sp_runtime::TryRuntimeError> {
+            Ok((v3::Orders::<T>::iter_keys().count() as u64).encode())
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn post_upgrade(state: Vec<u8>) -> Result<(), sp_runtime::TryRuntimeError> {
+            let before = u64::decode(&mut &state[..]).map_err(|_| "invalid pre-upgrade state")?;
+            frame_support::ensure!(
+                Orders::<T>::iter_values().count() as u64 == before,
+                "an order was lost in the translation"
+            );
+            Pallet::<T>::ensure_locked_funds()
+        }
+    }
+
+    /// Runs [`InnerMigrateToV4`] once, when the on-chain version is 3
+    pub type MigrateToV4<T> = VersionedMigration<
+        3,
+        4,
+        InnerMigrateToV4<T>,
+        Pallet<T>,
+        <T as frame_system::Config>::DbWeight,
+    >;
+}
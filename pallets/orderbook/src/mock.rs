@@ -1,8 +1,11 @@
 use crate as pallet_orderbook;
-use frame_support::derive_impl;
-use frame_system::pallet;
-use sp_runtime::BuildStorage;
+use crate::types::{
+    Amount, MarketId, OrderRequest, OrderSide, OrderType, PostOnly, ReduceOnly,
+    SelfTradePrevention, StopKind, TimeInForce,
+};
+use frame_support::{derive_impl, dispatch::DispatchResult};
 use sp_runtime::traits::parameter_types;
+use sp_runtime::{BuildStorage, Permill};
 
 type Block = frame_system::mocking::MockBlock<Test>;
 
@@ -49,6 +52,32 @@ parameter_types! {
     pub const MaxCancellationOrders: u32 = 50;       // Max 50 cancellations per block in tests
     pub const MaxOrders: u32 = 1000;                 // Max 1000 orders per price level in tests
     pub const MaxUserOrders: u32 = 100;              // Max 100 orders per user in tests
+    pub const MaxOrderHistory: u32 = 5;              // Last 5 finished orders per user in tests
+    pub const MaxPriceLevels: u32 = 50;              // Max 50 price levels per side in tests
+    pub const MaxStopOrders: u32 = 50;               // Max 50 untriggered stops per market in tests
+    pub const MaxExpiringOrders: u32 = 100;          // Max 100 orders expiring in one block in tests
+    pub const MaxCancelAllRequests: u32 = 10;        // Max 10 cancel-alls carried to the next block in tests
+    pub const TradeRetention: u64 = 3;               // Only the last 3 trades stay stored in tests
+    pub const FeeAccount: u64 = 99;                  // Collects trading fees in tests
+    pub const DefaultMakerFee: Permill = Permill::zero(); // Fee free unless a test sets rates
+    pub const DefaultTakerFee: Permill = Permill::zero();
+    pub const VolumeBucketBlocks: u32 = 10;          // Volume buckets of 10 blocks in tests
+    pub const VolumeBuckets: u32 = 3;                // Rolling volume over the last 3 buckets
+    pub const MaxFeeTiers: u32 = 5;
+    pub const DefaultTickSize: u128 = 1;             // Any price and size unless a test sets rules
+    pub const DefaultLotSize: u128 = 1;
+    pub const DefaultMinNotional: u128 = 0;
+}
+
+// same subject, same output within a block, a new one every block
+pub struct TestRandomness;
+
+impl frame_support::traits::Randomness<sp_core::H256, u64> for TestRandomness {
+    fn random(subject: &[u8]) -> (sp_core::H256, u64) {
+        let block = System::block_number();
+        let seed = sp_io::hashing::blake2_256(&[subject, &block.to_le_bytes()].concat());
+        (seed.into(), block)
+    }
 }
 
 impl pallet_orderbook::Config for Test {
@@ -56,12 +85,173 @@ impl pallet_orderbook::Config for Test {
     type MaxCancellationOrders = MaxCancellationOrders;
     type MaxOrders = MaxOrders;
     type MaxUserOrders = MaxUserOrders;
+    type MaxOrderHistory = MaxOrderHistory;
+    type MaxPriceLevels = MaxPriceLevels;
+    type MaxStopOrders = MaxStopOrders;
+    type MaxExpiringOrders = MaxExpiringOrders;
+    type MaxCancelAllRequests = MaxCancelAllRequests;
+    type TradeRetention = TradeRetention;
+    type FeeAccount = FeeAccount;
+    type DefaultMakerFee = DefaultMakerFee;
+    type DefaultTakerFee = DefaultTakerFee;
+    type VolumeBucketBlocks = VolumeBucketBlocks;
+    type VolumeBuckets = VolumeBuckets;
+    type MaxFeeTiers = MaxFeeTiers;
+    type DefaultTickSize = DefaultTickSize;
+    type DefaultLotSize = DefaultLotSize;
+    type DefaultMinNotional = DefaultMinNotional;
+    type Randomness = TestRandomness;
     type WeightInfo = pallet_orderbook::weights::SubstrateWeight<Test>;
 }
 
 pub fn new_test_ext() -> sp_io::TestExternalities {
-    frame_system::GenesisConfig::<Test>::default()
+    let mut t = frame_system::GenesisConfig::<Test>::default()
         .build_storage()
-        .unwrap()
-        .into()
+        .unwrap();
+
+    // ETH/USDT is market 0, same as the dev chain
+    pallet_orderbook::GenesisConfig::<Test> {
+        markets: vec![(pallet_assets::ETH, pallet_assets::USDT)],
+        ..Default::default()
+    }
+    .assimilate_storage(&mut t)
+    .unwrap();
+
+    let mut ext: sp_io::TestExternalities = t.into();
+    // events are only recorded from block 1 on
+    ext.execute_with(|| System::set_block_number(1));
+    ext
+}
+
+// Runs a test and checks the pallet invariants on whatever state it leaves behind,
+// the same checks try-runtime runs after every block
+pub fn build_and_execute(test: impl FnOnce()) {
+    new_test_ext().execute_with(|| {
+        test();
+        frame_support::assert_ok!(Orderbook::do_try_state());
+        frame_support::assert_ok!(Assets::ensure_conservation());
+    });
+}
+
+// An order for a test to place, a GTC limit order on market 0 until a field says otherwise.
+// With a stop set it goes in through `place_stop_order` and only the side, price, quantity
+// and order type are used.
+#[derive(Clone, Copy)]
+pub struct TestOrder {
+    request: OrderRequest,
+    stop: Option<(StopKind, Amount)>,
+}
+
+impl TestOrder {
+    pub fn new(side: OrderSide, price: Amount, quantity: Amount) -> Self {
+        Self {
+            request: OrderRequest {
+                market_id: 0,
+                side,
+                price,
+                quantity,
+                order_type: OrderType::Limit,
+                time_in_force: TimeInForce::GTC,
+                post_only: None,
+                expires_at: None,
+                self_trade: None,
+                display_quantity: None,
+                reduce_only: None,
+            },
+            stop: None,
+        }
+    }
+
+    pub fn on_market(mut self, market_id: MarketId) -> Self {
+        self.request.market_id = market_id;
+        self
+    }
+
+    pub fn order_type(mut self, order_type: OrderType) -> Self {
+        self.request.order_type = order_type;
+        self
+    }
+
+    pub fn market(self, quote_limit: Amount) -> Self {
+        self.order_type(OrderType::Market { quote_limit })
+    }
+
+    pub fn spend(self, quote_amount: Amount) -> Self {
+        self.order_type(OrderType::Spend { quote_amount })
+    }
+
+    pub fn time_in_force(mut self, time_in_force: TimeInForce) -> Self {
+        self.request.time_in_force = time_in_force;
+        self
+    }
+
+    pub fn post_only(mut self, mode: PostOnly) -> Self {
+        self.request.post_only = Some(mode);
+        self
+    }
+
+    pub fn expires_at(mut self, block: u32) -> Self {
+        self.request.expires_at = Some(block);
+        self
+    }
+
+    pub fn self_trade(mut self, mode: SelfTradePrevention) -> Self {
+        self.request.self_trade = Some(mode);
+        self
+    }
+
+    pub fn display(mut self, quantity: Amount) -> Self {
+        self.request.display_quantity = Some(quantity);
+        self
+    }
+
+    pub fn reduce_only(mut self, mode: ReduceOnly) -> Self {
+        self.request.reduce_only = Some(mode);
+        self
+    }
+
+    pub fn stop(mut self, kind: StopKind, trigger_price: Amount) -> Self {
+        self.stop = Some((kind, trigger_price));
+        self
+    }
+
+    // for the batch calls
+    pub fn request(self) -> OrderRequest {
+        self.request
+    }
+
+    pub fn try_place(self, account: u64) -> DispatchResult {
+        let r = self.request;
+        let origin = RuntimeOrigin::signed(account);
+        match self.stop {
+            Some((kind, trigger_price)) => Orderbook::place_stop_order(
+                origin,
+                r.market_id,
+                r.side,
+                kind,
+                trigger_price,
+                r.price,
+                r.quantity,
+                r.order_type,
+            ),
+            None => Orderbook::place_order(
+                origin,
+                r.market_id,
+                r.side,
+                r.price,
+                r.quantity,
+                r.order_type,
+                r.time_in_force,
+                r.post_only,
+                r.expires_at,
+                r.self_trade,
+                r.display_quantity,
+                r.reduce_only,
+            ),
+        }
+    }
+
+    pub fn place(self, account: u64) {
+        frame_support::assert_ok!(self.try_place(account));
+    }
 }
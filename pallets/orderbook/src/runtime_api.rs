@@ -0,0 +1,9 @@
+use crate::types::{EffectiveFees, MarketId};
+
+sp_api::decl_runtime_apis! {
+    /// Read-only orderbook queries for clients
+    pub trait OrderbookApi<AccountId> where AccountId: codec::Codec {
+        /// Rolling volume of `who` and the maker/taker rates it pays in `market_id`
+        fn effective_fees(market_id: MarketId, who: AccountId) -> EffectiveFees;
+    }
+}
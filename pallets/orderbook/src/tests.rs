@@ -1,8 +1,12 @@
 use crate::mock::*;
 use crate::types::*;
-use frame_support::{assert_noop, assert_ok, traits::Hooks};
+use crate::weights::WeightInfo;
+use frame_support::{assert_noop, assert_ok, traits::Hooks, weights::Weight};
 use pallet_assets::{ETH, USDT};
 
+// Market 0 is listed in the mock genesis
+const ETH_USDT: MarketId = 0;
+
 // Simple u64 accounts for testing
 fn alice() -> u64 {
     1
@@ -32,17 +36,24 @@ fn fund_account(account: u64, usdt: u128, eth: u128) {
 
 #[test]
 fn test_place_buy_order_works() {
-    new_test_ext().execute_with(|| {
+    build_and_execute(|| {
         let alice = alice();
         fund_account(alice, 10_000, 0);
 
         // Place buy order: 10 ETH @ $100
         assert_ok!(Orderbook::place_order(
             RuntimeOrigin::signed(alice),
+            ETH_USDT,
             OrderSide::Buy,
             100,
             10,
             OrderType::Limit,
+            TimeInForce::GTC,
+            None,
+            None,
+            None,
+            None,
+            None,
         ));
 
         // Check order was created
@@ -61,7 +72,7 @@ fn test_place_buy_order_works() {
         assert_eq!(Assets::get_locked_balance(&alice, USDT), 1_000);
 
         // Check order was added to pending bids
-        let pending = Orderbook::get_pending_bids_at_price(100);
+        let pending = Orderbook::get_pending_bids_at_price(ETH_USDT, 100);
         assert_eq!(pending.len(), 1);
         assert_eq!(pending[0], 0);
     });
@@ -69,17 +80,24 @@ fn test_place_buy_order_works() {
 
 #[test]
 fn test_place_sell_order_works() {
-    new_test_ext().execute_with(|| {
+    build_and_execute(|| {
         let alice = alice();
         fund_account(alice, 0, 100);
 
         // Place sell order: 10 ETH @ $100
         assert_ok!(Orderbook::place_order(
             RuntimeOrigin::signed(alice),
+            ETH_USDT,
             OrderSide::Sell,
             100,
             10,
             OrderType::Limit,
+            TimeInForce::GTC,
+            None,
+            None,
+            None,
+            None,
+            None,
         ));
 
         // Check order was created
@@ -92,7 +110,7 @@ fn test_place_sell_order_works() {
         assert_eq!(Assets::get_locked_balance(&alice, ETH), 10);
 
         // Check order was added to pending asks
-        let pending = Orderbook::get_pending_asks_at_price(100);
+        let pending = Orderbook::get_pending_asks_at_price(ETH_USDT, 100);
         assert_eq!(pending.len(), 1);
         assert_eq!(pending[0], 0);
     });
@@ -100,7 +118,7 @@ fn test_place_sell_order_works() {
 
 #[test]
 fn test_place_multiple_orders_same_price() {
-    new_test_ext().execute_with(|| {
+    build_and_execute(|| {
         let alice = alice();
         let bob = bob();
         fund_account(alice, 10_000, 0);
@@ -109,23 +127,37 @@ fn test_place_multiple_orders_same_price() {
         // Alice places buy order
         assert_ok!(Orderbook::place_order(
             RuntimeOrigin::signed(alice),
+            ETH_USDT,
             OrderSide::Buy,
             100,
             5,
             OrderType::Limit,
+            TimeInForce::GTC,
+            None,
+            None,
+            None,
+            None,
+            None,
         ));
 
         // Bob places buy order at same price
         assert_ok!(Orderbook::place_order(
             RuntimeOrigin::signed(bob),
+            ETH_USDT,
             OrderSide::Buy,
             100,
             10,
             OrderType::Limit,
+            TimeInForce::GTC,
+            None,
+            None,
+            None,
+            None,
+            None,
         ));
 
         // Check both orders in pending bids
-        let pending = Orderbook::get_pending_bids_at_price(100);
+        let pending = Orderbook::get_pending_bids_at_price(ETH_USDT, 100);
         assert_eq!(pending.len(), 2);
         assert_eq!(pending[0], 0); // Alice first (FIFO)
         assert_eq!(pending[1], 1); // Bob second
@@ -137,21 +169,24 @@ fn test_place_multiple_orders_same_price() {
 
 #[test]
 fn test_place_market_order() {
-    new_test_ext().execute_with(|| {
+    build_and_execute(|| {
         let alice = alice();
         fund_account(alice, 10_000, 0);
 
         // Place market buy order (price is ignored)
-        assert_ok!(Orderbook::place_order(
-            RuntimeOrigin::signed(alice),
-            OrderSide::Buy,
-            200,
-            10,
-            OrderType::Market,
-        ));
+        TestOrder::new(OrderSide::Buy, 200, 10)
+            .market(2_500)
+            .place(alice);
 
         let order = Orderbook::get_order(0).expect("Order should exist");
-        assert_eq!(order.order_type, OrderType::Market);
+        assert_eq!(order.order_type, OrderType::Market { quote_limit: 2_500 });
+        assert_eq!(order.price, 0);
+        // the whole quote limit is locked, not price * quantity
+        assert_eq!(Assets::get_locked_balance(&alice, USDT), 2_500);
+        assert_eq!(
+            Orderbook::get_price_levels(ETH_USDT),
+            (Vec::new(), Vec::new())
+        );
     });
 }
 
@@ -161,17 +196,24 @@ fn test_place_market_order() {
 
 #[test]
 fn test_place_order_invalid_price_fails() {
-    new_test_ext().execute_with(|| {
+    build_and_execute(|| {
         let alice = alice();
         fund_account(alice, 10_000, 0);
 
         assert_noop!(
             Orderbook::place_order(
                 RuntimeOrigin::signed(alice),
+                ETH_USDT,
                 OrderSide::Buy,
                 0, // Invalid price for limit order
                 10,
                 OrderType::Limit,
+                TimeInForce::GTC,
+                None,
+                None,
+                None,
+                None,
+                None,
             ),
             crate::Error::<Test>::InvalidPrice
         );
@@ -180,18 +222,17 @@ fn test_place_order_invalid_price_fails() {
 
 #[test]
 fn test_place_order_invalid_quantity_fails() {
-    new_test_ext().execute_with(|| {
+    build_and_execute(|| {
         let alice = alice();
         fund_account(alice, 10_000, 0);
 
         assert_noop!(
-            Orderbook::place_order(
-                RuntimeOrigin::signed(alice),
-                OrderSide::Buy,
-                100,
-                0, // Invalid quantity
-                OrderType::Limit,
-            ),
+            TestOrder::new(OrderSide::Buy, 100, 0)
+                .order_type(
+                    // Invalid quantity
+                    OrderType::Limit
+                )
+                .try_place(alice),
             crate::Error::<Test>::InvalidQuantity
         );
     });
@@ -199,7 +240,7 @@ fn test_place_order_invalid_quantity_fails() {
 
 #[test]
 fn test_place_order_insufficient_balance_fails() {
-    new_test_ext().execute_with(|| {
+    build_and_execute(|| {
         let alice = alice();
         fund_account(alice, 500, 0); // Only 500 USDT
 
@@ -207,10 +248,17 @@ fn test_place_order_insufficient_balance_fails() {
         assert_noop!(
             Orderbook::place_order(
                 RuntimeOrigin::signed(alice),
+                ETH_USDT,
                 OrderSide::Buy,
                 100,
                 10,
                 OrderType::Limit,
+                TimeInForce::GTC,
+                None,
+                None,
+                None,
+                None,
+                None,
             ),
             pallet_assets::Error::<Test>::InsufficientFreeBalance
         );
@@ -219,19 +267,18 @@ fn test_place_order_insufficient_balance_fails() {
 
 #[test]
 fn test_place_order_arithmetic_overflow() {
-    new_test_ext().execute_with(|| {
+    build_and_execute(|| {
         let alice = alice();
         fund_account(alice, u128::MAX, 0);
 
         // Try to create order that would overflow
         assert_noop!(
-            Orderbook::place_order(
-                RuntimeOrigin::signed(alice),
-                OrderSide::Buy,
-                u128::MAX,
-                u128::MAX, // This would overflow when multiplied
-                OrderType::Limit,
-            ),
+            TestOrder::new(OrderSide::Buy, u128::MAX, u128::MAX)
+                .order_type(
+                    // This would overflow when multiplied
+                    OrderType::Limit
+                )
+                .try_place(alice),
             crate::Error::<Test>::ArithmeticOverflow
         );
     });
@@ -243,27 +290,35 @@ fn test_place_order_arithmetic_overflow() {
 
 #[test]
 fn test_cancel_order_works() {
-    new_test_ext().execute_with(|| {
+    build_and_execute(|| {
         let alice = alice();
         fund_account(alice, 10_000, 0);
 
         // Place order
         assert_ok!(Orderbook::place_order(
             RuntimeOrigin::signed(alice),
+            ETH_USDT,
             OrderSide::Buy,
             100,
             10,
             OrderType::Limit,
+            TimeInForce::GTC,
+            None,
+            None,
+            None,
+            None,
+            None,
         ));
 
         // Cancel order
         assert_ok!(Orderbook::cancel_order(
             RuntimeOrigin::signed(alice),
+            ETH_USDT,
             0, // order_id
         ));
 
         // Check cancellation was queued
-        let cancellations = Orderbook::get_pending_cancellations();
+        let cancellations = Orderbook::get_pending_cancellations(ETH_USDT);
         assert_eq!(cancellations.len(), 1);
         assert_eq!(cancellations[0], 0);
     });
@@ -271,7 +326,7 @@ fn test_cancel_order_works() {
 
 #[test]
 fn test_cancel_order_not_owner_fails() {
-    new_test_ext().execute_with(|| {
+    build_and_execute(|| {
         let alice = alice();
         let bob = bob();
         fund_account(alice, 10_000, 0);
@@ -279,15 +334,22 @@ fn test_cancel_order_not_owner_fails() {
         // Alice places order
         assert_ok!(Orderbook::place_order(
             RuntimeOrigin::signed(alice),
+            ETH_USDT,
             OrderSide::Buy,
             100,
             10,
             OrderType::Limit,
+            TimeInForce::GTC,
+            None,
+            None,
+            None,
+            None,
+            None,
         ));
 
         // Bob tries to cancel Alice's order - should fail
         assert_noop!(
-            Orderbook::cancel_order(RuntimeOrigin::signed(bob), 0,),
+            Orderbook::cancel_order(RuntimeOrigin::signed(bob), ETH_USDT, 0,),
             crate::Error::<Test>::NotOrderOwner
         );
     });
@@ -295,12 +357,13 @@ fn test_cancel_order_not_owner_fails() {
 
 #[test]
 fn test_cancel_nonexistent_order_fails() {
-    new_test_ext().execute_with(|| {
+    build_and_execute(|| {
         let alice = alice();
 
         assert_noop!(
             Orderbook::cancel_order(
                 RuntimeOrigin::signed(alice),
+                ETH_USDT,
                 999, // Doesn't exist
             ),
             crate::Error::<Test>::OrderNotFound
@@ -310,7 +373,7 @@ fn test_cancel_nonexistent_order_fails() {
 
 #[test]
 fn test_cancel_multiple_orders() {
-    new_test_ext().execute_with(|| {
+    build_and_execute(|| {
         let alice = alice();
         fund_account(alice, 10_000, 0);
 
@@ -318,19 +381,34 @@ fn test_cancel_multiple_orders() {
         for i in 0..3 {
             assert_ok!(Orderbook::place_order(
                 RuntimeOrigin::signed(alice),
+                ETH_USDT,
                 OrderSide::Buy,
                 100 + i as u128,
                 10,
                 OrderType::Limit,
+                TimeInForce::GTC,
+                None,
+                None,
+                None,
+                None,
+                None,
             ));
         }
 
         // Cancel first and third order
-        assert_ok!(Orderbook::cancel_order(RuntimeOrigin::signed(alice), 0));
-        assert_ok!(Orderbook::cancel_order(RuntimeOrigin::signed(alice), 2));
+        assert_ok!(Orderbook::cancel_order(
+            RuntimeOrigin::signed(alice),
+            ETH_USDT,
+            0
+        ));
+        assert_ok!(Orderbook::cancel_order(
+            RuntimeOrigin::signed(alice),
+            ETH_USDT,
+            2
+        ));
 
         // Check both cancellations queued
-        let cancellations = Orderbook::get_pending_cancellations();
+        let cancellations = Orderbook::get_pending_cancellations(ETH_USDT);
         assert_eq!(cancellations.len(), 2);
         assert_eq!(cancellations[0], 0);
         assert_eq!(cancellations[1], 2);
@@ -343,18 +421,17 @@ fn test_cancel_multiple_orders() {
 
 #[test]
 fn test_place_order_with_exact_balance() {
-    new_test_ext().execute_with(|| {
+    build_and_execute(|| {
         let alice = alice();
         fund_account(alice, 1_000, 0); // Exactly 1000 USDT
 
         // Buy exactly what we can afford
-        assert_ok!(Orderbook::place_order(
-            RuntimeOrigin::signed(alice),
-            OrderSide::Buy,
-            100,
-            10, // Exactly 1000 USDT needed
-            OrderType::Limit,
-        ));
+        TestOrder::new(OrderSide::Buy, 100, 10)
+            .order_type(
+                // Exactly 1000 USDT needed
+                OrderType::Limit,
+            )
+            .place(alice);
 
         // Should have locked all funds
         assert_eq!(Assets::get_free_balance(&alice, USDT), 0);
@@ -364,18 +441,17 @@ fn test_place_order_with_exact_balance() {
 
 #[test]
 fn test_place_order_one_wei_short_fails() {
-    new_test_ext().execute_with(|| {
+    build_and_execute(|| {
         let alice = alice();
         fund_account(alice, 999, 0); // One less than needed
 
         assert_noop!(
-            Orderbook::place_order(
-                RuntimeOrigin::signed(alice),
-                OrderSide::Buy,
-                100,
-                10, // Needs 1000 USDT
-                OrderType::Limit,
-            ),
+            TestOrder::new(OrderSide::Buy, 100, 10)
+                .order_type(
+                    // Needs 1000 USDT
+                    OrderType::Limit
+                )
+                .try_place(alice),
             pallet_assets::Error::<Test>::InsufficientFreeBalance
         );
     });
@@ -383,7 +459,7 @@ fn test_place_order_one_wei_short_fails() {
 
 #[test]
 fn test_sequential_orders_increment_ids() {
-    new_test_ext().execute_with(|| {
+    build_and_execute(|| {
         let alice = alice();
         fund_account(alice, 100_000, 1000);
 
@@ -391,6 +467,7 @@ fn test_sequential_orders_increment_ids() {
         for i in 0..5 {
             assert_ok!(Orderbook::place_order(
                 RuntimeOrigin::signed(alice),
+                ETH_USDT,
                 if i % 2 == 0 {
                     OrderSide::Buy
                 } else {
@@ -399,6 +476,12 @@ fn test_sequential_orders_increment_ids() {
                 100,
                 1,
                 OrderType::Limit,
+                TimeInForce::GTC,
+                None,
+                None,
+                None,
+                None,
+                None,
             ));
 
             // Check ID incremented correctly
@@ -413,17 +496,24 @@ fn test_sequential_orders_increment_ids() {
 
 #[test]
 fn test_large_order_values() {
-    new_test_ext().execute_with(|| {
+    build_and_execute(|| {
         let alice = alice();
         fund_account(alice, 1_000_000_000, 0); // 1 billion USDT
 
         // Place large order
         assert_ok!(Orderbook::place_order(
             RuntimeOrigin::signed(alice),
+            ETH_USDT,
             OrderSide::Buy,
             10_000,  // $10,000 per ETH
             100_000, // 100k ETH
             OrderType::Limit,
+            TimeInForce::GTC,
+            None,
+            None,
+            None,
+            None,
+            None,
         ));
 
         // Check huge amount locked (10k * 100k = 1 billion)
@@ -433,7 +523,7 @@ fn test_large_order_values() {
 
 #[test]
 fn test_different_users_different_orders() {
-    new_test_ext().execute_with(|| {
+    build_and_execute(|| {
         let alice = alice();
         let bob = bob();
         let charlie = charlie();
@@ -445,26 +535,47 @@ fn test_different_users_different_orders() {
         // Each user places different order
         assert_ok!(Orderbook::place_order(
             RuntimeOrigin::signed(alice),
+            ETH_USDT,
             OrderSide::Buy,
             100,
             10,
             OrderType::Limit,
+            TimeInForce::GTC,
+            None,
+            None,
+            None,
+            None,
+            None,
         ));
 
         assert_ok!(Orderbook::place_order(
             RuntimeOrigin::signed(bob),
+            ETH_USDT,
             OrderSide::Sell,
             105,
             20,
             OrderType::Limit,
+            TimeInForce::GTC,
+            None,
+            None,
+            None,
+            None,
+            None,
         ));
 
         assert_ok!(Orderbook::place_order(
             RuntimeOrigin::signed(charlie),
+            ETH_USDT,
             OrderSide::Buy,
             98,
             5,
             OrderType::Limit,
+            TimeInForce::GTC,
+            None,
+            None,
+            None,
+            None,
+            None,
         ));
 
         // Verify each order has correct owner
@@ -479,7 +590,7 @@ fn test_different_users_different_orders() {
 
 #[test]
 fn test_simple_buy_sell_match() {
-    new_test_ext().execute_with(|| {
+    build_and_execute(|| {
         let alice = alice();
         let bob = bob();
 
@@ -490,24 +601,38 @@ fn test_simple_buy_sell_match() {
         // Alice: Buy 10 ETH @ $100
         assert_ok!(Orderbook::place_order(
             RuntimeOrigin::signed(alice),
+            ETH_USDT,
             OrderSide::Buy,
             100,
             10,
             OrderType::Limit,
+            TimeInForce::GTC,
+            None,
+            None,
+            None,
+            None,
+            None,
         ));
 
         // Bob: Sell 10 ETH @ $100
         assert_ok!(Orderbook::place_order(
             RuntimeOrigin::signed(bob),
+            ETH_USDT,
             OrderSide::Sell,
             100,
             10,
             OrderType::Limit,
+            TimeInForce::GTC,
+            None,
+            None,
+            None,
+            None,
+            None,
         ));
 
         // Both orders pending
-        assert_eq!(Orderbook::get_pending_bids_at_price(100).len(), 1);
-        assert_eq!(Orderbook::get_pending_asks_at_price(100).len(), 1);
+        assert_eq!(Orderbook::get_pending_bids_at_price(ETH_USDT, 100).len(), 1);
+        assert_eq!(Orderbook::get_pending_asks_at_price(ETH_USDT, 100).len(), 1);
 
         // Trigger matching by advancing to next block
         System::set_block_number(1);
@@ -533,24 +658,34 @@ fn test_simple_buy_sell_match() {
         assert_eq!(Assets::get_locked_balance(&bob, USDT), 0);
         assert_eq!(Assets::get_locked_balance(&bob, ETH), 0);
 
-        // Verify orders are filled
-        let alice_order = Orderbook::get_order(0).unwrap();
-        assert_eq!(alice_order.status, OrderStatus::Filled);
-        assert_eq!(alice_order.filled_quantity, 10);
-
-        let bob_order = Orderbook::get_order(1).unwrap();
-        assert_eq!(bob_order.status, OrderStatus::Filled);
-        assert_eq!(bob_order.filled_quantity, 10);
+        // Verify orders are filled (filled orders are dropped from storage)
+        System::assert_has_event(
+            crate::Event::OrderFilled {
+                market_id: ETH_USDT,
+                order_id: 0,
+                trader: alice,
+            }
+            .into(),
+        );
+        System::assert_has_event(
+            crate::Event::OrderFilled {
+                market_id: ETH_USDT,
+                order_id: 1,
+                trader: bob,
+            }
+            .into(),
+        );
+        assert!(Orderbook::get_order(0).is_none());
+        assert!(Orderbook::get_order(1).is_none());
 
         // Verify trade ID incremented
         assert_eq!(Orderbook::next_trade_id(), 1);
     });
 }
 
-#[test]
 #[test]
 fn test_partial_fill_matching_debug() {
-    new_test_ext().execute_with(|| {
+    build_and_execute(|| {
         let alice = alice();
         let bob = bob();
 
@@ -564,10 +699,17 @@ fn test_partial_fill_matching_debug() {
         // Alice: Buy 10 ETH @ $100
         assert_ok!(Orderbook::place_order(
             RuntimeOrigin::signed(alice),
+            ETH_USDT,
             OrderSide::Buy,
             100,
             10,
             OrderType::Limit,
+            TimeInForce::GTC,
+            None,
+            None,
+            None,
+            None,
+            None,
         ));
 
         println!("\n=== After Alice order ===");
@@ -583,10 +725,17 @@ fn test_partial_fill_matching_debug() {
         // Bob: Sell only 5 ETH @ $100
         assert_ok!(Orderbook::place_order(
             RuntimeOrigin::signed(bob),
+            ETH_USDT,
             OrderSide::Sell,
             100,
             5,
             OrderType::Limit,
+            TimeInForce::GTC,
+            None,
+            None,
+            None,
+            None,
+            None,
         ));
 
         println!("\n=== After Bob order ===");
@@ -603,12 +752,8 @@ fn test_partial_fill_matching_debug() {
             alice_order.filled_quantity, alice_order.quantity
         );
 
-        let bob_order = Orderbook::get_order(1).unwrap();
-        println!("Bob order status: {:?}", bob_order.status);
-        println!(
-            "Bob filled: {}/{}",
-            bob_order.filled_quantity, bob_order.quantity
-        );
+        // Bob's order is filled and gone from storage
+        println!("Bob order: {:?}", Orderbook::get_order(1));
 
         println!("\n=== Final balances ===");
         println!(
@@ -639,7 +784,7 @@ fn test_partial_fill_matching_debug() {
 }
 #[test]
 fn test_price_time_priority_fifo() {
-    new_test_ext().execute_with(|| {
+    build_and_execute(|| {
         let alice = alice();
         let bob = bob();
         let charlie = charlie();
@@ -651,28 +796,49 @@ fn test_price_time_priority_fifo() {
         // Alice: Sell 5 ETH @ $100 (FIRST)
         assert_ok!(Orderbook::place_order(
             RuntimeOrigin::signed(alice),
+            ETH_USDT,
             OrderSide::Sell,
             100,
             5,
             OrderType::Limit,
+            TimeInForce::GTC,
+            None,
+            None,
+            None,
+            None,
+            None,
         ));
 
         // Bob: Sell 5 ETH @ $100 (SECOND - same price, later time)
         assert_ok!(Orderbook::place_order(
             RuntimeOrigin::signed(bob),
+            ETH_USDT,
             OrderSide::Sell,
             100,
             5,
             OrderType::Limit,
+            TimeInForce::GTC,
+            None,
+            None,
+            None,
+            None,
+            None,
         ));
 
         // Charlie: Buy 5 ETH @ $100
         assert_ok!(Orderbook::place_order(
             RuntimeOrigin::signed(charlie),
+            ETH_USDT,
             OrderSide::Buy,
             100,
             5,
             OrderType::Limit,
+            TimeInForce::GTC,
+            None,
+            None,
+            None,
+            None,
+            None,
         ));
 
         // Trigger matching
@@ -685,8 +851,7 @@ fn test_price_time_priority_fifo() {
         assert_eq!(trade.buyer, charlie);
 
         // Alice's order filled, Bob's still open
-        let alice_order = Orderbook::get_order(0).unwrap();
-        assert_eq!(alice_order.status, OrderStatus::Filled);
+        assert!(Orderbook::get_order(0).is_none());
 
         let bob_order = Orderbook::get_order(1).unwrap();
         assert_eq!(bob_order.status, OrderStatus::Open); // Still waiting!
@@ -703,7 +868,7 @@ fn test_price_time_priority_fifo() {
 
 #[test]
 fn test_no_match_price_spread() {
-    new_test_ext().execute_with(|| {
+    build_and_execute(|| {
         let alice = alice();
         let bob = bob();
 
@@ -713,19 +878,33 @@ fn test_no_match_price_spread() {
         // Alice: Buy @ $95
         assert_ok!(Orderbook::place_order(
             RuntimeOrigin::signed(alice),
+            ETH_USDT,
             OrderSide::Buy,
             95,
             10,
             OrderType::Limit,
+            TimeInForce::GTC,
+            None,
+            None,
+            None,
+            None,
+            None,
         ));
 
         // Bob: Sell @ $105 (no match - spread too wide)
         assert_ok!(Orderbook::place_order(
             RuntimeOrigin::signed(bob),
+            ETH_USDT,
             OrderSide::Sell,
             105,
             10,
             OrderType::Limit,
+            TimeInForce::GTC,
+            None,
+            None,
+            None,
+            None,
+            None,
         ));
 
         // Trigger matching
@@ -752,7 +931,7 @@ fn test_no_match_price_spread() {
 
 #[test]
 fn test_multiple_trades_same_block() {
-    new_test_ext().execute_with(|| {
+    build_and_execute(|| {
         let alice = alice();
         let bob = bob();
         let charlie = charlie();
@@ -764,28 +943,49 @@ fn test_multiple_trades_same_block() {
         // Alice: Buy 20 ETH @ $100
         assert_ok!(Orderbook::place_order(
             RuntimeOrigin::signed(alice),
+            ETH_USDT,
             OrderSide::Buy,
             100,
             20,
             OrderType::Limit,
+            TimeInForce::GTC,
+            None,
+            None,
+            None,
+            None,
+            None,
         ));
 
         // Bob: Sell 10 ETH @ $100
         assert_ok!(Orderbook::place_order(
             RuntimeOrigin::signed(bob),
+            ETH_USDT,
             OrderSide::Sell,
             100,
             10,
             OrderType::Limit,
+            TimeInForce::GTC,
+            None,
+            None,
+            None,
+            None,
+            None,
         ));
 
         // Charlie: Sell 10 ETH @ $100
         assert_ok!(Orderbook::place_order(
             RuntimeOrigin::signed(charlie),
+            ETH_USDT,
             OrderSide::Sell,
             100,
             10,
             OrderType::Limit,
+            TimeInForce::GTC,
+            None,
+            None,
+            None,
+            None,
+            None,
         ));
 
         // Trigger matching
@@ -808,9 +1008,15 @@ fn test_multiple_trades_same_block() {
         assert!(trade1.seller == charlie || trade2.seller == charlie);
 
         // Alice's order should be fully filled (20 ETH total)
-        let alice_order = Orderbook::get_order(0).unwrap();
-        assert_eq!(alice_order.status, OrderStatus::Filled);
-        assert_eq!(alice_order.filled_quantity, 20);
+        System::assert_has_event(
+            crate::Event::OrderFilled {
+                market_id: ETH_USDT,
+                order_id: 0,
+                trader: alice,
+            }
+            .into(),
+        );
+        assert!(Orderbook::get_order(0).is_none());
 
         // Alice should have 20 ETH, spent 2000 USDT
         assert_eq!(Assets::get_free_balance(&alice, ETH), 20);
@@ -827,24 +1033,35 @@ fn test_multiple_trades_same_block() {
 
 #[test]
 fn test_cancellation_unlocks_funds() {
-    new_test_ext().execute_with(|| {
+    build_and_execute(|| {
         let alice = alice();
         fund_account(alice, 10_000, 0);
 
         // Place order
         assert_ok!(Orderbook::place_order(
             RuntimeOrigin::signed(alice),
+            ETH_USDT,
             OrderSide::Buy,
             100,
             10,
             OrderType::Limit,
+            TimeInForce::GTC,
+            None,
+            None,
+            None,
+            None,
+            None,
         ));
 
         // Verify funds locked
         assert_eq!(Assets::get_locked_balance(&alice, USDT), 1_000);
 
         // Cancel order
-        assert_ok!(Orderbook::cancel_order(RuntimeOrigin::signed(alice), 0,));
+        assert_ok!(Orderbook::cancel_order(
+            RuntimeOrigin::signed(alice),
+            ETH_USDT,
+            0,
+        ));
 
         // Trigger finalization to process cancellation
         System::set_block_number(1);
@@ -854,16 +1071,15 @@ fn test_cancellation_unlocks_funds() {
         assert_eq!(Assets::get_free_balance(&alice, USDT), 10_000);
         assert_eq!(Assets::get_locked_balance(&alice, USDT), 0);
 
-        // Verify order cancelled
-        let order = Orderbook::get_order(0).unwrap();
-        assert_eq!(order.status, OrderStatus::Cancelled);
+        // Verify order cancelled and moved to the history
+        assert!(Orderbook::get_order(0).is_none());
+        assert_eq!(order_status(0), Some(OrderStatus::Cancelled));
     });
 }
 
-#[test]
 #[test]
 fn test_market_order_matches_best_price() {
-    new_test_ext().execute_with(|| {
+    build_and_execute(|| {
         let alice = alice();
         let bob = bob();
 
@@ -872,20 +1088,23 @@ fn test_market_order_matches_best_price() {
 
         assert_ok!(Orderbook::place_order(
             RuntimeOrigin::signed(alice),
+            ETH_USDT,
             OrderSide::Sell,
             95,
             10,
             OrderType::Limit,
+            TimeInForce::GTC,
+            None,
+            None,
+            None,
+            None,
+            None,
         ));
 
-        // For batch matching, market orders still use the price for locking funds
-        assert_ok!(Orderbook::place_order(
-            RuntimeOrigin::signed(bob),
-            OrderSide::Buy,
-            95, // Match at same price
-            10,
-            OrderType::Market,
-        ));
+        // Market orders lock their quote limit, what is left of it is refunded
+        TestOrder::new(OrderSide::Buy, 0, 10)
+            .market(1_000)
+            .place(bob);
 
         <Orderbook as Hooks<u64>>::on_finalize(1);
 
@@ -896,9 +1115,72 @@ fn test_market_order_matches_best_price() {
         assert_eq!(Assets::get_free_balance(&bob, ETH), 10);
     });
 }
+
+#[test]
+fn test_market_buy_loads_only_what_its_quote_limit_reaches() {
+    use frame_support::dispatch::DispatchClass;
+
+    build_and_execute(|| {
+        let alice = alice();
+        let bob = bob();
+        fund_account(alice, 0, 10);
+        fund_account(bob, 10_000, 0);
+        for price in 100..110 {
+            place_limit(alice, OrderSide::Sell, price, 1);
+        }
+        <Orderbook as Hooks<u64>>::on_finalize(1);
+
+        // asks for 10 ETH, but 200 USDT buys 2 at most
+        System::set_block_number(2);
+        TestOrder::new(OrderSide::Buy, 0, 10).market(200).place(bob);
+        let before = *System::block_weight().get(DispatchClass::Mandatory);
+        <Orderbook as Hooks<u64>>::on_finalize(2);
+
+        let weights = crate::weights::SubstrateWeight::<Test>::on_finalize_market;
+        assert_eq!(
+            System::block_weight()
+                .get(DispatchClass::Mandatory)
+                .saturating_sub(before),
+            weights(0, 2, 0).saturating_sub(weights(0, 0, 0))
+        );
+        assert_eq!(Assets::get_free_balance(&bob, ETH), 1);
+    });
+}
+
+#[test]
+fn test_spend_order_loads_only_what_its_quote_amount_reaches() {
+    use frame_support::dispatch::DispatchClass;
+
+    build_and_execute(|| {
+        let alice = alice();
+        let bob = bob();
+        fund_account(alice, 0, 10);
+        fund_account(bob, 10_000, 0);
+        for price in 100..110 {
+            place_limit(alice, OrderSide::Sell, price, 1);
+        }
+        <Orderbook as Hooks<u64>>::on_finalize(1);
+
+        // sized at the tick, 200 USDT would be 200 ETH, at the best ask it buys 2 at most
+        System::set_block_number(2);
+        TestOrder::new(OrderSide::Buy, 0, 0).spend(200).place(bob);
+        let before = *System::block_weight().get(DispatchClass::Mandatory);
+        <Orderbook as Hooks<u64>>::on_finalize(2);
+
+        let weights = crate::weights::SubstrateWeight::<Test>::on_finalize_market;
+        assert_eq!(
+            System::block_weight()
+                .get(DispatchClass::Mandatory)
+                .saturating_sub(before),
+            weights(0, 2, 0).saturating_sub(weights(0, 0, 0))
+        );
+        assert_eq!(Assets::get_free_balance(&bob, ETH), 1);
+    });
+}
+
 #[test]
 fn test_simple_buy_sell_match_debug() {
-    new_test_ext().execute_with(|| {
+    build_and_execute(|| {
         let alice = alice();
         let bob = bob();
 
@@ -911,28 +1193,42 @@ fn test_simple_buy_sell_match_debug() {
 
         assert_ok!(Orderbook::place_order(
             RuntimeOrigin::signed(alice),
+            ETH_USDT,
             OrderSide::Buy,
             100,
             10,
             OrderType::Limit,
+            TimeInForce::GTC,
+            None,
+            None,
+            None,
+            None,
+            None,
         ));
 
         assert_ok!(Orderbook::place_order(
             RuntimeOrigin::signed(bob),
+            ETH_USDT,
             OrderSide::Sell,
             100,
             10,
             OrderType::Limit,
+            TimeInForce::GTC,
+            None,
+            None,
+            None,
+            None,
+            None,
         ));
 
         println!("=== After orders placed ===");
         println!(
             "Pending bids at 100: {:?}",
-            Orderbook::get_pending_bids_at_price(100)
+            Orderbook::get_pending_bids_at_price(ETH_USDT, 100)
         );
         println!(
             "Pending asks at 100: {:?}",
-            Orderbook::get_pending_asks_at_price(100)
+            Orderbook::get_pending_asks_at_price(ETH_USDT, 100)
         );
         println!("Order 0: {:?}", Orderbook::get_order(0));
         println!("Order 1: {:?}", Orderbook::get_order(1));
@@ -951,3 +1247,4790 @@ fn test_simple_buy_sell_match_debug() {
         println!("Bob ETH: {}", Assets::get_free_balance(&bob, ETH));
     });
 }
+
+// ============================================
+// MARKET TESTS
+// ============================================
+
+const BTC: u32 = 2;
+
+// assets only takes deposits of USDT/ETH, so other assets are credited directly
+fn fund_asset(account: u64, asset_id: u32, amount: u128) {
+    pallet_assets::FreeBalance::<Test>::mutate(account, asset_id, |balance| *balance += amount);
+    pallet_assets::TotalIssuance::<Test>::mutate(asset_id, |issuance| *issuance += amount);
+}
+
+fn create_btc_market() -> MarketId {
+    assert_ok!(Orderbook::create_market(RuntimeOrigin::root(), BTC, USDT));
+    1
+}
+
+#[test]
+fn test_genesis_market_is_listed() {
+    build_and_execute(|| {
+        let market = Orderbook::get_market(ETH_USDT).expect("Market should exist");
+        assert_eq!(market.pair.base_asset, ETH);
+        assert_eq!(market.pair.quote_asset, USDT);
+        assert_eq!(market.status, MarketStatus::Active);
+    });
+}
+
+#[test]
+fn test_create_market_works() {
+    build_and_execute(|| {
+        let market_id = create_btc_market();
+
+        let market = Orderbook::get_market(market_id).expect("Market should exist");
+        assert_eq!(market.pair.base_asset, BTC);
+        assert_eq!(market.status, MarketStatus::Active);
+        System::assert_last_event(
+            crate::Event::MarketCreated {
+                market_id,
+                base_asset: BTC,
+                quote_asset: USDT,
+            }
+            .into(),
+        );
+    });
+}
+
+#[test]
+fn test_create_market_requires_root() {
+    build_and_execute(|| {
+        assert_noop!(
+            Orderbook::create_market(RuntimeOrigin::signed(alice()), BTC, USDT),
+            sp_runtime::DispatchError::BadOrigin
+        );
+    });
+}
+
+#[test]
+fn test_create_market_rejects_bad_pairs() {
+    build_and_execute(|| {
+        assert_noop!(
+            Orderbook::create_market(RuntimeOrigin::root(), ETH, USDT),
+            crate::Error::<Test>::MarketAlreadyExists
+        );
+        assert_noop!(
+            Orderbook::create_market(RuntimeOrigin::root(), USDT, USDT),
+            crate::Error::<Test>::InvalidMarketPair
+        );
+    });
+}
+
+#[test]
+fn test_place_order_unknown_market_fails() {
+    build_and_execute(|| {
+        let alice = alice();
+        fund_account(alice, 10_000, 0);
+
+        assert_noop!(
+            Orderbook::place_order(
+                RuntimeOrigin::signed(alice),
+                42,
+                OrderSide::Buy,
+                100,
+                10,
+                OrderType::Limit,
+                TimeInForce::GTC,
+                None,
+                None,
+                None,
+                None,
+                None,
+            ),
+            crate::Error::<Test>::MarketNotFound
+        );
+    });
+}
+
+#[test]
+fn test_cancel_order_wrong_market_fails() {
+    build_and_execute(|| {
+        let alice = alice();
+        fund_account(alice, 10_000, 0);
+        let btc_usdt = create_btc_market();
+
+        assert_ok!(Orderbook::place_order(
+            RuntimeOrigin::signed(alice),
+            ETH_USDT,
+            OrderSide::Buy,
+            100,
+            10,
+            OrderType::Limit,
+            TimeInForce::GTC,
+            None,
+            None,
+            None,
+            None,
+            None,
+        ));
+
+        assert_noop!(
+            Orderbook::cancel_order(RuntimeOrigin::signed(alice), btc_usdt, 0),
+            crate::Error::<Test>::MarketMismatch
+        );
+    });
+}
+
+#[test]
+fn test_markets_are_matched_separately() {
+    build_and_execute(|| {
+        let alice = alice();
+        let bob = bob();
+        let btc_usdt = create_btc_market();
+
+        fund_account(alice, 10_000, 0);
+        fund_asset(bob, BTC, 100);
+
+        // Alice bids for ETH, Bob offers BTC at the same price
+        assert_ok!(Orderbook::place_order(
+            RuntimeOrigin::signed(alice),
+            ETH_USDT,
+            OrderSide::Buy,
+            100,
+            10,
+            OrderType::Limit,
+            TimeInForce::GTC,
+            None,
+            None,
+            None,
+            None,
+            None,
+        ));
+        assert_ok!(Orderbook::place_order(
+            RuntimeOrigin::signed(bob),
+            btc_usdt,
+            OrderSide::Sell,
+            100,
+            10,
+            OrderType::Limit,
+            TimeInForce::GTC,
+            None,
+            None,
+            None,
+            None,
+            None,
+        ));
+
+        // BTC is locked, not ETH
+        assert_eq!(Assets::get_locked_balance(&bob, BTC), 10);
+
+        Orderbook::on_finalize(1);
+
+        // No cross-market trade
+        assert!(Orderbook::get_trade(0).is_none());
+        assert_eq!(Orderbook::get_bids_at_price(ETH_USDT, 100), vec![0]);
+        assert_eq!(Orderbook::get_asks_at_price(btc_usdt, 100), vec![1]);
+        assert!(Orderbook::get_asks_at_price(ETH_USDT, 100).is_empty());
+    });
+}
+
+#[test]
+fn test_trade_settles_in_market_assets() {
+    build_and_execute(|| {
+        let alice = alice();
+        let bob = bob();
+        let btc_usdt = create_btc_market();
+
+        fund_account(alice, 10_000, 0);
+        fund_asset(bob, BTC, 100);
+
+        assert_ok!(Orderbook::place_order(
+            RuntimeOrigin::signed(alice),
+            btc_usdt,
+            OrderSide::Buy,
+            100,
+            10,
+            OrderType::Limit,
+            TimeInForce::GTC,
+            None,
+            None,
+            None,
+            None,
+            None,
+        ));
+        assert_ok!(Orderbook::place_order(
+            RuntimeOrigin::signed(bob),
+            btc_usdt,
+            OrderSide::Sell,
+            100,
+            10,
+            OrderType::Limit,
+            TimeInForce::GTC,
+            None,
+            None,
+            None,
+            None,
+            None,
+        ));
+
+        Orderbook::on_finalize(1);
+
+        let trade = Orderbook::get_trade(0).expect("Trade should exist");
+        assert_eq!(trade.market_id, btc_usdt);
+
+        assert_eq!(Assets::get_free_balance(&alice, BTC), 10);
+        assert_eq!(Assets::get_free_balance(&alice, ETH), 0);
+        assert_eq!(Assets::get_free_balance(&bob, USDT), 1_000);
+        assert_eq!(Assets::get_free_balance(&bob, BTC), 90);
+        System::assert_has_event(
+            crate::Event::MatchingCompleted {
+                market_id: btc_usdt,
+                total_trades: 1,
+                total_volume: 1_000,
+            }
+            .into(),
+        );
+    });
+}
+
+#[test]
+fn test_paused_market_rejects_orders_and_holds_matching() {
+    build_and_execute(|| {
+        let alice = alice();
+        let bob = bob();
+
+        fund_account(alice, 10_000, 0);
+        fund_account(bob, 0, 100);
+
+        assert_ok!(Orderbook::place_order(
+            RuntimeOrigin::signed(alice),
+            ETH_USDT,
+            OrderSide::Buy,
+            100,
+            10,
+            OrderType::Limit,
+            TimeInForce::GTC,
+            None,
+            None,
+            None,
+            None,
+            None,
+        ));
+        assert_ok!(Orderbook::place_order(
+            RuntimeOrigin::signed(bob),
+            ETH_USDT,
+            OrderSide::Sell,
+            100,
+            10,
+            OrderType::Limit,
+            TimeInForce::GTC,
+            None,
+            None,
+            None,
+            None,
+            None,
+        ));
+
+        assert_ok!(Orderbook::pause_market(RuntimeOrigin::root(), ETH_USDT));
+        assert_noop!(
+            Orderbook::place_order(
+                RuntimeOrigin::signed(alice),
+                ETH_USDT,
+                OrderSide::Buy,
+                100,
+                1,
+                OrderType::Limit,
+                TimeInForce::GTC,
+                None,
+                None,
+                None,
+                None,
+                None,
+            ),
+            crate::Error::<Test>::MarketNotActive
+        );
+
+        Orderbook::on_finalize(1);
+
+        // Nothing matched, both orders are still waiting
+        assert!(Orderbook::get_trade(0).is_none());
+        assert_eq!(Orderbook::get_pending_bids_at_price(ETH_USDT, 100), vec![0]);
+        assert_eq!(Orderbook::get_pending_asks_at_price(ETH_USDT, 100), vec![1]);
+
+        assert_ok!(Orderbook::resume_market(RuntimeOrigin::root(), ETH_USDT));
+        System::set_block_number(2);
+        Orderbook::on_finalize(2);
+
+        let trade = Orderbook::get_trade(0).expect("Trade should exist");
+        assert_eq!(trade.quantity, 10);
+    });
+}
+
+#[test]
+fn test_paused_market_still_processes_cancellations() {
+    build_and_execute(|| {
+        let alice = alice();
+        fund_account(alice, 10_000, 0);
+
+        assert_ok!(Orderbook::place_order(
+            RuntimeOrigin::signed(alice),
+            ETH_USDT,
+            OrderSide::Buy,
+            100,
+            10,
+            OrderType::Limit,
+            TimeInForce::GTC,
+            None,
+            None,
+            None,
+            None,
+            None,
+        ));
+        assert_ok!(Orderbook::pause_market(RuntimeOrigin::root(), ETH_USDT));
+        assert_ok!(Orderbook::cancel_order(
+            RuntimeOrigin::signed(alice),
+            ETH_USDT,
+            0
+        ));
+
+        Orderbook::on_finalize(1);
+
+        assert_eq!(Assets::get_free_balance(&alice, USDT), 10_000);
+        assert!(Orderbook::get_pending_bids_at_price(ETH_USDT, 100).is_empty());
+        assert_eq!(order_status(0), Some(OrderStatus::Cancelled));
+    });
+}
+
+#[test]
+fn test_market_status_transitions() {
+    build_and_execute(|| {
+        assert_noop!(
+            Orderbook::resume_market(RuntimeOrigin::root(), ETH_USDT),
+            crate::Error::<Test>::InvalidMarketStatus
+        );
+        assert_noop!(
+            Orderbook::pause_market(RuntimeOrigin::signed(alice()), ETH_USDT),
+            sp_runtime::DispatchError::BadOrigin
+        );
+
+        assert_ok!(Orderbook::delist_market(RuntimeOrigin::root(), ETH_USDT));
+        System::assert_last_event(
+            crate::Event::MarketStatusChanged {
+                market_id: ETH_USDT,
+                status: MarketStatus::Delisted,
+            }
+            .into(),
+        );
+
+        // Delisting is final
+        assert_noop!(
+            Orderbook::resume_market(RuntimeOrigin::root(), ETH_USDT),
+            crate::Error::<Test>::InvalidMarketStatus
+        );
+        assert_noop!(
+            Orderbook::delist_market(RuntimeOrigin::root(), ETH_USDT),
+            crate::Error::<Test>::InvalidMarketStatus
+        );
+        assert_noop!(
+            Orderbook::pause_market(RuntimeOrigin::root(), 42),
+            crate::Error::<Test>::MarketNotFound
+        );
+    });
+}
+
+// ============================================
+// INCREMENTAL MATCHING TESTS
+// ============================================
+
+// looks in the history once an order is finished
+fn finished_order(order_id: u64) -> Option<crate::types::Order<Test>> {
+    crate::OrderHistory::<Test>::iter_values()
+        .flatten()
+        .find(|order| order.order_id == order_id)
+}
+
+fn order_status(order_id: u64) -> Option<OrderStatus> {
+    Orderbook::get_order(order_id)
+        .or_else(|| finished_order(order_id))
+        .map(|order| order.status)
+}
+
+fn place_limit(account: u64, side: OrderSide, price: u128, quantity: u128) {
+    TestOrder::new(side, price, quantity).place(account);
+}
+
+#[test]
+fn test_price_index_and_best_prices() {
+    build_and_execute(|| {
+        let alice = alice();
+        let bob = bob();
+        fund_account(alice, 100_000, 0);
+        fund_account(bob, 0, 1_000);
+
+        place_limit(alice, OrderSide::Buy, 95, 1);
+        place_limit(alice, OrderSide::Buy, 90, 1);
+        place_limit(alice, OrderSide::Buy, 95, 1);
+        place_limit(bob, OrderSide::Sell, 110, 1);
+        place_limit(bob, OrderSide::Sell, 105, 1);
+
+        // pending prices are indexed straight away, the pointers move on finalize
+        assert_eq!(
+            Orderbook::get_price_levels(ETH_USDT),
+            (vec![90, 95], vec![105, 110])
+        );
+        assert_eq!(Orderbook::get_best_prices(ETH_USDT), (None, None));
+
+        Orderbook::on_finalize(1);
+
+        assert_eq!(Orderbook::get_best_prices(ETH_USDT), (Some(95), Some(105)));
+        assert_eq!(Orderbook::get_bids_at_price(ETH_USDT, 95), vec![0, 2]);
+    });
+}
+
+#[test]
+fn test_sweep_removes_emptied_levels() {
+    build_and_execute(|| {
+        let alice = alice();
+        let bob = bob();
+        fund_account(alice, 100_000, 0);
+        fund_account(bob, 0, 1_000);
+
+        place_limit(bob, OrderSide::Sell, 100, 5);
+        place_limit(bob, OrderSide::Sell, 101, 5);
+        place_limit(bob, OrderSide::Sell, 102, 5);
+        Orderbook::on_finalize(1);
+
+        System::set_block_number(2);
+        place_limit(alice, OrderSide::Buy, 101, 8);
+        Orderbook::on_finalize(2);
+
+        // 5 @ 100 and 3 @ 101, the bid is gone and so is the 100 level
+        assert_eq!(Orderbook::get_trade(0).map(|t| t.price), Some(100));
+        assert_eq!(Orderbook::get_trade(1).map(|t| t.quantity), Some(3));
+        assert_eq!(
+            Orderbook::get_price_levels(ETH_USDT),
+            (vec![], vec![101, 102])
+        );
+        assert_eq!(Orderbook::get_best_prices(ETH_USDT), (None, Some(101)));
+        assert!(Orderbook::get_asks_at_price(ETH_USDT, 100).is_empty());
+        assert_eq!(Orderbook::get_asks_at_price(ETH_USDT, 101), vec![1]);
+        assert_eq!(Orderbook::get_asks_at_price(ETH_USDT, 102), vec![2]);
+    });
+}
+
+#[test]
+fn test_levels_beyond_demand_are_kept() {
+    build_and_execute(|| {
+        let alice = alice();
+        let bob = bob();
+        let charlie = charlie();
+        fund_account(alice, 100_000, 0);
+        fund_account(bob, 0, 1_000);
+        fund_account(charlie, 0, 1_000);
+
+        place_limit(bob, OrderSide::Sell, 100, 5);
+        place_limit(bob, OrderSide::Sell, 101, 5);
+        place_limit(charlie, OrderSide::Sell, 101, 5);
+        Orderbook::on_finalize(1);
+
+        // the first level covers the bid, the 101 level must come through unchanged
+        System::set_block_number(2);
+        place_limit(alice, OrderSide::Buy, 105, 5);
+        Orderbook::on_finalize(2);
+
+        assert_eq!(Orderbook::next_trade_id(), 1);
+        assert_eq!(Orderbook::get_asks_at_price(ETH_USDT, 101), vec![1, 2]);
+        assert_eq!(Orderbook::get_best_prices(ETH_USDT), (None, Some(101)));
+
+        // a later bid still keeps time priority at 101
+        System::set_block_number(3);
+        place_limit(alice, OrderSide::Buy, 101, 7);
+        Orderbook::on_finalize(3);
+
+        assert_eq!(Orderbook::get_trade(1).map(|t| t.sell_order_id), Some(1));
+        assert_eq!(Orderbook::get_trade(2).map(|t| t.sell_order_id), Some(2));
+        assert_eq!(Orderbook::get_order(2).map(|o| o.filled_quantity), Some(2));
+        assert_eq!(Orderbook::get_price_levels(ETH_USDT), (vec![], vec![101]));
+    });
+}
+
+#[test]
+fn test_cancelling_best_level_moves_pointer() {
+    build_and_execute(|| {
+        let alice = alice();
+        fund_account(alice, 100_000, 0);
+
+        place_limit(alice, OrderSide::Buy, 95, 1);
+        place_limit(alice, OrderSide::Buy, 90, 1);
+        Orderbook::on_finalize(1);
+        assert_eq!(Orderbook::get_best_prices(ETH_USDT), (Some(95), None));
+
+        System::set_block_number(2);
+        assert_ok!(Orderbook::cancel_order(
+            RuntimeOrigin::signed(alice),
+            ETH_USDT,
+            0
+        ));
+        Orderbook::on_finalize(2);
+
+        assert_eq!(Orderbook::get_best_prices(ETH_USDT), (Some(90), None));
+        assert_eq!(Orderbook::get_price_levels(ETH_USDT), (vec![90], vec![]));
+    });
+}
+
+#[test]
+fn test_too_many_price_levels_fails() {
+    build_and_execute(|| {
+        let alice = alice();
+        fund_account(alice, 1_000_000, 0);
+
+        // mock allows 50 levels per side
+        for price in 1..=50u128 {
+            place_limit(alice, OrderSide::Buy, price, 1);
+        }
+        // an existing level is still fine
+        place_limit(alice, OrderSide::Buy, 50, 1);
+
+        assert_noop!(
+            Orderbook::place_order(
+                RuntimeOrigin::signed(alice),
+                ETH_USDT,
+                OrderSide::Buy,
+                51,
+                1,
+                OrderType::Limit,
+                TimeInForce::GTC,
+                None,
+                None,
+                None,
+                None,
+                None,
+            ),
+            crate::Error::<Test>::TooManyPriceLevels
+        );
+    });
+}
+
+#[test]
+fn test_on_initialize_weight_follows_pending_work() {
+    build_and_execute(|| {
+        let alice = alice();
+        fund_account(alice, 100_000, 0);
+
+        assert_eq!(
+            Orderbook::on_initialize(1),
+            <Test as crate::Config>::WeightInfo::on_finalize_empty()
+        );
+
+        for _ in 0..30 {
+            place_limit(alice, OrderSide::Buy, 90, 1);
+        }
+        assert_ok!(Orderbook::cancel_order(
+            RuntimeOrigin::signed(alice),
+            ETH_USDT,
+            0
+        ));
+
+        // no cap on the pending count any more
+        assert_eq!(
+            Orderbook::on_initialize(1),
+            <Test as crate::Config>::WeightInfo::on_finalize_market(30, 0, 1)
+        );
+
+        // an auction is weighed for its self-trade checks and rounds
+        set_auction();
+        assert_eq!(
+            Orderbook::on_initialize(1),
+            <Test as crate::Config>::WeightInfo::on_finalize_market(0, 0, 1)
+                .saturating_add(<Test as crate::Config>::WeightInfo::on_finalize_auction(30))
+        );
+    });
+}
+
+// ============================================
+// TIME IN FORCE TESTS
+// ============================================
+
+#[test]
+fn test_ioc_remainder_is_cancelled_and_unlocked() {
+    build_and_execute(|| {
+        let alice = alice();
+        let bob = bob();
+        fund_account(alice, 10_000, 0);
+        fund_account(bob, 0, 100);
+
+        place_limit(bob, OrderSide::Sell, 100, 5);
+        Orderbook::on_finalize(1);
+
+        System::set_block_number(2);
+        TestOrder::new(OrderSide::Buy, 100, 8)
+            .time_in_force(TimeInForce::IOC)
+            .place(alice);
+        Orderbook::on_finalize(2);
+
+        System::assert_has_event(
+            crate::Event::OrderRemainderCancelled {
+                market_id: ETH_USDT,
+                order_id: 1,
+                trader: alice,
+                filled_quantity: 5,
+                cancelled_quantity: 3,
+            }
+            .into(),
+        );
+
+        let order = finished_order(1).expect("order should be in the history");
+        assert_eq!(order.status, OrderStatus::Cancelled);
+        assert_eq!(order.filled_quantity, 5);
+
+        // nothing rests, the unfilled 3 * 100 went back to free
+        assert!(Orderbook::get_bids_at_price(ETH_USDT, 100).is_empty());
+        assert_eq!(Orderbook::get_best_prices(ETH_USDT), (None, None));
+        assert_eq!(Assets::get_locked_balance(&alice, USDT), 0);
+        assert_eq!(Assets::get_free_balance(&alice, USDT), 9_500);
+        assert_eq!(Assets::get_free_balance(&alice, ETH), 5);
+    });
+}
+
+#[test]
+fn test_ioc_without_liquidity_does_not_rest() {
+    build_and_execute(|| {
+        let alice = alice();
+        let bob = bob();
+        fund_account(alice, 10_000, 0);
+        fund_account(bob, 0, 100);
+
+        place_limit(bob, OrderSide::Sell, 100, 5);
+        TestOrder::new(OrderSide::Buy, 90, 10)
+            .time_in_force(TimeInForce::IOC)
+            .place(alice);
+        Orderbook::on_finalize(1);
+
+        assert!(Orderbook::get_trade(0).is_none());
+        System::assert_has_event(
+            crate::Event::OrderRemainderCancelled {
+                market_id: ETH_USDT,
+                order_id: 1,
+                trader: alice,
+                filled_quantity: 0,
+                cancelled_quantity: 10,
+            }
+            .into(),
+        );
+        assert!(Orderbook::get_bids_at_price(ETH_USDT, 90).is_empty());
+        assert_eq!(Orderbook::get_best_prices(ETH_USDT), (None, Some(100)));
+        assert_eq!(Assets::get_free_balance(&alice, USDT), 10_000);
+    });
+}
+
+#[test]
+fn test_fok_killed_when_not_fully_fillable() {
+    build_and_execute(|| {
+        let alice = alice();
+        let bob = bob();
+        fund_account(alice, 10_000, 0);
+        fund_account(bob, 0, 100);
+
+        place_limit(bob, OrderSide::Sell, 100, 5);
+        Orderbook::on_finalize(1);
+
+        System::set_block_number(2);
+        TestOrder::new(OrderSide::Buy, 100, 8)
+            .time_in_force(TimeInForce::FOK)
+            .place(alice);
+        Orderbook::on_finalize(2);
+
+        System::assert_has_event(
+            crate::Event::OrderKilled {
+                market_id: ETH_USDT,
+                order_id: 1,
+                trader: alice,
+                quantity: 8,
+            }
+            .into(),
+        );
+
+        // nothing traded, the resting ask is untouched
+        assert!(Orderbook::get_trade(0).is_none());
+        assert_eq!(Orderbook::get_order(0).map(|o| o.filled_quantity), Some(0));
+        assert_eq!(Orderbook::get_asks_at_price(ETH_USDT, 100), vec![0]);
+        assert_eq!(order_status(1), Some(OrderStatus::Cancelled));
+        assert_eq!(Assets::get_locked_balance(&alice, USDT), 0);
+        assert_eq!(Assets::get_free_balance(&alice, USDT), 10_000);
+    });
+}
+
+#[test]
+fn test_fok_fills_across_pending_and_resting() {
+    build_and_execute(|| {
+        let alice = alice();
+        let bob = bob();
+        let charlie = charlie();
+        fund_account(alice, 10_000, 0);
+        fund_account(bob, 0, 100);
+        fund_account(charlie, 0, 100);
+
+        place_limit(bob, OrderSide::Sell, 100, 5);
+        Orderbook::on_finalize(1);
+
+        // 3 more come from an ask in the same block
+        System::set_block_number(2);
+        place_limit(charlie, OrderSide::Sell, 101, 3);
+        TestOrder::new(OrderSide::Buy, 101, 8)
+            .time_in_force(TimeInForce::FOK)
+            .place(alice);
+        Orderbook::on_finalize(2);
+
+        assert_eq!(Orderbook::next_trade_id(), 2);
+        System::assert_has_event(
+            crate::Event::OrderFilled {
+                market_id: ETH_USDT,
+                order_id: 2,
+                trader: alice,
+            }
+            .into(),
+        );
+        assert_eq!(Assets::get_free_balance(&alice, ETH), 8);
+        assert_eq!(Orderbook::get_price_levels(ETH_USDT), (vec![], vec![]));
+    });
+}
+
+#[test]
+fn test_fok_is_never_a_maker_in_the_cache() {
+    build_and_execute(|| {
+        let alice = alice();
+        let bob = bob();
+        fund_account(alice, 10_000, 0);
+        fund_account(bob, 0, 100);
+
+        // the earlier bid is matched first but must not take part of the FOK
+        place_limit(alice, OrderSide::Buy, 100, 4);
+        TestOrder::new(OrderSide::Sell, 100, 10)
+            .time_in_force(TimeInForce::FOK)
+            .place(bob);
+        Orderbook::on_finalize(1);
+
+        assert!(Orderbook::get_trade(0).is_none());
+        System::assert_has_event(
+            crate::Event::OrderKilled {
+                market_id: ETH_USDT,
+                order_id: 1,
+                trader: bob,
+                quantity: 10,
+            }
+            .into(),
+        );
+        assert_eq!(Orderbook::get_bids_at_price(ETH_USDT, 100), vec![0]);
+        assert_eq!(Assets::get_free_balance(&bob, ETH), 100);
+    });
+}
+
+// ============================================
+// POST-ONLY TESTS
+// ============================================
+
+#[test]
+fn test_post_only_crossing_order_is_rejected() {
+    build_and_execute(|| {
+        let alice = alice();
+        let bob = bob();
+        fund_account(alice, 10_000, 0);
+        fund_account(bob, 0, 100);
+
+        place_limit(bob, OrderSide::Sell, 100, 5);
+        Orderbook::on_finalize(1);
+
+        System::set_block_number(2);
+        TestOrder::new(OrderSide::Buy, 100, 5)
+            .post_only(PostOnly::Reject)
+            .place(alice);
+        Orderbook::on_finalize(2);
+
+        System::assert_has_event(
+            crate::Event::OrderRejected {
+                market_id: ETH_USDT,
+                order_id: 1,
+                trader: alice,
+                reason: RejectReason::PostOnlyWouldCross,
+            }
+            .into(),
+        );
+        assert!(Orderbook::get_trade(0).is_none());
+        assert_eq!(order_status(1), Some(OrderStatus::Cancelled));
+        assert!(Orderbook::get_bids_at_price(ETH_USDT, 100).is_empty());
+        assert_eq!(Assets::get_locked_balance(&alice, USDT), 0);
+        assert_eq!(Assets::get_free_balance(&alice, USDT), 10_000);
+    });
+}
+
+#[test]
+fn test_post_only_rests_when_not_crossing() {
+    build_and_execute(|| {
+        let alice = alice();
+        let bob = bob();
+        fund_account(alice, 10_000, 0);
+        fund_account(bob, 0, 100);
+
+        place_limit(bob, OrderSide::Sell, 100, 5);
+        TestOrder::new(OrderSide::Buy, 99, 5)
+            .post_only(PostOnly::Reject)
+            .place(alice);
+        Orderbook::on_finalize(1);
+
+        assert!(Orderbook::get_trade(0).is_none());
+        assert_eq!(Orderbook::get_bids_at_price(ETH_USDT, 99), vec![1]);
+        assert_eq!(Orderbook::get_best_prices(ETH_USDT), (Some(99), Some(100)));
+    });
+}
+
+#[test]
+fn test_post_only_bid_is_repriced_inside_spread() {
+    build_and_execute(|| {
+        let alice = alice();
+        let bob = bob();
+        fund_account(alice, 10_000, 0);
+        fund_account(bob, 0, 100);
+
+        place_limit(bob, OrderSide::Sell, 100, 5);
+        Orderbook::on_finalize(1);
+
+        System::set_block_number(2);
+        TestOrder::new(OrderSide::Buy, 105, 10)
+            .post_only(PostOnly::Reprice)
+            .place(alice);
+        Orderbook::on_finalize(2);
+
+        System::assert_has_event(
+            crate::Event::OrderRepriced {
+                market_id: ETH_USDT,
+                order_id: 1,
+                trader: alice,
+                old_price: 105,
+                new_price: 99,
+            }
+            .into(),
+        );
+        assert!(Orderbook::get_trade(0).is_none());
+        assert_eq!(Orderbook::get_order(1).map(|o| o.price), Some(99));
+        assert_eq!(Orderbook::get_bids_at_price(ETH_USDT, 99), vec![1]);
+        assert_eq!(Orderbook::get_price_levels(ETH_USDT), (vec![99], vec![100]));
+        assert_eq!(Orderbook::get_best_prices(ETH_USDT), (Some(99), Some(100)));
+
+        // only 10 * 99 stays locked
+        assert_eq!(Assets::get_locked_balance(&alice, USDT), 990);
+        assert_eq!(Assets::get_free_balance(&alice, USDT), 9_010);
+    });
+}
+
+#[test]
+fn test_post_only_ask_is_repriced_against_pending_bid() {
+    build_and_execute(|| {
+        let alice = alice();
+        let bob = bob();
+        fund_account(alice, 10_000, 0);
+        fund_account(bob, 0, 100);
+
+        TestOrder::new(OrderSide::Sell, 95, 5)
+            .post_only(PostOnly::Reprice)
+            .place(bob);
+        place_limit(alice, OrderSide::Buy, 100, 5);
+        Orderbook::on_finalize(1);
+
+        assert!(Orderbook::get_trade(0).is_none());
+        assert_eq!(Orderbook::get_order(0).map(|o| o.price), Some(101));
+        assert_eq!(Orderbook::get_asks_at_price(ETH_USDT, 101), vec![0]);
+        assert_eq!(Orderbook::get_bids_at_price(ETH_USDT, 100), vec![1]);
+        assert_eq!(
+            Orderbook::get_price_levels(ETH_USDT),
+            (vec![100], vec![101])
+        );
+        assert_eq!(Assets::get_locked_balance(&bob, ETH), 5);
+    });
+}
+
+#[test]
+fn test_post_only_reprice_below_one_tick_is_rejected() {
+    build_and_execute(|| {
+        let alice = alice();
+        let bob = bob();
+        fund_account(alice, 10_000, 0);
+        fund_account(bob, 0, 100);
+
+        place_limit(bob, OrderSide::Sell, 1, 5);
+        Orderbook::on_finalize(1);
+
+        System::set_block_number(2);
+        TestOrder::new(OrderSide::Buy, 1, 5)
+            .post_only(PostOnly::Reprice)
+            .place(alice);
+        Orderbook::on_finalize(2);
+
+        System::assert_has_event(
+            crate::Event::OrderRejected {
+                market_id: ETH_USDT,
+                order_id: 1,
+                trader: alice,
+                reason: RejectReason::PostOnlyWouldCross,
+            }
+            .into(),
+        );
+        assert_eq!(Assets::get_free_balance(&alice, USDT), 10_000);
+    });
+}
+
+#[test]
+fn test_post_only_requires_gtc_limit() {
+    build_and_execute(|| {
+        let alice = alice();
+        fund_account(alice, 10_000, 0);
+
+        assert_noop!(
+            TestOrder::new(OrderSide::Buy, 100, 1)
+                .market(100)
+                .post_only(PostOnly::Reject)
+                .try_place(alice),
+            crate::Error::<Test>::InvalidPostOnly
+        );
+        assert_noop!(
+            TestOrder::new(OrderSide::Buy, 100, 1)
+                .time_in_force(TimeInForce::IOC)
+                .post_only(PostOnly::Reprice)
+                .try_place(alice),
+            crate::Error::<Test>::InvalidPostOnly
+        );
+    });
+}
+
+// ============================================
+// STOP ORDER TESTS
+// ============================================
+
+// one trade at `price` between bob (sell) and charlie (buy)
+fn trade_at(price: u128) {
+    place_limit(bob(), OrderSide::Sell, price, 1);
+    place_limit(charlie(), OrderSide::Buy, price, 1);
+}
+
+#[allow(clippy::too_many_arguments)]
+#[test]
+fn test_place_stop_order_locks_and_waits() {
+    build_and_execute(|| {
+        let alice = alice();
+        fund_account(alice, 0, 100);
+
+        TestOrder::new(OrderSide::Sell, 90, 10)
+            .stop(StopKind::StopLoss, 95)
+            .place(alice);
+
+        System::assert_last_event(
+            crate::Event::StopOrderPlaced {
+                market_id: ETH_USDT,
+                order_id: 0,
+                trader: alice,
+                side: OrderSide::Sell,
+                kind: StopKind::StopLoss,
+                trigger_price: 95,
+                price: 90,
+                quantity: 10,
+            }
+            .into(),
+        );
+        assert_eq!(Assets::get_locked_balance(&alice, ETH), 10);
+        assert_eq!(order_status(0), Some(OrderStatus::Untriggered));
+        assert_eq!(Orderbook::get_stop_orders(ETH_USDT).len(), 1);
+        assert!(Orderbook::get_pending_asks_at_price(ETH_USDT, 90).is_empty());
+
+        // nothing traded, so it stays put
+        Orderbook::on_finalize(1);
+        assert_eq!(Orderbook::get_stop_orders(ETH_USDT).len(), 1);
+    });
+}
+
+#[test]
+fn test_stop_loss_triggers_and_matches_next_block() {
+    build_and_execute(|| {
+        let alice = alice();
+        fund_account(alice, 0, 100);
+        fund_account(bob(), 0, 100);
+        fund_account(charlie(), 100_000, 0);
+
+        TestOrder::new(OrderSide::Sell, 90, 10)
+            .stop(StopKind::StopLoss, 95)
+            .place(alice);
+
+        // 100 is above the trigger
+        trade_at(100);
+        Orderbook::on_finalize(1);
+        assert_eq!(Orderbook::get_last_trade_price(ETH_USDT), Some(100));
+        assert_eq!(Orderbook::get_stop_orders(ETH_USDT).len(), 1);
+
+        System::set_block_number(2);
+        trade_at(95);
+        Orderbook::on_finalize(2);
+
+        System::assert_has_event(
+            crate::Event::StopOrderTriggered {
+                market_id: ETH_USDT,
+                order_id: 0,
+                trader: alice,
+                side: OrderSide::Sell,
+                price: 90,
+                quantity: 10,
+                last_price: 95,
+            }
+            .into(),
+        );
+        assert!(Orderbook::get_stop_orders(ETH_USDT).is_empty());
+        assert_eq!(Orderbook::get_pending_asks_at_price(ETH_USDT, 90), vec![0]);
+        assert_eq!(order_status(0), Some(OrderStatus::Open));
+
+        // matched like any other order in the next block
+        System::set_block_number(3);
+        place_limit(charlie(), OrderSide::Buy, 92, 10);
+        Orderbook::on_finalize(3);
+
+        System::assert_has_event(
+            crate::Event::OrderFilled {
+                market_id: ETH_USDT,
+                order_id: 0,
+                trader: alice,
+            }
+            .into(),
+        );
+        assert_eq!(Assets::get_free_balance(&alice, USDT), 920);
+    });
+}
+
+#[test]
+fn test_take_profit_buy_triggers_on_drop() {
+    build_and_execute(|| {
+        let alice = alice();
+        fund_account(alice, 10_000, 0);
+        fund_account(bob(), 0, 100);
+        fund_account(charlie(), 100_000, 0);
+
+        TestOrder::new(OrderSide::Buy, 0, 5)
+            .market(5_000)
+            .stop(StopKind::TakeProfit, 80)
+            .place(alice);
+        assert_eq!(Assets::get_locked_balance(&alice, USDT), 5_000);
+
+        trade_at(81);
+        Orderbook::on_finalize(1);
+        assert_eq!(Orderbook::get_stop_orders(ETH_USDT).len(), 1);
+
+        System::set_block_number(2);
+        trade_at(80);
+        Orderbook::on_finalize(2);
+        assert!(Orderbook::get_stop_orders(ETH_USDT).is_empty());
+
+        // the market order takes the resting ask
+        System::set_block_number(3);
+        place_limit(bob(), OrderSide::Sell, 82, 5);
+        Orderbook::on_finalize(3);
+
+        System::assert_has_event(
+            crate::Event::OrderFilled {
+                market_id: ETH_USDT,
+                order_id: 0,
+                trader: alice,
+            }
+            .into(),
+        );
+        assert_eq!(Assets::get_free_balance(&alice, ETH), 5);
+        // only 410 of the quote limit was spent
+        assert_eq!(Assets::get_free_balance(&alice, USDT), 9_590);
+        assert_eq!(Assets::get_locked_balance(&alice, USDT), 0);
+    });
+}
+
+#[test]
+fn test_stop_already_triggered_is_refused() {
+    build_and_execute(|| {
+        let alice = alice();
+        fund_account(alice, 0, 100);
+        fund_account(bob(), 0, 100);
+        fund_account(charlie(), 100_000, 0);
+
+        trade_at(90);
+        Orderbook::on_finalize(1);
+
+        assert_noop!(
+            Orderbook::place_stop_order(
+                RuntimeOrigin::signed(alice),
+                ETH_USDT,
+                OrderSide::Sell,
+                StopKind::StopLoss,
+                95,
+                90,
+                10,
+                OrderType::Limit,
+            ),
+            crate::Error::<Test>::StopAlreadyTriggered
+        );
+        assert_noop!(
+            Orderbook::place_stop_order(
+                RuntimeOrigin::signed(alice),
+                ETH_USDT,
+                OrderSide::Sell,
+                StopKind::StopLoss,
+                0,
+                90,
+                10,
+                OrderType::Limit,
+            ),
+            crate::Error::<Test>::InvalidTriggerPrice
+        );
+    });
+}
+
+#[test]
+fn test_cancel_untriggered_stop() {
+    build_and_execute(|| {
+        let alice = alice();
+        fund_account(alice, 10_000, 0);
+
+        TestOrder::new(OrderSide::Buy, 115, 10)
+            .stop(StopKind::StopLoss, 110)
+            .place(alice);
+        assert_eq!(Assets::get_locked_balance(&alice, USDT), 1_150);
+
+        // no need to wait for on_finalize
+        assert_ok!(Orderbook::cancel_order(
+            RuntimeOrigin::signed(alice),
+            ETH_USDT,
+            0
+        ));
+
+        System::assert_last_event(
+            crate::Event::OrderCancelled {
+                market_id: ETH_USDT,
+                order_id: 0,
+                trader: alice,
+            }
+            .into(),
+        );
+        assert!(Orderbook::get_stop_orders(ETH_USDT).is_empty());
+        assert!(Orderbook::get_pending_cancellations(ETH_USDT).is_empty());
+        assert_eq!(order_status(0), Some(OrderStatus::Cancelled));
+        assert_eq!(Assets::get_locked_balance(&alice, USDT), 0);
+        assert_eq!(Assets::get_free_balance(&alice, USDT), 10_000);
+
+        // finished orders are only kept in the history
+        assert_noop!(
+            Orderbook::cancel_order(RuntimeOrigin::signed(alice), ETH_USDT, 0),
+            crate::Error::<Test>::OrderNotFound
+        );
+    });
+}
+
+// ============================================
+// EXPIRY TESTS
+// ============================================
+
+#[test]
+fn test_resting_order_expires_and_unlocks() {
+    build_and_execute(|| {
+        let alice = alice();
+        fund_account(alice, 10_000, 0);
+
+        TestOrder::new(OrderSide::Buy, 100, 10)
+            .expires_at(3)
+            .place(alice);
+        assert_eq!(Orderbook::get_order(0).and_then(|o| o.ttl), Some(3));
+        assert_eq!(Orderbook::get_expiring_orders(3), vec![0]);
+        Orderbook::on_finalize(1);
+        assert_eq!(Orderbook::get_bids_at_price(ETH_USDT, 100), vec![0]);
+
+        // still alive one block before
+        System::set_block_number(2);
+        Orderbook::on_initialize(2);
+        assert_eq!(Orderbook::get_bids_at_price(ETH_USDT, 100), vec![0]);
+
+        System::set_block_number(3);
+        Orderbook::on_initialize(3);
+
+        System::assert_last_event(
+            crate::Event::OrderExpired {
+                market_id: ETH_USDT,
+                order_id: 0,
+                trader: alice,
+                filled_quantity: 0,
+                expired_quantity: 10,
+            }
+            .into(),
+        );
+        assert_eq!(order_status(0), Some(OrderStatus::Expired));
+        assert!(Orderbook::get_bids_at_price(ETH_USDT, 100).is_empty());
+        assert!(Orderbook::get_expiring_orders(3).is_empty());
+        assert_eq!(
+            Orderbook::get_price_levels(ETH_USDT),
+            (Vec::new(), Vec::new())
+        );
+        assert_eq!(Orderbook::get_best_prices(ETH_USDT), (None, None));
+        assert_eq!(Assets::get_locked_balance(&alice, USDT), 0);
+        assert_eq!(Assets::get_free_balance(&alice, USDT), 10_000);
+    });
+}
+
+#[test]
+fn test_partially_filled_order_expires_remainder() {
+    build_and_execute(|| {
+        let alice = alice();
+        let bob = bob();
+        fund_account(alice, 0, 100);
+        fund_account(bob, 10_000, 0);
+
+        TestOrder::new(OrderSide::Sell, 100, 10)
+            .expires_at(2)
+            .place(alice);
+        place_limit(bob, OrderSide::Buy, 100, 4);
+        Orderbook::on_finalize(1);
+
+        System::set_block_number(2);
+        Orderbook::on_initialize(2);
+
+        System::assert_last_event(
+            crate::Event::OrderExpired {
+                market_id: ETH_USDT,
+                order_id: 0,
+                trader: alice,
+                filled_quantity: 4,
+                expired_quantity: 6,
+            }
+            .into(),
+        );
+        assert!(Orderbook::get_asks_at_price(ETH_USDT, 100).is_empty());
+        assert_eq!(Assets::get_locked_balance(&alice, ETH), 0);
+        assert_eq!(Assets::get_free_balance(&alice, ETH), 96);
+    });
+}
+
+#[test]
+fn test_finished_orders_are_not_expired() {
+    build_and_execute(|| {
+        let alice = alice();
+        fund_account(alice, 0, 100);
+
+        TestOrder::new(OrderSide::Sell, 100, 10)
+            .expires_at(2)
+            .place(alice);
+        Orderbook::on_finalize(1);
+        assert_ok!(Orderbook::cancel_order(
+            RuntimeOrigin::signed(alice),
+            ETH_USDT,
+            0
+        ));
+        Orderbook::on_finalize(1);
+        let events = System::events().len();
+
+        System::set_block_number(2);
+        Orderbook::on_initialize(2);
+
+        assert_eq!(System::events().len(), events);
+        assert_eq!(order_status(0), Some(OrderStatus::Cancelled));
+        assert!(Orderbook::get_expiring_orders(2).is_empty());
+        assert_eq!(Assets::get_free_balance(&alice, ETH), 100);
+    });
+}
+
+#[test]
+fn test_pending_order_in_paused_market_expires() {
+    build_and_execute(|| {
+        let alice = alice();
+        fund_account(alice, 10_000, 0);
+
+        TestOrder::new(OrderSide::Buy, 100, 10)
+            .expires_at(2)
+            .place(alice);
+        place_limit(alice, OrderSide::Buy, 100, 5);
+        assert_ok!(Orderbook::pause_market(RuntimeOrigin::root(), ETH_USDT));
+        Orderbook::on_finalize(1);
+
+        System::set_block_number(2);
+        Orderbook::on_initialize(2);
+
+        // the other order at that price keeps the level in the index
+        assert_eq!(Orderbook::get_pending_bids_at_price(ETH_USDT, 100), vec![1]);
+        assert_eq!(Orderbook::get_price_levels(ETH_USDT).0, vec![100]);
+        assert_eq!(Assets::get_locked_balance(&alice, USDT), 500);
+    });
+}
+
+#[test]
+fn test_expiry_must_be_in_the_future() {
+    build_and_execute(|| {
+        let alice = alice();
+        fund_account(alice, 10_000, 0);
+
+        for expires_at in [0, 1] {
+            assert_noop!(
+                TestOrder::new(OrderSide::Buy, 100, 10)
+                    .expires_at(expires_at)
+                    .try_place(alice),
+                crate::Error::<Test>::InvalidExpiry
+            );
+        }
+    });
+}
+
+// ============================================
+// MARKET ORDER TESTS
+// ============================================
+
+#[test]
+fn test_market_buy_remainder_is_cancelled_and_refunded() {
+    build_and_execute(|| {
+        let alice = alice();
+        let bob = bob();
+        fund_account(alice, 0, 100);
+        fund_account(bob, 10_000, 0);
+
+        place_limit(alice, OrderSide::Sell, 100, 4);
+        Orderbook::on_finalize(1);
+
+        System::set_block_number(2);
+        TestOrder::new(OrderSide::Buy, 0, 10)
+            .market(5_000)
+            .place(bob);
+        Orderbook::on_finalize(2);
+
+        System::assert_has_event(
+            crate::Event::OrderRemainderCancelled {
+                market_id: ETH_USDT,
+                order_id: 1,
+                trader: bob,
+                filled_quantity: 4,
+                cancelled_quantity: 6,
+            }
+            .into(),
+        );
+        assert_eq!(order_status(1), Some(OrderStatus::Cancelled));
+        // nothing rests, not even at price 0
+        assert!(Orderbook::get_bids_at_price(ETH_USDT, 0).is_empty());
+        assert_eq!(
+            Orderbook::get_price_levels(ETH_USDT),
+            (Vec::new(), Vec::new())
+        );
+        assert_eq!(Assets::get_free_balance(&bob, ETH), 4);
+        assert_eq!(Assets::get_free_balance(&bob, USDT), 9_600);
+        assert_eq!(Assets::get_locked_balance(&bob, USDT), 0);
+    });
+}
+
+#[test]
+fn test_market_buy_stops_at_quote_limit() {
+    build_and_execute(|| {
+        let alice = alice();
+        let bob = bob();
+        fund_account(alice, 0, 100);
+        fund_account(bob, 10_000, 0);
+
+        place_limit(alice, OrderSide::Sell, 100, 5);
+        place_limit(alice, OrderSide::Sell, 110, 5);
+        Orderbook::on_finalize(1);
+
+        // 5 @ 100 uses 500, the other 500 only buys 4 @ 110
+        System::set_block_number(2);
+        TestOrder::new(OrderSide::Buy, 0, 10)
+            .market(1_000)
+            .place(bob);
+        Orderbook::on_finalize(2);
+
+        assert_eq!(
+            Orderbook::get_trade(0).map(|t| (t.price, t.quantity)),
+            Some((100, 5))
+        );
+        assert_eq!(
+            Orderbook::get_trade(1).map(|t| (t.price, t.quantity)),
+            Some((110, 4))
+        );
+        assert!(Orderbook::get_trade(2).is_none());
+        assert_eq!(Assets::get_free_balance(&bob, ETH), 9);
+        assert_eq!(Assets::get_free_balance(&bob, USDT), 9_060);
+        assert_eq!(Assets::get_locked_balance(&bob, USDT), 0);
+        assert_eq!(Orderbook::get_asks_at_price(ETH_USDT, 110), vec![1]);
+    });
+}
+
+#[test]
+fn test_market_sell_below_floor_is_killed() {
+    build_and_execute(|| {
+        let alice = alice();
+        let bob = bob();
+        fund_account(alice, 10_000, 0);
+        fund_account(bob, 0, 100);
+
+        place_limit(alice, OrderSide::Buy, 100, 5);
+        place_limit(alice, OrderSide::Buy, 90, 5);
+        Orderbook::on_finalize(1);
+
+        // the book only pays 950 for all 10
+        System::set_block_number(2);
+        TestOrder::new(OrderSide::Sell, 0, 10)
+            .market(1_000)
+            .place(bob);
+        Orderbook::on_finalize(2);
+
+        System::assert_has_event(
+            crate::Event::OrderKilled {
+                market_id: ETH_USDT,
+                order_id: 2,
+                trader: bob,
+                quantity: 10,
+            }
+            .into(),
+        );
+        assert!(Orderbook::get_trade(0).is_none());
+        assert_eq!(Assets::get_free_balance(&bob, ETH), 100);
+        assert_eq!(Assets::get_locked_balance(&bob, ETH), 0);
+
+        System::set_block_number(3);
+        TestOrder::new(OrderSide::Sell, 0, 10)
+            .market(950)
+            .place(bob);
+        Orderbook::on_finalize(3);
+
+        assert_eq!(Assets::get_free_balance(&bob, USDT), 950);
+        assert_eq!(Assets::get_free_balance(&bob, ETH), 90);
+        assert_eq!(
+            Orderbook::get_price_levels(ETH_USDT),
+            (Vec::new(), Vec::new())
+        );
+    });
+}
+
+#[test]
+fn test_market_order_is_never_a_maker() {
+    build_and_execute(|| {
+        let alice = alice();
+        let bob = bob();
+        fund_account(alice, 0, 100);
+        fund_account(bob, 10_000, 0);
+
+        // the bid goes first, it must not take the market sell at price 0
+        place_limit(bob, OrderSide::Buy, 100, 5);
+        TestOrder::new(OrderSide::Sell, 0, 5).market(0).place(alice);
+        Orderbook::on_finalize(1);
+
+        let trade = Orderbook::get_trade(0).expect("Trade should exist");
+        assert_eq!((trade.price, trade.quantity), (100, 5));
+        assert_eq!(trade.sell_order_id, 1);
+        assert!(Orderbook::get_trade(1).is_none());
+        assert_eq!(Assets::get_free_balance(&alice, USDT), 500);
+        assert_eq!(Assets::get_free_balance(&alice, ETH), 95);
+    });
+}
+
+#[test]
+fn test_market_order_takes_best_price_across_cache_and_book() {
+    build_and_execute(|| {
+        let alice = alice();
+        let bob = bob();
+        fund_account(alice, 0, 100);
+        fund_account(bob, 10_000, 0);
+
+        place_limit(alice, OrderSide::Sell, 105, 5);
+        Orderbook::on_finalize(1);
+
+        System::set_block_number(2);
+        place_limit(alice, OrderSide::Sell, 100, 5);
+        TestOrder::new(OrderSide::Buy, 0, 5)
+            .market(1_000)
+            .place(bob);
+        Orderbook::on_finalize(2);
+
+        let trade = Orderbook::get_trade(0).expect("Trade should exist");
+        assert_eq!((trade.price, trade.sell_order_id), (100, 1));
+        assert_eq!(Orderbook::get_asks_at_price(ETH_USDT, 105), vec![0]);
+        assert_eq!(Assets::get_free_balance(&bob, USDT), 9_500);
+    });
+}
+
+#[test]
+fn test_market_buy_needs_quote_limit() {
+    build_and_execute(|| {
+        let alice = alice();
+        fund_account(alice, 10_000, 0);
+
+        assert_noop!(
+            TestOrder::new(OrderSide::Buy, 100, 10)
+                .market(0)
+                .try_place(alice),
+            crate::Error::<Test>::InvalidQuoteLimit
+        );
+    });
+}
+
+// ============================================
+// PRICE IMPROVEMENT / LOCKED FUNDS TESTS
+// ============================================
+
+#[test]
+fn test_buy_filled_below_limit_gets_surplus_back() {
+    build_and_execute(|| {
+        let alice = alice();
+        let bob = bob();
+        fund_account(alice, 0, 100);
+        fund_account(bob, 10_000, 0);
+
+        place_limit(alice, OrderSide::Sell, 100, 10);
+        Orderbook::on_finalize(1);
+
+        System::set_block_number(2);
+        place_limit(bob, OrderSide::Buy, 110, 10);
+        assert_eq!(Assets::get_locked_balance(&bob, USDT), 1_100);
+        Orderbook::on_finalize(2);
+
+        // paid 1_000 at the maker's price, the other 100 is free again
+        assert_eq!(Assets::get_locked_balance(&bob, USDT), 0);
+        assert_eq!(Assets::get_free_balance(&bob, USDT), 9_000);
+        assert_ok!(Orderbook::ensure_locked_funds());
+    });
+}
+
+#[test]
+fn test_partial_fill_below_limit_keeps_only_rest_locked() {
+    build_and_execute(|| {
+        let alice = alice();
+        let bob = bob();
+        fund_account(alice, 0, 100);
+        fund_account(bob, 10_000, 0);
+
+        place_limit(alice, OrderSide::Sell, 95, 2);
+        place_limit(alice, OrderSide::Sell, 100, 2);
+        Orderbook::on_finalize(1);
+
+        System::set_block_number(2);
+        place_limit(bob, OrderSide::Buy, 110, 10);
+        Orderbook::on_finalize(2);
+
+        // 2 @ 95 + 2 @ 100 paid, 6 still resting at 110
+        assert_eq!(Assets::get_locked_balance(&bob, USDT), 660);
+        assert_eq!(Assets::get_free_balance(&bob, USDT), 10_000 - 390 - 660);
+        assert_ok!(Orderbook::ensure_locked_funds());
+
+        // cancelling gives back exactly what is left
+        assert_ok!(Orderbook::cancel_order(
+            RuntimeOrigin::signed(bob),
+            ETH_USDT,
+            2
+        ));
+        System::set_block_number(3);
+        Orderbook::on_finalize(3);
+        assert_eq!(Assets::get_locked_balance(&bob, USDT), 0);
+        assert_eq!(Assets::get_free_balance(&bob, USDT), 9_610);
+        assert_ok!(Orderbook::ensure_locked_funds());
+    });
+}
+
+#[test]
+fn test_locked_funds_match_open_orders() {
+    new_test_ext().execute_with(|| {
+        let alice = alice();
+        let bob = bob();
+        let charlie = charlie();
+        fund_account(alice, 10_000, 100);
+        fund_account(bob, 10_000, 100);
+        fund_account(charlie, 10_000, 100);
+
+        // resting, pending, untriggered and market orders all at once
+        place_limit(alice, OrderSide::Sell, 105, 5);
+        place_limit(alice, OrderSide::Buy, 90, 5);
+        place_limit(bob, OrderSide::Buy, 108, 3);
+        Orderbook::on_finalize(1);
+        assert_ok!(Orderbook::ensure_locked_funds());
+
+        System::set_block_number(2);
+        TestOrder::new(OrderSide::Buy, 0, 4)
+            .market(1_000)
+            .place(charlie);
+        TestOrder::new(OrderSide::Sell, 79, 2)
+            .stop(StopKind::StopLoss, 80)
+            .place(charlie);
+        place_limit(bob, OrderSide::Sell, 95, 1);
+        assert_ok!(Orderbook::ensure_locked_funds());
+        Orderbook::on_finalize(2);
+        assert_ok!(Orderbook::ensure_locked_funds());
+
+        // funds locked outside of any order are caught
+        assert_ok!(Assets::lock_funds(&charlie, USDT, 1));
+        assert_noop!(
+            Orderbook::ensure_locked_funds(),
+            pallet_assets::Error::<Test>::LockedBalanceMismatch
+        );
+    });
+}
+
+#[test]
+fn test_try_state_catches_broken_book() {
+    new_test_ext().execute_with(|| {
+        let alice = alice();
+        let bob = bob();
+        fund_account(alice, 10_000, 0);
+        fund_account(bob, 0, 100);
+
+        place_limit(alice, OrderSide::Buy, 99, 5);
+        place_limit(bob, OrderSide::Sell, 101, 5);
+        Orderbook::on_finalize(1);
+        assert_ok!(Orderbook::do_try_state());
+
+        // an id resting at a price its order isn't at
+        crate::Bids::<Test>::mutate(ETH_USDT, 98, |ids| ids.try_push(0).unwrap());
+        assert_noop!(
+            Orderbook::ensure_book_entries(),
+            crate::Error::<Test>::InvalidBookEntry
+        );
+        crate::Bids::<Test>::remove(ETH_USDT, 98);
+
+        // or on the wrong side
+        crate::Asks::<Test>::mutate(ETH_USDT, 99, |ids| ids.try_push(0).unwrap());
+        assert_noop!(
+            Orderbook::ensure_book_entries(),
+            crate::Error::<Test>::InvalidBookEntry
+        );
+        crate::Asks::<Test>::remove(ETH_USDT, 99);
+
+        // an order that is no longer open
+        crate::Orders::<Test>::mutate(1, |order| {
+            order.as_mut().unwrap().status = OrderStatus::Cancelled
+        });
+        assert_noop!(
+            Orderbook::ensure_book_entries(),
+            crate::Error::<Test>::InvalidBookEntry
+        );
+        crate::Orders::<Test>::mutate(1, |order| {
+            order.as_mut().unwrap().status = OrderStatus::Open
+        });
+        assert_ok!(Orderbook::do_try_state());
+
+        // a bid at the best ask
+        crate::Bids::<Test>::swap(ETH_USDT, 99, ETH_USDT, 101);
+        assert_noop!(
+            Orderbook::ensure_book_uncrossed(),
+            crate::Error::<Test>::BookCrossed
+        );
+    });
+}
+
+#[test]
+fn test_try_state_catches_stale_id_counters() {
+    new_test_ext().execute_with(|| {
+        let alice = alice();
+        let bob = bob();
+        fund_account(alice, 10_000, 0);
+        fund_account(bob, 0, 100);
+
+        place_limit(alice, OrderSide::Buy, 100, 5);
+        place_limit(bob, OrderSide::Sell, 100, 5);
+        Orderbook::on_finalize(1);
+        assert_ok!(Orderbook::ensure_id_counters());
+
+        // both orders only live on in the history now
+        crate::NextOrderId::<Test>::put(1);
+        assert_noop!(
+            Orderbook::ensure_id_counters(),
+            crate::Error::<Test>::IdCounterBehind
+        );
+        crate::NextOrderId::<Test>::put(2);
+
+        crate::NextTradeId::<Test>::put(0);
+        assert_noop!(
+            Orderbook::ensure_id_counters(),
+            crate::Error::<Test>::IdCounterBehind
+        );
+    });
+}
+
+// ============================================
+// TRADING FEE TESTS
+// ============================================
+
+const FEE_ACCOUNT: u64 = 99;
+
+#[test]
+fn test_set_market_fees_works() {
+    build_and_execute(|| {
+        let maker = sp_runtime::Permill::from_parts(1_000);
+        let taker = sp_runtime::Permill::from_parts(2_000);
+
+        assert_noop!(
+            Orderbook::set_market_fees(RuntimeOrigin::signed(alice()), ETH_USDT, maker, taker),
+            sp_runtime::DispatchError::BadOrigin
+        );
+        assert_noop!(
+            Orderbook::set_market_fees(RuntimeOrigin::root(), 7, maker, taker),
+            crate::Error::<Test>::MarketNotFound
+        );
+
+        // markets start on the Config defaults
+        assert_eq!(
+            Orderbook::market_fees(ETH_USDT).taker,
+            sp_runtime::Permill::zero()
+        );
+
+        assert_ok!(Orderbook::set_market_fees(
+            RuntimeOrigin::root(),
+            ETH_USDT,
+            maker,
+            taker
+        ));
+        assert_eq!(Orderbook::market_fees(ETH_USDT), FeeRates { maker, taker });
+        System::assert_last_event(RuntimeEvent::Orderbook(crate::Event::MarketFeesSet {
+            market_id: ETH_USDT,
+            maker_fee: maker,
+            taker_fee: taker,
+        }));
+    });
+}
+
+#[test]
+fn test_taker_buy_pays_fees_in_received_assets() {
+    build_and_execute(|| {
+        let alice = alice();
+        let bob = bob();
+        fund_account(alice, 0, 1_000_000);
+        fund_account(bob, 100_000_000, 0);
+        assert_ok!(Orderbook::set_market_fees(
+            RuntimeOrigin::root(),
+            ETH_USDT,
+            sp_runtime::Permill::from_parts(1_000),
+            sp_runtime::Permill::from_parts(2_000),
+        ));
+
+        place_limit(alice, OrderSide::Sell, 100, 1_000_000);
+        Orderbook::on_finalize(1);
+
+        System::set_block_number(2);
+        place_limit(bob, OrderSide::Buy, 100, 1_000_000);
+        Orderbook::on_finalize(2);
+
+        // bob took liquidity: 0.2% of the ETH he bought, alice made it: 0.1% of the USDT
+        assert_eq!(Assets::get_free_balance(&bob, ETH), 998_000);
+        assert_eq!(Assets::get_free_balance(&alice, USDT), 99_900_000);
+        assert_eq!(Assets::get_free_balance(&FEE_ACCOUNT, ETH), 2_000);
+        assert_eq!(Assets::get_free_balance(&FEE_ACCOUNT, USDT), 100_000);
+        assert_eq!(Assets::get_locked_balance(&alice, ETH), 0);
+        assert_eq!(Assets::get_locked_balance(&bob, USDT), 0);
+        assert_ok!(Orderbook::ensure_locked_funds());
+
+        let trade = Orderbook::get_trade(0).unwrap();
+        assert_eq!(trade.taker_side, OrderSide::Buy);
+        assert_eq!((trade.buyer_fee, trade.seller_fee), (2_000, 100_000));
+        System::assert_has_event(RuntimeEvent::Orderbook(crate::Event::TradeExecuted {
+            market_id: ETH_USDT,
+            trade_id: 0,
+            buy_order_id: 1,
+            sell_order_id: 0,
+            buyer: bob,
+            seller: alice,
+            price: 100,
+            quantity: 1_000_000,
+            taker_side: OrderSide::Buy,
+            buyer_fee: 2_000,
+            seller_fee: 100_000,
+            maker_rebate: 0,
+        }));
+    });
+}
+
+#[test]
+fn test_taker_sell_pays_taker_rate() {
+    build_and_execute(|| {
+        let alice = alice();
+        let bob = bob();
+        fund_account(alice, 0, 1_000_000);
+        fund_account(bob, 100_000_000, 0);
+        assert_ok!(Orderbook::set_market_fees(
+            RuntimeOrigin::root(),
+            ETH_USDT,
+            sp_runtime::Permill::from_parts(1_000),
+            sp_runtime::Permill::from_parts(2_000),
+        ));
+
+        place_limit(bob, OrderSide::Buy, 100, 1_000_000);
+        Orderbook::on_finalize(1);
+
+        System::set_block_number(2);
+        place_limit(alice, OrderSide::Sell, 100, 1_000_000);
+        Orderbook::on_finalize(2);
+
+        assert_eq!(Assets::get_free_balance(&bob, ETH), 999_000);
+        assert_eq!(Assets::get_free_balance(&alice, USDT), 99_800_000);
+        assert_eq!(Assets::get_free_balance(&FEE_ACCOUNT, ETH), 1_000);
+        assert_eq!(Assets::get_free_balance(&FEE_ACCOUNT, USDT), 200_000);
+        assert_ok!(Orderbook::ensure_locked_funds());
+    });
+}
+
+#[test]
+fn test_fees_round_down_to_zero_on_small_trades() {
+    build_and_execute(|| {
+        let alice = alice();
+        let bob = bob();
+        fund_account(alice, 0, 100);
+        fund_account(bob, 10_000, 0);
+        assert_ok!(Orderbook::set_market_fees(
+            RuntimeOrigin::root(),
+            ETH_USDT,
+            sp_runtime::Permill::from_parts(1_000),
+            sp_runtime::Permill::from_parts(2_000),
+        ));
+
+        place_limit(alice, OrderSide::Sell, 10, 10);
+        place_limit(bob, OrderSide::Buy, 10, 10);
+        Orderbook::on_finalize(1);
+
+        // 0.2% of 10 ETH and of 100 USDT are both below one unit
+        assert_eq!(Assets::get_free_balance(&bob, ETH), 10);
+        assert_eq!(Assets::get_free_balance(&alice, USDT), 100);
+        assert_eq!(Assets::get_free_balance(&FEE_ACCOUNT, ETH), 0);
+        assert_eq!(Assets::get_free_balance(&FEE_ACCOUNT, USDT), 0);
+    });
+}
+
+// ============================================
+// VOLUME TIER TESTS
+// ============================================
+
+fn tiers(tiers: Vec<FeeTier>) -> frame_support::BoundedVec<FeeTier, MaxFeeTiers> {
+    tiers.try_into().unwrap()
+}
+
+fn cross(maker: u64, taker: u64, quantity: u128) {
+    let block = System::block_number();
+    place_limit(maker, OrderSide::Sell, 100, quantity);
+    Orderbook::on_finalize(block);
+    System::set_block_number(block + 1);
+    place_limit(taker, OrderSide::Buy, 100, quantity);
+    Orderbook::on_finalize(block + 1);
+    System::set_block_number(block + 2);
+}
+
+#[test]
+fn test_set_fee_tiers_validates_table() {
+    build_and_execute(|| {
+        let tier = |min_volume, maker, taker| FeeTier {
+            min_volume,
+            maker,
+            taker: sp_runtime::Permill::from_parts(taker),
+        };
+        let fee = |parts| MakerFee::Fee(sp_runtime::Permill::from_parts(parts));
+        let rebate = |parts| MakerFee::Rebate(sp_runtime::Permill::from_parts(parts));
+
+        let table = tiers(vec![
+            tier(1_000, fee(500), 1_500),
+            tier(5_000, rebate(200), 1_000),
+        ]);
+        assert_noop!(
+            Orderbook::set_fee_tiers(RuntimeOrigin::signed(alice()), table.clone()),
+            sp_runtime::DispatchError::BadOrigin
+        );
+        assert_noop!(
+            Orderbook::set_fee_tiers(
+                RuntimeOrigin::root(),
+                tiers(vec![
+                    tier(5_000, fee(500), 1_500),
+                    tier(1_000, fee(200), 1_000)
+                ])
+            ),
+            crate::Error::<Test>::InvalidFeeTiers
+        );
+        // a rebate larger than the taker fee would drain the fee account
+        assert_noop!(
+            Orderbook::set_fee_tiers(
+                RuntimeOrigin::root(),
+                tiers(vec![tier(1_000, rebate(2_000), 1_000)])
+            ),
+            crate::Error::<Test>::InvalidFeeTiers
+        );
+        // ... and the taker it meets may be on a lower tier
+        assert_noop!(
+            Orderbook::set_fee_tiers(
+                RuntimeOrigin::root(),
+                tiers(vec![
+                    tier(1_000, fee(500), 300),
+                    tier(5_000, rebate(400), 1_000)
+                ])
+            ),
+            crate::Error::<Test>::InvalidFeeTiers
+        );
+
+        assert_ok!(Orderbook::set_fee_tiers(
+            RuntimeOrigin::root(),
+            table.clone()
+        ));
+        assert_eq!(crate::FeeTiers::<Test>::get(), table);
+        System::assert_last_event(RuntimeEvent::Orderbook(crate::Event::FeeTiersSet {
+            tiers: 2,
+        }));
+    });
+}
+
+#[test]
+fn test_volume_rolls_out_of_window() {
+    build_and_execute(|| {
+        let alice = alice();
+        let bob = bob();
+        fund_account(alice, 0, 100);
+        fund_account(bob, 10_000, 0);
+
+        cross(alice, bob, 10);
+        assert_eq!(Orderbook::effective_fees(ETH_USDT, &alice).volume, 1_000);
+        assert_eq!(Orderbook::effective_fees(ETH_USDT, &bob).volume, 1_000);
+
+        // traded in bucket 0, the window is 3 buckets of 10 blocks
+        System::set_block_number(29);
+        assert_eq!(Orderbook::effective_fees(ETH_USDT, &alice).volume, 1_000);
+        System::set_block_number(30);
+        assert_eq!(Orderbook::effective_fees(ETH_USDT, &alice).volume, 0);
+
+        cross(alice, bob, 5);
+        assert_eq!(Orderbook::effective_fees(ETH_USDT, &alice).volume, 500);
+        assert_eq!(crate::TradingVolume::<Test>::get(alice).len(), 1);
+    });
+}
+
+#[test]
+fn test_tier_rates_and_maker_rebate() {
+    build_and_execute(|| {
+        let alice = alice();
+        let bob = bob();
+        fund_account(alice, 0, 2_000_000);
+        fund_account(bob, 200_000_000, 0);
+        assert_ok!(Orderbook::set_market_fees(
+            RuntimeOrigin::root(),
+            ETH_USDT,
+            sp_runtime::Permill::from_parts(1_000),
+            sp_runtime::Permill::from_parts(2_000),
+        ));
+        let tier = FeeTier {
+            min_volume: 50_000_000,
+            maker: MakerFee::Rebate(sp_runtime::Permill::from_parts(500)),
+            taker: sp_runtime::Permill::from_parts(1_000),
+        };
+        assert_ok!(Orderbook::set_fee_tiers(
+            RuntimeOrigin::root(),
+            tiers(vec![tier])
+        ));
+
+        // no volume yet, both pay the market rates
+        let fees = Orderbook::effective_fees(ETH_USDT, &alice);
+        assert_eq!(
+            fees.maker,
+            MakerFee::Fee(sp_runtime::Permill::from_parts(1_000))
+        );
+        cross(alice, bob, 1_000_000);
+        assert_eq!(Assets::get_free_balance(&FEE_ACCOUNT, USDT), 100_000);
+        assert_eq!(Assets::get_free_balance(&FEE_ACCOUNT, ETH), 2_000);
+
+        // 100_000_000 traded reaches the tier, alice sells and is paid in USDT on the quote
+        // volume, like the tiers count it
+        let fees = Orderbook::effective_fees(ETH_USDT, &alice);
+        assert_eq!((fees.volume, fees.maker), (100_000_000, tier.maker));
+        cross(alice, bob, 1_000_000);
+
+        let trade = Orderbook::get_trade(1).unwrap();
+        assert_eq!(
+            (trade.buyer_fee, trade.seller_fee, trade.maker_rebate),
+            (1_000, 0, 50_000)
+        );
+        assert_eq!(Assets::get_free_balance(&alice, USDT), 199_950_000);
+        assert_eq!(Assets::get_free_balance(&alice, ETH), 0);
+        assert_eq!(Assets::get_free_balance(&bob, ETH), 1_997_000);
+        assert_eq!(Assets::get_free_balance(&FEE_ACCOUNT, USDT), 50_000);
+        assert_eq!(Assets::get_free_balance(&FEE_ACCOUNT, ETH), 3_000);
+        assert_ok!(Orderbook::ensure_locked_funds());
+    });
+}
+
+#[test]
+fn test_unfunded_rebate_rolls_back_the_trade() {
+    build_and_execute(|| {
+        let alice = alice();
+        let bob = bob();
+        let charlie = charlie();
+        fund_account(alice, 0, 1_000_010);
+        fund_account(bob, 100_000_000, 0);
+        fund_account(charlie, 1_000, 0);
+        assert_ok!(Orderbook::set_fee_tiers(
+            RuntimeOrigin::root(),
+            tiers(vec![FeeTier {
+                min_volume: 1_000,
+                maker: MakerFee::Rebate(sp_runtime::Permill::from_parts(500)),
+                taker: sp_runtime::Permill::from_parts(500),
+            }])
+        ));
+
+        // alice reaches the tier, bob is still on the fee free market rates
+        cross(alice, charlie, 10);
+        cross(alice, bob, 1_000_000);
+
+        // the fee account holds no USDT to pay alice's 50_000 rebate with
+        System::assert_has_event(RuntimeEvent::Orderbook(crate::Event::TradeRolledBack {
+            market_id: ETH_USDT,
+            buy_order_id: 3,
+            sell_order_id: 2,
+            price: 100,
+            quantity: 1_000_000,
+            error: pallet_assets::Error::<Test>::InsufficientFreeBalance.into(),
+        }));
+        assert_eq!(Orderbook::next_trade_id(), 1);
+        assert_eq!(order_status(2), Some(OrderStatus::Open));
+        assert_eq!(Assets::get_locked_balance(&alice, ETH), 1_000_000);
+        assert_eq!(Assets::get_free_balance(&alice, USDT), 1_000);
+        assert_eq!(Assets::get_free_balance(&bob, USDT), 100_000_000);
+        assert_eq!(Assets::get_free_balance(&FEE_ACCOUNT, USDT), 0);
+        assert_ok!(Orderbook::ensure_locked_funds());
+    });
+}
+
+// ============================================
+// TICK / LOT / MIN NOTIONAL TESTS
+// ============================================
+
+fn set_rules(tick_size: u128, lot_size: u128, min_notional: u128) {
+    assert_ok!(Orderbook::set_market_rules(
+        RuntimeOrigin::root(),
+        ETH_USDT,
+        TradingRules {
+            tick_size,
+            lot_size,
+            min_notional,
+        }
+    ));
+}
+
+fn try_limit(side: OrderSide, price: u128, quantity: u128) -> sp_runtime::DispatchResult {
+    TestOrder::new(side, price, quantity).try_place(alice())
+}
+
+#[test]
+fn test_set_market_rules_works() {
+    build_and_execute(|| {
+        let rules = TradingRules {
+            tick_size: 5,
+            lot_size: 10,
+            min_notional: 1_000,
+        };
+        assert_noop!(
+            Orderbook::set_market_rules(RuntimeOrigin::signed(alice()), ETH_USDT, rules),
+            sp_runtime::DispatchError::BadOrigin
+        );
+        assert_noop!(
+            Orderbook::set_market_rules(RuntimeOrigin::root(), 7, rules),
+            crate::Error::<Test>::MarketNotFound
+        );
+        assert_noop!(
+            Orderbook::set_market_rules(
+                RuntimeOrigin::root(),
+                ETH_USDT,
+                TradingRules {
+                    tick_size: 0,
+                    ..rules
+                }
+            ),
+            crate::Error::<Test>::InvalidTradingRules
+        );
+
+        assert_eq!(Orderbook::market_rules(ETH_USDT).tick_size, 1);
+        set_rules(5, 10, 1_000);
+        assert_eq!(Orderbook::market_rules(ETH_USDT), rules);
+        System::assert_last_event(RuntimeEvent::Orderbook(crate::Event::MarketRulesSet {
+            market_id: ETH_USDT,
+            rules,
+        }));
+    });
+}
+
+#[test]
+fn test_orders_must_follow_market_rules() {
+    build_and_execute(|| {
+        fund_account(alice(), 100_000, 1_000);
+        set_rules(5, 10, 1_000);
+
+        assert_noop!(
+            try_limit(OrderSide::Buy, 101, 10),
+            crate::Error::<Test>::PriceNotOnTick
+        );
+        assert_noop!(
+            try_limit(OrderSide::Sell, 100, 15),
+            crate::Error::<Test>::QuantityNotOnLot
+        );
+        // 95 * 10 = 950 is under the 1_000 minimum
+        assert_noop!(
+            try_limit(OrderSide::Buy, 95, 10),
+            crate::Error::<Test>::BelowMinNotional
+        );
+        assert_ok!(try_limit(OrderSide::Buy, 100, 10));
+        assert_ok!(try_limit(OrderSide::Sell, 105, 20));
+    });
+}
+
+#[test]
+fn test_market_and_stop_orders_follow_market_rules() {
+    build_and_execute(|| {
+        fund_account(alice(), 100_000, 1_000);
+        set_rules(5, 10, 1_000);
+
+        let market = |side, quantity, quote_limit| {
+            TestOrder::new(side, 0, quantity)
+                .market(quote_limit)
+                .time_in_force(TimeInForce::IOC)
+                .try_place(alice())
+        };
+        assert_noop!(
+            market(OrderSide::Buy, 15, 2_000),
+            crate::Error::<Test>::QuantityNotOnLot
+        );
+        assert_noop!(
+            market(OrderSide::Buy, 10, 999),
+            crate::Error::<Test>::BelowMinNotional
+        );
+        assert_ok!(market(OrderSide::Buy, 10, 1_000));
+        // a market sell has no price to hold to the notional
+        assert_ok!(market(OrderSide::Sell, 10, 0));
+
+        let stop = |trigger_price, price| {
+            Orderbook::place_stop_order(
+                RuntimeOrigin::signed(alice()),
+                ETH_USDT,
+                OrderSide::Sell,
+                StopKind::StopLoss,
+                trigger_price,
+                price,
+                10,
+                OrderType::Limit,
+            )
+        };
+        assert_noop!(stop(92, 100), crate::Error::<Test>::PriceNotOnTick);
+        assert_noop!(stop(90, 102), crate::Error::<Test>::PriceNotOnTick);
+        assert_ok!(stop(90, 100));
+    });
+}
+
+#[test]
+fn test_post_only_reprices_by_market_tick() {
+    build_and_execute(|| {
+        let alice = alice();
+        let bob = bob();
+        fund_account(alice, 0, 100);
+        fund_account(bob, 10_000, 0);
+        set_rules(5, 1, 0);
+
+        place_limit(alice, OrderSide::Sell, 100, 10);
+        Orderbook::on_finalize(1);
+
+        System::set_block_number(2);
+        TestOrder::new(OrderSide::Buy, 110, 10)
+            .post_only(PostOnly::Reprice)
+            .place(bob);
+        Orderbook::on_finalize(2);
+
+        // one tick below the best ask instead of one unit
+        assert_eq!(Orderbook::get_order(1).unwrap().price, 95);
+        assert_ok!(Orderbook::ensure_locked_funds());
+    });
+}
+
+// ============================================
+// BATCH ORDER TESTS
+// ============================================
+
+fn batch<T: Clone + core::fmt::Debug, S: sp_core::Get<u32>>(
+    items: &[T],
+) -> frame_support::BoundedVec<T, S> {
+    items.to_vec().try_into().unwrap()
+}
+
+#[test]
+fn test_place_orders_places_a_ladder() {
+    build_and_execute(|| {
+        let alice = alice();
+        fund_account(alice, 10_000, 100);
+
+        assert_ok!(Orderbook::place_orders(
+            RuntimeOrigin::signed(alice),
+            batch(&[
+                TestOrder::new(OrderSide::Buy, 98, 5).request(),
+                TestOrder::new(OrderSide::Buy, 99, 5).request(),
+                TestOrder::new(OrderSide::Sell, 101, 5).request(),
+                TestOrder::new(OrderSide::Sell, 102, 5).request(),
+            ])
+        ));
+
+        assert_eq!(Orderbook::next_order_id(), 4);
+        assert_eq!(Orderbook::get_order(3).unwrap().price, 102);
+        assert_eq!(Assets::get_locked_balance(&alice, USDT), 98 * 5 + 99 * 5);
+        assert_eq!(Assets::get_locked_balance(&alice, ETH), 10);
+        Orderbook::on_finalize(1);
+        assert_eq!(Orderbook::get_best_prices(ETH_USDT), (Some(99), Some(101)));
+        assert_ok!(Orderbook::ensure_locked_funds());
+    });
+}
+
+#[test]
+fn test_place_orders_is_all_or_nothing() {
+    build_and_execute(|| {
+        let alice = alice();
+        fund_account(alice, 1_000, 0);
+
+        assert_noop!(
+            Orderbook::place_orders(
+                RuntimeOrigin::signed(alice),
+                batch(&[
+                    TestOrder::new(OrderSide::Buy, 50, 10).request(),
+                    TestOrder::new(OrderSide::Buy, 60, 10).request(),
+                ])
+            ),
+            pallet_assets::Error::<Test>::InsufficientFreeBalance
+        );
+        assert_noop!(
+            Orderbook::place_orders(RuntimeOrigin::signed(alice), batch(&[])),
+            crate::Error::<Test>::EmptyBatch
+        );
+    });
+}
+
+#[test]
+fn test_cancel_orders_across_markets() {
+    build_and_execute(|| {
+        let alice = alice();
+        let bob = bob();
+        fund_account(alice, 10_000, 0);
+        fund_account(bob, 10_000, 0);
+        let btc = create_btc_market();
+
+        assert_ok!(Orderbook::place_orders(
+            RuntimeOrigin::signed(alice),
+            batch(&[
+                TestOrder::new(OrderSide::Buy, 90, 5).request(),
+                TestOrder::new(OrderSide::Buy, 80, 5)
+                    .on_market(btc)
+                    .request(),
+            ])
+        ));
+        place_limit(bob, OrderSide::Buy, 95, 5);
+
+        // bob's order in the batch fails the whole batch
+        assert_noop!(
+            Orderbook::cancel_orders(RuntimeOrigin::signed(alice), batch(&[0, 2])),
+            crate::Error::<Test>::NotOrderOwner
+        );
+        assert_noop!(
+            Orderbook::cancel_orders(RuntimeOrigin::signed(alice), batch(&[0, 7])),
+            crate::Error::<Test>::OrderNotFound
+        );
+
+        assert_ok!(Orderbook::cancel_orders(
+            RuntimeOrigin::signed(alice),
+            batch(&[0, 1])
+        ));
+        assert_eq!(Orderbook::get_pending_cancellations(ETH_USDT), vec![0]);
+        assert_eq!(Orderbook::get_pending_cancellations(btc), vec![1]);
+
+        Orderbook::on_finalize(1);
+        assert_eq!(Assets::get_locked_balance(&alice, USDT), 0);
+        assert_eq!(Assets::get_free_balance(&alice, USDT), 10_000);
+        assert_ok!(Orderbook::ensure_locked_funds());
+    });
+}
+
+#[test]
+fn test_replace_orders_cancels_and_places() {
+    build_and_execute(|| {
+        let alice = alice();
+        fund_account(alice, 2_000, 0);
+
+        assert_ok!(Orderbook::place_orders(
+            RuntimeOrigin::signed(alice),
+            batch(&[
+                TestOrder::new(OrderSide::Buy, 90, 5).request(),
+                TestOrder::new(OrderSide::Buy, 91, 5).request(),
+            ])
+        ));
+        Orderbook::on_finalize(1);
+
+        System::set_block_number(2);
+        // a failing placement keeps the cancels from happening too
+        assert_noop!(
+            Orderbook::replace_orders(
+                RuntimeOrigin::signed(alice),
+                batch(&[0, 1]),
+                batch(&[TestOrder::new(OrderSide::Buy, 0, 5).request()])
+            ),
+            crate::Error::<Test>::InvalidPrice
+        );
+
+        assert_ok!(Orderbook::replace_orders(
+            RuntimeOrigin::signed(alice),
+            batch(&[0, 1]),
+            batch(&[TestOrder::new(OrderSide::Buy, 92, 5).request()])
+        ));
+        Orderbook::on_finalize(2);
+
+        assert_eq!(order_status(0), Some(OrderStatus::Cancelled));
+        assert_eq!(Orderbook::get_best_prices(ETH_USDT).0, Some(92));
+        assert_eq!(Assets::get_locked_balance(&alice, USDT), 460);
+        assert_ok!(Orderbook::ensure_locked_funds());
+    });
+}
+
+// ============================================
+// CANCEL ALL TESTS
+// ============================================
+
+#[test]
+fn test_cancel_all_orders_by_side() {
+    build_and_execute(|| {
+        let alice = alice();
+        let bob = bob();
+        fund_account(alice, 10_000, 100);
+        fund_account(bob, 10_000, 0);
+
+        place_limit(alice, OrderSide::Buy, 90, 5);
+        place_limit(alice, OrderSide::Sell, 110, 5);
+        place_limit(alice, OrderSide::Buy, 91, 5);
+        place_limit(bob, OrderSide::Buy, 92, 5);
+        Orderbook::on_finalize(1);
+
+        System::set_block_number(2);
+        assert_ok!(Orderbook::cancel_all_orders(
+            RuntimeOrigin::signed(alice),
+            Some(OrderSide::Buy)
+        ));
+        assert_eq!(Orderbook::get_pending_cancellations(ETH_USDT), vec![0, 2]);
+        System::assert_last_event(RuntimeEvent::Orderbook(crate::Event::CancelAllQueued {
+            trader: alice,
+            side: Some(OrderSide::Buy),
+            queued: 2,
+            remaining: 0,
+        }));
+
+        // already queued orders are not queued twice
+        assert_ok!(Orderbook::cancel_all_orders(
+            RuntimeOrigin::signed(alice),
+            None
+        ));
+        assert_eq!(
+            Orderbook::get_pending_cancellations(ETH_USDT),
+            vec![0, 2, 1]
+        );
+        Orderbook::on_finalize(2);
+
+        for order_id in 0..3 {
+            assert_eq!(order_status(order_id), Some(OrderStatus::Cancelled));
+        }
+        assert_eq!(Orderbook::get_order(3).unwrap().status, OrderStatus::Open);
+        assert_eq!(Assets::get_locked_balance(&alice, USDT), 0);
+        assert_eq!(Assets::get_locked_balance(&alice, ETH), 0);
+        assert!(crate::CancelAllQueue::<Test>::get().is_empty());
+        assert_ok!(Orderbook::ensure_locked_funds());
+    });
+}
+
+#[test]
+fn test_cancel_all_cancels_untriggered_stops_right_away() {
+    build_and_execute(|| {
+        let alice = alice();
+        fund_account(alice, 0, 100);
+
+        TestOrder::new(OrderSide::Sell, 79, 2)
+            .stop(StopKind::StopLoss, 80)
+            .place(alice);
+        assert_ok!(Orderbook::cancel_all_orders(
+            RuntimeOrigin::signed(alice),
+            None
+        ));
+
+        assert_eq!(order_status(0), Some(OrderStatus::Cancelled));
+        assert!(Orderbook::get_stop_orders(ETH_USDT).is_empty());
+        assert_eq!(Assets::get_locked_balance(&alice, ETH), 0);
+    });
+}
+
+#[test]
+fn test_cancel_all_spreads_over_blocks() {
+    build_and_execute(|| {
+        let alice = alice();
+        fund_account(alice, 1_000_000, 0);
+
+        // 60 bids, 10 more than MaxCancellationOrders (50) takes in a block
+        let bids: Vec<OrderRequest> = (0..60)
+            .map(|i| TestOrder::new(OrderSide::Buy, 50 + i % 40, 1).request())
+            .collect();
+        assert_ok!(Orderbook::place_orders(
+            RuntimeOrigin::signed(alice),
+            batch(&bids)
+        ));
+        Orderbook::on_finalize(1);
+
+        System::set_block_number(2);
+        assert_ok!(Orderbook::cancel_all_orders(
+            RuntimeOrigin::signed(alice),
+            None
+        ));
+        System::assert_last_event(RuntimeEvent::Orderbook(crate::Event::CancelAllQueued {
+            trader: alice,
+            side: None,
+            queued: 50,
+            remaining: 10,
+        }));
+        assert_eq!(crate::CancelAllQueue::<Test>::get().len(), 1);
+        Orderbook::on_finalize(2);
+
+        System::set_block_number(3);
+        Orderbook::on_initialize(3);
+        assert_eq!(Orderbook::get_pending_cancellations(ETH_USDT).len(), 10);
+        assert!(crate::CancelAllQueue::<Test>::get().is_empty());
+        Orderbook::on_finalize(3);
+
+        assert_eq!(Assets::get_locked_balance(&alice, USDT), 0);
+        assert_eq!(Orderbook::get_best_prices(ETH_USDT), (None, None));
+        assert_ok!(Orderbook::ensure_locked_funds());
+    });
+}
+
+// ============================================
+// AMEND ORDER TESTS
+// ============================================
+
+#[test]
+fn test_amend_size_down_keeps_priority() {
+    build_and_execute(|| {
+        let alice = alice();
+        let bob = bob();
+        let charlie = charlie();
+        fund_account(alice, 10_000, 0);
+        fund_account(bob, 10_000, 0);
+        fund_account(charlie, 0, 100);
+
+        place_limit(alice, OrderSide::Buy, 100, 10);
+        place_limit(bob, OrderSide::Buy, 100, 10);
+        Orderbook::on_finalize(1);
+
+        System::set_block_number(2);
+        assert_ok!(Orderbook::amend_order(
+            RuntimeOrigin::signed(alice),
+            0,
+            100,
+            4
+        ));
+        System::assert_last_event(RuntimeEvent::Orderbook(crate::Event::OrderAmended {
+            market_id: ETH_USDT,
+            order_id: 0,
+            trader: alice,
+            old_price: 100,
+            old_quantity: 10,
+            new_price: 100,
+            new_quantity: 4,
+            kept_priority: true,
+        }));
+        assert_eq!(Orderbook::get_bids_at_price(ETH_USDT, 100), vec![0, 1]);
+        assert_eq!(Assets::get_locked_balance(&alice, USDT), 400);
+        assert_eq!(Assets::get_free_balance(&alice, USDT), 9_600);
+
+        // alice is still first in line
+        place_limit(charlie, OrderSide::Sell, 100, 4);
+        Orderbook::on_finalize(2);
+        assert!(Orderbook::get_order(0).is_none());
+        assert_eq!(Orderbook::get_order(1).unwrap().filled_quantity, 0);
+        assert_eq!(Assets::get_free_balance(&alice, ETH), 4);
+        assert_eq!(Assets::get_locked_balance(&alice, USDT), 0);
+        assert_ok!(Orderbook::ensure_locked_funds());
+    });
+}
+
+#[test]
+fn test_amend_size_up_loses_priority() {
+    build_and_execute(|| {
+        let alice = alice();
+        let bob = bob();
+        fund_account(alice, 10_000, 0);
+        fund_account(bob, 10_000, 0);
+
+        place_limit(alice, OrderSide::Buy, 100, 10);
+        place_limit(bob, OrderSide::Buy, 100, 10);
+        Orderbook::on_finalize(1);
+
+        System::set_block_number(2);
+        assert_ok!(Orderbook::amend_order(
+            RuntimeOrigin::signed(alice),
+            0,
+            100,
+            15
+        ));
+        assert_eq!(Orderbook::get_bids_at_price(ETH_USDT, 100), vec![1]);
+        assert_eq!(Orderbook::get_pending_bids_at_price(ETH_USDT, 100), vec![0]);
+        assert_eq!(Assets::get_locked_balance(&alice, USDT), 1_500);
+
+        Orderbook::on_finalize(2);
+        assert_eq!(Orderbook::get_bids_at_price(ETH_USDT, 100), vec![1, 0]);
+        assert_eq!(Orderbook::get_order(0).unwrap().quantity, 15);
+        assert_ok!(Orderbook::ensure_locked_funds());
+    });
+}
+
+#[test]
+fn test_amend_price_of_partially_filled_order() {
+    build_and_execute(|| {
+        let alice = alice();
+        let bob = bob();
+        fund_account(alice, 0, 10);
+        fund_account(bob, 10_000, 0);
+
+        place_limit(alice, OrderSide::Sell, 110, 10);
+        place_limit(bob, OrderSide::Buy, 110, 4);
+        place_limit(bob, OrderSide::Buy, 100, 6);
+        Orderbook::on_finalize(1);
+        assert_eq!(
+            Orderbook::get_order(0).unwrap().status,
+            OrderStatus::PartiallyFilled
+        );
+
+        // a sell only locks base, so a new price leaves the lock alone
+        System::set_block_number(2);
+        assert_ok!(Orderbook::amend_order(
+            RuntimeOrigin::signed(alice),
+            0,
+            100,
+            10
+        ));
+        assert!(Orderbook::get_asks_at_price(ETH_USDT, 110).is_empty());
+        assert_eq!(Orderbook::get_pending_asks_at_price(ETH_USDT, 100), vec![0]);
+        assert_eq!(Assets::get_locked_balance(&alice, ETH), 6);
+
+        // the amended order crosses the resting bid when the block closes
+        Orderbook::on_finalize(2);
+        assert!(Orderbook::get_order(0).is_none());
+        assert!(Orderbook::get_order(2).is_none());
+        assert_eq!(Assets::get_free_balance(&alice, USDT), 440 + 600);
+        assert_eq!(Assets::get_locked_balance(&alice, ETH), 0);
+        assert_ok!(Orderbook::ensure_locked_funds());
+    });
+}
+
+#[test]
+fn test_amend_order_fails() {
+    build_and_execute(|| {
+        let alice = alice();
+        let bob = bob();
+        fund_account(alice, 1_000, 0);
+        fund_account(bob, 0, 10);
+
+        place_limit(alice, OrderSide::Buy, 100, 5);
+        place_limit(bob, OrderSide::Sell, 100, 2);
+        Orderbook::on_finalize(1);
+
+        System::set_block_number(2);
+        assert_noop!(
+            Orderbook::amend_order(RuntimeOrigin::signed(bob), 0, 100, 4),
+            crate::Error::<Test>::NotOrderOwner
+        );
+        assert_noop!(
+            Orderbook::amend_order(RuntimeOrigin::signed(alice), 0, 100, 5),
+            crate::Error::<Test>::InvalidAmendment
+        );
+        // two of the five already filled
+        assert_noop!(
+            Orderbook::amend_order(RuntimeOrigin::signed(alice), 0, 100, 2),
+            crate::Error::<Test>::InvalidQuantity
+        );
+        assert_noop!(
+            Orderbook::amend_order(RuntimeOrigin::signed(alice), 0, 0, 5),
+            crate::Error::<Test>::InvalidPrice
+        );
+        assert_noop!(
+            Orderbook::amend_order(RuntimeOrigin::signed(alice), 0, 100, 20),
+            pallet_assets::Error::<Test>::InsufficientFreeBalance
+        );
+        assert_noop!(
+            Orderbook::amend_order(RuntimeOrigin::signed(alice), 7, 100, 4),
+            crate::Error::<Test>::OrderNotFound
+        );
+
+        assert_ok!(Orderbook::cancel_order(
+            RuntimeOrigin::signed(alice),
+            ETH_USDT,
+            0
+        ));
+        assert_noop!(
+            Orderbook::amend_order(RuntimeOrigin::signed(alice), 0, 100, 4),
+            crate::Error::<Test>::OrderNotActive
+        );
+    });
+}
+
+// ============================================
+// ORDER PRUNING TESTS
+// ============================================
+
+#[test]
+fn test_finished_orders_are_moved_to_history() {
+    build_and_execute(|| {
+        let alice = alice();
+        let bob = bob();
+        fund_account(alice, 10_000, 0);
+        fund_account(bob, 0, 100);
+
+        place_limit(alice, OrderSide::Buy, 100, 5);
+        place_limit(alice, OrderSide::Buy, 90, 5);
+        place_limit(alice, OrderSide::Buy, 80, 5);
+        place_limit(bob, OrderSide::Sell, 100, 5);
+        Orderbook::on_finalize(1);
+
+        System::set_block_number(2);
+        assert_ok!(Orderbook::cancel_order(
+            RuntimeOrigin::signed(alice),
+            ETH_USDT,
+            1
+        ));
+        Orderbook::on_finalize(2);
+
+        assert_eq!(Orderbook::get_user_orders(&alice), vec![2]);
+        assert!(Orderbook::get_user_orders(&bob).is_empty());
+        assert!(Orderbook::get_order(0).is_none());
+        assert!(Orderbook::get_order(1).is_none());
+
+        let history: Vec<_> = Orderbook::get_order_history(&alice)
+            .into_iter()
+            .map(|order| (order.order_id, order.status, order.filled_quantity))
+            .collect();
+        assert_eq!(
+            history,
+            vec![(0, OrderStatus::Filled, 5), (1, OrderStatus::Cancelled, 0)]
+        );
+        assert_eq!(Orderbook::get_order_history(&bob).len(), 1);
+        assert_ok!(Orderbook::ensure_locked_funds());
+    });
+}
+
+#[test]
+fn test_order_history_drops_oldest() {
+    build_and_execute(|| {
+        let alice = alice();
+        fund_account(alice, 10_000, 0);
+
+        for _ in 0..7 {
+            place_limit(alice, OrderSide::Buy, 100, 1);
+        }
+        Orderbook::on_finalize(1);
+
+        System::set_block_number(2);
+        assert_ok!(Orderbook::cancel_all_orders(
+            RuntimeOrigin::signed(alice),
+            None
+        ));
+        Orderbook::on_finalize(2);
+
+        // MaxOrderHistory is 5 in the mock
+        let kept: Vec<_> = Orderbook::get_order_history(&alice)
+            .into_iter()
+            .map(|order| order.order_id)
+            .collect();
+        assert_eq!(kept, vec![2, 3, 4, 5, 6]);
+        assert!(Orderbook::get_user_orders(&alice).is_empty());
+    });
+}
+
+#[test]
+fn test_user_order_slots_are_freed() {
+    build_and_execute(|| {
+        let alice = alice();
+        fund_account(alice, 100_000, 0);
+
+        let max = <Test as crate::Config>::MaxUserOrders::get();
+        for i in 0..max {
+            place_limit(alice, OrderSide::Buy, 100 - (i % 10) as u128, 1);
+        }
+        assert_noop!(
+            Orderbook::place_order(
+                RuntimeOrigin::signed(alice),
+                ETH_USDT,
+                OrderSide::Buy,
+                100,
+                1,
+                OrderType::Limit,
+                TimeInForce::GTC,
+                None,
+                None,
+                None,
+                None,
+                None,
+            ),
+            crate::Error::<Test>::TooManyUserOrders
+        );
+        Orderbook::on_finalize(1);
+
+        System::set_block_number(2);
+        assert_ok!(Orderbook::cancel_orders(
+            RuntimeOrigin::signed(alice),
+            vec![0, 1].try_into().unwrap()
+        ));
+        Orderbook::on_finalize(2);
+
+        System::set_block_number(3);
+        place_limit(alice, OrderSide::Buy, 100, 1);
+        place_limit(alice, OrderSide::Buy, 100, 1);
+        assert_eq!(Orderbook::get_user_orders(&alice).len() as u32, max);
+    });
+}
+
+#[test]
+fn test_migration_from_the_v0_layout() {
+    use crate::migrations::{V0Order, V0OrderType, V0Trade, v0, v1, v2, v3, v4};
+    use frame_support::{
+        BoundedVec,
+        traits::{GetStorageVersion, OnRuntimeUpgrade, StorageVersion},
+    };
+
+    new_test_ext().execute_with(|| {
+        let alice = alice();
+        let bob = bob();
+        fund_account(alice, 10_000, 0);
+        fund_account(bob, 0, 100);
+        assert_ok!(Assets::lock_funds(&alice, USDT, 400));
+        assert_ok!(Assets::lock_funds(&bob, ETH, 5));
+
+        // v0 had no market registry
+        crate::Markets::<Test>::remove(ETH_USDT);
+        crate::MarketsByPair::<Test>::remove(MarketPair {
+            base_asset: ETH,
+            quote_asset: USDT,
+        });
+        crate::NextMarketId::<Test>::kill();
+
+        let order = |order_id, trader, side, status, order_type, price| V0Order::<Test> {
+            order_id,
+            trader,
+            side,
+            status,
+            order_type,
+            price,
+            quantity: 5,
+            filled_quantity: 0,
+            ttl: None,
+        };
+        // 0 and 3 filled and were removed, 1 was cancelled but stayed, still listed at 90
+        let cancelled = order(
+            1,
+            alice,
+            OrderSide::Buy,
+            OrderStatus::Cancelled,
+            V0OrderType::Limit,
+            90,
+        );
+        v0::Orders::<Test>::insert(1, &cancelled);
+        v0::Orders::<Test>::insert(
+            2,
+            order(
+                2,
+                alice,
+                OrderSide::Buy,
+                OrderStatus::Open,
+                V0OrderType::Limit,
+                80,
+            ),
+        );
+        v0::Orders::<Test>::insert(
+            4,
+            order(
+                4,
+                bob,
+                OrderSide::Sell,
+                OrderStatus::Open,
+                V0OrderType::Market,
+                120,
+            ),
+        );
+        v0::Bids::<Test>::insert(90, BoundedVec::truncate_from(vec![1]));
+        v0::Bids::<Test>::insert(80, BoundedVec::truncate_from(vec![2]));
+        v0::Asks::<Test>::insert(120, BoundedVec::truncate_from(vec![4]));
+        v0::Trades::<Test>::insert(
+            0,
+            V0Trade {
+                trade_id: 0,
+                buyer: alice,
+                seller: bob,
+                buy_order_id: 0,
+                sell_order_id: 3,
+                price: 100,
+                quantity: 5,
+            },
+        );
+        crate::UserOrders::<Test>::insert(alice, BoundedVec::truncate_from(vec![0, 1, 2]));
+        crate::UserOrders::<Test>::insert(bob, BoundedVec::truncate_from(vec![3, 4]));
+        crate::NextOrderId::<Test>::put(5);
+        crate::NextTradeId::<Test>::put(1);
+        StorageVersion::new(0).put::<Orderbook>();
+
+        <(
+            v1::MigrateToV1<Test>,
+            v2::MigrateToV2<Test>,
+            v3::MigrateToV3<Test>,
+            v4::MigrateToV4<Test>,
+        )>::on_runtime_upgrade();
+
+        assert_eq!(
+            Orderbook::on_chain_storage_version(),
+            StorageVersion::new(4)
+        );
+        assert_eq!(
+            Orderbook::get_market(ETH_USDT).map(|market| market.pair),
+            Some(MarketPair {
+                base_asset: ETH,
+                quote_asset: USDT,
+            })
+        );
+
+        // open orders carry on in the market, the market order as the limit order it was
+        let order = Orderbook::get_order(2).unwrap();
+        assert_eq!(
+            (order.market_id, order.status, order.time_in_force),
+            (ETH_USDT, OrderStatus::Open, TimeInForce::GTC)
+        );
+        assert_eq!(
+            Orderbook::get_order(4).unwrap().order_type,
+            OrderType::Limit
+        );
+        assert_eq!(Orderbook::get_user_orders(&alice), vec![2]);
+        assert_eq!(Orderbook::get_user_orders(&bob), vec![4]);
+
+        // the finished one went to the history
+        assert!(Orderbook::get_order(1).is_none());
+        let history = Orderbook::get_order_history(&alice);
+        assert_eq!(history.len(), 1);
+        assert_eq!(
+            (history[0].order_id, history[0].status, history[0].price),
+            (1, OrderStatus::Cancelled, cancelled.price)
+        );
+        assert!(Orderbook::get_order_history(&bob).is_empty());
+
+        // books are keyed by market, without the order that is gone
+        assert_eq!(Orderbook::get_bids_at_price(ETH_USDT, 80), vec![2]);
+        assert!(Orderbook::get_bids_at_price(ETH_USDT, 90).is_empty());
+        assert_eq!(Orderbook::get_asks_at_price(ETH_USDT, 120), vec![4]);
+        assert_eq!(
+            crate::BidPrices::<Test>::get(ETH_USDT).into_inner(),
+            vec![80]
+        );
+        assert_eq!(
+            crate::AskPrices::<Test>::get(ETH_USDT).into_inner(),
+            vec![120]
+        );
+        assert_eq!(crate::BestBid::<Test>::get(ETH_USDT), Some(80));
+        assert_eq!(crate::BestAsk::<Test>::get(ETH_USDT), Some(120));
+
+        let trade = Orderbook::get_trade(0).unwrap();
+        assert_eq!(
+            (
+                trade.market_id,
+                trade.price,
+                trade.quantity,
+                trade.taker_side
+            ),
+            (ETH_USDT, 100, 5, OrderSide::Sell)
+        );
+        assert_eq!((trade.buyer_fee, trade.seller_fee), (0, 0));
+
+        assert_ok!(Orderbook::do_try_state());
+    });
+}
+
+#[test]
+fn test_migration_releases_what_v0_kept_locked() {
+    use crate::migrations::{V0Order, V0OrderType, V0Trade, v0, v1, v2, v3, v4};
+    use frame_support::{
+        BoundedVec,
+        traits::{OnRuntimeUpgrade, StorageVersion},
+    };
+
+    new_test_ext().execute_with(|| {
+        let alice = alice();
+        fund_account(alice, 10_000, 0);
+        // order 0 bought 5 at 100 and traded at 90, v0 kept the 50 it saved locked. Order 1
+        // rests at 80 with 2 of 5 filled, it needs 240.
+        assert_ok!(Assets::lock_funds(&alice, USDT, 50 + 240));
+
+        crate::Markets::<Test>::remove(ETH_USDT);
+        crate::MarketsByPair::<Test>::remove(MarketPair {
+            base_asset: ETH,
+            quote_asset: USDT,
+        });
+        crate::NextMarketId::<Test>::kill();
+
+        v0::Orders::<Test>::insert(
+            1,
+            V0Order::<Test> {
+                order_id: 1,
+                trader: alice,
+                side: OrderSide::Buy,
+                status: OrderStatus::PartiallyFilled,
+                order_type: V0OrderType::Limit,
+                price: 80,
+                quantity: 5,
+                filled_quantity: 2,
+                ttl: None,
+            },
+        );
+        v0::Bids::<Test>::insert(80, BoundedVec::truncate_from(vec![1]));
+        v0::Trades::<Test>::insert(
+            0,
+            V0Trade {
+                trade_id: 0,
+                buyer: alice,
+                seller: bob(),
+                buy_order_id: 0,
+                sell_order_id: 3,
+                price: 90,
+                quantity: 5,
+            },
+        );
+        crate::UserOrders::<Test>::insert(alice, BoundedVec::truncate_from(vec![0, 1]));
+        crate::NextOrderId::<Test>::put(4);
+        crate::NextTradeId::<Test>::put(1);
+        StorageVersion::new(0).put::<Orderbook>();
+
+        <(
+            v1::MigrateToV1<Test>,
+            v2::MigrateToV2<Test>,
+            v3::MigrateToV3<Test>,
+            v4::MigrateToV4<Test>,
+        )>::on_runtime_upgrade();
+
+        assert_eq!(Assets::get_locked_balance(&alice, USDT), 240);
+        assert_eq!(Assets::get_free_balance(&alice, USDT), 10_000 - 240);
+        assert_ok!(Orderbook::do_try_state());
+    });
+}
+
+// ============================================
+// TRADE RETENTION TESTS
+// ============================================
+
+// field bytes of every TradeExecuted event so far, what an indexer archives
+fn archived_trades() -> Vec<Vec<u8>> {
+    use codec::Encode;
+
+    System::events()
+        .into_iter()
+        .filter_map(|record| match record.event {
+            RuntimeEvent::Orderbook(event @ crate::Event::TradeExecuted { .. }) => {
+                Some(event.encode()[1..].to_vec())
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+fn chain_commitment(trades: &[Vec<u8>]) -> sp_core::H256 {
+    use sp_runtime::traits::{BlakeTwo256, Hash};
+
+    trades
+        .iter()
+        .fold(sp_core::H256::zero(), |commitment, trade| {
+            BlakeTwo256::hash(&[commitment.as_bytes(), trade].concat())
+        })
+}
+
+#[test]
+fn test_on_idle_prunes_trades_past_retention() {
+    build_and_execute(|| {
+        let alice = alice();
+        let bob = bob();
+        fund_account(alice, 0, 100);
+        fund_account(bob, 10_000, 0);
+
+        for _ in 0..5 {
+            cross(alice, bob, 2);
+        }
+        assert_eq!(Orderbook::next_trade_id(), 5);
+        let archive = archived_trades();
+        assert_eq!(archive.len(), 5);
+
+        let block = System::block_number();
+        Orderbook::on_idle(block, Weight::MAX);
+
+        // TradeRetention is 3 in the mock
+        assert!(Orderbook::get_trade(0).is_none());
+        assert!(Orderbook::get_trade(1).is_none());
+        assert!(Orderbook::get_trade(2).is_some());
+        assert_eq!(crate::PrunedTradesUpTo::<Test>::get(), 2);
+
+        // the indexer can rebuild the commitment from the events alone
+        let commitment = chain_commitment(&archive[..2]);
+        assert_eq!(crate::TradeArchiveCommitment::<Test>::get(), commitment);
+        System::assert_last_event(RuntimeEvent::Orderbook(crate::Event::TradesPruned {
+            first_trade_id: 0,
+            last_trade_id: 1,
+            commitment,
+        }));
+
+        // nothing left past the window
+        let events = System::events().len();
+        Orderbook::on_idle(block, Weight::MAX);
+        assert_eq!(System::events().len(), events);
+
+        cross(alice, bob, 2);
+        Orderbook::on_idle(System::block_number(), Weight::MAX);
+        assert!(Orderbook::get_trade(2).is_none());
+        assert_eq!(
+            crate::TradeArchiveCommitment::<Test>::get(),
+            chain_commitment(&archived_trades()[..3])
+        );
+    });
+}
+
+#[test]
+fn test_trade_pruning_respects_idle_weight() {
+    build_and_execute(|| {
+        let alice = alice();
+        let bob = bob();
+        fund_account(alice, 0, 100);
+        fund_account(bob, 10_000, 0);
+
+        for _ in 0..6 {
+            cross(alice, bob, 2);
+        }
+
+        let one = <Test as crate::Config>::WeightInfo::prune_trades(1);
+        let block = System::block_number();
+        assert_eq!(Orderbook::on_idle(block, one), one);
+        assert_eq!(crate::PrunedTradesUpTo::<Test>::get(), 1);
+
+        // not even room for one trade
+        assert_eq!(
+            Orderbook::on_idle(block, <Test as crate::Config>::WeightInfo::prune_trades(0)),
+            Weight::zero()
+        );
+        assert_eq!(crate::PrunedTradesUpTo::<Test>::get(), 1);
+
+        Orderbook::on_idle(block, Weight::MAX);
+        assert_eq!(crate::PrunedTradesUpTo::<Test>::get(), 3);
+        assert_eq!(
+            crate::TradeArchiveCommitment::<Test>::get(),
+            chain_commitment(&archived_trades()[..3])
+        );
+    });
+}
+
+// ============================================
+// SELF-TRADE PREVENTION TESTS
+// ============================================
+
+fn self_trade_prevented(
+    trader: u64,
+    taker_order_id: u64,
+    maker_order_id: u64,
+    mode: SelfTradePrevention,
+    quantity: u128,
+) -> RuntimeEvent {
+    RuntimeEvent::Orderbook(crate::Event::SelfTradePrevented {
+        market_id: ETH_USDT,
+        trader,
+        taker_order_id,
+        maker_order_id,
+        mode,
+        quantity,
+    })
+}
+
+#[test]
+fn test_self_trade_without_mode_still_matches() {
+    build_and_execute(|| {
+        let alice = alice();
+        fund_account(alice, 10_000, 100);
+
+        place_limit(alice, OrderSide::Sell, 100, 5);
+        place_limit(alice, OrderSide::Buy, 100, 5);
+        Orderbook::on_finalize(1);
+
+        assert_eq!(order_status(0), Some(OrderStatus::Filled));
+        assert_eq!(order_status(1), Some(OrderStatus::Filled));
+        assert_eq!(Orderbook::next_trade_id(), 1);
+    });
+}
+
+#[test]
+fn test_self_trade_cancel_newest() {
+    build_and_execute(|| {
+        let alice = alice();
+        fund_account(alice, 10_000, 100);
+
+        place_limit(alice, OrderSide::Sell, 100, 5);
+        Orderbook::on_finalize(1);
+
+        System::set_block_number(2);
+        TestOrder::new(OrderSide::Buy, 100, 5)
+            .self_trade(SelfTradePrevention::CancelNewest)
+            .place(alice);
+        Orderbook::on_finalize(2);
+
+        System::assert_has_event(self_trade_prevented(
+            alice,
+            1,
+            0,
+            SelfTradePrevention::CancelNewest,
+            0,
+        ));
+        assert_eq!(Orderbook::next_trade_id(), 0);
+        assert_eq!(order_status(0), Some(OrderStatus::Open));
+        assert_eq!(order_status(1), Some(OrderStatus::Cancelled));
+        assert_eq!(Orderbook::get_asks_at_price(ETH_USDT, 100), vec![0]);
+        assert!(Orderbook::get_bids_at_price(ETH_USDT, 100).is_empty());
+        assert_eq!(Assets::get_locked_balance(&alice, USDT), 0);
+        assert_eq!(Assets::get_locked_balance(&alice, ETH), 5);
+        assert_ok!(Orderbook::ensure_locked_funds());
+    });
+}
+
+#[test]
+fn test_self_trade_account_default_cancel_oldest() {
+    build_and_execute(|| {
+        let alice = alice();
+        let bob = bob();
+        fund_account(alice, 10_000, 100);
+        fund_account(bob, 0, 100);
+
+        assert_ok!(Orderbook::set_self_trade_prevention(
+            RuntimeOrigin::signed(alice),
+            Some(SelfTradePrevention::CancelOldest)
+        ));
+        System::assert_last_event(RuntimeEvent::Orderbook(
+            crate::Event::SelfTradePreventionSet {
+                trader: alice,
+                mode: Some(SelfTradePrevention::CancelOldest),
+            },
+        ));
+
+        place_limit(alice, OrderSide::Sell, 100, 5);
+        place_limit(bob, OrderSide::Sell, 101, 5);
+        Orderbook::on_finalize(1);
+
+        // the resting sell goes, the buy trades on with bob
+        System::set_block_number(2);
+        place_limit(alice, OrderSide::Buy, 101, 5);
+        Orderbook::on_finalize(2);
+
+        System::assert_has_event(self_trade_prevented(
+            alice,
+            2,
+            0,
+            SelfTradePrevention::CancelOldest,
+            0,
+        ));
+        assert_eq!(order_status(0), Some(OrderStatus::Cancelled));
+        assert_eq!(order_status(1), Some(OrderStatus::Filled));
+        assert_eq!(order_status(2), Some(OrderStatus::Filled));
+        assert_eq!(Assets::get_free_balance(&alice, ETH), 105);
+        assert_eq!(Assets::get_locked_balance(&alice, ETH), 0);
+        assert_eq!(Assets::get_locked_balance(&alice, USDT), 0);
+        assert_ok!(Orderbook::ensure_locked_funds());
+
+        // cleared, own orders trade again
+        assert_ok!(Orderbook::set_self_trade_prevention(
+            RuntimeOrigin::signed(alice),
+            None
+        ));
+        assert!(crate::AccountSelfTradePrevention::<Test>::get(alice).is_none());
+    });
+}
+
+#[test]
+fn test_self_trade_cancel_both_in_cache() {
+    build_and_execute(|| {
+        let alice = alice();
+        fund_account(alice, 10_000, 100);
+
+        place_limit(alice, OrderSide::Sell, 100, 5);
+        TestOrder::new(OrderSide::Buy, 100, 5)
+            .self_trade(SelfTradePrevention::CancelBoth)
+            .place(alice);
+        Orderbook::on_finalize(1);
+
+        // the sell came first in the cache and took, the buy is still the newest
+        System::assert_has_event(self_trade_prevented(
+            alice,
+            0,
+            1,
+            SelfTradePrevention::CancelBoth,
+            0,
+        ));
+        assert_eq!(order_status(0), Some(OrderStatus::Cancelled));
+        assert_eq!(order_status(1), Some(OrderStatus::Cancelled));
+        assert!(Orderbook::get_user_orders(&alice).is_empty());
+        assert_eq!(Assets::get_free_balance(&alice, USDT), 10_000);
+        assert_eq!(Assets::get_free_balance(&alice, ETH), 100);
+        assert_ok!(Orderbook::ensure_locked_funds());
+    });
+}
+
+#[test]
+fn test_self_trade_decrement_and_cancel() {
+    build_and_execute(|| {
+        let alice = alice();
+        let bob = bob();
+        fund_account(alice, 10_000, 100);
+        fund_account(bob, 10_000, 0);
+
+        place_limit(alice, OrderSide::Sell, 100, 8);
+        Orderbook::on_finalize(1);
+
+        System::set_block_number(2);
+        TestOrder::new(OrderSide::Buy, 100, 5)
+            .self_trade(SelfTradePrevention::DecrementAndCancel)
+            .place(alice);
+        Orderbook::on_finalize(2);
+
+        System::assert_has_event(self_trade_prevented(
+            alice,
+            1,
+            0,
+            SelfTradePrevention::DecrementAndCancel,
+            5,
+        ));
+        // the buy had nothing left, the sell keeps the other 3
+        assert_eq!(order_status(1), Some(OrderStatus::Cancelled));
+        let sell = Orderbook::get_order(0).unwrap();
+        assert_eq!(sell.status, OrderStatus::Open);
+        assert_eq!(sell.quantity, 3);
+        assert_eq!(Assets::get_locked_balance(&alice, ETH), 3);
+        assert_eq!(Assets::get_locked_balance(&alice, USDT), 0);
+        assert_ok!(Orderbook::ensure_locked_funds());
+
+        System::set_block_number(3);
+        place_limit(bob, OrderSide::Buy, 100, 3);
+        Orderbook::on_finalize(3);
+        assert_eq!(order_status(0), Some(OrderStatus::Filled));
+        assert_eq!(Assets::get_free_balance(&bob, ETH), 3);
+        assert_ok!(Orderbook::ensure_locked_funds());
+    });
+}
+
+#[test]
+fn test_self_trade_order_mode_overrides_account() {
+    build_and_execute(|| {
+        let alice = alice();
+        fund_account(alice, 10_000, 100);
+
+        assert_ok!(Orderbook::set_self_trade_prevention(
+            RuntimeOrigin::signed(alice),
+            Some(SelfTradePrevention::CancelBoth)
+        ));
+        place_limit(alice, OrderSide::Sell, 100, 5);
+        Orderbook::on_finalize(1);
+        assert_eq!(
+            Orderbook::get_order(0).unwrap().self_trade,
+            Some(SelfTradePrevention::CancelBoth)
+        );
+
+        System::set_block_number(2);
+        TestOrder::new(OrderSide::Buy, 100, 5)
+            .self_trade(SelfTradePrevention::CancelNewest)
+            .place(alice);
+        Orderbook::on_finalize(2);
+
+        assert_eq!(order_status(0), Some(OrderStatus::Open));
+        assert_eq!(order_status(1), Some(OrderStatus::Cancelled));
+        assert_ok!(Orderbook::ensure_locked_funds());
+    });
+}
+
+#[test]
+fn test_self_trade_kills_fok_reaching_own_order() {
+    build_and_execute(|| {
+        let alice = alice();
+        let bob = bob();
+        fund_account(alice, 10_000, 100);
+        fund_account(bob, 0, 100);
+
+        place_limit(bob, OrderSide::Sell, 99, 5);
+        place_limit(alice, OrderSide::Sell, 100, 5);
+        Orderbook::on_finalize(1);
+
+        // bob's sell alone would not fill it, and cancelling the own one would leave it partial
+        System::set_block_number(2);
+        TestOrder::new(OrderSide::Buy, 100, 10)
+            .time_in_force(TimeInForce::FOK)
+            .self_trade(SelfTradePrevention::CancelNewest)
+            .place(alice);
+        Orderbook::on_finalize(2);
+
+        assert_eq!(order_status(2), Some(OrderStatus::Cancelled));
+        assert_eq!(Orderbook::next_trade_id(), 0);
+        assert_eq!(order_status(0), Some(OrderStatus::Open));
+        assert_eq!(order_status(1), Some(OrderStatus::Open));
+        assert_eq!(Assets::get_locked_balance(&alice, USDT), 0);
+        assert_ok!(Orderbook::ensure_locked_funds());
+    });
+}
+
+// alice rests `own` for sale at 100, bob 5 at 101
+fn rest_own_and_other_sell(own: u128) {
+    fund_account(alice(), 10_000, 100);
+    fund_account(bob(), 0, 100);
+    place_limit(alice(), OrderSide::Sell, 100, own);
+    place_limit(bob(), OrderSide::Sell, 101, 5);
+    Orderbook::on_finalize(1);
+    System::set_block_number(2);
+}
+
+#[test]
+fn test_self_trade_market_taker_cancel_newest() {
+    build_and_execute(|| {
+        let alice = alice();
+        rest_own_and_other_sell(5);
+
+        TestOrder::new(OrderSide::Buy, 0, 5)
+            .market(1_000)
+            .self_trade(SelfTradePrevention::CancelNewest)
+            .place(alice);
+        Orderbook::on_finalize(2);
+
+        System::assert_has_event(self_trade_prevented(
+            alice,
+            2,
+            0,
+            SelfTradePrevention::CancelNewest,
+            0,
+        ));
+        assert_eq!(Orderbook::next_trade_id(), 0);
+        assert_eq!(order_status(2), Some(OrderStatus::Cancelled));
+        assert_eq!(order_status(0), Some(OrderStatus::Open));
+        assert_eq!(order_status(1), Some(OrderStatus::Open));
+        assert_eq!(Assets::get_free_balance(&alice, USDT), 10_000);
+        assert_ok!(Orderbook::ensure_locked_funds());
+    });
+}
+
+#[test]
+fn test_self_trade_market_taker_cancel_oldest() {
+    build_and_execute(|| {
+        let alice = alice();
+        rest_own_and_other_sell(5);
+
+        TestOrder::new(OrderSide::Buy, 0, 5)
+            .market(1_000)
+            .self_trade(SelfTradePrevention::CancelOldest)
+            .place(alice);
+        Orderbook::on_finalize(2);
+
+        // the own sell goes and the buy takes bob's instead
+        System::assert_has_event(self_trade_prevented(
+            alice,
+            2,
+            0,
+            SelfTradePrevention::CancelOldest,
+            0,
+        ));
+        assert_eq!(order_status(0), Some(OrderStatus::Cancelled));
+        assert_eq!(order_status(1), Some(OrderStatus::Filled));
+        assert_eq!(order_status(2), Some(OrderStatus::Filled));
+        assert_eq!(Orderbook::get_trade(0).unwrap().price, 101);
+        assert_eq!(Assets::get_free_balance(&alice, USDT), 10_000 - 505);
+        assert_eq!(Assets::get_free_balance(&alice, ETH), 105);
+        assert_eq!(Assets::get_locked_balance(&alice, USDT), 0);
+        assert_eq!(Assets::get_locked_balance(&alice, ETH), 0);
+        assert_ok!(Orderbook::ensure_locked_funds());
+    });
+}
+
+#[test]
+fn test_self_trade_market_taker_cancel_both() {
+    build_and_execute(|| {
+        let alice = alice();
+        rest_own_and_other_sell(5);
+
+        TestOrder::new(OrderSide::Buy, 0, 5)
+            .market(1_000)
+            .self_trade(SelfTradePrevention::CancelBoth)
+            .place(alice);
+        Orderbook::on_finalize(2);
+
+        System::assert_has_event(self_trade_prevented(
+            alice,
+            2,
+            0,
+            SelfTradePrevention::CancelBoth,
+            0,
+        ));
+        assert_eq!(Orderbook::next_trade_id(), 0);
+        assert_eq!(order_status(0), Some(OrderStatus::Cancelled));
+        assert_eq!(order_status(2), Some(OrderStatus::Cancelled));
+        assert_eq!(order_status(1), Some(OrderStatus::Open));
+        assert_eq!(Assets::get_free_balance(&alice, USDT), 10_000);
+        assert_eq!(Assets::get_free_balance(&alice, ETH), 100);
+        assert_ok!(Orderbook::ensure_locked_funds());
+    });
+}
+
+#[test]
+fn test_self_trade_market_taker_decrement_and_cancel() {
+    build_and_execute(|| {
+        let alice = alice();
+        rest_own_and_other_sell(3);
+
+        TestOrder::new(OrderSide::Buy, 0, 5)
+            .market(1_000)
+            .self_trade(SelfTradePrevention::DecrementAndCancel)
+            .place(alice);
+        Orderbook::on_finalize(2);
+
+        // 3 come off both, the buy takes the other 2 from bob
+        System::assert_has_event(self_trade_prevented(
+            alice,
+            2,
+            0,
+            SelfTradePrevention::DecrementAndCancel,
+            3,
+        ));
+        assert_eq!(order_status(0), Some(OrderStatus::Cancelled));
+        let buy = finished_order(2).unwrap();
+        assert_eq!(buy.status, OrderStatus::Filled);
+        assert_eq!((buy.quantity, buy.filled_quantity), (2, 2));
+        assert_eq!(Orderbook::get_order(1).unwrap().filled_quantity, 2);
+        assert_eq!(Assets::get_free_balance(&alice, USDT), 10_000 - 202);
+        assert_eq!(Assets::get_free_balance(&alice, ETH), 100 + 2);
+        assert_eq!(Assets::get_locked_balance(&alice, USDT), 0);
+        assert_eq!(Assets::get_locked_balance(&alice, ETH), 0);
+        assert_ok!(Orderbook::ensure_locked_funds());
+    });
+}
+
+#[test]
+fn test_self_trade_market_taker_is_killed_when_prevention_leaves_it_short() {
+    build_and_execute(|| {
+        let alice = alice();
+        let bob = bob();
+        fund_account(alice, 10_000, 100);
+        fund_account(bob, 10_000, 0);
+
+        place_limit(alice, OrderSide::Buy, 100, 5);
+        place_limit(bob, OrderSide::Buy, 90, 5);
+        Orderbook::on_finalize(1);
+
+        // shrunk by the own bid, the sell would get 450 for its floor of 900
+        System::set_block_number(2);
+        TestOrder::new(OrderSide::Sell, 0, 10)
+            .market(900)
+            .self_trade(SelfTradePrevention::DecrementAndCancel)
+            .place(alice);
+        Orderbook::on_finalize(2);
+
+        System::assert_has_event(RuntimeEvent::Orderbook(crate::Event::OrderKilled {
+            market_id: ETH_USDT,
+            order_id: 2,
+            trader: alice,
+            quantity: 10,
+        }));
+        assert!(!System::events().iter().any(|record| matches!(
+            record.event,
+            RuntimeEvent::Orderbook(crate::Event::SelfTradePrevented { .. })
+        )));
+        assert_eq!(Orderbook::next_trade_id(), 0);
+        assert_eq!(Orderbook::get_order(0).unwrap().quantity, 5);
+        assert_eq!(order_status(1), Some(OrderStatus::Open));
+        assert_eq!(Assets::get_locked_balance(&alice, ETH), 0);
+        assert_eq!(Assets::get_locked_balance(&alice, USDT), 500);
+        assert_ok!(Orderbook::ensure_locked_funds());
+    });
+}
+
+// ============================================
+// SETTLEMENT FAILURE TESTS
+// ============================================
+
+#[test]
+fn test_failed_persistent_matching_rolls_the_market_back() {
+    new_test_ext().execute_with(|| {
+        let alice = alice();
+        let bob = bob();
+        fund_account(alice, 10_000, 0);
+        fund_account(bob, 0, 100);
+
+        place_limit(bob, OrderSide::Sell, 100, 10);
+        Orderbook::on_finalize(1);
+
+        // both orders were corrupted to sizes whose trade can't be valued in quote
+        System::set_block_number(2);
+        place_limit(alice, OrderSide::Buy, 100, 10);
+        for order_id in [0, 1] {
+            crate::Orders::<Test>::mutate(order_id, |order| {
+                order.as_mut().unwrap().quantity = u128::MAX;
+            });
+        }
+        Orderbook::on_finalize(2);
+
+        assert!(System::events().iter().any(|record| matches!(
+            record.event,
+            RuntimeEvent::Orderbook(crate::Event::MatchingFailed {
+                market_id: ETH_USDT,
+                ..
+            })
+        )));
+
+        // nothing traded, the resting ask is untouched and the bid is still waiting
+        assert_eq!(Orderbook::next_trade_id(), 0);
+        assert_eq!(Orderbook::get_order(0).unwrap().filled_quantity, 0);
+        assert_eq!(Orderbook::get_asks_at_price(ETH_USDT, 100), vec![0]);
+        let order = Orderbook::get_order(1).unwrap();
+        assert_eq!(
+            (order.status, order.filled_quantity),
+            (OrderStatus::Open, 0)
+        );
+        assert!(Orderbook::get_bids_at_price(ETH_USDT, 100).is_empty());
+        assert_eq!(Assets::get_locked_balance(&alice, USDT), 1_000);
+        assert_eq!(Assets::get_locked_balance(&bob, ETH), 10);
+    });
+}
+
+#[test]
+fn test_failed_trade_is_rolled_back() {
+    new_test_ext().execute_with(|| {
+        let alice = alice();
+        let bob = bob();
+        let charlie = charlie();
+        fund_account(alice, 10_000, 0);
+        fund_account(bob, 0, 100);
+        fund_account(charlie, 0, 100);
+
+        place_limit(bob, OrderSide::Sell, 100, 5);
+        place_limit(charlie, OrderSide::Sell, 101, 5);
+        Orderbook::on_finalize(1);
+
+        // charlie's lock went missing, the funds are still theirs but free
+        pallet_assets::LockedBalance::<Test>::insert(charlie, ETH, 0);
+        pallet_assets::FreeBalance::<Test>::mutate(charlie, ETH, |free| *free += 5);
+
+        System::set_block_number(2);
+        place_limit(alice, OrderSide::Buy, 101, 10);
+        Orderbook::on_finalize(2);
+
+        System::assert_has_event(RuntimeEvent::Orderbook(crate::Event::TradeRolledBack {
+            market_id: ETH_USDT,
+            buy_order_id: 2,
+            sell_order_id: 1,
+            price: 101,
+            quantity: 5,
+            error: pallet_assets::Error::<Test>::InsufficientLockedBalance.into(),
+        }));
+        System::assert_has_event(RuntimeEvent::Orderbook(
+            crate::Event::OrderRemainderCancelled {
+                market_id: ETH_USDT,
+                order_id: 2,
+                trader: alice,
+                filled_quantity: 5,
+                cancelled_quantity: 5,
+            },
+        ));
+
+        // only bob's trade happened
+        assert_eq!(Orderbook::next_trade_id(), 1);
+        assert_eq!(order_status(0), Some(OrderStatus::Filled));
+        assert_eq!(Assets::get_free_balance(&bob, USDT), 500);
+        assert_eq!(Assets::get_free_balance(&alice, ETH), 5);
+        assert_eq!(Assets::get_free_balance(&alice, USDT), 9_500);
+        assert_eq!(Assets::get_locked_balance(&alice, USDT), 0);
+
+        // charlie's order is back in the book untouched, alice's did not rest across it
+        let order = Orderbook::get_order(1).unwrap();
+        assert_eq!(
+            (order.status, order.filled_quantity),
+            (OrderStatus::Open, 0)
+        );
+        assert_eq!(Orderbook::get_asks_at_price(ETH_USDT, 101), vec![1]);
+        assert!(Orderbook::get_bids_at_price(ETH_USDT, 101).is_empty());
+        assert_eq!(Assets::get_free_balance(&charlie, USDT), 0);
+        assert_ok!(Assets::ensure_conservation());
+    });
+}
+
+#[test]
+fn test_settlement_conserves_balances() {
+    build_and_execute(|| {
+        let alice = alice();
+        let bob = bob();
+        fund_account(alice, 10_000, 0);
+        fund_account(bob, 0, 100);
+        assert_ok!(Orderbook::set_market_fees(
+            RuntimeOrigin::root(),
+            ETH_USDT,
+            sp_runtime::Permill::from_percent(1),
+            sp_runtime::Permill::from_percent(2)
+        ));
+
+        place_limit(bob, OrderSide::Sell, 100, 10);
+        Orderbook::on_finalize(1);
+        System::set_block_number(2);
+        place_limit(alice, OrderSide::Buy, 105, 4);
+        Orderbook::on_finalize(2);
+
+        assert_eq!(Orderbook::next_trade_id(), 1);
+        assert_ok!(Assets::ensure_conservation());
+        assert_ok!(Orderbook::ensure_locked_funds());
+    });
+}
+
+// ============================================
+// PRO-RATA MATCHING TESTS
+// ============================================
+
+fn set_pro_rata(fifo_percent: u32) {
+    assert_ok!(Orderbook::set_matching_mode(
+        RuntimeOrigin::root(),
+        ETH_USDT,
+        MatchingMode::ProRata {
+            fifo_slice: sp_runtime::Permill::from_percent(fifo_percent),
+        }
+    ));
+}
+
+#[test]
+fn test_set_matching_mode_works() {
+    build_and_execute(|| {
+        let mode = MatchingMode::ProRata {
+            fifo_slice: sp_runtime::Permill::from_percent(10),
+        };
+        assert_noop!(
+            Orderbook::set_matching_mode(RuntimeOrigin::signed(alice()), ETH_USDT, mode),
+            sp_runtime::DispatchError::BadOrigin
+        );
+        assert_noop!(
+            Orderbook::set_matching_mode(RuntimeOrigin::root(), 7, mode),
+            crate::Error::<Test>::MarketNotFound
+        );
+
+        assert_eq!(
+            crate::MarketMatchingMode::<Test>::get(ETH_USDT),
+            MatchingMode::Fifo
+        );
+        assert_ok!(Orderbook::set_matching_mode(
+            RuntimeOrigin::root(),
+            ETH_USDT,
+            mode
+        ));
+        assert_eq!(crate::MarketMatchingMode::<Test>::get(ETH_USDT), mode);
+        System::assert_last_event(RuntimeEvent::Orderbook(crate::Event::MatchingModeSet {
+            market_id: ETH_USDT,
+            mode,
+        }));
+    });
+}
+
+#[test]
+fn test_allocation_rounding_conserves_quantity() {
+    use crate::engine::{Allocation, Fifo, ProRata};
+
+    let pro_rata = |percent, lot| ProRata {
+        fifo_slice: sp_runtime::Permill::from_percent(percent),
+        lot,
+    };
+
+    assert_eq!(Fifo.allocate(7, &[5, 5, 5]), vec![5, 2, 0]);
+    assert_eq!(pro_rata(0, 1).allocate(10, &[1, 2, 3, 4]), vec![1, 2, 3, 4]);
+    // the unit rounding leaves over goes to the front of the queue
+    assert_eq!(pro_rata(0, 1).allocate(7, &[5, 5, 5]), vec![3, 2, 2]);
+    // shares are whole lots
+    assert_eq!(pro_rata(0, 2).allocate(8, &[10, 10, 10]), vec![4, 2, 2]);
+    // half goes FIFO first, the rest by what is left open
+    assert_eq!(pro_rata(50, 1).allocate(10, &[4, 8, 8]), vec![4, 4, 2]);
+    // never more than the makers have
+    assert_eq!(pro_rata(0, 1).allocate(50, &[3, 3, 3]), vec![3, 3, 3]);
+
+    for quantity in 0..=40u128 {
+        let shares = pro_rata(30, 3).allocate(quantity, &[7, 1, 12, 9, 4]);
+        assert_eq!(shares.iter().sum::<u128>(), quantity.min(33));
+        assert!(
+            shares
+                .iter()
+                .zip([7, 1, 12, 9, 4])
+                .all(|(share, open)| *share <= open)
+        );
+    }
+}
+
+#[test]
+fn test_pro_rata_shares_a_level() {
+    build_and_execute(|| {
+        let alice = alice();
+        let bob = bob();
+        let charlie = charlie();
+        fund_account(alice, 10_000, 0);
+        fund_account(bob, 0, 100);
+        fund_account(charlie, 0, 100);
+        set_pro_rata(0);
+
+        place_limit(bob, OrderSide::Sell, 100, 10);
+        place_limit(charlie, OrderSide::Sell, 100, 30);
+        Orderbook::on_finalize(1);
+
+        System::set_block_number(2);
+        place_limit(alice, OrderSide::Buy, 100, 20);
+        Orderbook::on_finalize(2);
+
+        // a quarter of the level was bob's, so is a quarter of the fill
+        assert_eq!(Orderbook::get_order(0).unwrap().filled_quantity, 5);
+        assert_eq!(Orderbook::get_order(1).unwrap().filled_quantity, 15);
+        assert_eq!(order_status(2), Some(OrderStatus::Filled));
+        assert_eq!(Assets::get_free_balance(&bob, USDT), 500);
+        assert_eq!(Assets::get_free_balance(&charlie, USDT), 1_500);
+        assert_eq!(Assets::get_free_balance(&alice, ETH), 20);
+        assert_eq!(Orderbook::get_asks_at_price(ETH_USDT, 100), vec![0, 1]);
+    });
+}
+
+#[test]
+fn test_pro_rata_fifo_slice_for_market_orders() {
+    build_and_execute(|| {
+        let alice = alice();
+        let bob = bob();
+        let charlie = charlie();
+        fund_account(alice, 10_000, 0);
+        fund_account(bob, 0, 100);
+        fund_account(charlie, 0, 100);
+        set_pro_rata(25);
+
+        place_limit(bob, OrderSide::Sell, 100, 10);
+        place_limit(charlie, OrderSide::Sell, 100, 30);
+        Orderbook::on_finalize(1);
+
+        System::set_block_number(2);
+        TestOrder::new(OrderSide::Buy, 0, 20)
+            .market(2_000)
+            .place(alice);
+        Orderbook::on_finalize(2);
+
+        // 5 to bob first, the other 15 split 5:30 rounds to 2 and 12, the last unit is bob's
+        assert_eq!(Orderbook::get_order(0).unwrap().filled_quantity, 8);
+        assert_eq!(Orderbook::get_order(1).unwrap().filled_quantity, 12);
+        assert_eq!(Assets::get_free_balance(&alice, ETH), 20);
+        assert_eq!(Assets::get_free_balance(&alice, USDT), 8_000);
+    });
+}
+
+// ============================================
+// BATCH AUCTION TESTS
+// ============================================
+
+fn set_auction() {
+    assert_ok!(Orderbook::set_clearing_mode(
+        RuntimeOrigin::root(),
+        ETH_USDT,
+        ClearingMode::BatchAuction
+    ));
+}
+
+#[test]
+fn test_set_clearing_mode_works() {
+    build_and_execute(|| {
+        assert_noop!(
+            Orderbook::set_clearing_mode(
+                RuntimeOrigin::signed(alice()),
+                ETH_USDT,
+                ClearingMode::BatchAuction
+            ),
+            sp_runtime::DispatchError::BadOrigin
+        );
+        assert_noop!(
+            Orderbook::set_clearing_mode(RuntimeOrigin::root(), 7, ClearingMode::BatchAuction),
+            crate::Error::<Test>::MarketNotFound
+        );
+
+        assert_eq!(
+            crate::MarketClearingMode::<Test>::get(ETH_USDT),
+            ClearingMode::Continuous
+        );
+        set_auction();
+        assert_eq!(
+            crate::MarketClearingMode::<Test>::get(ETH_USDT),
+            ClearingMode::BatchAuction
+        );
+        System::assert_last_event(RuntimeEvent::Orderbook(crate::Event::ClearingModeSet {
+            market_id: ETH_USDT,
+            mode: ClearingMode::BatchAuction,
+        }));
+    });
+}
+
+#[test]
+fn test_auction_clears_at_one_price() {
+    // the same orders in any order within the block give the same fills
+    let orders = [
+        (bob(), OrderSide::Sell, 100, 10),
+        (charlie(), OrderSide::Sell, 102, 10),
+        (alice(), OrderSide::Buy, 105, 15),
+        (4, OrderSide::Buy, 101, 5),
+    ];
+    for reversed in [false, true] {
+        build_and_execute(|| {
+            fund_account(alice(), 10_000, 0);
+            fund_account(4, 10_000, 0);
+            fund_account(bob(), 0, 100);
+            fund_account(charlie(), 0, 100);
+            set_auction();
+
+            let mut block = orders.to_vec();
+            if reversed {
+                block.reverse();
+            }
+            for (account, side, price, quantity) in block {
+                place_limit(account, side, price, quantity);
+            }
+            Orderbook::on_finalize(1);
+
+            // 15 trade at 102 and at 105 with the same imbalance, 102 is the lower price
+            let trades: Vec<_> = crate::Trades::<Test>::iter_values().collect();
+            assert_eq!(trades.len(), 2);
+            assert!(trades.iter().all(|trade| trade.price == 102));
+
+            assert_eq!(Assets::get_free_balance(&alice(), ETH), 15);
+            assert_eq!(Assets::get_free_balance(&alice(), USDT), 10_000 - 1_530);
+            assert_eq!(Assets::get_locked_balance(&alice(), USDT), 0);
+            // bob asked for 100 and gets the clearing price
+            assert_eq!(Assets::get_free_balance(&bob(), USDT), 1_020);
+            assert_eq!(Assets::get_free_balance(&charlie(), USDT), 510);
+            assert_eq!(Assets::get_locked_balance(&charlie(), ETH), 5);
+
+            // what didn't trade rests on a book that no longer crosses
+            assert_eq!(crate::BestBid::<Test>::get(ETH_USDT), Some(101));
+            assert_eq!(crate::BestAsk::<Test>::get(ETH_USDT), Some(102));
+            assert_eq!(Assets::get_locked_balance(&4, USDT), 505);
+        });
+    }
+}
+
+#[test]
+fn test_auction_uses_resting_orders_and_last_price() {
+    build_and_execute(|| {
+        let alice = alice();
+        let bob = bob();
+        let charlie = charlie();
+        fund_account(alice, 10_000, 0);
+        fund_account(bob, 0, 100);
+        fund_account(charlie, 10_000, 100);
+
+        // continuous trade to set a last price of 103
+        place_limit(bob, OrderSide::Sell, 103, 1);
+        place_limit(charlie, OrderSide::Buy, 103, 1);
+        Orderbook::on_finalize(1);
+        assert_eq!(crate::LastTradePrice::<Test>::get(ETH_USDT), Some(103));
+
+        System::set_block_number(2);
+        place_limit(bob, OrderSide::Sell, 100, 5);
+        Orderbook::on_finalize(2);
+        set_auction();
+
+        // 5 trade anywhere from 100 to 106 with the same imbalance, 103 is closest to the
+        // last trade
+        System::set_block_number(3);
+        place_limit(alice, OrderSide::Buy, 106, 5);
+        Orderbook::on_finalize(3);
+
+        assert_eq!(Orderbook::get_trade(1).unwrap().price, 103);
+        assert_eq!(order_status(2), Some(OrderStatus::Filled));
+        assert_eq!(Assets::get_free_balance(&alice, USDT), 10_000 - 515);
+        assert_eq!(Assets::get_free_balance(&alice, ETH), 5);
+    });
+}
+
+#[test]
+fn test_auction_time_in_force_and_post_only() {
+    build_and_execute(|| {
+        let alice = alice();
+        let bob = bob();
+        let charlie = charlie();
+        fund_account(alice, 10_000, 0);
+        fund_account(bob, 0, 100);
+        fund_account(charlie, 10_000, 0);
+        fund_account(4, 10_000, 0);
+        set_auction();
+
+        place_limit(bob, OrderSide::Sell, 100, 10);
+        // FOK for more than there is, it is killed and the auction runs without it
+        TestOrder::new(OrderSide::Buy, 100, 20)
+            .time_in_force(TimeInForce::FOK)
+            .place(alice);
+        TestOrder::new(OrderSide::Buy, 100, 4)
+            .time_in_force(TimeInForce::IOC)
+            .place(charlie);
+        // would take what is left of bob's ask
+        TestOrder::new(OrderSide::Buy, 100, 5)
+            .post_only(PostOnly::Reject)
+            .place(4);
+        Orderbook::on_finalize(1);
+
+        assert_eq!(order_status(1), Some(OrderStatus::Cancelled));
+        assert_eq!(order_status(2), Some(OrderStatus::Filled));
+        assert_eq!(order_status(3), Some(OrderStatus::Cancelled));
+        assert_eq!(Assets::get_free_balance(&charlie, ETH), 4);
+        assert_eq!(Assets::get_locked_balance(&alice, USDT), 0);
+        assert_eq!(Assets::get_locked_balance(&4, USDT), 0);
+
+        let bob_order = Orderbook::get_order(0).unwrap();
+        assert_eq!(bob_order.filled_quantity, 4);
+        assert_eq!(Orderbook::get_asks_at_price(ETH_USDT, 100), vec![0]);
+        assert!(Orderbook::get_bids_at_price(ETH_USDT, 100).is_empty());
+    });
+}
+
+#[test]
+fn test_auction_rations_with_pro_rata() {
+    build_and_execute(|| {
+        let alice = alice();
+        let bob = bob();
+        let charlie = charlie();
+        fund_account(alice, 10_000, 0);
+        fund_account(bob, 0, 100);
+        fund_account(charlie, 0, 100);
+        set_auction();
+        set_pro_rata(0);
+
+        place_limit(bob, OrderSide::Sell, 100, 10);
+        place_limit(charlie, OrderSide::Sell, 100, 30);
+        place_limit(alice, OrderSide::Buy, 100, 20);
+        Orderbook::on_finalize(1);
+
+        assert_eq!(Orderbook::get_order(0).unwrap().filled_quantity, 5);
+        assert_eq!(Orderbook::get_order(1).unwrap().filled_quantity, 15);
+        assert_eq!(Assets::get_free_balance(&alice, ETH), 20);
+    });
+}
+
+#[test]
+fn test_auction_market_orders() {
+    build_and_execute(|| {
+        let alice = alice();
+        let bob = bob();
+        let charlie = charlie();
+        fund_account(alice, 10_000, 0);
+        fund_account(bob, 0, 100);
+        fund_account(charlie, 0, 100);
+        set_auction();
+
+        place_limit(bob, OrderSide::Sell, 100, 10);
+        place_limit(bob, OrderSide::Sell, 110, 10);
+        // its quote limit only pays for 12 at 100, 10 at 110
+        TestOrder::new(OrderSide::Buy, 0, 15)
+            .market(1_200)
+            .place(alice);
+        // wants at least 1_000 for its 10
+        TestOrder::new(OrderSide::Sell, 0, 10)
+            .market(1_000)
+            .place(charlie);
+        Orderbook::on_finalize(1);
+
+        // at 100 alice takes 12 of the 20 offered, at 110 only 10 of 30
+        let trades: Vec<_> = crate::Trades::<Test>::iter_values().collect();
+        assert!(trades.iter().all(|trade| trade.price == 100));
+        assert_eq!(Assets::get_free_balance(&alice, ETH), 12);
+        assert_eq!(Assets::get_free_balance(&alice, USDT), 10_000 - 1_200);
+        assert_eq!(order_status(2), Some(OrderStatus::Cancelled));
+        // market orders come first on their side, charlie got exactly the floor
+        assert_eq!(Assets::get_free_balance(&charlie, USDT), 1_000);
+        assert_eq!(Assets::get_free_balance(&charlie, ETH), 90);
+        assert_eq!(Orderbook::get_order(0).unwrap().filled_quantity, 2);
+    });
+}
+
+#[test]
+fn test_auction_refunds_filled_market_buy() {
+    build_and_execute(|| {
+        let alice = alice();
+        let bob = bob();
+        fund_account(alice, 10_000, 0);
+        fund_account(bob, 0, 100);
+        set_auction();
+
+        place_limit(bob, OrderSide::Sell, 100, 10);
+        TestOrder::new(OrderSide::Buy, 0, 5)
+            .market(1_000)
+            .place(alice);
+        Orderbook::on_finalize(1);
+
+        assert_eq!(order_status(1), Some(OrderStatus::Filled));
+        assert_eq!(Assets::get_free_balance(&alice, USDT), 10_000 - 500);
+        assert_eq!(Assets::get_locked_balance(&alice, USDT), 0);
+        assert_ok!(Orderbook::ensure_locked_funds());
+    });
+}
+
+#[test]
+fn test_auction_self_trade_cancel_oldest() {
+    build_and_execute(|| {
+        let alice = alice();
+        let bob = bob();
+        fund_account(alice, 10_000, 100);
+        fund_account(bob, 0, 100);
+        set_auction();
+
+        place_limit(alice, OrderSide::Sell, 100, 5);
+        place_limit(bob, OrderSide::Sell, 101, 5);
+        TestOrder::new(OrderSide::Buy, 101, 5)
+            .self_trade(SelfTradePrevention::CancelOldest)
+            .place(alice);
+        Orderbook::on_finalize(1);
+
+        // the own sell is out before the price is picked, the buy clears against bob's
+        System::assert_has_event(self_trade_prevented(
+            alice,
+            2,
+            0,
+            SelfTradePrevention::CancelOldest,
+            0,
+        ));
+        let trades: Vec<_> = crate::Trades::<Test>::iter_values().collect();
+        assert_eq!(trades.len(), 1);
+        assert_eq!((trades[0].price, trades[0].seller), (101, bob));
+        assert_eq!(order_status(0), Some(OrderStatus::Cancelled));
+        assert_eq!(order_status(2), Some(OrderStatus::Filled));
+        assert_eq!(Orderbook::get_best_prices(ETH_USDT), (None, None));
+        assert_eq!(Assets::get_free_balance(&alice, ETH), 105);
+        assert_eq!(Assets::get_locked_balance(&alice, ETH), 0);
+        assert_eq!(Assets::get_locked_balance(&alice, USDT), 0);
+        assert_ok!(Orderbook::ensure_locked_funds());
+    });
+}
+
+#[test]
+fn test_auction_self_trade_leaves_no_own_orders_crossed() {
+    build_and_execute(|| {
+        let alice = alice();
+        let bob = bob();
+        fund_account(alice, 10_000, 100);
+        fund_account(bob, 10_000, 0);
+        set_auction();
+
+        place_limit(alice, OrderSide::Sell, 100, 8);
+        TestOrder::new(OrderSide::Buy, 102, 5)
+            .self_trade(SelfTradePrevention::DecrementAndCancel)
+            .place(alice);
+        place_limit(bob, OrderSide::Buy, 100, 2);
+        Orderbook::on_finalize(1);
+
+        // 5 come off both, the buy is gone and bob takes 2 of the 3 left
+        System::assert_has_event(self_trade_prevented(
+            alice,
+            1,
+            0,
+            SelfTradePrevention::DecrementAndCancel,
+            5,
+        ));
+        assert_eq!(order_status(1), Some(OrderStatus::Cancelled));
+        assert_eq!(order_status(2), Some(OrderStatus::Filled));
+        let sell = Orderbook::get_order(0).unwrap();
+        assert_eq!((sell.quantity, sell.filled_quantity), (3, 2));
+        assert_eq!(Orderbook::get_best_prices(ETH_USDT), (None, Some(100)));
+        assert_eq!(Assets::get_locked_balance(&alice, ETH), 1);
+        assert_eq!(Assets::get_locked_balance(&alice, USDT), 0);
+        assert_ok!(Orderbook::ensure_locked_funds());
+    });
+}
+
+#[test]
+fn test_auction_self_trade_market_order() {
+    build_and_execute(|| {
+        let alice = alice();
+        fund_account(alice, 10_000, 100);
+        set_auction();
+
+        place_limit(alice, OrderSide::Sell, 100, 5);
+        TestOrder::new(OrderSide::Buy, 0, 5)
+            .market(1_000)
+            .self_trade(SelfTradePrevention::CancelNewest)
+            .place(alice);
+        Orderbook::on_finalize(1);
+
+        System::assert_has_event(self_trade_prevented(
+            alice,
+            1,
+            0,
+            SelfTradePrevention::CancelNewest,
+            0,
+        ));
+        assert_eq!(Orderbook::next_trade_id(), 0);
+        assert_eq!(order_status(1), Some(OrderStatus::Cancelled));
+        assert_eq!(Orderbook::get_best_prices(ETH_USDT), (None, Some(100)));
+        assert_eq!(Assets::get_free_balance(&alice, USDT), 10_000);
+        assert_eq!(Assets::get_locked_balance(&alice, ETH), 5);
+        assert_ok!(Orderbook::ensure_locked_funds());
+    });
+}
+
+#[test]
+fn test_auction_kills_what_is_left_after_the_last_round() {
+    build_and_execute(|| {
+        let bob = bob();
+        fund_account(bob, 0, 100);
+        set_auction();
+
+        place_limit(bob, OrderSide::Sell, 100, 10);
+        // each round only the best of them gets the 10 and is killed for being short
+        let rounds = crate::engine::MAX_AUCTION_ROUNDS as u64;
+        for trader in 10..10 + rounds {
+            fund_account(trader, 2_000, 0);
+            TestOrder::new(OrderSide::Buy, 92 + trader as u128, 11)
+                .time_in_force(TimeInForce::FOK)
+                .place(trader);
+        }
+        // would be filled in the round after, but there is none
+        fund_account(alice(), 2_000, 0);
+        TestOrder::new(OrderSide::Buy, 101, 10)
+            .time_in_force(TimeInForce::FOK)
+            .place(alice());
+        Orderbook::on_finalize(1);
+
+        assert_eq!(Orderbook::next_trade_id(), 0);
+        for order_id in 1..=rounds + 1 {
+            assert_eq!(order_status(order_id), Some(OrderStatus::Cancelled));
+        }
+        assert_eq!(Orderbook::get_asks_at_price(ETH_USDT, 100), vec![0]);
+        assert_eq!(Assets::get_free_balance(&alice(), USDT), 2_000);
+    });
+}
+
+// ============================================
+// ORDERING POLICY TESTS
+// ============================================
+
+fn set_ordering(policy: OrderingPolicy) {
+    assert_ok!(Orderbook::set_ordering_policy(
+        RuntimeOrigin::root(),
+        ETH_USDT,
+        policy
+    ));
+}
+
+// bob rests 5 in block 1, then alice (order 1) and charlie (order 2) both bid for all of it
+// in block 2 under the given parent hash; returns who got filled
+fn competing_takers(policy: OrderingPolicy, parent_hash: sp_core::H256) -> u64 {
+    use codec::Encode;
+
+    let mut winner = 0;
+    build_and_execute(|| {
+        fund_account(alice(), 1_000, 0);
+        fund_account(bob(), 0, 100);
+        fund_account(charlie(), 1_000, 0);
+        set_ordering(policy);
+
+        System::set_block_number(1);
+        place_limit(bob(), OrderSide::Sell, 100, 5);
+        Orderbook::on_finalize(1);
+
+        System::set_block_number(2);
+        System::set_parent_hash(parent_hash);
+        place_limit(alice(), OrderSide::Buy, 100, 5);
+        place_limit(charlie(), OrderSide::Buy, 100, 5);
+        Orderbook::on_finalize(2);
+
+        assert_eq!(order_status(0), Some(OrderStatus::Filled));
+        winner = match (order_status(1), order_status(2)) {
+            (Some(OrderStatus::Filled), Some(OrderStatus::Open)) => alice(),
+            (Some(OrderStatus::Open), Some(OrderStatus::Filled)) => charlie(),
+            other => panic!("unexpected statuses {other:?}"),
+        };
+
+        // anyone can redo the shuffle from the seed: sort by blake2(seed, order id)
+        let seed = Orderbook::ordering_seed(ETH_USDT);
+        let expected = match seed {
+            None => alice(),
+            Some(seed) => {
+                let first = [1u64, 2]
+                    .into_iter()
+                    .min_by_key(|id| sp_io::hashing::blake2_256(&(seed, id).encode()))
+                    .unwrap();
+                if first == 1 { alice() } else { charlie() }
+            }
+        };
+        assert_eq!(winner, expected);
+    });
+    winner
+}
+
+#[test]
+fn test_set_ordering_policy_works() {
+    build_and_execute(|| {
+        assert_noop!(
+            Orderbook::set_ordering_policy(
+                RuntimeOrigin::signed(alice()),
+                ETH_USDT,
+                OrderingPolicy::ParentHash
+            ),
+            sp_runtime::DispatchError::BadOrigin
+        );
+        assert_noop!(
+            Orderbook::set_ordering_policy(RuntimeOrigin::root(), 7, OrderingPolicy::ParentHash),
+            crate::Error::<Test>::MarketNotFound
+        );
+
+        assert_eq!(
+            crate::MarketOrderingPolicy::<Test>::get(ETH_USDT),
+            OrderingPolicy::ById
+        );
+        assert_eq!(Orderbook::ordering_seed(ETH_USDT), None);
+        set_ordering(OrderingPolicy::ParentHash);
+        assert_eq!(
+            crate::MarketOrderingPolicy::<Test>::get(ETH_USDT),
+            OrderingPolicy::ParentHash
+        );
+        System::assert_last_event(RuntimeEvent::Orderbook(crate::Event::OrderingPolicySet {
+            market_id: ETH_USDT,
+            policy: OrderingPolicy::ParentHash,
+        }));
+    });
+}
+
+#[test]
+fn test_order_by_id_ignores_parent_hash() {
+    for byte in 0..8 {
+        let parent = sp_core::H256::repeat_byte(byte);
+        assert_eq!(competing_takers(OrderingPolicy::ById, parent), alice());
+    }
+}
+
+#[test]
+fn test_parent_hash_shuffle_is_reproducible() {
+    let winners: Vec<_> = (0..8)
+        .map(|byte| {
+            let parent = sp_core::H256::repeat_byte(byte);
+            let winner = competing_takers(OrderingPolicy::ParentHash, parent);
+            // the same parent hash always gives the same order
+            assert_eq!(competing_takers(OrderingPolicy::ParentHash, parent), winner);
+            winner
+        })
+        .collect();
+
+    // the lower id no longer wins every time
+    assert!(winners.contains(&alice()));
+    assert!(winners.contains(&charlie()));
+}
+
+#[test]
+fn test_randomness_shuffle_is_reproducible() {
+    // the test source is seeded by block number, not the parent hash
+    let parent = sp_core::H256::repeat_byte(1);
+    let winner = competing_takers(OrderingPolicy::Randomness, parent);
+    assert_eq!(
+        competing_takers(OrderingPolicy::Randomness, sp_core::H256::repeat_byte(2)),
+        winner
+    );
+}
+
+// ============================================
+// ICEBERG ORDER TESTS
+// ============================================
+
+fn visible_quantity(order_id: u64) -> u128 {
+    Orderbook::get_order(order_id)
+        .and_then(|order| order.iceberg)
+        .map(|iceberg| iceberg.visible_quantity)
+        .unwrap()
+}
+
+#[test]
+fn test_place_iceberg_validates_display_quantity() {
+    build_and_execute(|| {
+        let alice = alice();
+        fund_account(alice, 0, 100);
+        set_rules(1, 5, 0);
+
+        let place = |display, order_type, time_in_force| {
+            TestOrder::new(OrderSide::Sell, 100, 30)
+                .order_type(order_type)
+                .time_in_force(time_in_force)
+                .display(display)
+                .try_place(alice)
+        };
+        for (display, order_type, time_in_force) in [
+            (0, OrderType::Limit, TimeInForce::GTC),
+            (30, OrderType::Limit, TimeInForce::GTC),
+            (40, OrderType::Limit, TimeInForce::GTC),
+            // off the lot size
+            (7, OrderType::Limit, TimeInForce::GTC),
+            (10, OrderType::Limit, TimeInForce::IOC),
+            (10, OrderType::Limit, TimeInForce::FOK),
+            (10, OrderType::Market { quote_limit: 0 }, TimeInForce::GTC),
+        ] {
+            assert_noop!(
+                place(display, order_type, time_in_force),
+                crate::Error::<Test>::InvalidDisplayQuantity
+            );
+        }
+
+        // all of it is locked, only the slice is announced
+        assert_ok!(place(10, OrderType::Limit, TimeInForce::GTC));
+        assert_eq!(Assets::get_locked_balance(&alice, ETH), 30);
+        System::assert_last_event(RuntimeEvent::Orderbook(crate::Event::OrderPlaced {
+            market_id: ETH_USDT,
+            order_id: 0,
+            side: OrderSide::Sell,
+            asset_id: ETH,
+            price: 100,
+            quantity: 10,
+        }));
+        assert_eq!(
+            Orderbook::get_order(0).unwrap().iceberg,
+            Some(Iceberg {
+                display_quantity: 10,
+                visible_quantity: 10,
+            })
+        );
+    });
+}
+
+#[test]
+fn test_iceberg_refill_goes_to_back_of_queue() {
+    build_and_execute(|| {
+        let alice = alice();
+        let bob = bob();
+        let charlie = charlie();
+        fund_account(alice, 10_000, 0);
+        fund_account(bob, 0, 100);
+        fund_account(charlie, 0, 100);
+
+        TestOrder::new(OrderSide::Sell, 100, 30)
+            .display(10)
+            .place(bob);
+        place_limit(charlie, OrderSide::Sell, 100, 10);
+        Orderbook::on_finalize(1);
+        assert_eq!(
+            crate::Asks::<Test>::get(ETH_USDT, 100).into_inner(),
+            vec![0, 1]
+        );
+
+        // bob's slice is filled, the next one waits behind charlie
+        place_limit(alice, OrderSide::Buy, 100, 10);
+        Orderbook::on_finalize(2);
+        assert_eq!(Orderbook::get_order(0).unwrap().filled_quantity, 10);
+        assert_eq!(visible_quantity(0), 10);
+        assert_eq!(
+            crate::Asks::<Test>::get(ETH_USDT, 100).into_inner(),
+            vec![1, 0]
+        );
+        System::assert_has_event(RuntimeEvent::Orderbook(
+            crate::Event::OrderPartiallyFilled {
+                market_id: ETH_USDT,
+                order_id: 0,
+                trader: bob,
+                filled_quantity: 10,
+                remaining_quantity: 10,
+            },
+        ));
+
+        place_limit(alice, OrderSide::Buy, 100, 15);
+        Orderbook::on_finalize(3);
+        assert_eq!(order_status(1), Some(OrderStatus::Filled));
+        assert_eq!(Orderbook::get_order(0).unwrap().filled_quantity, 15);
+        assert_eq!(visible_quantity(0), 5);
+        assert_eq!(Assets::get_free_balance(&alice, ETH), 25);
+    });
+}
+
+#[test]
+fn test_iceberg_shows_slices_until_the_taker_is_filled() {
+    build_and_execute(|| {
+        let alice = alice();
+        let bob = bob();
+        fund_account(alice, 10_000, 0);
+        fund_account(bob, 0, 100);
+
+        TestOrder::new(OrderSide::Sell, 100, 30)
+            .display(10)
+            .place(bob);
+        Orderbook::on_finalize(1);
+
+        // the hidden size counts for fill-or-kill, the level is shared again after each slice
+        TestOrder::new(OrderSide::Buy, 100, 25)
+            .time_in_force(TimeInForce::FOK)
+            .place(alice);
+        Orderbook::on_finalize(2);
+
+        assert_eq!(order_status(1), Some(OrderStatus::Filled));
+        let trades: Vec<u128> = crate::Trades::<Test>::iter_values()
+            .map(|trade| trade.quantity)
+            .collect();
+        assert_eq!(trades, vec![10, 10, 5]);
+        assert_eq!(visible_quantity(0), 5);
+        System::assert_has_event(RuntimeEvent::Orderbook(
+            crate::Event::OrderPartiallyFilled {
+                market_id: ETH_USDT,
+                order_id: 0,
+                trader: bob,
+                filled_quantity: 25,
+                remaining_quantity: 5,
+            },
+        ));
+
+        // the last slice is whatever is left
+        place_limit(alice, OrderSide::Buy, 100, 5);
+        Orderbook::on_finalize(3);
+        assert_eq!(order_status(0), Some(OrderStatus::Filled));
+        assert_eq!(Assets::get_free_balance(&bob, USDT), 3_000);
+    });
+}
+
+#[test]
+fn test_iceberg_taker_rests_with_a_full_slice() {
+    build_and_execute(|| {
+        let alice = alice();
+        let bob = bob();
+        fund_account(alice, 10_000, 0);
+        fund_account(bob, 0, 100);
+
+        place_limit(bob, OrderSide::Sell, 100, 4);
+        Orderbook::on_finalize(1);
+
+        // takes with all of its size, then shows a slice of what is left
+        TestOrder::new(OrderSide::Buy, 100, 30)
+            .display(10)
+            .place(alice);
+        Orderbook::on_finalize(2);
+        assert_eq!(Orderbook::get_order(1).unwrap().filled_quantity, 4);
+        assert_eq!(Orderbook::get_order(1).unwrap().shown_quantity(), 10);
+
+        place_limit(bob, OrderSide::Sell, 100, 12);
+        Orderbook::on_finalize(3);
+        assert_eq!(Orderbook::get_order(1).unwrap().filled_quantity, 16);
+        assert_eq!(Orderbook::get_order(1).unwrap().shown_quantity(), 8);
+    });
+}
+
+// ============================================
+// REDUCE-ONLY ORDER TESTS
+// ============================================
+
+#[test]
+fn test_reduce_only_rejections() {
+    build_and_execute(|| {
+        let alice = alice();
+        fund_account(alice, 10_000, 4);
+        set_rules(1, 5, 0);
+
+        assert_noop!(
+            TestOrder::new(OrderSide::Buy, 100, 10)
+                .reduce_only(ReduceOnly::Reduce)
+                .try_place(alice),
+            crate::Error::<Test>::ReduceOnlyBuy
+        );
+        // four ETH is less than one lot
+        assert_noop!(
+            TestOrder::new(OrderSide::Sell, 100, 10)
+                .reduce_only(ReduceOnly::Reduce)
+                .try_place(alice),
+            crate::Error::<Test>::NoPositionToReduce
+        );
+        assert_noop!(
+            TestOrder::new(OrderSide::Sell, 100, 10)
+                .reduce_only(ReduceOnly::ClosePosition)
+                .try_place(bob()),
+            crate::Error::<Test>::NoPositionToReduce
+        );
+
+        // asks for 1_000 worth, but only the 5 it holds could ever sell
+        fund_account(alice, 0, 1);
+        set_rules(1, 5, 1_000);
+        assert_noop!(
+            TestOrder::new(OrderSide::Sell, 100, 10)
+                .reduce_only(ReduceOnly::Reduce)
+                .try_place(alice),
+            crate::Error::<Test>::BelowMinNotional
+        );
+        assert_ok!(
+            TestOrder::new(OrderSide::Sell, 200, 10)
+                .reduce_only(ReduceOnly::Reduce)
+                .try_place(alice)
+        );
+    });
+}
+
+#[test]
+fn test_reduce_only_locks_at_most_the_free_balance() {
+    build_and_execute(|| {
+        let alice = alice();
+        fund_account(alice, 0, 12);
+
+        assert_ok!(
+            TestOrder::new(OrderSide::Sell, 100, 20)
+                .reduce_only(ReduceOnly::Reduce)
+                .try_place(alice)
+        );
+        Orderbook::on_finalize(1);
+
+        let order = Orderbook::get_order(0).unwrap();
+        assert_eq!(order.quantity, 20);
+        assert_eq!(order.reduce_only, Some(12));
+        assert_eq!(order.shown_quantity(), 12);
+        assert_eq!(Assets::get_locked_balance(&alice, ETH), 12);
+
+        // a deposit after placement stays free
+        fund_account(alice, 0, 50);
+        Orderbook::on_finalize(2);
+        assert_eq!(Assets::get_free_balance(&alice, ETH), 50);
+        assert_eq!(Assets::get_locked_balance(&alice, ETH), 12);
+
+        // shrinking the order lowers the cap, growing it again doesn't lock the deposit
+        assert_ok!(Orderbook::amend_order(
+            RuntimeOrigin::signed(alice),
+            0,
+            100,
+            10
+        ));
+        assert_ok!(Orderbook::amend_order(
+            RuntimeOrigin::signed(alice),
+            0,
+            100,
+            20
+        ));
+        assert_eq!(Orderbook::get_order(0).unwrap().reduce_only, Some(10));
+        assert_eq!(Assets::get_free_balance(&alice, ETH), 52);
+        assert_eq!(Assets::get_locked_balance(&alice, ETH), 10);
+    });
+}
+
+#[test]
+fn test_reduce_only_remainder_is_cancelled_once_the_position_is_sold() {
+    build_and_execute(|| {
+        let alice = alice();
+        let bob = bob();
+        fund_account(alice, 0, 12);
+        fund_account(bob, 10_000, 0);
+
+        assert_ok!(
+            TestOrder::new(OrderSide::Sell, 100, 20)
+                .reduce_only(ReduceOnly::Reduce)
+                .try_place(alice)
+        );
+        Orderbook::on_finalize(1);
+        fund_account(alice, 0, 50);
+
+        place_limit(bob, OrderSide::Buy, 100, 20);
+        Orderbook::on_finalize(2);
+
+        assert_eq!(order_status(0), Some(OrderStatus::Cancelled));
+        System::assert_has_event(
+            crate::Event::OrderRemainderCancelled {
+                market_id: ETH_USDT,
+                order_id: 0,
+                trader: alice,
+                filled_quantity: 12,
+                cancelled_quantity: 8,
+            }
+            .into(),
+        );
+        assert_eq!(Assets::get_free_balance(&alice, ETH), 50);
+        assert_eq!(Assets::get_locked_balance(&alice, ETH), 0);
+        assert_eq!(Assets::get_free_balance(&alice, USDT), 1_200);
+        assert_eq!(Orderbook::get_order(1).unwrap().filled_quantity, 12);
+    });
+}
+
+#[test]
+fn test_close_position_sells_the_free_balance() {
+    build_and_execute(|| {
+        let alice = alice();
+        let bob = bob();
+        fund_account(alice, 0, 37);
+        fund_account(bob, 10_000, 0);
+        set_rules(1, 5, 0);
+
+        place_limit(bob, OrderSide::Buy, 100, 50);
+        Orderbook::on_finalize(1);
+
+        // the requested size is ignored, the whole position rounded to the lot is sold
+        assert_ok!(
+            TestOrder::new(OrderSide::Sell, 100, 5)
+                .reduce_only(ReduceOnly::ClosePosition)
+                .try_place(alice)
+        );
+        Orderbook::on_finalize(2);
+
+        let order = finished_order(1).unwrap();
+        assert_eq!(order.quantity, 35);
+        assert_eq!(order.status, OrderStatus::Filled);
+        assert_eq!(Assets::get_free_balance(&alice, ETH), 2);
+        assert_eq!(Assets::get_locked_balance(&alice, ETH), 0);
+        assert_eq!(Assets::get_free_balance(&alice, USDT), 3_500);
+    });
+}
+
+#[test]
+fn test_reduce_only_market_sell_refunds_what_it_could_not_sell() {
+    build_and_execute(|| {
+        let alice = alice();
+        let bob = bob();
+        fund_account(alice, 0, 12);
+        fund_account(bob, 10_000, 0);
+
+        place_limit(bob, OrderSide::Buy, 100, 8);
+        Orderbook::on_finalize(1);
+
+        assert_ok!(
+            TestOrder::new(OrderSide::Sell, 0, 20)
+                .market(0)
+                .reduce_only(ReduceOnly::Reduce)
+                .try_place(alice)
+        );
+        Orderbook::on_finalize(2);
+
+        System::assert_has_event(
+            crate::Event::OrderRemainderCancelled {
+                market_id: ETH_USDT,
+                order_id: 1,
+                trader: alice,
+                filled_quantity: 8,
+                cancelled_quantity: 12,
+            }
+            .into(),
+        );
+        assert_eq!(Assets::get_free_balance(&alice, ETH), 4);
+        assert_eq!(Assets::get_locked_balance(&alice, ETH), 0);
+    });
+}
+
+// ============================================
+// SPEND ORDER TESTS
+// ============================================
+
+#[test]
+fn test_spend_rejections() {
+    build_and_execute(|| {
+        let alice = alice();
+        fund_account(alice, 10_000, 100);
+        set_rules(1, 1, 500);
+
+        assert_noop!(
+            TestOrder::new(OrderSide::Sell, 0, 0)
+                .spend(1_000)
+                .try_place(alice),
+            crate::Error::<Test>::InvalidSpend
+        );
+        assert_noop!(
+            TestOrder::new(OrderSide::Buy, 0, 0)
+                .spend(1_000)
+                .time_in_force(TimeInForce::FOK)
+                .try_place(alice),
+            crate::Error::<Test>::InvalidSpend
+        );
+        assert_noop!(
+            TestOrder::new(OrderSide::Buy, 0, 0)
+                .spend(0)
+                .try_place(alice),
+            crate::Error::<Test>::InvalidQuoteLimit
+        );
+        assert_noop!(
+            TestOrder::new(OrderSide::Buy, 0, 0)
+                .spend(400)
+                .try_place(alice),
+            crate::Error::<Test>::BelowMinNotional
+        );
+    });
+}
+
+#[test]
+fn test_spend_buys_across_levels_and_refunds_the_rest() {
+    build_and_execute(|| {
+        let alice = alice();
+        let bob = bob();
+        fund_account(alice, 10_000, 0);
+        fund_account(bob, 0, 100);
+
+        place_limit(bob, OrderSide::Sell, 100, 10);
+        place_limit(bob, OrderSide::Sell, 110, 10);
+        Orderbook::on_finalize(1);
+
+        // the whole budget is locked until it has matched
+        assert_ok!(
+            TestOrder::new(OrderSide::Buy, 0, 0)
+                .spend(1_650)
+                .try_place(alice)
+        );
+        assert_eq!(Assets::get_locked_balance(&alice, USDT), 1_650);
+        Orderbook::on_finalize(2);
+
+        // 10 at 100, then the 650 left pays for 5 at 110
+        let order = finished_order(2).unwrap();
+        assert_eq!(order.status, OrderStatus::Filled);
+        assert_eq!(order.quantity, 15);
+        assert_eq!(order.filled_quantity, 15);
+        assert_eq!(Assets::get_free_balance(&alice, ETH), 15);
+        assert_eq!(Assets::get_free_balance(&alice, USDT), 10_000 - 1_550);
+        assert_eq!(Assets::get_locked_balance(&alice, USDT), 0);
+        assert_eq!(Orderbook::get_order(1).unwrap().filled_quantity, 5);
+    });
+}
+
+#[test]
+fn test_spend_buys_whole_lots_only() {
+    build_and_execute(|| {
+        let alice = alice();
+        let bob = bob();
+        fund_account(alice, 10_000, 0);
+        fund_account(bob, 0, 100);
+        set_rules(1, 5, 0);
+
+        place_limit(bob, OrderSide::Sell, 100, 20);
+        Orderbook::on_finalize(1);
+
+        // 1_299 pays for 12, that is two lots
+        assert_ok!(
+            TestOrder::new(OrderSide::Buy, 0, 0)
+                .spend(1_299)
+                .try_place(alice)
+        );
+        Orderbook::on_finalize(2);
+
+        assert_eq!(finished_order(1).unwrap().quantity, 10);
+        assert_eq!(Assets::get_free_balance(&alice, ETH), 10);
+        assert_eq!(Assets::get_free_balance(&alice, USDT), 9_000);
+        assert_eq!(Assets::get_locked_balance(&alice, USDT), 0);
+        assert_eq!(Orderbook::get_order(0).unwrap().filled_quantity, 10);
+    });
+}
+
+#[test]
+fn test_spend_that_buys_nothing_is_killed() {
+    build_and_execute(|| {
+        let alice = alice();
+        let bob = bob();
+        fund_account(alice, 10_000, 0);
+        fund_account(bob, 0, 100);
+
+        place_limit(bob, OrderSide::Sell, 100, 10);
+        Orderbook::on_finalize(1);
+
+        // not enough for one at the best ask
+        assert_ok!(
+            TestOrder::new(OrderSide::Buy, 0, 0)
+                .spend(99)
+                .try_place(alice)
+        );
+        Orderbook::on_finalize(2);
+
+        assert_eq!(order_status(1), Some(OrderStatus::Cancelled));
+        assert!(Orderbook::get_trade(0).is_none());
+        assert_eq!(Assets::get_free_balance(&alice, USDT), 10_000);
+        assert_eq!(Assets::get_locked_balance(&alice, USDT), 0);
+    });
+}
+
+#[test]
+fn test_auction_spend_order() {
+    build_and_execute(|| {
+        let alice = alice();
+        let bob = bob();
+        fund_account(alice, 10_000, 0);
+        fund_account(bob, 0, 100);
+        set_auction();
+
+        place_limit(bob, OrderSide::Sell, 100, 10);
+        place_limit(bob, OrderSide::Sell, 110, 10);
+        assert_ok!(
+            TestOrder::new(OrderSide::Buy, 0, 0)
+                .spend(1_250)
+                .try_place(alice)
+        );
+        Orderbook::on_finalize(1);
+
+        // 1_250 pays for 12 at 100 but only 10 are offered there, at 110 it pays for 11
+        // and that is the most that trades
+        let trades: Vec<_> = crate::Trades::<Test>::iter_values().collect();
+        assert!(trades.iter().all(|trade| trade.price == 110));
+        let order = finished_order(2).unwrap();
+        assert_eq!(order.status, OrderStatus::Filled);
+        assert_eq!(order.quantity, 11);
+        assert_eq!(Assets::get_free_balance(&alice, ETH), 11);
+        assert_eq!(Assets::get_free_balance(&alice, USDT), 10_000 - 1_210);
+        assert_eq!(Assets::get_locked_balance(&alice, USDT), 0);
+    });
+}
@@ -3,6 +3,7 @@ use frame_support::pallet_prelude::*;
 use frame_support::sp_runtime::RuntimeDebug;
 use frame_system::*;
 use scale_info::TypeInfo;
+use sp_runtime::Permill;
 
 #[derive(
     Encode,
@@ -27,6 +28,18 @@ pub enum OrderStatus {
     Cancelled,
     Expired,
     Open,
+    // stop order waiting in the trigger book
+    Untriggered,
+}
+
+impl OrderStatus {
+    /// Filled, cancelled or expired, the order will not trade again
+    pub fn is_finished(&self) -> bool {
+        matches!(
+            self,
+            OrderStatus::Filled | OrderStatus::Cancelled | OrderStatus::Expired
+        )
+    }
 }
 
 #[derive(
@@ -42,9 +55,209 @@ pub enum OrderStatus {
     DecodeWithMemTracking,
 )]
 pub enum OrderType {
-    Market,
+    // never rests, takes whatever the book offers within the quote limit:
+    // the most quote a buy may spend, the least quote a sell must get for what it fills
+    Market { quote_limit: Amount },
     Limit,
-    // will add the other stuff like IOK, Stop etc later
+    // a market buy for a quote amount instead of a base quantity: it buys across the asks
+    // until `quote_amount` is spent, in whole lots, and the quote left over goes back
+    Spend { quote_amount: Amount },
+}
+
+impl OrderType {
+    pub fn is_market(&self) -> bool {
+        matches!(self, OrderType::Market { .. } | OrderType::Spend { .. })
+    }
+}
+
+#[allow(clippy::upper_case_acronyms)]
+#[derive(
+    Encode,
+    Decode,
+    Clone,
+    Copy,
+    Default,
+    RuntimeDebug,
+    PartialEq,
+    Eq,
+    TypeInfo,
+    MaxEncodedLen,
+    DecodeWithMemTracking,
+)]
+pub enum TimeInForce {
+    // rests in the book until filled or cancelled
+    #[default]
+    GTC,
+    // fills what it can in its matching round, the rest is cancelled
+    IOC,
+    // fills completely in its matching round or not at all
+    FOK,
+}
+
+// what a post-only order does when it would take liquidity
+#[derive(
+    Encode,
+    Decode,
+    Clone,
+    Copy,
+    RuntimeDebug,
+    PartialEq,
+    Eq,
+    TypeInfo,
+    MaxEncodedLen,
+    DecodeWithMemTracking,
+)]
+pub enum PostOnly {
+    Reject,
+    // moved to one tick inside the spread
+    Reprice,
+}
+
+// what happens when an order would trade against a resting order of the same trader,
+// the incoming order is the newest and the resting one the oldest
+#[derive(
+    Encode,
+    Decode,
+    Clone,
+    Copy,
+    RuntimeDebug,
+    PartialEq,
+    Eq,
+    TypeInfo,
+    MaxEncodedLen,
+    DecodeWithMemTracking,
+)]
+pub enum SelfTradePrevention {
+    CancelNewest,
+    CancelOldest,
+    CancelBoth,
+    // the smaller open quantity is taken off both without a trade, an order left with
+    // nothing open is cancelled
+    DecrementAndCancel,
+}
+
+// how a taker's quantity is shared among the makers resting at one price
+#[derive(
+    Encode,
+    Decode,
+    Clone,
+    Copy,
+    Default,
+    RuntimeDebug,
+    PartialEq,
+    Eq,
+    TypeInfo,
+    MaxEncodedLen,
+    DecodeWithMemTracking,
+)]
+pub enum MatchingMode {
+    // price-time priority, the oldest maker is filled first
+    #[default]
+    Fifo,
+    // in proportion to each maker's open quantity, after the first `fifo_slice` of the
+    // quantity went to the oldest makers
+    ProRata {
+        fifo_slice: Permill,
+    },
+}
+
+// how a market turns crossing orders into trades
+#[derive(
+    Encode,
+    Decode,
+    Clone,
+    Copy,
+    Default,
+    RuntimeDebug,
+    PartialEq,
+    Eq,
+    TypeInfo,
+    MaxEncodedLen,
+    DecodeWithMemTracking,
+)]
+pub enum ClearingMode {
+    // each order is matched on its own against the book, in the order they were placed
+    #[default]
+    Continuous,
+    // one batch auction per block, every trade happens at a single clearing price
+    BatchAuction,
+}
+
+// the order new orders of a block are matched in
+#[derive(
+    Encode,
+    Decode,
+    Clone,
+    Copy,
+    Default,
+    RuntimeDebug,
+    PartialEq,
+    Eq,
+    TypeInfo,
+    MaxEncodedLen,
+    DecodeWithMemTracking,
+)]
+pub enum OrderingPolicy {
+    // by order id, the order the block author included them in
+    #[default]
+    ById,
+    // shuffled with a seed from the parent block hash
+    ParentHash,
+    // shuffled with a seed from the runtime's randomness source
+    Randomness,
+}
+
+#[derive(
+    Encode,
+    Decode,
+    Clone,
+    Copy,
+    RuntimeDebug,
+    PartialEq,
+    Eq,
+    TypeInfo,
+    MaxEncodedLen,
+    DecodeWithMemTracking,
+)]
+pub enum RejectReason {
+    // post-only order would have crossed the book
+    PostOnlyWouldCross,
+    // repriced post-only order found no room for its new price level
+    TooManyPriceLevels,
+}
+
+// which way the last trade price has to move for a stop order to trigger
+#[derive(
+    Encode,
+    Decode,
+    Clone,
+    Copy,
+    RuntimeDebug,
+    PartialEq,
+    Eq,
+    TypeInfo,
+    MaxEncodedLen,
+    DecodeWithMemTracking,
+)]
+pub enum StopKind {
+    // sells when the price drops to the trigger, buys when it rises to it
+    StopLoss,
+    // sells when the price rises to the trigger, buys when it drops to it
+    TakeProfit,
+}
+
+#[derive(Encode, Decode, Clone, Copy, RuntimeDebug, PartialEq, Eq, TypeInfo, MaxEncodedLen)]
+pub struct StopTrigger {
+    pub kind: StopKind,
+    pub trigger_price: Amount,
+}
+
+// entry of the trigger book, keeps what is needed to check it without loading the order
+#[derive(Encode, Decode, Clone, Copy, RuntimeDebug, PartialEq, Eq, TypeInfo, MaxEncodedLen)]
+pub struct StopEntry {
+    pub order_id: OrderId,
+    pub side: OrderSide,
+    pub trigger: StopTrigger,
 }
 
 #[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
@@ -54,33 +267,228 @@ pub struct MarketPair {
     pub quote_asset: AssetId,
 }
 
+#[derive(
+    Encode,
+    Decode,
+    Clone,
+    Copy,
+    RuntimeDebug,
+    PartialEq,
+    Eq,
+    TypeInfo,
+    MaxEncodedLen,
+    DecodeWithMemTracking,
+)]
+pub enum MarketStatus {
+    Active,
+    // no new orders and no matching, cancellations still go through
+    Paused,
+    // same as paused but there is no way back
+    Delisted,
+}
+
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+pub struct Market {
+    pub pair: MarketPair,
+    pub status: MarketStatus,
+}
+
+// fee rates of a market, the maker rate applies to the resting side of a trade
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+pub struct FeeRates {
+    pub maker: Permill,
+    pub taker: Permill,
+}
+
+// order size rules of a market. `min_notional` is in price * quantity units, what a bid locks
+#[derive(
+    Encode,
+    Decode,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    RuntimeDebug,
+    TypeInfo,
+    MaxEncodedLen,
+    DecodeWithMemTracking,
+)]
+pub struct TradingRules {
+    pub tick_size: Amount,
+    pub lot_size: Amount,
+    pub min_notional: Amount,
+}
+
+// maker side of a fee tier, high volume makers can be paid instead of charged
+#[derive(
+    Encode,
+    Decode,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    RuntimeDebug,
+    TypeInfo,
+    MaxEncodedLen,
+    DecodeWithMemTracking,
+)]
+pub enum MakerFee {
+    Fee(Permill),
+    Rebate(Permill),
+}
+
+// rates for accounts that traded at least `min_volume` quote over the volume window
+#[derive(
+    Encode,
+    Decode,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    RuntimeDebug,
+    TypeInfo,
+    MaxEncodedLen,
+    DecodeWithMemTracking,
+)]
+pub struct FeeTier {
+    pub min_volume: Amount,
+    pub maker: MakerFee,
+    pub taker: Permill,
+}
+
+// what an account pays in a market right now, returned by the runtime api
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub struct EffectiveFees {
+    pub volume: Amount,
+    pub maker: MakerFee,
+    pub taker: Permill,
+}
+
+// one order of a batch, the same options `place_order` takes
+#[derive(
+    Encode,
+    Decode,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    RuntimeDebug,
+    TypeInfo,
+    MaxEncodedLen,
+    DecodeWithMemTracking,
+)]
+pub struct OrderRequest {
+    pub market_id: MarketId,
+    pub side: OrderSide,
+    pub price: Amount,
+    pub quantity: Amount,
+    pub order_type: OrderType,
+    pub time_in_force: TimeInForce,
+    pub post_only: Option<PostOnly>,
+    pub expires_at: Option<u32>,
+    pub self_trade: Option<SelfTradePrevention>,
+    pub display_quantity: Option<Amount>,
+    pub reduce_only: Option<ReduceOnly>,
+}
+
+// sells that only get rid of base the trader already holds, deposits made later are never
+// locked for them
+#[derive(
+    Encode,
+    Decode,
+    Clone,
+    Copy,
+    RuntimeDebug,
+    PartialEq,
+    Eq,
+    TypeInfo,
+    MaxEncodedLen,
+    DecodeWithMemTracking,
+)]
+pub enum ReduceOnly {
+    // locks no more than the free base balance, what it can't sell is cancelled once that
+    // is sold
+    Reduce,
+    // sells the whole free base balance, rounded down to the lot size, whatever the quantity
+    ClosePosition,
+}
+
+// an order that only shows a slice of its size, the next slice is shown from the back of the
+// price level once the current one is filled. Batch auctions trade the hidden size as well.
+#[derive(Encode, Decode, Clone, Copy, RuntimeDebug, PartialEq, Eq, TypeInfo, MaxEncodedLen)]
+pub struct Iceberg {
+    pub display_quantity: Amount,
+    // what is left of the slice shown now
+    pub visible_quantity: Amount,
+}
+
 #[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq, TypeInfo, MaxEncodedLen)]
 #[scale_info(skip_type_params(T))]
 pub struct Order<T: Config> {
     pub order_id: OrderId,
+    pub market_id: MarketId,
     pub trader: T::AccountId,
     pub side: OrderSide,
     pub status: OrderStatus,
     pub order_type: OrderType,
+    pub time_in_force: TimeInForce,
+    pub post_only: Option<PostOnly>,
+    pub stop: Option<StopTrigger>,
     pub price: Amount,
     pub quantity: Amount,
     pub filled_quantity: Amount,
+    // block number the order expires at, it is dropped from the book in that block's on_initialize
     pub ttl: Option<u32>,
+    // the order's own mode or the trader's default when it was placed
+    pub self_trade: Option<SelfTradePrevention>,
+    pub iceberg: Option<Iceberg>,
+    // base a reduce-only sell locked when it was placed, it never sells more than that
+    pub reduce_only: Option<Amount>,
+}
+
+impl<T: Config> Order<T> {
+    // what the order can still trade: its unfilled size, for a reduce-only sell no more than
+    // what is left of the base it locked
+    pub fn open_quantity(&self) -> Amount {
+        let quantity = match self.reduce_only {
+            Some(locked) => self.quantity.min(locked),
+            None => self.quantity,
+        };
+        quantity.saturating_sub(self.filled_quantity)
+    }
+
+    // what the book shows of the order: all it can trade, or the current slice of an iceberg
+    pub fn shown_quantity(&self) -> Amount {
+        let remaining = self.open_quantity();
+        match self.iceberg {
+            Some(iceberg) => remaining.min(iceberg.visible_quantity),
+            None => remaining,
+        }
+    }
 }
 
 #[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq, TypeInfo, MaxEncodedLen)]
 #[scale_info(skip_type_params(T))]
 pub struct Trade<T: Config> {
     pub trade_id: TradeId,
+    pub market_id: MarketId,
     pub buyer: T::AccountId,
     pub seller: T::AccountId,
     pub buy_order_id: OrderId,
     pub sell_order_id: OrderId,
     pub price: Amount,
     pub quantity: Amount,
+    // side of the order that took liquidity
+    pub taker_side: OrderSide,
+    // the buyer pays in base and the seller in quote, both go to the fee account
+    pub buyer_fee: Amount,
+    pub seller_fee: Amount,
+    // paid to the maker out of the fee account in the quote asset
+    pub maker_rebate: Amount,
 }
 
 pub type OrderId = u64;
 pub type TradeId = u64;
 pub type AssetId = u32;
+pub type MarketId = u32;
 pub type Amount = u128;
@@ -1,10 +1,10 @@
 
 //! Autogenerated weights for `pallet_orderbook`
 //!
-//! THIS FILE WAS AUTO-GENERATED USING THE SUBSTRATE BENCHMARK CLI VERSION 49.0.0
-//! DATE: 2025-10-16, STEPS: `50`, REPEAT: `20`, LOW RANGE: `[]`, HIGH RANGE: `[]`
+//! THIS FILE WAS AUTO-GENERATED USING THE SUBSTRATE BENCHMARK CLI VERSION 51.0.0
+//! DATE: 2026-10-18, STEPS: `50`, REPEAT: `20`, LOW RANGE: `[]`, HIGH RANGE: `[]`
 //! WORST CASE MAP SIZE: `1000000`
-//! HOSTNAME: `Randalls-MacBook-Air.local`, CPU: `<UNKNOWN>`
+//! HOSTNAME: `vm`, CPU: `Intel(R) Xeon(R) Processor`
 //! WASM-EXECUTION: `Compiled`, CHAIN: `None`, DB CACHE: `1024`
 
 // Executed Command:
@@ -18,6 +18,10 @@
 // pallet_orderbook
 // --extrinsic
 // *
+// --steps
+// 50
+// --repeat
+// 20
 // --template
 // ./pallets/benchmarking/frame-umbrella-weight-template.hbs
 // --output
@@ -36,515 +40,1888 @@ use core::marker::PhantomData;
 pub trait WeightInfo {
 	fn place_order() -> Weight;
 	fn cancel_order() -> Weight;
+	fn place_stop_order() -> Weight;
+	fn cancel_stop_order() -> Weight;
+	fn create_market() -> Weight;
+	fn set_market_status() -> Weight;
+	fn set_market_fees() -> Weight;
+	fn set_market_rules() -> Weight;
+	fn set_fee_tiers(t: u32, ) -> Weight;
 	fn on_finalize_empty() -> Weight;
 	fn on_finalize_with_matches(b: u32, a: u32, ) -> Weight;
 	fn on_finalize_no_matches(b: u32, a: u32, ) -> Weight;
 	fn on_finalize_with_cancellations(c: u32, ) -> Weight;
 	fn on_finalize_persistent_matching(p: u32, n: u32, ) -> Weight;
 	fn on_finalize_complex(m: u32, n: u32, c: u32, ) -> Weight;
+	fn on_finalize_market(p: u32, r: u32, c: u32, ) -> Weight;
+	fn on_finalize_auction(p: u32, ) -> Weight;
+	fn trigger_stop_orders(s: u32, ) -> Weight;
+	fn expire_orders(e: u32, ) -> Weight;
+	fn place_orders(n: u32, ) -> Weight;
+	fn cancel_orders(n: u32, ) -> Weight;
+	fn replace_orders(c: u32, p: u32, ) -> Weight;
+	fn amend_order() -> Weight;
+	fn cancel_all_orders(n: u32, ) -> Weight;
+	fn prune_trades(n: u32, ) -> Weight;
+	fn set_self_trade_prevention() -> Weight;
+	fn set_matching_mode() -> Weight;
+	fn set_clearing_mode() -> Weight;
+	fn set_ordering_policy() -> Weight;
 }
 
 /// Weights for `pallet_orderbook` using the Substrate node and recommended hardware.
 pub struct SubstrateWeight<T>(PhantomData<T>);
 impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	/// Storage: `Orderbook::Markets` (r:1 w:0)
+	/// Proof: `Orderbook::Markets` (`max_values`: None, `max_size`: Some(29), added: 2504, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::MarketRules` (r:1 w:0)
+	/// Proof: `Orderbook::MarketRules` (`max_values`: None, `max_size`: Some(68), added: 2543, mode: `MaxEncodedLen`)
 	/// Storage: `Assets::FreeBalance` (r:1 w:1)
 	/// Proof: `Assets::FreeBalance` (`max_values`: None, `max_size`: Some(84), added: 2559, mode: `MaxEncodedLen`)
 	/// Storage: `Assets::LockedBalance` (r:1 w:1)
 	/// Proof: `Assets::LockedBalance` (`max_values`: None, `max_size`: Some(84), added: 2559, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::BidPrices` (r:1 w:1)
+	/// Proof: `Orderbook::BidPrices` (`max_values`: None, `max_size`: Some(16022), added: 18497, mode: `MaxEncodedLen`)
 	/// Storage: `Orderbook::NextOrderId` (r:1 w:1)
 	/// Proof: `Orderbook::NextOrderId` (`max_values`: Some(1), `max_size`: Some(8), added: 503, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::AccountSelfTradePrevention` (r:1 w:0)
+	/// Proof: `Orderbook::AccountSelfTradePrevention` (`max_values`: None, `max_size`: Some(49), added: 2524, mode: `MaxEncodedLen`)
 	/// Storage: `Orderbook::PendingBids` (r:1 w:1)
-	/// Proof: `Orderbook::PendingBids` (`max_values`: None, `max_size`: Some(8034), added: 10509, mode: `MaxEncodedLen`)
+	/// Proof: `Orderbook::PendingBids` (`max_values`: None, `max_size`: Some(8054), added: 10529, mode: `MaxEncodedLen`)
 	/// Storage: `Orderbook::UserOrders` (r:1 w:1)
 	/// Proof: `Orderbook::UserOrders` (`max_values`: None, `max_size`: Some(8050), added: 10525, mode: `MaxEncodedLen`)
 	/// Storage: `Orderbook::Orders` (r:0 w:1)
-	/// Proof: `Orderbook::Orders` (`max_values`: None, `max_size`: Some(120), added: 2595, mode: `MaxEncodedLen`)
+	/// Proof: `Orderbook::Orders` (`max_values`: None, `max_size`: Some(213), added: 2688, mode: `MaxEncodedLen`)
 	fn place_order() -> Weight {
 		// Proof Size summary in bytes:
-		//  Measured:  `159`
-		//  Estimated: `11515`
-		// Minimum execution time: 27_000_000 picoseconds.
-		Weight::from_parts(28_000_000, 11515)
-			.saturating_add(T::DbWeight::get().reads(5_u64))
-			.saturating_add(T::DbWeight::get().writes(6_u64))
+		//  Measured:  `394`
+		//  Estimated: `19487`
+		// Minimum execution time: 66_041_000 picoseconds.
+		Weight::from_parts(68_524_000, 19487)
+			.saturating_add(T::DbWeight::get().reads(9_u64))
+			.saturating_add(T::DbWeight::get().writes(7_u64))
 	}
 	/// Storage: `Orderbook::Orders` (r:1 w:0)
-	/// Proof: `Orderbook::Orders` (`max_values`: None, `max_size`: Some(120), added: 2595, mode: `MaxEncodedLen`)
+	/// Proof: `Orderbook::Orders` (`max_values`: None, `max_size`: Some(213), added: 2688, mode: `MaxEncodedLen`)
 	/// Storage: `Orderbook::PendingCancellations` (r:1 w:1)
-	/// Proof: `Orderbook::PendingCancellations` (`max_values`: Some(1), `max_size`: Some(401), added: 896, mode: `MaxEncodedLen`)
+	/// Proof: `Orderbook::PendingCancellations` (`max_values`: None, `max_size`: Some(421), added: 2896, mode: `MaxEncodedLen`)
 	fn cancel_order() -> Weight {
 		// Proof Size summary in bytes:
-		//  Measured:  `289`
-		//  Estimated: `3585`
-		// Minimum execution time: 15_000_000 picoseconds.
-		Weight::from_parts(15_000_000, 3585)
+		//  Measured:  `414`
+		//  Estimated: `3886`
+		// Minimum execution time: 26_913_000 picoseconds.
+		Weight::from_parts(35_712_000, 3886)
 			.saturating_add(T::DbWeight::get().reads(2_u64))
 			.saturating_add(T::DbWeight::get().writes(1_u64))
 	}
-	/// Storage: `Orderbook::Orders` (r:1 w:0)
-	/// Proof: `Orderbook::Orders` (`max_values`: None, `max_size`: Some(120), added: 2595, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::Markets` (r:1 w:0)
+	/// Proof: `Orderbook::Markets` (`max_values`: None, `max_size`: Some(29), added: 2504, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::MarketRules` (r:1 w:0)
+	/// Proof: `Orderbook::MarketRules` (`max_values`: None, `max_size`: Some(68), added: 2543, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::LastTradePrice` (r:1 w:0)
+	/// Proof: `Orderbook::LastTradePrice` (`max_values`: None, `max_size`: Some(36), added: 2511, mode: `MaxEncodedLen`)
+	/// Storage: `Assets::FreeBalance` (r:1 w:1)
+	/// Proof: `Assets::FreeBalance` (`max_values`: None, `max_size`: Some(84), added: 2559, mode: `MaxEncodedLen`)
+	/// Storage: `Assets::LockedBalance` (r:1 w:1)
+	/// Proof: `Assets::LockedBalance` (`max_values`: None, `max_size`: Some(84), added: 2559, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::NextOrderId` (r:1 w:1)
+	/// Proof: `Orderbook::NextOrderId` (`max_values`: Some(1), `max_size`: Some(8), added: 503, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::AccountSelfTradePrevention` (r:1 w:0)
+	/// Proof: `Orderbook::AccountSelfTradePrevention` (`max_values`: None, `max_size`: Some(49), added: 2524, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::StopOrders` (r:1 w:1)
+	/// Proof: `Orderbook::StopOrders` (`max_values`: None, `max_size`: Some(26022), added: 28497, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::UserOrders` (r:1 w:1)
+	/// Proof: `Orderbook::UserOrders` (`max_values`: None, `max_size`: Some(8050), added: 10525, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::Orders` (r:0 w:1)
+	/// Proof: `Orderbook::Orders` (`max_values`: None, `max_size`: Some(213), added: 2688, mode: `MaxEncodedLen`)
+	fn place_stop_order() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `394`
+		//  Estimated: `29487`
+		// Minimum execution time: 64_478_000 picoseconds.
+		Weight::from_parts(103_655_000, 29487)
+			.saturating_add(T::DbWeight::get().reads(9_u64))
+			.saturating_add(T::DbWeight::get().writes(6_u64))
+	}
+	/// Storage: `Orderbook::Orders` (r:1 w:1)
+	/// Proof: `Orderbook::Orders` (`max_values`: None, `max_size`: Some(213), added: 2688, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::Markets` (r:1 w:0)
+	/// Proof: `Orderbook::Markets` (`max_values`: None, `max_size`: Some(29), added: 2504, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::StopOrders` (r:1 w:1)
+	/// Proof: `Orderbook::StopOrders` (`max_values`: None, `max_size`: Some(26022), added: 28497, mode: `MaxEncodedLen`)
+	/// Storage: `Assets::LockedBalance` (r:1 w:1)
+	/// Proof: `Assets::LockedBalance` (`max_values`: None, `max_size`: Some(84), added: 2559, mode: `MaxEncodedLen`)
+	/// Storage: `Assets::FreeBalance` (r:1 w:1)
+	/// Proof: `Assets::FreeBalance` (`max_values`: None, `max_size`: Some(84), added: 2559, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::UserOrders` (r:1 w:1)
+	/// Proof: `Orderbook::UserOrders` (`max_values`: None, `max_size`: Some(8050), added: 10525, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::OrderHistory` (r:1 w:1)
+	/// Proof: `Orderbook::OrderHistory` (`max_values`: None, `max_size`: Some(18950), added: 21425, mode: `MaxEncodedLen`)
+	fn cancel_stop_order() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `803`
+		//  Estimated: `29487`
+		// Minimum execution time: 81_230_000 picoseconds.
+		Weight::from_parts(122_023_000, 29487)
+			.saturating_add(T::DbWeight::get().reads(7_u64))
+			.saturating_add(T::DbWeight::get().writes(6_u64))
+	}
+	/// Storage: `Orderbook::MarketsByPair` (r:1 w:1)
+	/// Proof: `Orderbook::MarketsByPair` (`max_values`: None, `max_size`: Some(28), added: 2503, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::NextMarketId` (r:1 w:1)
+	/// Proof: `Orderbook::NextMarketId` (`max_values`: Some(1), `max_size`: Some(4), added: 499, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::Markets` (r:0 w:1)
+	/// Proof: `Orderbook::Markets` (`max_values`: None, `max_size`: Some(29), added: 2504, mode: `MaxEncodedLen`)
+	fn create_market() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `115`
+		//  Estimated: `3493`
+		// Minimum execution time: 37_685_000 picoseconds.
+		Weight::from_parts(43_805_000, 3493)
+			.saturating_add(T::DbWeight::get().reads(2_u64))
+			.saturating_add(T::DbWeight::get().writes(3_u64))
+	}
+	/// Storage: `Orderbook::Markets` (r:1 w:1)
+	/// Proof: `Orderbook::Markets` (`max_values`: None, `max_size`: Some(29), added: 2504, mode: `MaxEncodedLen`)
+	fn set_market_status() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `149`
+		//  Estimated: `3494`
+		// Minimum execution time: 27_598_000 picoseconds.
+		Weight::from_parts(30_846_000, 3494)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: `Orderbook::Markets` (r:1 w:0)
+	/// Proof: `Orderbook::Markets` (`max_values`: None, `max_size`: Some(29), added: 2504, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::MarketFees` (r:0 w:1)
+	/// Proof: `Orderbook::MarketFees` (`max_values`: None, `max_size`: Some(28), added: 2503, mode: `MaxEncodedLen`)
+	fn set_market_fees() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `149`
+		//  Estimated: `3494`
+		// Minimum execution time: 25_987_000 picoseconds.
+		Weight::from_parts(37_713_000, 3494)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: `Orderbook::Markets` (r:1 w:0)
+	/// Proof: `Orderbook::Markets` (`max_values`: None, `max_size`: Some(29), added: 2504, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::MarketRules` (r:0 w:1)
+	/// Proof: `Orderbook::MarketRules` (`max_values`: None, `max_size`: Some(68), added: 2543, mode: `MaxEncodedLen`)
+	fn set_market_rules() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `149`
+		//  Estimated: `3494`
+		// Minimum execution time: 19_516_000 picoseconds.
+		Weight::from_parts(22_520_000, 3494)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: `Orderbook::FeeTiers` (r:0 w:1)
+	/// Proof: `Orderbook::FeeTiers` (`max_values`: Some(1), `max_size`: Some(251), added: 746, mode: `MaxEncodedLen`)
+	/// The range of component `t` is `[0, 10]`.
+	fn set_fee_tiers(_t: u32, ) -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `0`
+		//  Estimated: `0`
+		// Minimum execution time: 9_023_000 picoseconds.
+		Weight::from_parts(16_138_906, 0)
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: `Orderbook::Markets` (r:2 w:0)
+	/// Proof: `Orderbook::Markets` (`max_values`: None, `max_size`: Some(29), added: 2504, mode: `MaxEncodedLen`)
 	/// Storage: `Orderbook::PendingBids` (r:1 w:0)
-	/// Proof: `Orderbook::PendingBids` (`max_values`: None, `max_size`: Some(8034), added: 10509, mode: `MaxEncodedLen`)
+	/// Proof: `Orderbook::PendingBids` (`max_values`: None, `max_size`: Some(8054), added: 10529, mode: `MaxEncodedLen`)
 	/// Storage: `Orderbook::PendingAsks` (r:1 w:0)
-	/// Proof: `Orderbook::PendingAsks` (`max_values`: None, `max_size`: Some(8034), added: 10509, mode: `MaxEncodedLen`)
-	/// Storage: `Orderbook::Bids` (r:1 w:0)
-	/// Proof: `Orderbook::Bids` (`max_values`: None, `max_size`: Some(80034), added: 82509, mode: `MaxEncodedLen`)
-	/// Storage: `Orderbook::Asks` (r:1 w:0)
-	/// Proof: `Orderbook::Asks` (`max_values`: None, `max_size`: Some(80034), added: 82509, mode: `MaxEncodedLen`)
-	/// Storage: `Orderbook::PendingCancellations` (r:1 w:1)
-	/// Proof: `Orderbook::PendingCancellations` (`max_values`: Some(1), `max_size`: Some(401), added: 896, mode: `MaxEncodedLen`)
+	/// Proof: `Orderbook::PendingAsks` (`max_values`: None, `max_size`: Some(8054), added: 10529, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::PendingCancellations` (r:1 w:0)
+	/// Proof: `Orderbook::PendingCancellations` (`max_values`: None, `max_size`: Some(421), added: 2896, mode: `MaxEncodedLen`)
 	fn on_finalize_empty() -> Weight {
 		// Proof Size summary in bytes:
-		//  Measured:  `6`
-		//  Estimated: `83499`
-		// Minimum execution time: 23_000_000 picoseconds.
-		Weight::from_parts(24_000_000, 83499)
-			.saturating_add(T::DbWeight::get().reads(6_u64))
-			.saturating_add(T::DbWeight::get().writes(1_u64))
+		//  Measured:  `116`
+		//  Estimated: `11519`
+		// Minimum execution time: 24_722_000 picoseconds.
+		Weight::from_parts(25_392_000, 11519)
+			.saturating_add(T::DbWeight::get().reads(5_u64))
 	}
-	/// Storage: `Orderbook::Orders` (r:101 w:100)
-	/// Proof: `Orderbook::Orders` (`max_values`: None, `max_size`: Some(120), added: 2595, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::Markets` (r:2 w:0)
+	/// Proof: `Orderbook::Markets` (`max_values`: None, `max_size`: Some(29), added: 2504, mode: `MaxEncodedLen`)
 	/// Storage: `Orderbook::PendingBids` (r:2 w:1)
-	/// Proof: `Orderbook::PendingBids` (`max_values`: None, `max_size`: Some(8034), added: 10509, mode: `MaxEncodedLen`)
+	/// Proof: `Orderbook::PendingBids` (`max_values`: None, `max_size`: Some(8054), added: 10529, mode: `MaxEncodedLen`)
 	/// Storage: `Orderbook::PendingAsks` (r:2 w:1)
-	/// Proof: `Orderbook::PendingAsks` (`max_values`: None, `max_size`: Some(8034), added: 10509, mode: `MaxEncodedLen`)
-	/// Storage: `Orderbook::Bids` (r:1 w:0)
-	/// Proof: `Orderbook::Bids` (`max_values`: None, `max_size`: Some(80034), added: 82509, mode: `MaxEncodedLen`)
-	/// Storage: `Orderbook::Asks` (r:1 w:0)
-	/// Proof: `Orderbook::Asks` (`max_values`: None, `max_size`: Some(80034), added: 82509, mode: `MaxEncodedLen`)
-	/// Storage: `Orderbook::PendingCancellations` (r:1 w:1)
-	/// Proof: `Orderbook::PendingCancellations` (`max_values`: Some(1), `max_size`: Some(401), added: 896, mode: `MaxEncodedLen`)
-	/// Storage: `Orderbook::NextTradeId` (r:1 w:1)
-	/// Proof: `Orderbook::NextTradeId` (`max_values`: Some(1), `max_size`: Some(8), added: 503, mode: `MaxEncodedLen`)
+	/// Proof: `Orderbook::PendingAsks` (`max_values`: None, `max_size`: Some(8054), added: 10529, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::PendingCancellations` (r:1 w:0)
+	/// Proof: `Orderbook::PendingCancellations` (`max_values`: None, `max_size`: Some(421), added: 2896, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::Orders` (r:100 w:100)
+	/// Proof: `Orderbook::Orders` (`max_values`: None, `max_size`: Some(213), added: 2688, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::Bids` (r:1 w:1)
+	/// Proof: `Orderbook::Bids` (`max_values`: None, `max_size`: Some(80054), added: 82529, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::Asks` (r:1 w:1)
+	/// Proof: `Orderbook::Asks` (`max_values`: None, `max_size`: Some(80054), added: 82529, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::MarketRules` (r:1 w:0)
+	/// Proof: `Orderbook::MarketRules` (`max_values`: None, `max_size`: Some(68), added: 2543, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::MarketMatchingMode` (r:1 w:0)
+	/// Proof: `Orderbook::MarketMatchingMode` (`max_values`: None, `max_size`: Some(25), added: 2500, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::MarketOrderingPolicy` (r:1 w:0)
+	/// Proof: `Orderbook::MarketOrderingPolicy` (`max_values`: None, `max_size`: Some(21), added: 2496, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::BestBid` (r:1 w:1)
+	/// Proof: `Orderbook::BestBid` (`max_values`: None, `max_size`: Some(36), added: 2511, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::BestAsk` (r:1 w:1)
+	/// Proof: `Orderbook::BestAsk` (`max_values`: None, `max_size`: Some(36), added: 2511, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::MarketClearingMode` (r:1 w:0)
+	/// Proof: `Orderbook::MarketClearingMode` (`max_values`: None, `max_size`: Some(21), added: 2496, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::MarketFees` (r:1 w:0)
+	/// Proof: `Orderbook::MarketFees` (`max_values`: None, `max_size`: Some(28), added: 2503, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::FeeTiers` (r:1 w:0)
+	/// Proof: `Orderbook::FeeTiers` (`max_values`: Some(1), `max_size`: Some(251), added: 746, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::TradingVolume` (r:100 w:100)
+	/// Proof: `Orderbook::TradingVolume` (`max_values`: None, `max_size`: Some(649), added: 3124, mode: `MaxEncodedLen`)
 	/// Storage: `Assets::LockedBalance` (r:100 w:100)
 	/// Proof: `Assets::LockedBalance` (`max_values`: None, `max_size`: Some(84), added: 2559, mode: `MaxEncodedLen`)
-	/// Storage: `Assets::FreeBalance` (r:100 w:100)
+	/// Storage: `Assets::FreeBalance` (r:101 w:101)
 	/// Proof: `Assets::FreeBalance` (`max_values`: None, `max_size`: Some(84), added: 2559, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::NextTradeId` (r:1 w:1)
+	/// Proof: `Orderbook::NextTradeId` (`max_values`: Some(1), `max_size`: Some(8), added: 503, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::UserOrders` (r:100 w:100)
+	/// Proof: `Orderbook::UserOrders` (`max_values`: None, `max_size`: Some(8050), added: 10525, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::OrderHistory` (r:100 w:100)
+	/// Proof: `Orderbook::OrderHistory` (`max_values`: None, `max_size`: Some(18950), added: 21425, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::BidPrices` (r:1 w:1)
+	/// Proof: `Orderbook::BidPrices` (`max_values`: None, `max_size`: Some(16022), added: 18497, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::AskPrices` (r:1 w:1)
+	/// Proof: `Orderbook::AskPrices` (`max_values`: None, `max_size`: Some(16022), added: 18497, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::StopOrders` (r:1 w:0)
+	/// Proof: `Orderbook::StopOrders` (`max_values`: None, `max_size`: Some(26022), added: 28497, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::LastTradePrice` (r:0 w:1)
+	/// Proof: `Orderbook::LastTradePrice` (`max_values`: None, `max_size`: Some(36), added: 2511, mode: `MaxEncodedLen`)
 	/// Storage: `Orderbook::Trades` (r:0 w:50)
-	/// Proof: `Orderbook::Trades` (`max_values`: None, `max_size`: Some(144), added: 2619, mode: `MaxEncodedLen`)
+	/// Proof: `Orderbook::Trades` (`max_values`: None, `max_size`: Some(197), added: 2672, mode: `MaxEncodedLen`)
 	/// The range of component `b` is `[1, 50]`.
 	/// The range of component `a` is `[1, 50]`.
 	fn on_finalize_with_matches(b: u32, a: u32, ) -> Weight {
 		// Proof Size summary in bytes:
-		//  Measured:  `0 + a * (509 ±0) + b * (510 ±0)`
-		//  Estimated: `83499 + a * (2595 ±0) + b * (2595 ±0)`
-		// Minimum execution time: 378_000_000 picoseconds.
-		Weight::from_parts(380_000_000, 83499)
-			// Standard Error: 455_618
-			.saturating_add(Weight::from_parts(14_754_607, 0).saturating_mul(b.into()))
-			// Standard Error: 455_618
-			.saturating_add(Weight::from_parts(13_465_412, 0).saturating_mul(a.into()))
-			.saturating_add(T::DbWeight::get().reads(64_u64))
-			.saturating_add(T::DbWeight::get().reads((2_u64).saturating_mul(b.into())))
-			.saturating_add(T::DbWeight::get().reads((2_u64).saturating_mul(a.into())))
-			.saturating_add(T::DbWeight::get().writes(61_u64))
-			.saturating_add(T::DbWeight::get().writes((2_u64).saturating_mul(b.into())))
-			.saturating_add(T::DbWeight::get().writes((2_u64).saturating_mul(a.into())))
-			.saturating_add(Weight::from_parts(0, 2595).saturating_mul(a.into()))
-			.saturating_add(Weight::from_parts(0, 2595).saturating_mul(b.into()))
-	}
-	/// Storage: `Orderbook::Orders` (r:101 w:100)
-	/// Proof: `Orderbook::Orders` (`max_values`: None, `max_size`: Some(120), added: 2595, mode: `MaxEncodedLen`)
+		//  Measured:  `0 + a * (611 ±0) + b * (613 ±0)`
+		//  Estimated: `83519 + a * (14925 ±239) + b * (14925 ±239)`
+		// Minimum execution time: 763_812_000 picoseconds.
+		Weight::from_parts(781_620_000, 83519)
+			// Standard Error: 2_076_685
+			.saturating_add(Weight::from_parts(66_065_966, 0).saturating_mul(b.into()))
+			// Standard Error: 2_076_685
+			.saturating_add(Weight::from_parts(69_997_765, 0).saturating_mul(a.into()))
+			.saturating_add(T::DbWeight::get().reads(83_u64))
+			.saturating_add(T::DbWeight::get().reads((4_u64).saturating_mul(b.into())))
+			.saturating_add(T::DbWeight::get().reads((4_u64).saturating_mul(a.into())))
+			.saturating_add(T::DbWeight::get().writes(73_u64))
+			.saturating_add(T::DbWeight::get().writes((4_u64).saturating_mul(b.into())))
+			.saturating_add(T::DbWeight::get().writes((4_u64).saturating_mul(a.into())))
+			.saturating_add(Weight::from_parts(0, 14925).saturating_mul(a.into()))
+			.saturating_add(Weight::from_parts(0, 14925).saturating_mul(b.into()))
+	}
+	/// Storage: `Orderbook::Markets` (r:2 w:0)
+	/// Proof: `Orderbook::Markets` (`max_values`: None, `max_size`: Some(29), added: 2504, mode: `MaxEncodedLen`)
 	/// Storage: `Orderbook::PendingBids` (r:2 w:1)
-	/// Proof: `Orderbook::PendingBids` (`max_values`: None, `max_size`: Some(8034), added: 10509, mode: `MaxEncodedLen`)
+	/// Proof: `Orderbook::PendingBids` (`max_values`: None, `max_size`: Some(8054), added: 10529, mode: `MaxEncodedLen`)
 	/// Storage: `Orderbook::PendingAsks` (r:2 w:1)
-	/// Proof: `Orderbook::PendingAsks` (`max_values`: None, `max_size`: Some(8034), added: 10509, mode: `MaxEncodedLen`)
+	/// Proof: `Orderbook::PendingAsks` (`max_values`: None, `max_size`: Some(8054), added: 10529, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::PendingCancellations` (r:1 w:0)
+	/// Proof: `Orderbook::PendingCancellations` (`max_values`: None, `max_size`: Some(421), added: 2896, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::Orders` (r:100 w:100)
+	/// Proof: `Orderbook::Orders` (`max_values`: None, `max_size`: Some(213), added: 2688, mode: `MaxEncodedLen`)
 	/// Storage: `Orderbook::Bids` (r:1 w:1)
-	/// Proof: `Orderbook::Bids` (`max_values`: None, `max_size`: Some(80034), added: 82509, mode: `MaxEncodedLen`)
+	/// Proof: `Orderbook::Bids` (`max_values`: None, `max_size`: Some(80054), added: 82529, mode: `MaxEncodedLen`)
 	/// Storage: `Orderbook::Asks` (r:1 w:1)
-	/// Proof: `Orderbook::Asks` (`max_values`: None, `max_size`: Some(80034), added: 82509, mode: `MaxEncodedLen`)
-	/// Storage: `Orderbook::PendingCancellations` (r:1 w:1)
-	/// Proof: `Orderbook::PendingCancellations` (`max_values`: Some(1), `max_size`: Some(401), added: 896, mode: `MaxEncodedLen`)
+	/// Proof: `Orderbook::Asks` (`max_values`: None, `max_size`: Some(80054), added: 82529, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::MarketRules` (r:1 w:0)
+	/// Proof: `Orderbook::MarketRules` (`max_values`: None, `max_size`: Some(68), added: 2543, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::MarketMatchingMode` (r:1 w:0)
+	/// Proof: `Orderbook::MarketMatchingMode` (`max_values`: None, `max_size`: Some(25), added: 2500, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::MarketOrderingPolicy` (r:1 w:0)
+	/// Proof: `Orderbook::MarketOrderingPolicy` (`max_values`: None, `max_size`: Some(21), added: 2496, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::BestBid` (r:1 w:1)
+	/// Proof: `Orderbook::BestBid` (`max_values`: None, `max_size`: Some(36), added: 2511, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::BestAsk` (r:1 w:1)
+	/// Proof: `Orderbook::BestAsk` (`max_values`: None, `max_size`: Some(36), added: 2511, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::MarketClearingMode` (r:1 w:0)
+	/// Proof: `Orderbook::MarketClearingMode` (`max_values`: None, `max_size`: Some(21), added: 2496, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::MarketFees` (r:1 w:0)
+	/// Proof: `Orderbook::MarketFees` (`max_values`: None, `max_size`: Some(28), added: 2503, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::FeeTiers` (r:1 w:0)
+	/// Proof: `Orderbook::FeeTiers` (`max_values`: Some(1), `max_size`: Some(251), added: 746, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::BidPrices` (r:1 w:1)
+	/// Proof: `Orderbook::BidPrices` (`max_values`: None, `max_size`: Some(16022), added: 18497, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::AskPrices` (r:1 w:1)
+	/// Proof: `Orderbook::AskPrices` (`max_values`: None, `max_size`: Some(16022), added: 18497, mode: `MaxEncodedLen`)
 	/// The range of component `b` is `[1, 50]`.
 	/// The range of component `a` is `[1, 50]`.
 	fn on_finalize_no_matches(b: u32, a: u32, ) -> Weight {
 		// Proof Size summary in bytes:
-		//  Measured:  `709 + a * (134 ±0) + b * (134 ±0)`
-		//  Estimated: `83499 + a * (2595 ±0) + b * (2595 ±0)`
-		// Minimum execution time: 338_000_000 picoseconds.
-		Weight::from_parts(340_000_000, 83499)
-			// Standard Error: 106_234
-			.saturating_add(Weight::from_parts(2_374_357, 0).saturating_mul(b.into()))
-			// Standard Error: 106_234
-			.saturating_add(Weight::from_parts(2_260_042, 0).saturating_mul(a.into()))
-			.saturating_add(T::DbWeight::get().reads(8_u64))
+		//  Measured:  `542 + a * (142 ±0) + b * (142 ±0)`
+		//  Estimated: `83519 + a * (2688 ±0) + b * (2688 ±0)`
+		// Minimum execution time: 1_014_473_000 picoseconds.
+		Weight::from_parts(200_762_501, 83519)
+			// Standard Error: 78_665
+			.saturating_add(Weight::from_parts(16_811_291, 0).saturating_mul(b.into()))
+			// Standard Error: 78_665
+			.saturating_add(Weight::from_parts(17_170_734, 0).saturating_mul(a.into()))
+			.saturating_add(T::DbWeight::get().reads(19_u64))
 			.saturating_add(T::DbWeight::get().reads((1_u64).saturating_mul(b.into())))
 			.saturating_add(T::DbWeight::get().reads((1_u64).saturating_mul(a.into())))
-			.saturating_add(T::DbWeight::get().writes(5_u64))
+			.saturating_add(T::DbWeight::get().writes(8_u64))
 			.saturating_add(T::DbWeight::get().writes((1_u64).saturating_mul(b.into())))
 			.saturating_add(T::DbWeight::get().writes((1_u64).saturating_mul(a.into())))
-			.saturating_add(Weight::from_parts(0, 2595).saturating_mul(a.into()))
-			.saturating_add(Weight::from_parts(0, 2595).saturating_mul(b.into()))
+			.saturating_add(Weight::from_parts(0, 2688).saturating_mul(a.into()))
+			.saturating_add(Weight::from_parts(0, 2688).saturating_mul(b.into()))
 	}
-	/// Storage: `Orderbook::Orders` (r:51 w:50)
-	/// Proof: `Orderbook::Orders` (`max_values`: None, `max_size`: Some(120), added: 2595, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::Markets` (r:2 w:0)
+	/// Proof: `Orderbook::Markets` (`max_values`: None, `max_size`: Some(29), added: 2504, mode: `MaxEncodedLen`)
 	/// Storage: `Orderbook::PendingBids` (r:2 w:1)
-	/// Proof: `Orderbook::PendingBids` (`max_values`: None, `max_size`: Some(8034), added: 10509, mode: `MaxEncodedLen`)
+	/// Proof: `Orderbook::PendingBids` (`max_values`: None, `max_size`: Some(8054), added: 10529, mode: `MaxEncodedLen`)
 	/// Storage: `Orderbook::PendingAsks` (r:1 w:0)
-	/// Proof: `Orderbook::PendingAsks` (`max_values`: None, `max_size`: Some(8034), added: 10509, mode: `MaxEncodedLen`)
-	/// Storage: `Orderbook::Bids` (r:1 w:1)
-	/// Proof: `Orderbook::Bids` (`max_values`: None, `max_size`: Some(80034), added: 82509, mode: `MaxEncodedLen`)
-	/// Storage: `Orderbook::Asks` (r:1 w:0)
-	/// Proof: `Orderbook::Asks` (`max_values`: None, `max_size`: Some(80034), added: 82509, mode: `MaxEncodedLen`)
+	/// Proof: `Orderbook::PendingAsks` (`max_values`: None, `max_size`: Some(8054), added: 10529, mode: `MaxEncodedLen`)
 	/// Storage: `Orderbook::PendingCancellations` (r:1 w:1)
-	/// Proof: `Orderbook::PendingCancellations` (`max_values`: Some(1), `max_size`: Some(401), added: 896, mode: `MaxEncodedLen`)
+	/// Proof: `Orderbook::PendingCancellations` (`max_values`: None, `max_size`: Some(421), added: 2896, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::Orders` (r:50 w:50)
+	/// Proof: `Orderbook::Orders` (`max_values`: None, `max_size`: Some(213), added: 2688, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::Bids` (r:1 w:1)
+	/// Proof: `Orderbook::Bids` (`max_values`: None, `max_size`: Some(80054), added: 82529, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::MarketRules` (r:1 w:0)
+	/// Proof: `Orderbook::MarketRules` (`max_values`: None, `max_size`: Some(68), added: 2543, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::MarketMatchingMode` (r:1 w:0)
+	/// Proof: `Orderbook::MarketMatchingMode` (`max_values`: None, `max_size`: Some(25), added: 2500, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::MarketOrderingPolicy` (r:1 w:0)
+	/// Proof: `Orderbook::MarketOrderingPolicy` (`max_values`: None, `max_size`: Some(21), added: 2496, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::BestBid` (r:1 w:1)
+	/// Proof: `Orderbook::BestBid` (`max_values`: None, `max_size`: Some(36), added: 2511, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::BestAsk` (r:1 w:1)
+	/// Proof: `Orderbook::BestAsk` (`max_values`: None, `max_size`: Some(36), added: 2511, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::MarketClearingMode` (r:1 w:0)
+	/// Proof: `Orderbook::MarketClearingMode` (`max_values`: None, `max_size`: Some(21), added: 2496, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::MarketFees` (r:1 w:0)
+	/// Proof: `Orderbook::MarketFees` (`max_values`: None, `max_size`: Some(28), added: 2503, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::FeeTiers` (r:1 w:0)
+	/// Proof: `Orderbook::FeeTiers` (`max_values`: Some(1), `max_size`: Some(251), added: 746, mode: `MaxEncodedLen`)
 	/// Storage: `Assets::LockedBalance` (r:50 w:50)
 	/// Proof: `Assets::LockedBalance` (`max_values`: None, `max_size`: Some(84), added: 2559, mode: `MaxEncodedLen`)
 	/// Storage: `Assets::FreeBalance` (r:50 w:50)
 	/// Proof: `Assets::FreeBalance` (`max_values`: None, `max_size`: Some(84), added: 2559, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::UserOrders` (r:50 w:50)
+	/// Proof: `Orderbook::UserOrders` (`max_values`: None, `max_size`: Some(8050), added: 10525, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::OrderHistory` (r:50 w:50)
+	/// Proof: `Orderbook::OrderHistory` (`max_values`: None, `max_size`: Some(18950), added: 21425, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::BidPrices` (r:1 w:1)
+	/// Proof: `Orderbook::BidPrices` (`max_values`: None, `max_size`: Some(16022), added: 18497, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::AskPrices` (r:1 w:1)
+	/// Proof: `Orderbook::AskPrices` (`max_values`: None, `max_size`: Some(16022), added: 18497, mode: `MaxEncodedLen`)
 	/// The range of component `c` is `[1, 50]`.
 	fn on_finalize_with_cancellations(c: u32, ) -> Weight {
 		// Proof Size summary in bytes:
-		//  Measured:  `225 + c * (359 ±0)`
-		//  Estimated: `83499 + c * (2595 ±0)`
-		// Minimum execution time: 57_000_000 picoseconds.
-		Weight::from_parts(45_799_510, 83499)
-			// Standard Error: 38_272
-			.saturating_add(Weight::from_parts(20_011_078, 0).saturating_mul(c.into()))
-			.saturating_add(T::DbWeight::get().reads(7_u64))
-			.saturating_add(T::DbWeight::get().reads((3_u64).saturating_mul(c.into())))
-			.saturating_add(T::DbWeight::get().writes(3_u64))
-			.saturating_add(T::DbWeight::get().writes((3_u64).saturating_mul(c.into())))
-			.saturating_add(Weight::from_parts(0, 2595).saturating_mul(c.into()))
+		//  Measured:  `556 + c * (432 ±0)`
+		//  Estimated: `83519 + c * (21425 ±0)`
+		// Minimum execution time: 257_477_000 picoseconds.
+		Weight::from_parts(203_245_337, 83519)
+			// Standard Error: 91_958
+			.saturating_add(Weight::from_parts(73_172_238, 0).saturating_mul(c.into()))
+			.saturating_add(T::DbWeight::get().reads(17_u64))
+			.saturating_add(T::DbWeight::get().reads((5_u64).saturating_mul(c.into())))
+			.saturating_add(T::DbWeight::get().writes(7_u64))
+			.saturating_add(T::DbWeight::get().writes((5_u64).saturating_mul(c.into())))
+			.saturating_add(Weight::from_parts(0, 21425).saturating_mul(c.into()))
 	}
-	/// Storage: `Orderbook::Orders` (r:41 w:40)
-	/// Proof: `Orderbook::Orders` (`max_values`: None, `max_size`: Some(120), added: 2595, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::Markets` (r:2 w:0)
+	/// Proof: `Orderbook::Markets` (`max_values`: None, `max_size`: Some(29), added: 2504, mode: `MaxEncodedLen`)
 	/// Storage: `Orderbook::PendingBids` (r:2 w:1)
-	/// Proof: `Orderbook::PendingBids` (`max_values`: None, `max_size`: Some(8034), added: 10509, mode: `MaxEncodedLen`)
+	/// Proof: `Orderbook::PendingBids` (`max_values`: None, `max_size`: Some(8054), added: 10529, mode: `MaxEncodedLen`)
 	/// Storage: `Orderbook::PendingAsks` (r:1 w:0)
-	/// Proof: `Orderbook::PendingAsks` (`max_values`: None, `max_size`: Some(8034), added: 10509, mode: `MaxEncodedLen`)
-	/// Storage: `Orderbook::Bids` (r:1 w:0)
-	/// Proof: `Orderbook::Bids` (`max_values`: None, `max_size`: Some(80034), added: 82509, mode: `MaxEncodedLen`)
-	/// Storage: `Orderbook::Asks` (r:2 w:0)
-	/// Proof: `Orderbook::Asks` (`max_values`: None, `max_size`: Some(80034), added: 82509, mode: `MaxEncodedLen`)
-	/// Storage: `Orderbook::PendingCancellations` (r:1 w:1)
-	/// Proof: `Orderbook::PendingCancellations` (`max_values`: Some(1), `max_size`: Some(401), added: 896, mode: `MaxEncodedLen`)
-	/// Storage: `Orderbook::NextTradeId` (r:1 w:1)
-	/// Proof: `Orderbook::NextTradeId` (`max_values`: Some(1), `max_size`: Some(8), added: 503, mode: `MaxEncodedLen`)
+	/// Proof: `Orderbook::PendingAsks` (`max_values`: None, `max_size`: Some(8054), added: 10529, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::PendingCancellations` (r:1 w:0)
+	/// Proof: `Orderbook::PendingCancellations` (`max_values`: None, `max_size`: Some(421), added: 2896, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::Orders` (r:40 w:40)
+	/// Proof: `Orderbook::Orders` (`max_values`: None, `max_size`: Some(213), added: 2688, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::Bids` (r:1 w:1)
+	/// Proof: `Orderbook::Bids` (`max_values`: None, `max_size`: Some(80054), added: 82529, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::MarketRules` (r:1 w:0)
+	/// Proof: `Orderbook::MarketRules` (`max_values`: None, `max_size`: Some(68), added: 2543, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::MarketMatchingMode` (r:1 w:0)
+	/// Proof: `Orderbook::MarketMatchingMode` (`max_values`: None, `max_size`: Some(25), added: 2500, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::MarketOrderingPolicy` (r:1 w:0)
+	/// Proof: `Orderbook::MarketOrderingPolicy` (`max_values`: None, `max_size`: Some(21), added: 2496, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::BestBid` (r:1 w:1)
+	/// Proof: `Orderbook::BestBid` (`max_values`: None, `max_size`: Some(36), added: 2511, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::BestAsk` (r:1 w:1)
+	/// Proof: `Orderbook::BestAsk` (`max_values`: None, `max_size`: Some(36), added: 2511, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::AskPrices` (r:1 w:1)
+	/// Proof: `Orderbook::AskPrices` (`max_values`: None, `max_size`: Some(16022), added: 18497, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::Asks` (r:1 w:1)
+	/// Proof: `Orderbook::Asks` (`max_values`: None, `max_size`: Some(80054), added: 82529, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::MarketClearingMode` (r:1 w:0)
+	/// Proof: `Orderbook::MarketClearingMode` (`max_values`: None, `max_size`: Some(21), added: 2496, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::MarketFees` (r:1 w:0)
+	/// Proof: `Orderbook::MarketFees` (`max_values`: None, `max_size`: Some(28), added: 2503, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::FeeTiers` (r:1 w:0)
+	/// Proof: `Orderbook::FeeTiers` (`max_values`: Some(1), `max_size`: Some(251), added: 746, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::TradingVolume` (r:40 w:40)
+	/// Proof: `Orderbook::TradingVolume` (`max_values`: None, `max_size`: Some(649), added: 3124, mode: `MaxEncodedLen`)
 	/// Storage: `Assets::LockedBalance` (r:40 w:40)
 	/// Proof: `Assets::LockedBalance` (`max_values`: None, `max_size`: Some(84), added: 2559, mode: `MaxEncodedLen`)
-	/// Storage: `Assets::FreeBalance` (r:40 w:40)
+	/// Storage: `Assets::FreeBalance` (r:41 w:41)
 	/// Proof: `Assets::FreeBalance` (`max_values`: None, `max_size`: Some(84), added: 2559, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::NextTradeId` (r:1 w:1)
+	/// Proof: `Orderbook::NextTradeId` (`max_values`: Some(1), `max_size`: Some(8), added: 503, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::UserOrders` (r:40 w:40)
+	/// Proof: `Orderbook::UserOrders` (`max_values`: None, `max_size`: Some(8050), added: 10525, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::OrderHistory` (r:40 w:40)
+	/// Proof: `Orderbook::OrderHistory` (`max_values`: None, `max_size`: Some(18950), added: 21425, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::BidPrices` (r:1 w:1)
+	/// Proof: `Orderbook::BidPrices` (`max_values`: None, `max_size`: Some(16022), added: 18497, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::StopOrders` (r:1 w:0)
+	/// Proof: `Orderbook::StopOrders` (`max_values`: None, `max_size`: Some(26022), added: 28497, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::LastTradePrice` (r:0 w:1)
+	/// Proof: `Orderbook::LastTradePrice` (`max_values`: None, `max_size`: Some(36), added: 2511, mode: `MaxEncodedLen`)
 	/// Storage: `Orderbook::Trades` (r:0 w:20)
-	/// Proof: `Orderbook::Trades` (`max_values`: None, `max_size`: Some(144), added: 2619, mode: `MaxEncodedLen`)
+	/// Proof: `Orderbook::Trades` (`max_values`: None, `max_size`: Some(197), added: 2672, mode: `MaxEncodedLen`)
 	/// The range of component `p` is `[1, 20]`.
 	/// The range of component `n` is `[1, 20]`.
 	fn on_finalize_persistent_matching(p: u32, n: u32, ) -> Weight {
 		// Proof Size summary in bytes:
-		//  Measured:  `0 + n * (534 ±0) + p * (518 ±0)`
-		//  Estimated: `166008 + n * (2595 ±58) + p * (2595 ±58)`
-		// Minimum execution time: 203_000_000 picoseconds.
-		Weight::from_parts(205_000_000, 166008)
-			// Standard Error: 428_260
-			.saturating_add(Weight::from_parts(12_659_114, 0).saturating_mul(p.into()))
-			// Standard Error: 428_260
-			.saturating_add(Weight::from_parts(13_031_874, 0).saturating_mul(n.into()))
-			.saturating_add(T::DbWeight::get().reads(34_u64))
-			.saturating_add(T::DbWeight::get().reads((2_u64).saturating_mul(p.into())))
-			.saturating_add(T::DbWeight::get().reads((2_u64).saturating_mul(n.into())))
-			.saturating_add(T::DbWeight::get().writes(30_u64))
-			.saturating_add(T::DbWeight::get().writes((2_u64).saturating_mul(p.into())))
-			.saturating_add(T::DbWeight::get().writes((2_u64).saturating_mul(n.into())))
-			.saturating_add(Weight::from_parts(0, 2595).saturating_mul(n.into()))
-			.saturating_add(Weight::from_parts(0, 2595).saturating_mul(p.into()))
-	}
-	/// Storage: `Orderbook::Orders` (r:71 w:70)
-	/// Proof: `Orderbook::Orders` (`max_values`: None, `max_size`: Some(120), added: 2595, mode: `MaxEncodedLen`)
+		//  Measured:  `0 + n * (630 ±0) + p * (615 ±0)`
+		//  Estimated: `83519 + n * (13886 ±58) + p * (13886 ±58)`
+		// Minimum execution time: 594_724_000 picoseconds.
+		Weight::from_parts(658_088_000, 83519)
+			// Standard Error: 1_897_946
+			.saturating_add(Weight::from_parts(62_119_021, 0).saturating_mul(p.into()))
+			// Standard Error: 1_897_946
+			.saturating_add(Weight::from_parts(64_837_198, 0).saturating_mul(n.into()))
+			.saturating_add(T::DbWeight::get().reads(52_u64))
+			.saturating_add(T::DbWeight::get().reads((4_u64).saturating_mul(p.into())))
+			.saturating_add(T::DbWeight::get().reads((4_u64).saturating_mul(n.into())))
+			.saturating_add(T::DbWeight::get().writes(42_u64))
+			.saturating_add(T::DbWeight::get().writes((4_u64).saturating_mul(p.into())))
+			.saturating_add(T::DbWeight::get().writes((4_u64).saturating_mul(n.into())))
+			.saturating_add(Weight::from_parts(0, 13886).saturating_mul(n.into()))
+			.saturating_add(Weight::from_parts(0, 13886).saturating_mul(p.into()))
+	}
+	/// Storage: `Orderbook::Markets` (r:2 w:0)
+	/// Proof: `Orderbook::Markets` (`max_values`: None, `max_size`: Some(29), added: 2504, mode: `MaxEncodedLen`)
 	/// Storage: `Orderbook::PendingBids` (r:3 w:2)
-	/// Proof: `Orderbook::PendingBids` (`max_values`: None, `max_size`: Some(8034), added: 10509, mode: `MaxEncodedLen`)
+	/// Proof: `Orderbook::PendingBids` (`max_values`: None, `max_size`: Some(8054), added: 10529, mode: `MaxEncodedLen`)
 	/// Storage: `Orderbook::PendingAsks` (r:2 w:1)
-	/// Proof: `Orderbook::PendingAsks` (`max_values`: None, `max_size`: Some(8034), added: 10509, mode: `MaxEncodedLen`)
-	/// Storage: `Orderbook::Bids` (r:1 w:2)
-	/// Proof: `Orderbook::Bids` (`max_values`: None, `max_size`: Some(80034), added: 82509, mode: `MaxEncodedLen`)
-	/// Storage: `Orderbook::Asks` (r:1 w:0)
-	/// Proof: `Orderbook::Asks` (`max_values`: None, `max_size`: Some(80034), added: 82509, mode: `MaxEncodedLen`)
+	/// Proof: `Orderbook::PendingAsks` (`max_values`: None, `max_size`: Some(8054), added: 10529, mode: `MaxEncodedLen`)
 	/// Storage: `Orderbook::PendingCancellations` (r:1 w:1)
-	/// Proof: `Orderbook::PendingCancellations` (`max_values`: Some(1), `max_size`: Some(401), added: 896, mode: `MaxEncodedLen`)
-	/// Storage: `Orderbook::NextTradeId` (r:1 w:1)
-	/// Proof: `Orderbook::NextTradeId` (`max_values`: Some(1), `max_size`: Some(8), added: 503, mode: `MaxEncodedLen`)
+	/// Proof: `Orderbook::PendingCancellations` (`max_values`: None, `max_size`: Some(421), added: 2896, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::Orders` (r:70 w:70)
+	/// Proof: `Orderbook::Orders` (`max_values`: None, `max_size`: Some(213), added: 2688, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::Bids` (r:2 w:2)
+	/// Proof: `Orderbook::Bids` (`max_values`: None, `max_size`: Some(80054), added: 82529, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::Asks` (r:1 w:1)
+	/// Proof: `Orderbook::Asks` (`max_values`: None, `max_size`: Some(80054), added: 82529, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::MarketRules` (r:1 w:0)
+	/// Proof: `Orderbook::MarketRules` (`max_values`: None, `max_size`: Some(68), added: 2543, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::MarketMatchingMode` (r:1 w:0)
+	/// Proof: `Orderbook::MarketMatchingMode` (`max_values`: None, `max_size`: Some(25), added: 2500, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::MarketOrderingPolicy` (r:1 w:0)
+	/// Proof: `Orderbook::MarketOrderingPolicy` (`max_values`: None, `max_size`: Some(21), added: 2496, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::BestBid` (r:1 w:1)
+	/// Proof: `Orderbook::BestBid` (`max_values`: None, `max_size`: Some(36), added: 2511, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::BestAsk` (r:1 w:1)
+	/// Proof: `Orderbook::BestAsk` (`max_values`: None, `max_size`: Some(36), added: 2511, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::MarketClearingMode` (r:1 w:0)
+	/// Proof: `Orderbook::MarketClearingMode` (`max_values`: None, `max_size`: Some(21), added: 2496, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::MarketFees` (r:1 w:0)
+	/// Proof: `Orderbook::MarketFees` (`max_values`: None, `max_size`: Some(28), added: 2503, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::FeeTiers` (r:1 w:0)
+	/// Proof: `Orderbook::FeeTiers` (`max_values`: Some(1), `max_size`: Some(251), added: 746, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::TradingVolume` (r:40 w:40)
+	/// Proof: `Orderbook::TradingVolume` (`max_values`: None, `max_size`: Some(649), added: 3124, mode: `MaxEncodedLen`)
 	/// Storage: `Assets::LockedBalance` (r:50 w:50)
 	/// Proof: `Assets::LockedBalance` (`max_values`: None, `max_size`: Some(84), added: 2559, mode: `MaxEncodedLen`)
-	/// Storage: `Assets::FreeBalance` (r:50 w:50)
+	/// Storage: `Assets::FreeBalance` (r:51 w:51)
 	/// Proof: `Assets::FreeBalance` (`max_values`: None, `max_size`: Some(84), added: 2559, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::NextTradeId` (r:1 w:1)
+	/// Proof: `Orderbook::NextTradeId` (`max_values`: Some(1), `max_size`: Some(8), added: 503, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::UserOrders` (r:50 w:50)
+	/// Proof: `Orderbook::UserOrders` (`max_values`: None, `max_size`: Some(8050), added: 10525, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::OrderHistory` (r:50 w:50)
+	/// Proof: `Orderbook::OrderHistory` (`max_values`: None, `max_size`: Some(18950), added: 21425, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::BidPrices` (r:1 w:1)
+	/// Proof: `Orderbook::BidPrices` (`max_values`: None, `max_size`: Some(16022), added: 18497, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::AskPrices` (r:1 w:1)
+	/// Proof: `Orderbook::AskPrices` (`max_values`: None, `max_size`: Some(16022), added: 18497, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::StopOrders` (r:1 w:0)
+	/// Proof: `Orderbook::StopOrders` (`max_values`: None, `max_size`: Some(26022), added: 28497, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::LastTradePrice` (r:0 w:1)
+	/// Proof: `Orderbook::LastTradePrice` (`max_values`: None, `max_size`: Some(36), added: 2511, mode: `MaxEncodedLen`)
 	/// Storage: `Orderbook::Trades` (r:0 w:20)
-	/// Proof: `Orderbook::Trades` (`max_values`: None, `max_size`: Some(144), added: 2619, mode: `MaxEncodedLen`)
+	/// Proof: `Orderbook::Trades` (`max_values`: None, `max_size`: Some(197), added: 2672, mode: `MaxEncodedLen`)
 	/// The range of component `m` is `[1, 20]`.
 	/// The range of component `n` is `[1, 20]`.
 	/// The range of component `c` is `[1, 10]`.
 	fn on_finalize_complex(m: u32, n: u32, c: u32, ) -> Weight {
 		// Proof Size summary in bytes:
-		//  Measured:  `187 + c * (366 ±0) + m * (713 ±0) + n * (198 ±0)`
-		//  Estimated: `83499 + c * (2595 ±0) + m * (5190 ±0) + n * (2595 ±0)`
-		// Minimum execution time: 429_000_000 picoseconds.
-		Weight::from_parts(64_754_480, 83499)
-			// Standard Error: 102_973
-			.saturating_add(Weight::from_parts(44_112_949, 0).saturating_mul(m.into()))
-			// Standard Error: 102_973
-			.saturating_add(Weight::from_parts(6_550_077, 0).saturating_mul(n.into()))
-			// Standard Error: 210_571
-			.saturating_add(Weight::from_parts(21_044_681, 0).saturating_mul(c.into()))
-			.saturating_add(T::DbWeight::get().reads(10_u64))
-			.saturating_add(T::DbWeight::get().reads((6_u64).saturating_mul(m.into())))
+		//  Measured:  `528 + c * (439 ±0) + m * (862 ±0) + n * (242 ±0)`
+		//  Estimated: `166048 + c * (21425 ±0) + m * (42850 ±0) + n * (2688 ±0)`
+		// Minimum execution time: 1_222_354_000 picoseconds.
+		Weight::from_parts(342_338_766, 166048)
+			// Standard Error: 781_841
+			.saturating_add(Weight::from_parts(212_844_988, 0).saturating_mul(m.into()))
+			// Standard Error: 781_841
+			.saturating_add(Weight::from_parts(7_835_582, 0).saturating_mul(n.into()))
+			// Standard Error: 1_598_795
+			.saturating_add(Weight::from_parts(67_575_695, 0).saturating_mul(c.into()))
+			.saturating_add(T::DbWeight::get().reads(24_u64))
+			.saturating_add(T::DbWeight::get().reads((12_u64).saturating_mul(m.into())))
 			.saturating_add(T::DbWeight::get().reads((1_u64).saturating_mul(n.into())))
-			.saturating_add(T::DbWeight::get().reads((3_u64).saturating_mul(c.into())))
+			.saturating_add(T::DbWeight::get().reads((5_u64).saturating_mul(c.into())))
+			.saturating_add(T::DbWeight::get().writes(14_u64))
+			.saturating_add(T::DbWeight::get().writes((13_u64).saturating_mul(m.into())))
+			.saturating_add(T::DbWeight::get().writes((1_u64).saturating_mul(n.into())))
+			.saturating_add(T::DbWeight::get().writes((5_u64).saturating_mul(c.into())))
+			.saturating_add(Weight::from_parts(0, 21425).saturating_mul(c.into()))
+			.saturating_add(Weight::from_parts(0, 42850).saturating_mul(m.into()))
+			.saturating_add(Weight::from_parts(0, 2688).saturating_mul(n.into()))
+	}
+	/// Storage: `Orderbook::Markets` (r:2 w:0)
+	/// Proof: `Orderbook::Markets` (`max_values`: None, `max_size`: Some(29), added: 2504, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::PendingBids` (r:2 w:1)
+	/// Proof: `Orderbook::PendingBids` (`max_values`: None, `max_size`: Some(8054), added: 10529, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::PendingAsks` (r:1 w:0)
+	/// Proof: `Orderbook::PendingAsks` (`max_values`: None, `max_size`: Some(8054), added: 10529, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::PendingCancellations` (r:1 w:1)
+	/// Proof: `Orderbook::PendingCancellations` (`max_values`: None, `max_size`: Some(421), added: 2896, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::Orders` (r:120 w:120)
+	/// Proof: `Orderbook::Orders` (`max_values`: None, `max_size`: Some(213), added: 2688, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::Bids` (r:1 w:1)
+	/// Proof: `Orderbook::Bids` (`max_values`: None, `max_size`: Some(80054), added: 82529, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::MarketRules` (r:1 w:0)
+	/// Proof: `Orderbook::MarketRules` (`max_values`: None, `max_size`: Some(68), added: 2543, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::MarketMatchingMode` (r:1 w:0)
+	/// Proof: `Orderbook::MarketMatchingMode` (`max_values`: None, `max_size`: Some(25), added: 2500, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::MarketOrderingPolicy` (r:1 w:0)
+	/// Proof: `Orderbook::MarketOrderingPolicy` (`max_values`: None, `max_size`: Some(21), added: 2496, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::BestBid` (r:1 w:1)
+	/// Proof: `Orderbook::BestBid` (`max_values`: None, `max_size`: Some(36), added: 2511, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::BestAsk` (r:1 w:1)
+	/// Proof: `Orderbook::BestAsk` (`max_values`: None, `max_size`: Some(36), added: 2511, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::AskPrices` (r:1 w:1)
+	/// Proof: `Orderbook::AskPrices` (`max_values`: None, `max_size`: Some(16022), added: 18497, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::Asks` (r:1 w:1)
+	/// Proof: `Orderbook::Asks` (`max_values`: None, `max_size`: Some(80054), added: 82529, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::MarketClearingMode` (r:1 w:0)
+	/// Proof: `Orderbook::MarketClearingMode` (`max_values`: None, `max_size`: Some(21), added: 2496, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::MarketFees` (r:1 w:0)
+	/// Proof: `Orderbook::MarketFees` (`max_values`: None, `max_size`: Some(28), added: 2503, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::FeeTiers` (r:1 w:0)
+	/// Proof: `Orderbook::FeeTiers` (`max_values`: Some(1), `max_size`: Some(251), added: 746, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::TradingVolume` (r:100 w:100)
+	/// Proof: `Orderbook::TradingVolume` (`max_values`: None, `max_size`: Some(649), added: 3124, mode: `MaxEncodedLen`)
+	/// Storage: `Assets::LockedBalance` (r:120 w:120)
+	/// Proof: `Assets::LockedBalance` (`max_values`: None, `max_size`: Some(84), added: 2559, mode: `MaxEncodedLen`)
+	/// Storage: `Assets::FreeBalance` (r:121 w:121)
+	/// Proof: `Assets::FreeBalance` (`max_values`: None, `max_size`: Some(84), added: 2559, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::NextTradeId` (r:1 w:1)
+	/// Proof: `Orderbook::NextTradeId` (`max_values`: Some(1), `max_size`: Some(8), added: 503, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::UserOrders` (r:120 w:120)
+	/// Proof: `Orderbook::UserOrders` (`max_values`: None, `max_size`: Some(8050), added: 10525, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::OrderHistory` (r:120 w:120)
+	/// Proof: `Orderbook::OrderHistory` (`max_values`: None, `max_size`: Some(18950), added: 21425, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::BidPrices` (r:1 w:1)
+	/// Proof: `Orderbook::BidPrices` (`max_values`: None, `max_size`: Some(16022), added: 18497, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::StopOrders` (r:1 w:0)
+	/// Proof: `Orderbook::StopOrders` (`max_values`: None, `max_size`: Some(26022), added: 28497, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::LastTradePrice` (r:0 w:1)
+	/// Proof: `Orderbook::LastTradePrice` (`max_values`: None, `max_size`: Some(36), added: 2511, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::Trades` (r:0 w:50)
+	/// Proof: `Orderbook::Trades` (`max_values`: None, `max_size`: Some(197), added: 2672, mode: `MaxEncodedLen`)
+	/// The range of component `p` is `[1, 50]`.
+	/// The range of component `r` is `[0, 50]`.
+	/// The range of component `c` is `[0, 20]`.
+	fn on_finalize_market(p: u32, r: u32, c: u32, ) -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `0 + c * (435 ±0) + p * (613 ±0) + r * (626 ±0)`
+		//  Estimated: `429490 + c * (2688 ±0) + p * (21842 ±187) + r * (22515 ±185)`
+		// Minimum execution time: 1_527_175_000 picoseconds.
+		Weight::from_parts(2_051_077_000, 429490)
+			// Standard Error: 1_653_467
+			.saturating_add(Weight::from_parts(94_646_491, 0).saturating_mul(p.into()))
+			// Standard Error: 1_635_275
+			.saturating_add(Weight::from_parts(100_030_842, 0).saturating_mul(r.into()))
+			.saturating_add(T::DbWeight::get().reads(167_u64))
+			.saturating_add(T::DbWeight::get().reads((6_u64).saturating_mul(p.into())))
+			.saturating_add(T::DbWeight::get().reads((6_u64).saturating_mul(r.into())))
+			.saturating_add(T::DbWeight::get().writes(157_u64))
+			.saturating_add(T::DbWeight::get().writes((6_u64).saturating_mul(p.into())))
+			.saturating_add(T::DbWeight::get().writes((6_u64).saturating_mul(r.into())))
+			.saturating_add(Weight::from_parts(0, 2688).saturating_mul(c.into()))
+			.saturating_add(Weight::from_parts(0, 21842).saturating_mul(p.into()))
+			.saturating_add(Weight::from_parts(0, 22515).saturating_mul(r.into()))
+	}
+	/// Storage: `Orderbook::Markets` (r:2 w:0)
+	/// Proof: `Orderbook::Markets` (`max_values`: None, `max_size`: Some(29), added: 2504, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::PendingBids` (r:10 w:9)
+	/// Proof: `Orderbook::PendingBids` (`max_values`: None, `max_size`: Some(8054), added: 10529, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::PendingAsks` (r:2 w:1)
+	/// Proof: `Orderbook::PendingAsks` (`max_values`: None, `max_size`: Some(8054), added: 10529, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::PendingCancellations` (r:1 w:0)
+	/// Proof: `Orderbook::PendingCancellations` (`max_values`: None, `max_size`: Some(421), added: 2896, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::Orders` (r:59 w:59)
+	/// Proof: `Orderbook::Orders` (`max_values`: None, `max_size`: Some(213), added: 2688, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::Bids` (r:9 w:9)
+	/// Proof: `Orderbook::Bids` (`max_values`: None, `max_size`: Some(80054), added: 82529, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::Asks` (r:1 w:1)
+	/// Proof: `Orderbook::Asks` (`max_values`: None, `max_size`: Some(80054), added: 82529, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::MarketRules` (r:1 w:0)
+	/// Proof: `Orderbook::MarketRules` (`max_values`: None, `max_size`: Some(68), added: 2543, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::MarketMatchingMode` (r:1 w:0)
+	/// Proof: `Orderbook::MarketMatchingMode` (`max_values`: None, `max_size`: Some(25), added: 2500, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::MarketOrderingPolicy` (r:1 w:0)
+	/// Proof: `Orderbook::MarketOrderingPolicy` (`max_values`: None, `max_size`: Some(21), added: 2496, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::BestBid` (r:1 w:1)
+	/// Proof: `Orderbook::BestBid` (`max_values`: None, `max_size`: Some(36), added: 2511, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::BestAsk` (r:1 w:1)
+	/// Proof: `Orderbook::BestAsk` (`max_values`: None, `max_size`: Some(36), added: 2511, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::MarketClearingMode` (r:1 w:0)
+	/// Proof: `Orderbook::MarketClearingMode` (`max_values`: None, `max_size`: Some(21), added: 2496, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::LastTradePrice` (r:1 w:0)
+	/// Proof: `Orderbook::LastTradePrice` (`max_values`: None, `max_size`: Some(36), added: 2511, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::MarketFees` (r:1 w:0)
+	/// Proof: `Orderbook::MarketFees` (`max_values`: None, `max_size`: Some(28), added: 2503, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::FeeTiers` (r:1 w:0)
+	/// Proof: `Orderbook::FeeTiers` (`max_values`: Some(1), `max_size`: Some(251), added: 746, mode: `MaxEncodedLen`)
+	/// Storage: `Assets::LockedBalance` (r:10 w:10)
+	/// Proof: `Assets::LockedBalance` (`max_values`: None, `max_size`: Some(84), added: 2559, mode: `MaxEncodedLen`)
+	/// Storage: `Assets::FreeBalance` (r:10 w:10)
+	/// Proof: `Assets::FreeBalance` (`max_values`: None, `max_size`: Some(84), added: 2559, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::UserOrders` (r:9 w:9)
+	/// Proof: `Orderbook::UserOrders` (`max_values`: None, `max_size`: Some(8050), added: 10525, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::OrderHistory` (r:9 w:9)
+	/// Proof: `Orderbook::OrderHistory` (`max_values`: None, `max_size`: Some(18950), added: 21425, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::BidPrices` (r:1 w:1)
+	/// Proof: `Orderbook::BidPrices` (`max_values`: None, `max_size`: Some(16022), added: 18497, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::AskPrices` (r:1 w:1)
+	/// Proof: `Orderbook::AskPrices` (`max_values`: None, `max_size`: Some(16022), added: 18497, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::TradingVolume` (r:2 w:2)
+	/// Proof: `Orderbook::TradingVolume` (`max_values`: None, `max_size`: Some(649), added: 3124, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::NextTradeId` (r:1 w:1)
+	/// Proof: `Orderbook::NextTradeId` (`max_values`: Some(1), `max_size`: Some(8), added: 503, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::StopOrders` (r:1 w:0)
+	/// Proof: `Orderbook::StopOrders` (`max_values`: None, `max_size`: Some(26022), added: 28497, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::Trades` (r:0 w:1)
+	/// Proof: `Orderbook::Trades` (`max_values`: None, `max_size`: Some(197), added: 2672, mode: `MaxEncodedLen`)
+	/// The range of component `p` is `[1, 50]`.
+	fn on_finalize_auction(p: u32, ) -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `5265 + p * (150 ±0)`
+		//  Estimated: `743751 + p * (2688 ±3)`
+		// Minimum execution time: 934_360_000 picoseconds.
+		Weight::from_parts(1_231_352_932, 743751)
+			// Standard Error: 1_074_917
+			.saturating_add(Weight::from_parts(44_020_939, 0).saturating_mul(p.into()))
+			.saturating_add(T::DbWeight::get().reads(88_u64))
+			.saturating_add(T::DbWeight::get().reads((1_u64).saturating_mul(p.into())))
+			.saturating_add(T::DbWeight::get().writes(76_u64))
+			.saturating_add(T::DbWeight::get().writes((1_u64).saturating_mul(p.into())))
+			.saturating_add(Weight::from_parts(0, 2688).saturating_mul(p.into()))
+	}
+	/// Storage: `Orderbook::StopOrders` (r:1 w:1)
+	/// Proof: `Orderbook::StopOrders` (`max_values`: None, `max_size`: Some(26022), added: 28497, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::Orders` (r:50 w:50)
+	/// Proof: `Orderbook::Orders` (`max_values`: None, `max_size`: Some(213), added: 2688, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::AskPrices` (r:1 w:1)
+	/// Proof: `Orderbook::AskPrices` (`max_values`: None, `max_size`: Some(16022), added: 18497, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::PendingAsks` (r:50 w:50)
+	/// Proof: `Orderbook::PendingAsks` (`max_values`: None, `max_size`: Some(8054), added: 10529, mode: `MaxEncodedLen`)
+	/// The range of component `s` is `[1, 50]`.
+	fn trigger_stop_orders(s: u32, ) -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `255 + s * (176 ±0)`
+		//  Estimated: `29487 + s * (10529 ±0)`
+		// Minimum execution time: 44_608_000 picoseconds.
+		Weight::from_parts(16_771_697, 29487)
+			// Standard Error: 145_806
+			.saturating_add(Weight::from_parts(27_842_283, 0).saturating_mul(s.into()))
+			.saturating_add(T::DbWeight::get().reads(2_u64))
+			.saturating_add(T::DbWeight::get().reads((2_u64).saturating_mul(s.into())))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+			.saturating_add(T::DbWeight::get().writes((2_u64).saturating_mul(s.into())))
+			.saturating_add(Weight::from_parts(0, 10529).saturating_mul(s.into()))
+	}
+	/// Storage: `Orderbook::ExpiringOrders` (r:1 w:1)
+	/// Proof: `Orderbook::ExpiringOrders` (`max_values`: None, `max_size`: Some(8022), added: 10497, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::Orders` (r:100 w:100)
+	/// Proof: `Orderbook::Orders` (`max_values`: None, `max_size`: Some(213), added: 2688, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::Markets` (r:1 w:0)
+	/// Proof: `Orderbook::Markets` (`max_values`: None, `max_size`: Some(29), added: 2504, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::Bids` (r:40 w:40)
+	/// Proof: `Orderbook::Bids` (`max_values`: None, `max_size`: Some(80054), added: 82529, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::PendingBids` (r:40 w:0)
+	/// Proof: `Orderbook::PendingBids` (`max_values`: None, `max_size`: Some(8054), added: 10529, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::BidPrices` (r:1 w:1)
+	/// Proof: `Orderbook::BidPrices` (`max_values`: None, `max_size`: Some(16022), added: 18497, mode: `MaxEncodedLen`)
+	/// Storage: `Assets::LockedBalance` (r:100 w:100)
+	/// Proof: `Assets::LockedBalance` (`max_values`: None, `max_size`: Some(84), added: 2559, mode: `MaxEncodedLen`)
+	/// Storage: `Assets::FreeBalance` (r:100 w:100)
+	/// Proof: `Assets::FreeBalance` (`max_values`: None, `max_size`: Some(84), added: 2559, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::UserOrders` (r:100 w:100)
+	/// Proof: `Orderbook::UserOrders` (`max_values`: None, `max_size`: Some(8050), added: 10525, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::OrderHistory` (r:100 w:100)
+	/// Proof: `Orderbook::OrderHistory` (`max_values`: None, `max_size`: Some(18950), added: 21425, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::BestBid` (r:0 w:1)
+	/// Proof: `Orderbook::BestBid` (`max_values`: None, `max_size`: Some(36), added: 2511, mode: `MaxEncodedLen`)
+	/// The range of component `e` is `[1, 100]`.
+	fn expire_orders(e: u32, ) -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `1550 + e * (450 ±0)`
+		//  Estimated: `1191560 + e * (28996 ±0)`
+		// Minimum execution time: 137_093_000 picoseconds.
+		Weight::from_parts(146_417_000, 1191560)
+			// Standard Error: 476_633
+			.saturating_add(Weight::from_parts(86_729_299, 0).saturating_mul(e.into()))
+			.saturating_add(T::DbWeight::get().reads(32_u64))
+			.saturating_add(T::DbWeight::get().reads((6_u64).saturating_mul(e.into())))
+			.saturating_add(T::DbWeight::get().writes(17_u64))
+			.saturating_add(T::DbWeight::get().writes((5_u64).saturating_mul(e.into())))
+			.saturating_add(Weight::from_parts(0, 28996).saturating_mul(e.into()))
+	}
+	/// Storage: `Orderbook::Markets` (r:1 w:0)
+	/// Proof: `Orderbook::Markets` (`max_values`: None, `max_size`: Some(29), added: 2504, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::MarketRules` (r:1 w:0)
+	/// Proof: `Orderbook::MarketRules` (`max_values`: None, `max_size`: Some(68), added: 2543, mode: `MaxEncodedLen`)
+	/// Storage: `Assets::FreeBalance` (r:1 w:1)
+	/// Proof: `Assets::FreeBalance` (`max_values`: None, `max_size`: Some(84), added: 2559, mode: `MaxEncodedLen`)
+	/// Storage: `Assets::LockedBalance` (r:1 w:1)
+	/// Proof: `Assets::LockedBalance` (`max_values`: None, `max_size`: Some(84), added: 2559, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::BidPrices` (r:1 w:1)
+	/// Proof: `Orderbook::BidPrices` (`max_values`: None, `max_size`: Some(16022), added: 18497, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::NextOrderId` (r:1 w:1)
+	/// Proof: `Orderbook::NextOrderId` (`max_values`: Some(1), `max_size`: Some(8), added: 503, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::AccountSelfTradePrevention` (r:1 w:0)
+	/// Proof: `Orderbook::AccountSelfTradePrevention` (`max_values`: None, `max_size`: Some(49), added: 2524, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::PendingBids` (r:40 w:40)
+	/// Proof: `Orderbook::PendingBids` (`max_values`: None, `max_size`: Some(8054), added: 10529, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::UserOrders` (r:1 w:1)
+	/// Proof: `Orderbook::UserOrders` (`max_values`: None, `max_size`: Some(8050), added: 10525, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::Orders` (r:0 w:999)
+	/// Proof: `Orderbook::Orders` (`max_values`: None, `max_size`: Some(213), added: 2688, mode: `MaxEncodedLen`)
+	/// The range of component `n` is `[1, 1000]`.
+	fn place_orders(n: u32, ) -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `394`
+		//  Estimated: `375188 + n * (69 ±9)`
+		// Minimum execution time: 100_200_000 picoseconds.
+		Weight::from_parts(102_295_000, 375188)
+			// Standard Error: 233_044
+			.saturating_add(Weight::from_parts(55_052_804, 0).saturating_mul(n.into()))
+			.saturating_add(T::DbWeight::get().reads(44_u64))
+			.saturating_add(T::DbWeight::get().writes(41_u64))
+			.saturating_add(T::DbWeight::get().writes((1_u64).saturating_mul(n.into())))
+			.saturating_add(Weight::from_parts(0, 69).saturating_mul(n.into()))
+	}
+	/// Storage: `Orderbook::Orders` (r:50 w:0)
+	/// Proof: `Orderbook::Orders` (`max_values`: None, `max_size`: Some(213), added: 2688, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::PendingCancellations` (r:1 w:1)
+	/// Proof: `Orderbook::PendingCancellations` (`max_values`: None, `max_size`: Some(421), added: 2896, mode: `MaxEncodedLen`)
+	/// The range of component `n` is `[1, 50]`.
+	fn cancel_orders(n: u32, ) -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `282 + n * (133 ±0)`
+		//  Estimated: `3886 + n * (2688 ±0)`
+		// Minimum execution time: 40_373_000 picoseconds.
+		Weight::from_parts(12_592_826, 3886)
+			// Standard Error: 85_009
+			.saturating_add(Weight::from_parts(22_843_631, 0).saturating_mul(n.into()))
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().reads((1_u64).saturating_mul(n.into())))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+			.saturating_add(Weight::from_parts(0, 2688).saturating_mul(n.into()))
+	}
+	/// Storage: `Orderbook::Orders` (r:50 w:950)
+	/// Proof: `Orderbook::Orders` (`max_values`: None, `max_size`: Some(213), added: 2688, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::PendingCancellations` (r:1 w:1)
+	/// Proof: `Orderbook::PendingCancellations` (`max_values`: None, `max_size`: Some(421), added: 2896, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::Markets` (r:1 w:0)
+	/// Proof: `Orderbook::Markets` (`max_values`: None, `max_size`: Some(29), added: 2504, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::MarketRules` (r:1 w:0)
+	/// Proof: `Orderbook::MarketRules` (`max_values`: None, `max_size`: Some(68), added: 2543, mode: `MaxEncodedLen`)
+	/// Storage: `Assets::FreeBalance` (r:1 w:1)
+	/// Proof: `Assets::FreeBalance` (`max_values`: None, `max_size`: Some(84), added: 2559, mode: `MaxEncodedLen`)
+	/// Storage: `Assets::LockedBalance` (r:1 w:1)
+	/// Proof: `Assets::LockedBalance` (`max_values`: None, `max_size`: Some(84), added: 2559, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::BidPrices` (r:1 w:1)
+	/// Proof: `Orderbook::BidPrices` (`max_values`: None, `max_size`: Some(16022), added: 18497, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::NextOrderId` (r:1 w:1)
+	/// Proof: `Orderbook::NextOrderId` (`max_values`: Some(1), `max_size`: Some(8), added: 503, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::AccountSelfTradePrevention` (r:1 w:0)
+	/// Proof: `Orderbook::AccountSelfTradePrevention` (`max_values`: None, `max_size`: Some(49), added: 2524, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::PendingBids` (r:40 w:40)
+	/// Proof: `Orderbook::PendingBids` (`max_values`: None, `max_size`: Some(8054), added: 10529, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::UserOrders` (r:1 w:1)
+	/// Proof: `Orderbook::UserOrders` (`max_values`: None, `max_size`: Some(8050), added: 10525, mode: `MaxEncodedLen`)
+	/// The range of component `c` is `[0, 50]`.
+	/// The range of component `p` is `[1, 950]`.
+	fn replace_orders(c: u32, p: u32, ) -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `715 + c * (203 ±0)`
+		//  Estimated: `361409 + c * (2690 ±0) + p * (58 ±5)`
+		// Minimum execution time: 1_133_863_000 picoseconds.
+		Weight::from_parts(3_678_946_909, 361409)
+			// Standard Error: 901_814
+			.saturating_add(Weight::from_parts(46_776_930, 0).saturating_mul(p.into()))
+			.saturating_add(T::DbWeight::get().reads(43_u64))
+			.saturating_add(T::DbWeight::get().reads((1_u64).saturating_mul(c.into())))
+			.saturating_add(T::DbWeight::get().writes(40_u64))
+			.saturating_add(T::DbWeight::get().writes((1_u64).saturating_mul(p.into())))
+			.saturating_add(Weight::from_parts(0, 2690).saturating_mul(c.into()))
+			.saturating_add(Weight::from_parts(0, 58).saturating_mul(p.into()))
+	}
+	/// Storage: `Orderbook::Orders` (r:1 w:1)
+	/// Proof: `Orderbook::Orders` (`max_values`: None, `max_size`: Some(213), added: 2688, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::PendingCancellations` (r:1 w:0)
+	/// Proof: `Orderbook::PendingCancellations` (`max_values`: None, `max_size`: Some(421), added: 2896, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::Markets` (r:1 w:0)
+	/// Proof: `Orderbook::Markets` (`max_values`: None, `max_size`: Some(29), added: 2504, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::MarketRules` (r:1 w:0)
+	/// Proof: `Orderbook::MarketRules` (`max_values`: None, `max_size`: Some(68), added: 2543, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::Bids` (r:1 w:1)
+	/// Proof: `Orderbook::Bids` (`max_values`: None, `max_size`: Some(80054), added: 82529, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::PendingBids` (r:2 w:1)
+	/// Proof: `Orderbook::PendingBids` (`max_values`: None, `max_size`: Some(8054), added: 10529, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::BidPrices` (r:1 w:1)
+	/// Proof: `Orderbook::BidPrices` (`max_values`: None, `max_size`: Some(16022), added: 18497, mode: `MaxEncodedLen`)
+	/// Storage: `Assets::FreeBalance` (r:1 w:1)
+	/// Proof: `Assets::FreeBalance` (`max_values`: None, `max_size`: Some(84), added: 2559, mode: `MaxEncodedLen`)
+	/// Storage: `Assets::LockedBalance` (r:1 w:1)
+	/// Proof: `Assets::LockedBalance` (`max_values`: None, `max_size`: Some(84), added: 2559, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::BestBid` (r:0 w:1)
+	/// Proof: `Orderbook::BestBid` (`max_values`: None, `max_size`: Some(36), added: 2511, mode: `MaxEncodedLen`)
+	fn amend_order() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `906`
+		//  Estimated: `83519`
+		// Minimum execution time: 128_602_000 picoseconds.
+		Weight::from_parts(139_042_000, 83519)
+			.saturating_add(T::DbWeight::get().reads(10_u64))
 			.saturating_add(T::DbWeight::get().writes(7_u64))
-			.saturating_add(T::DbWeight::get().writes((7_u64).saturating_mul(m.into())))
+	}
+	/// Storage: `Orderbook::UserOrders` (r:1 w:0)
+	/// Proof: `Orderbook::UserOrders` (`max_values`: None, `max_size`: Some(8050), added: 10525, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::Orders` (r:999 w:0)
+	/// Proof: `Orderbook::Orders` (`max_values`: None, `max_size`: Some(213), added: 2688, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::PendingCancellations` (r:1 w:1)
+	/// Proof: `Orderbook::PendingCancellations` (`max_values`: None, `max_size`: Some(421), added: 2896, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::CancelAllQueue` (r:1 w:1)
+	/// Proof: `Orderbook::CancelAllQueue` (`max_values`: Some(1), `max_size`: Some(3402), added: 3897, mode: `MaxEncodedLen`)
+	/// The range of component `n` is `[1, 1000]`.
+	fn cancel_all_orders(n: u32, ) -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `376 + n * (141 ±0)`
+		//  Estimated: `11515 + n * (2688 ±0)`
+		// Minimum execution time: 60_052_000 picoseconds.
+		Weight::from_parts(61_175_000, 11515)
+			// Standard Error: 119_798
+			.saturating_add(Weight::from_parts(19_370_222, 0).saturating_mul(n.into()))
+			.saturating_add(T::DbWeight::get().reads(3_u64))
+			.saturating_add(T::DbWeight::get().reads((1_u64).saturating_mul(n.into())))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+			.saturating_add(Weight::from_parts(0, 2688).saturating_mul(n.into()))
+	}
+	/// Storage: `Orderbook::PrunedTradesUpTo` (r:1 w:1)
+	/// Proof: `Orderbook::PrunedTradesUpTo` (`max_values`: Some(1), `max_size`: Some(8), added: 503, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::NextTradeId` (r:1 w:0)
+	/// Proof: `Orderbook::NextTradeId` (`max_values`: Some(1), `max_size`: Some(8), added: 503, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::TradeArchiveCommitment` (r:1 w:1)
+	/// Proof: `Orderbook::TradeArchiveCommitment` (`max_values`: Some(1), `max_size`: Some(32), added: 527, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::Trades` (r:100 w:100)
+	/// Proof: `Orderbook::Trades` (`max_values`: None, `max_size`: Some(197), added: 2672, mode: `MaxEncodedLen`)
+	/// The range of component `n` is `[0, 100]`.
+	fn prune_trades(n: u32, ) -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `144 + n * (204 ±0)`
+		//  Estimated: `1517 + n * (2672 ±0)`
+		// Minimum execution time: 12_334_000 picoseconds.
+		Weight::from_parts(68_196_542, 1517)
+			// Standard Error: 160_305
+			.saturating_add(Weight::from_parts(9_461_861, 0).saturating_mul(n.into()))
+			.saturating_add(T::DbWeight::get().reads(3_u64))
+			.saturating_add(T::DbWeight::get().reads((1_u64).saturating_mul(n.into())))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
 			.saturating_add(T::DbWeight::get().writes((1_u64).saturating_mul(n.into())))
-			.saturating_add(T::DbWeight::get().writes((3_u64).saturating_mul(c.into())))
-			.saturating_add(Weight::from_parts(0, 2595).saturating_mul(c.into()))
-			.saturating_add(Weight::from_parts(0, 5190).saturating_mul(m.into()))
-			.saturating_add(Weight::from_parts(0, 2595).saturating_mul(n.into()))
+			.saturating_add(Weight::from_parts(0, 2672).saturating_mul(n.into()))
+	}
+	/// Storage: `Orderbook::AccountSelfTradePrevention` (r:0 w:1)
+	/// Proof: `Orderbook::AccountSelfTradePrevention` (`max_values`: None, `max_size`: Some(49), added: 2524, mode: `MaxEncodedLen`)
+	fn set_self_trade_prevention() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `0`
+		//  Estimated: `0`
+		// Minimum execution time: 10_602_000 picoseconds.
+		Weight::from_parts(10_907_000, 0)
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: `Orderbook::Markets` (r:1 w:0)
+	/// Proof: `Orderbook::Markets` (`max_values`: None, `max_size`: Some(29), added: 2504, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::MarketMatchingMode` (r:0 w:1)
+	/// Proof: `Orderbook::MarketMatchingMode` (`max_values`: None, `max_size`: Some(25), added: 2500, mode: `MaxEncodedLen`)
+	fn set_matching_mode() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `149`
+		//  Estimated: `3494`
+		// Minimum execution time: 19_011_000 picoseconds.
+		Weight::from_parts(30_184_000, 3494)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: `Orderbook::Markets` (r:1 w:0)
+	/// Proof: `Orderbook::Markets` (`max_values`: None, `max_size`: Some(29), added: 2504, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::MarketClearingMode` (r:0 w:1)
+	/// Proof: `Orderbook::MarketClearingMode` (`max_values`: None, `max_size`: Some(21), added: 2496, mode: `MaxEncodedLen`)
+	fn set_clearing_mode() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `149`
+		//  Estimated: `3494`
+		// Minimum execution time: 18_631_000 picoseconds.
+		Weight::from_parts(20_350_000, 3494)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: `Orderbook::Markets` (r:1 w:0)
+	/// Proof: `Orderbook::Markets` (`max_values`: None, `max_size`: Some(29), added: 2504, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::MarketOrderingPolicy` (r:0 w:1)
+	/// Proof: `Orderbook::MarketOrderingPolicy` (`max_values`: None, `max_size`: Some(21), added: 2496, mode: `MaxEncodedLen`)
+	fn set_ordering_policy() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `149`
+		//  Estimated: `3494`
+		// Minimum execution time: 18_564_000 picoseconds.
+		Weight::from_parts(19_870_000, 3494)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
 	}
 }
 
 // For backwards compatibility and tests.
 impl WeightInfo for () {
+	/// Storage: `Orderbook::Markets` (r:1 w:0)
+	/// Proof: `Orderbook::Markets` (`max_values`: None, `max_size`: Some(29), added: 2504, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::MarketRules` (r:1 w:0)
+	/// Proof: `Orderbook::MarketRules` (`max_values`: None, `max_size`: Some(68), added: 2543, mode: `MaxEncodedLen`)
 	/// Storage: `Assets::FreeBalance` (r:1 w:1)
 	/// Proof: `Assets::FreeBalance` (`max_values`: None, `max_size`: Some(84), added: 2559, mode: `MaxEncodedLen`)
 	/// Storage: `Assets::LockedBalance` (r:1 w:1)
 	/// Proof: `Assets::LockedBalance` (`max_values`: None, `max_size`: Some(84), added: 2559, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::BidPrices` (r:1 w:1)
+	/// Proof: `Orderbook::BidPrices` (`max_values`: None, `max_size`: Some(16022), added: 18497, mode: `MaxEncodedLen`)
 	/// Storage: `Orderbook::NextOrderId` (r:1 w:1)
 	/// Proof: `Orderbook::NextOrderId` (`max_values`: Some(1), `max_size`: Some(8), added: 503, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::AccountSelfTradePrevention` (r:1 w:0)
+	/// Proof: `Orderbook::AccountSelfTradePrevention` (`max_values`: None, `max_size`: Some(49), added: 2524, mode: `MaxEncodedLen`)
 	/// Storage: `Orderbook::PendingBids` (r:1 w:1)
-	/// Proof: `Orderbook::PendingBids` (`max_values`: None, `max_size`: Some(8034), added: 10509, mode: `MaxEncodedLen`)
+	/// Proof: `Orderbook::PendingBids` (`max_values`: None, `max_size`: Some(8054), added: 10529, mode: `MaxEncodedLen`)
 	/// Storage: `Orderbook::UserOrders` (r:1 w:1)
 	/// Proof: `Orderbook::UserOrders` (`max_values`: None, `max_size`: Some(8050), added: 10525, mode: `MaxEncodedLen`)
 	/// Storage: `Orderbook::Orders` (r:0 w:1)
-	/// Proof: `Orderbook::Orders` (`max_values`: None, `max_size`: Some(120), added: 2595, mode: `MaxEncodedLen`)
+	/// Proof: `Orderbook::Orders` (`max_values`: None, `max_size`: Some(213), added: 2688, mode: `MaxEncodedLen`)
 	fn place_order() -> Weight {
 		// Proof Size summary in bytes:
-		//  Measured:  `159`
-		//  Estimated: `11515`
-		// Minimum execution time: 27_000_000 picoseconds.
-		Weight::from_parts(28_000_000, 11515)
-			.saturating_add(RocksDbWeight::get().reads(5_u64))
-			.saturating_add(RocksDbWeight::get().writes(6_u64))
+		//  Measured:  `394`
+		//  Estimated: `19487`
+		// Minimum execution time: 66_041_000 picoseconds.
+		Weight::from_parts(68_524_000, 19487)
+			.saturating_add(RocksDbWeight::get().reads(9_u64))
+			.saturating_add(RocksDbWeight::get().writes(7_u64))
 	}
 	/// Storage: `Orderbook::Orders` (r:1 w:0)
-	/// Proof: `Orderbook::Orders` (`max_values`: None, `max_size`: Some(120), added: 2595, mode: `MaxEncodedLen`)
+	/// Proof: `Orderbook::Orders` (`max_values`: None, `max_size`: Some(213), added: 2688, mode: `MaxEncodedLen`)
 	/// Storage: `Orderbook::PendingCancellations` (r:1 w:1)
-	/// Proof: `Orderbook::PendingCancellations` (`max_values`: Some(1), `max_size`: Some(401), added: 896, mode: `MaxEncodedLen`)
+	/// Proof: `Orderbook::PendingCancellations` (`max_values`: None, `max_size`: Some(421), added: 2896, mode: `MaxEncodedLen`)
 	fn cancel_order() -> Weight {
 		// Proof Size summary in bytes:
-		//  Measured:  `289`
-		//  Estimated: `3585`
-		// Minimum execution time: 15_000_000 picoseconds.
-		Weight::from_parts(15_000_000, 3585)
+		//  Measured:  `414`
+		//  Estimated: `3886`
+		// Minimum execution time: 26_913_000 picoseconds.
+		Weight::from_parts(35_712_000, 3886)
 			.saturating_add(RocksDbWeight::get().reads(2_u64))
 			.saturating_add(RocksDbWeight::get().writes(1_u64))
 	}
-	/// Storage: `Orderbook::Orders` (r:1 w:0)
-	/// Proof: `Orderbook::Orders` (`max_values`: None, `max_size`: Some(120), added: 2595, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::Markets` (r:1 w:0)
+	/// Proof: `Orderbook::Markets` (`max_values`: None, `max_size`: Some(29), added: 2504, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::MarketRules` (r:1 w:0)
+	/// Proof: `Orderbook::MarketRules` (`max_values`: None, `max_size`: Some(68), added: 2543, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::LastTradePrice` (r:1 w:0)
+	/// Proof: `Orderbook::LastTradePrice` (`max_values`: None, `max_size`: Some(36), added: 2511, mode: `MaxEncodedLen`)
+	/// Storage: `Assets::FreeBalance` (r:1 w:1)
+	/// Proof: `Assets::FreeBalance` (`max_values`: None, `max_size`: Some(84), added: 2559, mode: `MaxEncodedLen`)
+	/// Storage: `Assets::LockedBalance` (r:1 w:1)
+	/// Proof: `Assets::LockedBalance` (`max_values`: None, `max_size`: Some(84), added: 2559, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::NextOrderId` (r:1 w:1)
+	/// Proof: `Orderbook::NextOrderId` (`max_values`: Some(1), `max_size`: Some(8), added: 503, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::AccountSelfTradePrevention` (r:1 w:0)
+	/// Proof: `Orderbook::AccountSelfTradePrevention` (`max_values`: None, `max_size`: Some(49), added: 2524, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::StopOrders` (r:1 w:1)
+	/// Proof: `Orderbook::StopOrders` (`max_values`: None, `max_size`: Some(26022), added: 28497, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::UserOrders` (r:1 w:1)
+	/// Proof: `Orderbook::UserOrders` (`max_values`: None, `max_size`: Some(8050), added: 10525, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::Orders` (r:0 w:1)
+	/// Proof: `Orderbook::Orders` (`max_values`: None, `max_size`: Some(213), added: 2688, mode: `MaxEncodedLen`)
+	fn place_stop_order() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `394`
+		//  Estimated: `29487`
+		// Minimum execution time: 64_478_000 picoseconds.
+		Weight::from_parts(103_655_000, 29487)
+			.saturating_add(RocksDbWeight::get().reads(9_u64))
+			.saturating_add(RocksDbWeight::get().writes(6_u64))
+	}
+	/// Storage: `Orderbook::Orders` (r:1 w:1)
+	/// Proof: `Orderbook::Orders` (`max_values`: None, `max_size`: Some(213), added: 2688, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::Markets` (r:1 w:0)
+	/// Proof: `Orderbook::Markets` (`max_values`: None, `max_size`: Some(29), added: 2504, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::StopOrders` (r:1 w:1)
+	/// Proof: `Orderbook::StopOrders` (`max_values`: None, `max_size`: Some(26022), added: 28497, mode: `MaxEncodedLen`)
+	/// Storage: `Assets::LockedBalance` (r:1 w:1)
+	/// Proof: `Assets::LockedBalance` (`max_values`: None, `max_size`: Some(84), added: 2559, mode: `MaxEncodedLen`)
+	/// Storage: `Assets::FreeBalance` (r:1 w:1)
+	/// Proof: `Assets::FreeBalance` (`max_values`: None, `max_size`: Some(84), added: 2559, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::UserOrders` (r:1 w:1)
+	/// Proof: `Orderbook::UserOrders` (`max_values`: None, `max_size`: Some(8050), added: 10525, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::OrderHistory` (r:1 w:1)
+	/// Proof: `Orderbook::OrderHistory` (`max_values`: None, `max_size`: Some(18950), added: 21425, mode: `MaxEncodedLen`)
+	fn cancel_stop_order() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `803`
+		//  Estimated: `29487`
+		// Minimum execution time: 81_230_000 picoseconds.
+		Weight::from_parts(122_023_000, 29487)
+			.saturating_add(RocksDbWeight::get().reads(7_u64))
+			.saturating_add(RocksDbWeight::get().writes(6_u64))
+	}
+	/// Storage: `Orderbook::MarketsByPair` (r:1 w:1)
+	/// Proof: `Orderbook::MarketsByPair` (`max_values`: None, `max_size`: Some(28), added: 2503, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::NextMarketId` (r:1 w:1)
+	/// Proof: `Orderbook::NextMarketId` (`max_values`: Some(1), `max_size`: Some(4), added: 499, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::Markets` (r:0 w:1)
+	/// Proof: `Orderbook::Markets` (`max_values`: None, `max_size`: Some(29), added: 2504, mode: `MaxEncodedLen`)
+	fn create_market() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `115`
+		//  Estimated: `3493`
+		// Minimum execution time: 37_685_000 picoseconds.
+		Weight::from_parts(43_805_000, 3493)
+			.saturating_add(RocksDbWeight::get().reads(2_u64))
+			.saturating_add(RocksDbWeight::get().writes(3_u64))
+	}
+	/// Storage: `Orderbook::Markets` (r:1 w:1)
+	/// Proof: `Orderbook::Markets` (`max_values`: None, `max_size`: Some(29), added: 2504, mode: `MaxEncodedLen`)
+	fn set_market_status() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `149`
+		//  Estimated: `3494`
+		// Minimum execution time: 27_598_000 picoseconds.
+		Weight::from_parts(30_846_000, 3494)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: `Orderbook::Markets` (r:1 w:0)
+	/// Proof: `Orderbook::Markets` (`max_values`: None, `max_size`: Some(29), added: 2504, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::MarketFees` (r:0 w:1)
+	/// Proof: `Orderbook::MarketFees` (`max_values`: None, `max_size`: Some(28), added: 2503, mode: `MaxEncodedLen`)
+	fn set_market_fees() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `149`
+		//  Estimated: `3494`
+		// Minimum execution time: 25_987_000 picoseconds.
+		Weight::from_parts(37_713_000, 3494)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: `Orderbook::Markets` (r:1 w:0)
+	/// Proof: `Orderbook::Markets` (`max_values`: None, `max_size`: Some(29), added: 2504, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::MarketRules` (r:0 w:1)
+	/// Proof: `Orderbook::MarketRules` (`max_values`: None, `max_size`: Some(68), added: 2543, mode: `MaxEncodedLen`)
+	fn set_market_rules() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `149`
+		//  Estimated: `3494`
+		// Minimum execution time: 19_516_000 picoseconds.
+		Weight::from_parts(22_520_000, 3494)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: `Orderbook::FeeTiers` (r:0 w:1)
+	/// Proof: `Orderbook::FeeTiers` (`max_values`: Some(1), `max_size`: Some(251), added: 746, mode: `MaxEncodedLen`)
+	/// The range of component `t` is `[0, 10]`.
+	fn set_fee_tiers(_t: u32, ) -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `0`
+		//  Estimated: `0`
+		// Minimum execution time: 9_023_000 picoseconds.
+		Weight::from_parts(16_138_906, 0)
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: `Orderbook::Markets` (r:2 w:0)
+	/// Proof: `Orderbook::Markets` (`max_values`: None, `max_size`: Some(29), added: 2504, mode: `MaxEncodedLen`)
 	/// Storage: `Orderbook::PendingBids` (r:1 w:0)
-	/// Proof: `Orderbook::PendingBids` (`max_values`: None, `max_size`: Some(8034), added: 10509, mode: `MaxEncodedLen`)
+	/// Proof: `Orderbook::PendingBids` (`max_values`: None, `max_size`: Some(8054), added: 10529, mode: `MaxEncodedLen`)
 	/// Storage: `Orderbook::PendingAsks` (r:1 w:0)
-	/// Proof: `Orderbook::PendingAsks` (`max_values`: None, `max_size`: Some(8034), added: 10509, mode: `MaxEncodedLen`)
-	/// Storage: `Orderbook::Bids` (r:1 w:0)
-	/// Proof: `Orderbook::Bids` (`max_values`: None, `max_size`: Some(80034), added: 82509, mode: `MaxEncodedLen`)
-	/// Storage: `Orderbook::Asks` (r:1 w:0)
-	/// Proof: `Orderbook::Asks` (`max_values`: None, `max_size`: Some(80034), added: 82509, mode: `MaxEncodedLen`)
-	/// Storage: `Orderbook::PendingCancellations` (r:1 w:1)
-	/// Proof: `Orderbook::PendingCancellations` (`max_values`: Some(1), `max_size`: Some(401), added: 896, mode: `MaxEncodedLen`)
+	/// Proof: `Orderbook::PendingAsks` (`max_values`: None, `max_size`: Some(8054), added: 10529, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::PendingCancellations` (r:1 w:0)
+	/// Proof: `Orderbook::PendingCancellations` (`max_values`: None, `max_size`: Some(421), added: 2896, mode: `MaxEncodedLen`)
 	fn on_finalize_empty() -> Weight {
 		// Proof Size summary in bytes:
-		//  Measured:  `6`
-		//  Estimated: `83499`
-		// Minimum execution time: 23_000_000 picoseconds.
-		Weight::from_parts(24_000_000, 83499)
-			.saturating_add(RocksDbWeight::get().reads(6_u64))
-			.saturating_add(RocksDbWeight::get().writes(1_u64))
+		//  Measured:  `116`
+		//  Estimated: `11519`
+		// Minimum execution time: 24_722_000 picoseconds.
+		Weight::from_parts(25_392_000, 11519)
+			.saturating_add(RocksDbWeight::get().reads(5_u64))
 	}
-	/// Storage: `Orderbook::Orders` (r:101 w:100)
-	/// Proof: `Orderbook::Orders` (`max_values`: None, `max_size`: Some(120), added: 2595, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::Markets` (r:2 w:0)
+	/// Proof: `Orderbook::Markets` (`max_values`: None, `max_size`: Some(29), added: 2504, mode: `MaxEncodedLen`)
 	/// Storage: `Orderbook::PendingBids` (r:2 w:1)
-	/// Proof: `Orderbook::PendingBids` (`max_values`: None, `max_size`: Some(8034), added: 10509, mode: `MaxEncodedLen`)
+	/// Proof: `Orderbook::PendingBids` (`max_values`: None, `max_size`: Some(8054), added: 10529, mode: `MaxEncodedLen`)
 	/// Storage: `Orderbook::PendingAsks` (r:2 w:1)
-	/// Proof: `Orderbook::PendingAsks` (`max_values`: None, `max_size`: Some(8034), added: 10509, mode: `MaxEncodedLen`)
-	/// Storage: `Orderbook::Bids` (r:1 w:0)
-	/// Proof: `Orderbook::Bids` (`max_values`: None, `max_size`: Some(80034), added: 82509, mode: `MaxEncodedLen`)
-	/// Storage: `Orderbook::Asks` (r:1 w:0)
-	/// Proof: `Orderbook::Asks` (`max_values`: None, `max_size`: Some(80034), added: 82509, mode: `MaxEncodedLen`)
-	/// Storage: `Orderbook::PendingCancellations` (r:1 w:1)
-	/// Proof: `Orderbook::PendingCancellations` (`max_values`: Some(1), `max_size`: Some(401), added: 896, mode: `MaxEncodedLen`)
-	/// Storage: `Orderbook::NextTradeId` (r:1 w:1)
-	/// Proof: `Orderbook::NextTradeId` (`max_values`: Some(1), `max_size`: Some(8), added: 503, mode: `MaxEncodedLen`)
+	/// Proof: `Orderbook::PendingAsks` (`max_values`: None, `max_size`: Some(8054), added: 10529, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::PendingCancellations` (r:1 w:0)
+	/// Proof: `Orderbook::PendingCancellations` (`max_values`: None, `max_size`: Some(421), added: 2896, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::Orders` (r:100 w:100)
+	/// Proof: `Orderbook::Orders` (`max_values`: None, `max_size`: Some(213), added: 2688, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::Bids` (r:1 w:1)
+	/// Proof: `Orderbook::Bids` (`max_values`: None, `max_size`: Some(80054), added: 82529, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::Asks` (r:1 w:1)
+	/// Proof: `Orderbook::Asks` (`max_values`: None, `max_size`: Some(80054), added: 82529, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::MarketRules` (r:1 w:0)
+	/// Proof: `Orderbook::MarketRules` (`max_values`: None, `max_size`: Some(68), added: 2543, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::MarketMatchingMode` (r:1 w:0)
+	/// Proof: `Orderbook::MarketMatchingMode` (`max_values`: None, `max_size`: Some(25), added: 2500, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::MarketOrderingPolicy` (r:1 w:0)
+	/// Proof: `Orderbook::MarketOrderingPolicy` (`max_values`: None, `max_size`: Some(21), added: 2496, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::BestBid` (r:1 w:1)
+	/// Proof: `Orderbook::BestBid` (`max_values`: None, `max_size`: Some(36), added: 2511, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::BestAsk` (r:1 w:1)
+	/// Proof: `Orderbook::BestAsk` (`max_values`: None, `max_size`: Some(36), added: 2511, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::MarketClearingMode` (r:1 w:0)
+	/// Proof: `Orderbook::MarketClearingMode` (`max_values`: None, `max_size`: Some(21), added: 2496, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::MarketFees` (r:1 w:0)
+	/// Proof: `Orderbook::MarketFees` (`max_values`: None, `max_size`: Some(28), added: 2503, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::FeeTiers` (r:1 w:0)
+	/// Proof: `Orderbook::FeeTiers` (`max_values`: Some(1), `max_size`: Some(251), added: 746, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::TradingVolume` (r:100 w:100)
+	/// Proof: `Orderbook::TradingVolume` (`max_values`: None, `max_size`: Some(649), added: 3124, mode: `MaxEncodedLen`)
 	/// Storage: `Assets::LockedBalance` (r:100 w:100)
 	/// Proof: `Assets::LockedBalance` (`max_values`: None, `max_size`: Some(84), added: 2559, mode: `MaxEncodedLen`)
-	/// Storage: `Assets::FreeBalance` (r:100 w:100)
+	/// Storage: `Assets::FreeBalance` (r:101 w:101)
 	/// Proof: `Assets::FreeBalance` (`max_values`: None, `max_size`: Some(84), added: 2559, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::NextTradeId` (r:1 w:1)
+	/// Proof: `Orderbook::NextTradeId` (`max_values`: Some(1), `max_size`: Some(8), added: 503, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::UserOrders` (r:100 w:100)
+	/// Proof: `Orderbook::UserOrders` (`max_values`: None, `max_size`: Some(8050), added: 10525, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::OrderHistory` (r:100 w:100)
+	/// Proof: `Orderbook::OrderHistory` (`max_values`: None, `max_size`: Some(18950), added: 21425, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::BidPrices` (r:1 w:1)
+	/// Proof: `Orderbook::BidPrices` (`max_values`: None, `max_size`: Some(16022), added: 18497, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::AskPrices` (r:1 w:1)
+	/// Proof: `Orderbook::AskPrices` (`max_values`: None, `max_size`: Some(16022), added: 18497, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::StopOrders` (r:1 w:0)
+	/// Proof: `Orderbook::StopOrders` (`max_values`: None, `max_size`: Some(26022), added: 28497, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::LastTradePrice` (r:0 w:1)
+	/// Proof: `Orderbook::LastTradePrice` (`max_values`: None, `max_size`: Some(36), added: 2511, mode: `MaxEncodedLen`)
 	/// Storage: `Orderbook::Trades` (r:0 w:50)
-	/// Proof: `Orderbook::Trades` (`max_values`: None, `max_size`: Some(144), added: 2619, mode: `MaxEncodedLen`)
+	/// Proof: `Orderbook::Trades` (`max_values`: None, `max_size`: Some(197), added: 2672, mode: `MaxEncodedLen`)
 	/// The range of component `b` is `[1, 50]`.
 	/// The range of component `a` is `[1, 50]`.
 	fn on_finalize_with_matches(b: u32, a: u32, ) -> Weight {
 		// Proof Size summary in bytes:
-		//  Measured:  `0 + a * (509 ±0) + b * (510 ±0)`
-		//  Estimated: `83499 + a * (2595 ±0) + b * (2595 ±0)`
-		// Minimum execution time: 378_000_000 picoseconds.
-		Weight::from_parts(380_000_000, 83499)
-			// Standard Error: 455_618
-			.saturating_add(Weight::from_parts(14_754_607, 0).saturating_mul(b.into()))
-			// Standard Error: 455_618
-			.saturating_add(Weight::from_parts(13_465_412, 0).saturating_mul(a.into()))
-			.saturating_add(RocksDbWeight::get().reads(64_u64))
-			.saturating_add(RocksDbWeight::get().reads((2_u64).saturating_mul(b.into())))
-			.saturating_add(RocksDbWeight::get().reads((2_u64).saturating_mul(a.into())))
-			.saturating_add(RocksDbWeight::get().writes(61_u64))
-			.saturating_add(RocksDbWeight::get().writes((2_u64).saturating_mul(b.into())))
-			.saturating_add(RocksDbWeight::get().writes((2_u64).saturating_mul(a.into())))
-			.saturating_add(Weight::from_parts(0, 2595).saturating_mul(a.into()))
-			.saturating_add(Weight::from_parts(0, 2595).saturating_mul(b.into()))
-	}
-	/// Storage: `Orderbook::Orders` (r:101 w:100)
-	/// Proof: `Orderbook::Orders` (`max_values`: None, `max_size`: Some(120), added: 2595, mode: `MaxEncodedLen`)
+		//  Measured:  `0 + a * (611 ±0) + b * (613 ±0)`
+		//  Estimated: `83519 + a * (14925 ±239) + b * (14925 ±239)`
+		// Minimum execution time: 763_812_000 picoseconds.
+		Weight::from_parts(781_620_000, 83519)
+			// Standard Error: 2_076_685
+			.saturating_add(Weight::from_parts(66_065_966, 0).saturating_mul(b.into()))
+			// Standard Error: 2_076_685
+			.saturating_add(Weight::from_parts(69_997_765, 0).saturating_mul(a.into()))
+			.saturating_add(RocksDbWeight::get().reads(83_u64))
+			.saturating_add(RocksDbWeight::get().reads((4_u64).saturating_mul(b.into())))
+			.saturating_add(RocksDbWeight::get().reads((4_u64).saturating_mul(a.into())))
+			.saturating_add(RocksDbWeight::get().writes(73_u64))
+			.saturating_add(RocksDbWeight::get().writes((4_u64).saturating_mul(b.into())))
+			.saturating_add(RocksDbWeight::get().writes((4_u64).saturating_mul(a.into())))
+			.saturating_add(Weight::from_parts(0, 14925).saturating_mul(a.into()))
+			.saturating_add(Weight::from_parts(0, 14925).saturating_mul(b.into()))
+	}
+	/// Storage: `Orderbook::Markets` (r:2 w:0)
+	/// Proof: `Orderbook::Markets` (`max_values`: None, `max_size`: Some(29), added: 2504, mode: `MaxEncodedLen`)
 	/// Storage: `Orderbook::PendingBids` (r:2 w:1)
-	/// Proof: `Orderbook::PendingBids` (`max_values`: None, `max_size`: Some(8034), added: 10509, mode: `MaxEncodedLen`)
+	/// Proof: `Orderbook::PendingBids` (`max_values`: None, `max_size`: Some(8054), added: 10529, mode: `MaxEncodedLen`)
 	/// Storage: `Orderbook::PendingAsks` (r:2 w:1)
-	/// Proof: `Orderbook::PendingAsks` (`max_values`: None, `max_size`: Some(8034), added: 10509, mode: `MaxEncodedLen`)
+	/// Proof: `Orderbook::PendingAsks` (`max_values`: None, `max_size`: Some(8054), added: 10529, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::PendingCancellations` (r:1 w:0)
+	/// Proof: `Orderbook::PendingCancellations` (`max_values`: None, `max_size`: Some(421), added: 2896, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::Orders` (r:100 w:100)
+	/// Proof: `Orderbook::Orders` (`max_values`: None, `max_size`: Some(213), added: 2688, mode: `MaxEncodedLen`)
 	/// Storage: `Orderbook::Bids` (r:1 w:1)
-	/// Proof: `Orderbook::Bids` (`max_values`: None, `max_size`: Some(80034), added: 82509, mode: `MaxEncodedLen`)
+	/// Proof: `Orderbook::Bids` (`max_values`: None, `max_size`: Some(80054), added: 82529, mode: `MaxEncodedLen`)
 	/// Storage: `Orderbook::Asks` (r:1 w:1)
-	/// Proof: `Orderbook::Asks` (`max_values`: None, `max_size`: Some(80034), added: 82509, mode: `MaxEncodedLen`)
-	/// Storage: `Orderbook::PendingCancellations` (r:1 w:1)
-	/// Proof: `Orderbook::PendingCancellations` (`max_values`: Some(1), `max_size`: Some(401), added: 896, mode: `MaxEncodedLen`)
+	/// Proof: `Orderbook::Asks` (`max_values`: None, `max_size`: Some(80054), added: 82529, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::MarketRules` (r:1 w:0)
+	/// Proof: `Orderbook::MarketRules` (`max_values`: None, `max_size`: Some(68), added: 2543, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::MarketMatchingMode` (r:1 w:0)
+	/// Proof: `Orderbook::MarketMatchingMode` (`max_values`: None, `max_size`: Some(25), added: 2500, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::MarketOrderingPolicy` (r:1 w:0)
+	/// Proof: `Orderbook::MarketOrderingPolicy` (`max_values`: None, `max_size`: Some(21), added: 2496, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::BestBid` (r:1 w:1)
+	/// Proof: `Orderbook::BestBid` (`max_values`: None, `max_size`: Some(36), added: 2511, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::BestAsk` (r:1 w:1)
+	/// Proof: `Orderbook::BestAsk` (`max_values`: None, `max_size`: Some(36), added: 2511, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::MarketClearingMode` (r:1 w:0)
+	/// Proof: `Orderbook::MarketClearingMode` (`max_values`: None, `max_size`: Some(21), added: 2496, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::MarketFees` (r:1 w:0)
+	/// Proof: `Orderbook::MarketFees` (`max_values`: None, `max_size`: Some(28), added: 2503, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::FeeTiers` (r:1 w:0)
+	/// Proof: `Orderbook::FeeTiers` (`max_values`: Some(1), `max_size`: Some(251), added: 746, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::BidPrices` (r:1 w:1)
+	/// Proof: `Orderbook::BidPrices` (`max_values`: None, `max_size`: Some(16022), added: 18497, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::AskPrices` (r:1 w:1)
+	/// Proof: `Orderbook::AskPrices` (`max_values`: None, `max_size`: Some(16022), added: 18497, mode: `MaxEncodedLen`)
 	/// The range of component `b` is `[1, 50]`.
 	/// The range of component `a` is `[1, 50]`.
 	fn on_finalize_no_matches(b: u32, a: u32, ) -> Weight {
 		// Proof Size summary in bytes:
-		//  Measured:  `709 + a * (134 ±0) + b * (134 ±0)`
-		//  Estimated: `83499 + a * (2595 ±0) + b * (2595 ±0)`
-		// Minimum execution time: 338_000_000 picoseconds.
-		Weight::from_parts(340_000_000, 83499)
-			// Standard Error: 106_234
-			.saturating_add(Weight::from_parts(2_374_357, 0).saturating_mul(b.into()))
-			// Standard Error: 106_234
-			.saturating_add(Weight::from_parts(2_260_042, 0).saturating_mul(a.into()))
-			.saturating_add(RocksDbWeight::get().reads(8_u64))
+		//  Measured:  `542 + a * (142 ±0) + b * (142 ±0)`
+		//  Estimated: `83519 + a * (2688 ±0) + b * (2688 ±0)`
+		// Minimum execution time: 1_014_473_000 picoseconds.
+		Weight::from_parts(200_762_501, 83519)
+			// Standard Error: 78_665
+			.saturating_add(Weight::from_parts(16_811_291, 0).saturating_mul(b.into()))
+			// Standard Error: 78_665
+			.saturating_add(Weight::from_parts(17_170_734, 0).saturating_mul(a.into()))
+			.saturating_add(RocksDbWeight::get().reads(19_u64))
 			.saturating_add(RocksDbWeight::get().reads((1_u64).saturating_mul(b.into())))
 			.saturating_add(RocksDbWeight::get().reads((1_u64).saturating_mul(a.into())))
-			.saturating_add(RocksDbWeight::get().writes(5_u64))
+			.saturating_add(RocksDbWeight::get().writes(8_u64))
 			.saturating_add(RocksDbWeight::get().writes((1_u64).saturating_mul(b.into())))
 			.saturating_add(RocksDbWeight::get().writes((1_u64).saturating_mul(a.into())))
-			.saturating_add(Weight::from_parts(0, 2595).saturating_mul(a.into()))
-			.saturating_add(Weight::from_parts(0, 2595).saturating_mul(b.into()))
+			.saturating_add(Weight::from_parts(0, 2688).saturating_mul(a.into()))
+			.saturating_add(Weight::from_parts(0, 2688).saturating_mul(b.into()))
 	}
-	/// Storage: `Orderbook::Orders` (r:51 w:50)
-	/// Proof: `Orderbook::Orders` (`max_values`: None, `max_size`: Some(120), added: 2595, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::Markets` (r:2 w:0)
+	/// Proof: `Orderbook::Markets` (`max_values`: None, `max_size`: Some(29), added: 2504, mode: `MaxEncodedLen`)
 	/// Storage: `Orderbook::PendingBids` (r:2 w:1)
-	/// Proof: `Orderbook::PendingBids` (`max_values`: None, `max_size`: Some(8034), added: 10509, mode: `MaxEncodedLen`)
+	/// Proof: `Orderbook::PendingBids` (`max_values`: None, `max_size`: Some(8054), added: 10529, mode: `MaxEncodedLen`)
 	/// Storage: `Orderbook::PendingAsks` (r:1 w:0)
-	/// Proof: `Orderbook::PendingAsks` (`max_values`: None, `max_size`: Some(8034), added: 10509, mode: `MaxEncodedLen`)
-	/// Storage: `Orderbook::Bids` (r:1 w:1)
-	/// Proof: `Orderbook::Bids` (`max_values`: None, `max_size`: Some(80034), added: 82509, mode: `MaxEncodedLen`)
-	/// Storage: `Orderbook::Asks` (r:1 w:0)
-	/// Proof: `Orderbook::Asks` (`max_values`: None, `max_size`: Some(80034), added: 82509, mode: `MaxEncodedLen`)
+	/// Proof: `Orderbook::PendingAsks` (`max_values`: None, `max_size`: Some(8054), added: 10529, mode: `MaxEncodedLen`)
 	/// Storage: `Orderbook::PendingCancellations` (r:1 w:1)
-	/// Proof: `Orderbook::PendingCancellations` (`max_values`: Some(1), `max_size`: Some(401), added: 896, mode: `MaxEncodedLen`)
+	/// Proof: `Orderbook::PendingCancellations` (`max_values`: None, `max_size`: Some(421), added: 2896, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::Orders` (r:50 w:50)
+	/// Proof: `Orderbook::Orders` (`max_values`: None, `max_size`: Some(213), added: 2688, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::Bids` (r:1 w:1)
+	/// Proof: `Orderbook::Bids` (`max_values`: None, `max_size`: Some(80054), added: 82529, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::MarketRules` (r:1 w:0)
+	/// Proof: `Orderbook::MarketRules` (`max_values`: None, `max_size`: Some(68), added: 2543, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::MarketMatchingMode` (r:1 w:0)
+	/// Proof: `Orderbook::MarketMatchingMode` (`max_values`: None, `max_size`: Some(25), added: 2500, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::MarketOrderingPolicy` (r:1 w:0)
+	/// Proof: `Orderbook::MarketOrderingPolicy` (`max_values`: None, `max_size`: Some(21), added: 2496, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::BestBid` (r:1 w:1)
+	/// Proof: `Orderbook::BestBid` (`max_values`: None, `max_size`: Some(36), added: 2511, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::BestAsk` (r:1 w:1)
+	/// Proof: `Orderbook::BestAsk` (`max_values`: None, `max_size`: Some(36), added: 2511, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::MarketClearingMode` (r:1 w:0)
+	/// Proof: `Orderbook::MarketClearingMode` (`max_values`: None, `max_size`: Some(21), added: 2496, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::MarketFees` (r:1 w:0)
+	/// Proof: `Orderbook::MarketFees` (`max_values`: None, `max_size`: Some(28), added: 2503, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::FeeTiers` (r:1 w:0)
+	/// Proof: `Orderbook::FeeTiers` (`max_values`: Some(1), `max_size`: Some(251), added: 746, mode: `MaxEncodedLen`)
 	/// Storage: `Assets::LockedBalance` (r:50 w:50)
 	/// Proof: `Assets::LockedBalance` (`max_values`: None, `max_size`: Some(84), added: 2559, mode: `MaxEncodedLen`)
 	/// Storage: `Assets::FreeBalance` (r:50 w:50)
 	/// Proof: `Assets::FreeBalance` (`max_values`: None, `max_size`: Some(84), added: 2559, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::UserOrders` (r:50 w:50)
+	/// Proof: `Orderbook::UserOrders` (`max_values`: None, `max_size`: Some(8050), added: 10525, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::OrderHistory` (r:50 w:50)
+	/// Proof: `Orderbook::OrderHistory` (`max_values`: None, `max_size`: Some(18950), added: 21425, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::BidPrices` (r:1 w:1)
+	/// Proof: `Orderbook::BidPrices` (`max_values`: None, `max_size`: Some(16022), added: 18497, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::AskPrices` (r:1 w:1)
+	/// Proof: `Orderbook::AskPrices` (`max_values`: None, `max_size`: Some(16022), added: 18497, mode: `MaxEncodedLen`)
 	/// The range of component `c` is `[1, 50]`.
 	fn on_finalize_with_cancellations(c: u32, ) -> Weight {
 		// Proof Size summary in bytes:
-		//  Measured:  `225 + c * (359 ±0)`
-		//  Estimated: `83499 + c * (2595 ±0)`
-		// Minimum execution time: 57_000_000 picoseconds.
-		Weight::from_parts(45_799_510, 83499)
-			// Standard Error: 38_272
-			.saturating_add(Weight::from_parts(20_011_078, 0).saturating_mul(c.into()))
-			.saturating_add(RocksDbWeight::get().reads(7_u64))
-			.saturating_add(RocksDbWeight::get().reads((3_u64).saturating_mul(c.into())))
-			.saturating_add(RocksDbWeight::get().writes(3_u64))
-			.saturating_add(RocksDbWeight::get().writes((3_u64).saturating_mul(c.into())))
-			.saturating_add(Weight::from_parts(0, 2595).saturating_mul(c.into()))
+		//  Measured:  `556 + c * (432 ±0)`
+		//  Estimated: `83519 + c * (21425 ±0)`
+		// Minimum execution time: 257_477_000 picoseconds.
+		Weight::from_parts(203_245_337, 83519)
+			// Standard Error: 91_958
+			.saturating_add(Weight::from_parts(73_172_238, 0).saturating_mul(c.into()))
+			.saturating_add(RocksDbWeight::get().reads(17_u64))
+			.saturating_add(RocksDbWeight::get().reads((5_u64).saturating_mul(c.into())))
+			.saturating_add(RocksDbWeight::get().writes(7_u64))
+			.saturating_add(RocksDbWeight::get().writes((5_u64).saturating_mul(c.into())))
+			.saturating_add(Weight::from_parts(0, 21425).saturating_mul(c.into()))
 	}
-	/// Storage: `Orderbook::Orders` (r:41 w:40)
-	/// Proof: `Orderbook::Orders` (`max_values`: None, `max_size`: Some(120), added: 2595, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::Markets` (r:2 w:0)
+	/// Proof: `Orderbook::Markets` (`max_values`: None, `max_size`: Some(29), added: 2504, mode: `MaxEncodedLen`)
 	/// Storage: `Orderbook::PendingBids` (r:2 w:1)
-	/// Proof: `Orderbook::PendingBids` (`max_values`: None, `max_size`: Some(8034), added: 10509, mode: `MaxEncodedLen`)
+	/// Proof: `Orderbook::PendingBids` (`max_values`: None, `max_size`: Some(8054), added: 10529, mode: `MaxEncodedLen`)
 	/// Storage: `Orderbook::PendingAsks` (r:1 w:0)
-	/// Proof: `Orderbook::PendingAsks` (`max_values`: None, `max_size`: Some(8034), added: 10509, mode: `MaxEncodedLen`)
-	/// Storage: `Orderbook::Bids` (r:1 w:0)
-	/// Proof: `Orderbook::Bids` (`max_values`: None, `max_size`: Some(80034), added: 82509, mode: `MaxEncodedLen`)
-	/// Storage: `Orderbook::Asks` (r:2 w:0)
-	/// Proof: `Orderbook::Asks` (`max_values`: None, `max_size`: Some(80034), added: 82509, mode: `MaxEncodedLen`)
-	/// Storage: `Orderbook::PendingCancellations` (r:1 w:1)
-	/// Proof: `Orderbook::PendingCancellations` (`max_values`: Some(1), `max_size`: Some(401), added: 896, mode: `MaxEncodedLen`)
-	/// Storage: `Orderbook::NextTradeId` (r:1 w:1)
-	/// Proof: `Orderbook::NextTradeId` (`max_values`: Some(1), `max_size`: Some(8), added: 503, mode: `MaxEncodedLen`)
+	/// Proof: `Orderbook::PendingAsks` (`max_values`: None, `max_size`: Some(8054), added: 10529, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::PendingCancellations` (r:1 w:0)
+	/// Proof: `Orderbook::PendingCancellations` (`max_values`: None, `max_size`: Some(421), added: 2896, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::Orders` (r:40 w:40)
+	/// Proof: `Orderbook::Orders` (`max_values`: None, `max_size`: Some(213), added: 2688, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::Bids` (r:1 w:1)
+	/// Proof: `Orderbook::Bids` (`max_values`: None, `max_size`: Some(80054), added: 82529, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::MarketRules` (r:1 w:0)
+	/// Proof: `Orderbook::MarketRules` (`max_values`: None, `max_size`: Some(68), added: 2543, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::MarketMatchingMode` (r:1 w:0)
+	/// Proof: `Orderbook::MarketMatchingMode` (`max_values`: None, `max_size`: Some(25), added: 2500, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::MarketOrderingPolicy` (r:1 w:0)
+	/// Proof: `Orderbook::MarketOrderingPolicy` (`max_values`: None, `max_size`: Some(21), added: 2496, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::BestBid` (r:1 w:1)
+	/// Proof: `Orderbook::BestBid` (`max_values`: None, `max_size`: Some(36), added: 2511, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::BestAsk` (r:1 w:1)
+	/// Proof: `Orderbook::BestAsk` (`max_values`: None, `max_size`: Some(36), added: 2511, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::AskPrices` (r:1 w:1)
+	/// Proof: `Orderbook::AskPrices` (`max_values`: None, `max_size`: Some(16022), added: 18497, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::Asks` (r:1 w:1)
+	/// Proof: `Orderbook::Asks` (`max_values`: None, `max_size`: Some(80054), added: 82529, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::MarketClearingMode` (r:1 w:0)
+	/// Proof: `Orderbook::MarketClearingMode` (`max_values`: None, `max_size`: Some(21), added: 2496, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::MarketFees` (r:1 w:0)
+	/// Proof: `Orderbook::MarketFees` (`max_values`: None, `max_size`: Some(28), added: 2503, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::FeeTiers` (r:1 w:0)
+	/// Proof: `Orderbook::FeeTiers` (`max_values`: Some(1), `max_size`: Some(251), added: 746, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::TradingVolume` (r:40 w:40)
+	/// Proof: `Orderbook::TradingVolume` (`max_values`: None, `max_size`: Some(649), added: 3124, mode: `MaxEncodedLen`)
 	/// Storage: `Assets::LockedBalance` (r:40 w:40)
 	/// Proof: `Assets::LockedBalance` (`max_values`: None, `max_size`: Some(84), added: 2559, mode: `MaxEncodedLen`)
-	/// Storage: `Assets::FreeBalance` (r:40 w:40)
+	/// Storage: `Assets::FreeBalance` (r:41 w:41)
 	/// Proof: `Assets::FreeBalance` (`max_values`: None, `max_size`: Some(84), added: 2559, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::NextTradeId` (r:1 w:1)
+	/// Proof: `Orderbook::NextTradeId` (`max_values`: Some(1), `max_size`: Some(8), added: 503, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::UserOrders` (r:40 w:40)
+	/// Proof: `Orderbook::UserOrders` (`max_values`: None, `max_size`: Some(8050), added: 10525, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::OrderHistory` (r:40 w:40)
+	/// Proof: `Orderbook::OrderHistory` (`max_values`: None, `max_size`: Some(18950), added: 21425, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::BidPrices` (r:1 w:1)
+	/// Proof: `Orderbook::BidPrices` (`max_values`: None, `max_size`: Some(16022), added: 18497, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::StopOrders` (r:1 w:0)
+	/// Proof: `Orderbook::StopOrders` (`max_values`: None, `max_size`: Some(26022), added: 28497, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::LastTradePrice` (r:0 w:1)
+	/// Proof: `Orderbook::LastTradePrice` (`max_values`: None, `max_size`: Some(36), added: 2511, mode: `MaxEncodedLen`)
 	/// Storage: `Orderbook::Trades` (r:0 w:20)
-	/// Proof: `Orderbook::Trades` (`max_values`: None, `max_size`: Some(144), added: 2619, mode: `MaxEncodedLen`)
+	/// Proof: `Orderbook::Trades` (`max_values`: None, `max_size`: Some(197), added: 2672, mode: `MaxEncodedLen`)
 	/// The range of component `p` is `[1, 20]`.
 	/// The range of component `n` is `[1, 20]`.
 	fn on_finalize_persistent_matching(p: u32, n: u32, ) -> Weight {
 		// Proof Size summary in bytes:
-		//  Measured:  `0 + n * (534 ±0) + p * (518 ±0)`
-		//  Estimated: `166008 + n * (2595 ±58) + p * (2595 ±58)`
-		// Minimum execution time: 203_000_000 picoseconds.
-		Weight::from_parts(205_000_000, 166008)
-			// Standard Error: 428_260
-			.saturating_add(Weight::from_parts(12_659_114, 0).saturating_mul(p.into()))
-			// Standard Error: 428_260
-			.saturating_add(Weight::from_parts(13_031_874, 0).saturating_mul(n.into()))
-			.saturating_add(RocksDbWeight::get().reads(34_u64))
-			.saturating_add(RocksDbWeight::get().reads((2_u64).saturating_mul(p.into())))
-			.saturating_add(RocksDbWeight::get().reads((2_u64).saturating_mul(n.into())))
-			.saturating_add(RocksDbWeight::get().writes(30_u64))
-			.saturating_add(RocksDbWeight::get().writes((2_u64).saturating_mul(p.into())))
-			.saturating_add(RocksDbWeight::get().writes((2_u64).saturating_mul(n.into())))
-			.saturating_add(Weight::from_parts(0, 2595).saturating_mul(n.into()))
-			.saturating_add(Weight::from_parts(0, 2595).saturating_mul(p.into()))
-	}
-	/// Storage: `Orderbook::Orders` (r:71 w:70)
-	/// Proof: `Orderbook::Orders` (`max_values`: None, `max_size`: Some(120), added: 2595, mode: `MaxEncodedLen`)
+		//  Measured:  `0 + n * (630 ±0) + p * (615 ±0)`
+		//  Estimated: `83519 + n * (13886 ±58) + p * (13886 ±58)`
+		// Minimum execution time: 594_724_000 picoseconds.
+		Weight::from_parts(658_088_000, 83519)
+			// Standard Error: 1_897_946
+			.saturating_add(Weight::from_parts(62_119_021, 0).saturating_mul(p.into()))
+			// Standard Error: 1_897_946
+			.saturating_add(Weight::from_parts(64_837_198, 0).saturating_mul(n.into()))
+			.saturating_add(RocksDbWeight::get().reads(52_u64))
+			.saturating_add(RocksDbWeight::get().reads((4_u64).saturating_mul(p.into())))
+			.saturating_add(RocksDbWeight::get().reads((4_u64).saturating_mul(n.into())))
+			.saturating_add(RocksDbWeight::get().writes(42_u64))
+			.saturating_add(RocksDbWeight::get().writes((4_u64).saturating_mul(p.into())))
+			.saturating_add(RocksDbWeight::get().writes((4_u64).saturating_mul(n.into())))
+			.saturating_add(Weight::from_parts(0, 13886).saturating_mul(n.into()))
+			.saturating_add(Weight::from_parts(0, 13886).saturating_mul(p.into()))
+	}
+	/// Storage: `Orderbook::Markets` (r:2 w:0)
+	/// Proof: `Orderbook::Markets` (`max_values`: None, `max_size`: Some(29), added: 2504, mode: `MaxEncodedLen`)
 	/// Storage: `Orderbook::PendingBids` (r:3 w:2)
-	/// Proof: `Orderbook::PendingBids` (`max_values`: None, `max_size`: Some(8034), added: 10509, mode: `MaxEncodedLen`)
+	/// Proof: `Orderbook::PendingBids` (`max_values`: None, `max_size`: Some(8054), added: 10529, mode: `MaxEncodedLen`)
 	/// Storage: `Orderbook::PendingAsks` (r:2 w:1)
-	/// Proof: `Orderbook::PendingAsks` (`max_values`: None, `max_size`: Some(8034), added: 10509, mode: `MaxEncodedLen`)
-	/// Storage: `Orderbook::Bids` (r:1 w:2)
-	/// Proof: `Orderbook::Bids` (`max_values`: None, `max_size`: Some(80034), added: 82509, mode: `MaxEncodedLen`)
-	/// Storage: `Orderbook::Asks` (r:1 w:0)
-	/// Proof: `Orderbook::Asks` (`max_values`: None, `max_size`: Some(80034), added: 82509, mode: `MaxEncodedLen`)
+	/// Proof: `Orderbook::PendingAsks` (`max_values`: None, `max_size`: Some(8054), added: 10529, mode: `MaxEncodedLen`)
 	/// Storage: `Orderbook::PendingCancellations` (r:1 w:1)
-	/// Proof: `Orderbook::PendingCancellations` (`max_values`: Some(1), `max_size`: Some(401), added: 896, mode: `MaxEncodedLen`)
-	/// Storage: `Orderbook::NextTradeId` (r:1 w:1)
-	/// Proof: `Orderbook::NextTradeId` (`max_values`: Some(1), `max_size`: Some(8), added: 503, mode: `MaxEncodedLen`)
+	/// Proof: `Orderbook::PendingCancellations` (`max_values`: None, `max_size`: Some(421), added: 2896, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::Orders` (r:70 w:70)
+	/// Proof: `Orderbook::Orders` (`max_values`: None, `max_size`: Some(213), added: 2688, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::Bids` (r:2 w:2)
+	/// Proof: `Orderbook::Bids` (`max_values`: None, `max_size`: Some(80054), added: 82529, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::Asks` (r:1 w:1)
+	/// Proof: `Orderbook::Asks` (`max_values`: None, `max_size`: Some(80054), added: 82529, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::MarketRules` (r:1 w:0)
+	/// Proof: `Orderbook::MarketRules` (`max_values`: None, `max_size`: Some(68), added: 2543, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::MarketMatchingMode` (r:1 w:0)
+	/// Proof: `Orderbook::MarketMatchingMode` (`max_values`: None, `max_size`: Some(25), added: 2500, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::MarketOrderingPolicy` (r:1 w:0)
+	/// Proof: `Orderbook::MarketOrderingPolicy` (`max_values`: None, `max_size`: Some(21), added: 2496, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::BestBid` (r:1 w:1)
+	/// Proof: `Orderbook::BestBid` (`max_values`: None, `max_size`: Some(36), added: 2511, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::BestAsk` (r:1 w:1)
+	/// Proof: `Orderbook::BestAsk` (`max_values`: None, `max_size`: Some(36), added: 2511, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::MarketClearingMode` (r:1 w:0)
+	/// Proof: `Orderbook::MarketClearingMode` (`max_values`: None, `max_size`: Some(21), added: 2496, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::MarketFees` (r:1 w:0)
+	/// Proof: `Orderbook::MarketFees` (`max_values`: None, `max_size`: Some(28), added: 2503, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::FeeTiers` (r:1 w:0)
+	/// Proof: `Orderbook::FeeTiers` (`max_values`: Some(1), `max_size`: Some(251), added: 746, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::TradingVolume` (r:40 w:40)
+	/// Proof: `Orderbook::TradingVolume` (`max_values`: None, `max_size`: Some(649), added: 3124, mode: `MaxEncodedLen`)
 	/// Storage: `Assets::LockedBalance` (r:50 w:50)
 	/// Proof: `Assets::LockedBalance` (`max_values`: None, `max_size`: Some(84), added: 2559, mode: `MaxEncodedLen`)
-	/// Storage: `Assets::FreeBalance` (r:50 w:50)
+	/// Storage: `Assets::FreeBalance` (r:51 w:51)
 	/// Proof: `Assets::FreeBalance` (`max_values`: None, `max_size`: Some(84), added: 2559, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::NextTradeId` (r:1 w:1)
+	/// Proof: `Orderbook::NextTradeId` (`max_values`: Some(1), `max_size`: Some(8), added: 503, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::UserOrders` (r:50 w:50)
+	/// Proof: `Orderbook::UserOrders` (`max_values`: None, `max_size`: Some(8050), added: 10525, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::OrderHistory` (r:50 w:50)
+	/// Proof: `Orderbook::OrderHistory` (`max_values`: None, `max_size`: Some(18950), added: 21425, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::BidPrices` (r:1 w:1)
+	/// Proof: `Orderbook::BidPrices` (`max_values`: None, `max_size`: Some(16022), added: 18497, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::AskPrices` (r:1 w:1)
+	/// Proof: `Orderbook::AskPrices` (`max_values`: None, `max_size`: Some(16022), added: 18497, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::StopOrders` (r:1 w:0)
+	/// Proof: `Orderbook::StopOrders` (`max_values`: None, `max_size`: Some(26022), added: 28497, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::LastTradePrice` (r:0 w:1)
+	/// Proof: `Orderbook::LastTradePrice` (`max_values`: None, `max_size`: Some(36), added: 2511, mode: `MaxEncodedLen`)
 	/// Storage: `Orderbook::Trades` (r:0 w:20)
-	/// Proof: `Orderbook::Trades` (`max_values`: None, `max_size`: Some(144), added: 2619, mode: `MaxEncodedLen`)
+	/// Proof: `Orderbook::Trades` (`max_values`: None, `max_size`: Some(197), added: 2672, mode: `MaxEncodedLen`)
 	/// The range of component `m` is `[1, 20]`.
 	/// The range of component `n` is `[1, 20]`.
 	/// The range of component `c` is `[1, 10]`.
 	fn on_finalize_complex(m: u32, n: u32, c: u32, ) -> Weight {
 		// Proof Size summary in bytes:
-		//  Measured:  `187 + c * (366 ±0) + m * (713 ±0) + n * (198 ±0)`
-		//  Estimated: `83499 + c * (2595 ±0) + m * (5190 ±0) + n * (2595 ±0)`
-		// Minimum execution time: 429_000_000 picoseconds.
-		Weight::from_parts(64_754_480, 83499)
-			// Standard Error: 102_973
-			.saturating_add(Weight::from_parts(44_112_949, 0).saturating_mul(m.into()))
-			// Standard Error: 102_973
-			.saturating_add(Weight::from_parts(6_550_077, 0).saturating_mul(n.into()))
-			// Standard Error: 210_571
-			.saturating_add(Weight::from_parts(21_044_681, 0).saturating_mul(c.into()))
-			.saturating_add(RocksDbWeight::get().reads(10_u64))
-			.saturating_add(RocksDbWeight::get().reads((6_u64).saturating_mul(m.into())))
+		//  Measured:  `528 + c * (439 ±0) + m * (862 ±0) + n * (242 ±0)`
+		//  Estimated: `166048 + c * (21425 ±0) + m * (42850 ±0) + n * (2688 ±0)`
+		// Minimum execution time: 1_222_354_000 picoseconds.
+		Weight::from_parts(342_338_766, 166048)
+			// Standard Error: 781_841
+			.saturating_add(Weight::from_parts(212_844_988, 0).saturating_mul(m.into()))
+			// Standard Error: 781_841
+			.saturating_add(Weight::from_parts(7_835_582, 0).saturating_mul(n.into()))
+			// Standard Error: 1_598_795
+			.saturating_add(Weight::from_parts(67_575_695, 0).saturating_mul(c.into()))
+			.saturating_add(RocksDbWeight::get().reads(24_u64))
+			.saturating_add(RocksDbWeight::get().reads((12_u64).saturating_mul(m.into())))
 			.saturating_add(RocksDbWeight::get().reads((1_u64).saturating_mul(n.into())))
-			.saturating_add(RocksDbWeight::get().reads((3_u64).saturating_mul(c.into())))
+			.saturating_add(RocksDbWeight::get().reads((5_u64).saturating_mul(c.into())))
+			.saturating_add(RocksDbWeight::get().writes(14_u64))
+			.saturating_add(RocksDbWeight::get().writes((13_u64).saturating_mul(m.into())))
+			.saturating_add(RocksDbWeight::get().writes((1_u64).saturating_mul(n.into())))
+			.saturating_add(RocksDbWeight::get().writes((5_u64).saturating_mul(c.into())))
+			.saturating_add(Weight::from_parts(0, 21425).saturating_mul(c.into()))
+			.saturating_add(Weight::from_parts(0, 42850).saturating_mul(m.into()))
+			.saturating_add(Weight::from_parts(0, 2688).saturating_mul(n.into()))
+	}
+	/// Storage: `Orderbook::Markets` (r:2 w:0)
+	/// Proof: `Orderbook::Markets` (`max_values`: None, `max_size`: Some(29), added: 2504, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::PendingBids` (r:2 w:1)
+	/// Proof: `Orderbook::PendingBids` (`max_values`: None, `max_size`: Some(8054), added: 10529, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::PendingAsks` (r:1 w:0)
+	/// Proof: `Orderbook::PendingAsks` (`max_values`: None, `max_size`: Some(8054), added: 10529, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::PendingCancellations` (r:1 w:1)
+	/// Proof: `Orderbook::PendingCancellations` (`max_values`: None, `max_size`: Some(421), added: 2896, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::Orders` (r:120 w:120)
+	/// Proof: `Orderbook::Orders` (`max_values`: None, `max_size`: Some(213), added: 2688, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::Bids` (r:1 w:1)
+	/// Proof: `Orderbook::Bids` (`max_values`: None, `max_size`: Some(80054), added: 82529, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::MarketRules` (r:1 w:0)
+	/// Proof: `Orderbook::MarketRules` (`max_values`: None, `max_size`: Some(68), added: 2543, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::MarketMatchingMode` (r:1 w:0)
+	/// Proof: `Orderbook::MarketMatchingMode` (`max_values`: None, `max_size`: Some(25), added: 2500, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::MarketOrderingPolicy` (r:1 w:0)
+	/// Proof: `Orderbook::MarketOrderingPolicy` (`max_values`: None, `max_size`: Some(21), added: 2496, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::BestBid` (r:1 w:1)
+	/// Proof: `Orderbook::BestBid` (`max_values`: None, `max_size`: Some(36), added: 2511, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::BestAsk` (r:1 w:1)
+	/// Proof: `Orderbook::BestAsk` (`max_values`: None, `max_size`: Some(36), added: 2511, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::AskPrices` (r:1 w:1)
+	/// Proof: `Orderbook::AskPrices` (`max_values`: None, `max_size`: Some(16022), added: 18497, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::Asks` (r:1 w:1)
+	/// Proof: `Orderbook::Asks` (`max_values`: None, `max_size`: Some(80054), added: 82529, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::MarketClearingMode` (r:1 w:0)
+	/// Proof: `Orderbook::MarketClearingMode` (`max_values`: None, `max_size`: Some(21), added: 2496, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::MarketFees` (r:1 w:0)
+	/// Proof: `Orderbook::MarketFees` (`max_values`: None, `max_size`: Some(28), added: 2503, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::FeeTiers` (r:1 w:0)
+	/// Proof: `Orderbook::FeeTiers` (`max_values`: Some(1), `max_size`: Some(251), added: 746, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::TradingVolume` (r:100 w:100)
+	/// Proof: `Orderbook::TradingVolume` (`max_values`: None, `max_size`: Some(649), added: 3124, mode: `MaxEncodedLen`)
+	/// Storage: `Assets::LockedBalance` (r:120 w:120)
+	/// Proof: `Assets::LockedBalance` (`max_values`: None, `max_size`: Some(84), added: 2559, mode: `MaxEncodedLen`)
+	/// Storage: `Assets::FreeBalance` (r:121 w:121)
+	/// Proof: `Assets::FreeBalance` (`max_values`: None, `max_size`: Some(84), added: 2559, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::NextTradeId` (r:1 w:1)
+	/// Proof: `Orderbook::NextTradeId` (`max_values`: Some(1), `max_size`: Some(8), added: 503, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::UserOrders` (r:120 w:120)
+	/// Proof: `Orderbook::UserOrders` (`max_values`: None, `max_size`: Some(8050), added: 10525, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::OrderHistory` (r:120 w:120)
+	/// Proof: `Orderbook::OrderHistory` (`max_values`: None, `max_size`: Some(18950), added: 21425, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::BidPrices` (r:1 w:1)
+	/// Proof: `Orderbook::BidPrices` (`max_values`: None, `max_size`: Some(16022), added: 18497, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::StopOrders` (r:1 w:0)
+	/// Proof: `Orderbook::StopOrders` (`max_values`: None, `max_size`: Some(26022), added: 28497, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::LastTradePrice` (r:0 w:1)
+	/// Proof: `Orderbook::LastTradePrice` (`max_values`: None, `max_size`: Some(36), added: 2511, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::Trades` (r:0 w:50)
+	/// Proof: `Orderbook::Trades` (`max_values`: None, `max_size`: Some(197), added: 2672, mode: `MaxEncodedLen`)
+	/// The range of component `p` is `[1, 50]`.
+	/// The range of component `r` is `[0, 50]`.
+	/// The range of component `c` is `[0, 20]`.
+	fn on_finalize_market(p: u32, r: u32, c: u32, ) -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `0 + c * (435 ±0) + p * (613 ±0) + r * (626 ±0)`
+		//  Estimated: `429490 + c * (2688 ±0) + p * (21842 ±187) + r * (22515 ±185)`
+		// Minimum execution time: 1_527_175_000 picoseconds.
+		Weight::from_parts(2_051_077_000, 429490)
+			// Standard Error: 1_653_467
+			.saturating_add(Weight::from_parts(94_646_491, 0).saturating_mul(p.into()))
+			// Standard Error: 1_635_275
+			.saturating_add(Weight::from_parts(100_030_842, 0).saturating_mul(r.into()))
+			.saturating_add(RocksDbWeight::get().reads(167_u64))
+			.saturating_add(RocksDbWeight::get().reads((6_u64).saturating_mul(p.into())))
+			.saturating_add(RocksDbWeight::get().reads((6_u64).saturating_mul(r.into())))
+			.saturating_add(RocksDbWeight::get().writes(157_u64))
+			.saturating_add(RocksDbWeight::get().writes((6_u64).saturating_mul(p.into())))
+			.saturating_add(RocksDbWeight::get().writes((6_u64).saturating_mul(r.into())))
+			.saturating_add(Weight::from_parts(0, 2688).saturating_mul(c.into()))
+			.saturating_add(Weight::from_parts(0, 21842).saturating_mul(p.into()))
+			.saturating_add(Weight::from_parts(0, 22515).saturating_mul(r.into()))
+	}
+	/// Storage: `Orderbook::Markets` (r:2 w:0)
+	/// Proof: `Orderbook::Markets` (`max_values`: None, `max_size`: Some(29), added: 2504, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::PendingBids` (r:10 w:9)
+	/// Proof: `Orderbook::PendingBids` (`max_values`: None, `max_size`: Some(8054), added: 10529, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::PendingAsks` (r:2 w:1)
+	/// Proof: `Orderbook::PendingAsks` (`max_values`: None, `max_size`: Some(8054), added: 10529, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::PendingCancellations` (r:1 w:0)
+	/// Proof: `Orderbook::PendingCancellations` (`max_values`: None, `max_size`: Some(421), added: 2896, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::Orders` (r:59 w:59)
+	/// Proof: `Orderbook::Orders` (`max_values`: None, `max_size`: Some(213), added: 2688, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::Bids` (r:9 w:9)
+	/// Proof: `Orderbook::Bids` (`max_values`: None, `max_size`: Some(80054), added: 82529, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::Asks` (r:1 w:1)
+	/// Proof: `Orderbook::Asks` (`max_values`: None, `max_size`: Some(80054), added: 82529, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::MarketRules` (r:1 w:0)
+	/// Proof: `Orderbook::MarketRules` (`max_values`: None, `max_size`: Some(68), added: 2543, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::MarketMatchingMode` (r:1 w:0)
+	/// Proof: `Orderbook::MarketMatchingMode` (`max_values`: None, `max_size`: Some(25), added: 2500, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::MarketOrderingPolicy` (r:1 w:0)
+	/// Proof: `Orderbook::MarketOrderingPolicy` (`max_values`: None, `max_size`: Some(21), added: 2496, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::BestBid` (r:1 w:1)
+	/// Proof: `Orderbook::BestBid` (`max_values`: None, `max_size`: Some(36), added: 2511, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::BestAsk` (r:1 w:1)
+	/// Proof: `Orderbook::BestAsk` (`max_values`: None, `max_size`: Some(36), added: 2511, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::MarketClearingMode` (r:1 w:0)
+	/// Proof: `Orderbook::MarketClearingMode` (`max_values`: None, `max_size`: Some(21), added: 2496, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::LastTradePrice` (r:1 w:0)
+	/// Proof: `Orderbook::LastTradePrice` (`max_values`: None, `max_size`: Some(36), added: 2511, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::MarketFees` (r:1 w:0)
+	/// Proof: `Orderbook::MarketFees` (`max_values`: None, `max_size`: Some(28), added: 2503, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::FeeTiers` (r:1 w:0)
+	/// Proof: `Orderbook::FeeTiers` (`max_values`: Some(1), `max_size`: Some(251), added: 746, mode: `MaxEncodedLen`)
+	/// Storage: `Assets::LockedBalance` (r:10 w:10)
+	/// Proof: `Assets::LockedBalance` (`max_values`: None, `max_size`: Some(84), added: 2559, mode: `MaxEncodedLen`)
+	/// Storage: `Assets::FreeBalance` (r:10 w:10)
+	/// Proof: `Assets::FreeBalance` (`max_values`: None, `max_size`: Some(84), added: 2559, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::UserOrders` (r:9 w:9)
+	/// Proof: `Orderbook::UserOrders` (`max_values`: None, `max_size`: Some(8050), added: 10525, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::OrderHistory` (r:9 w:9)
+	/// Proof: `Orderbook::OrderHistory` (`max_values`: None, `max_size`: Some(18950), added: 21425, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::BidPrices` (r:1 w:1)
+	/// Proof: `Orderbook::BidPrices` (`max_values`: None, `max_size`: Some(16022), added: 18497, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::AskPrices` (r:1 w:1)
+	/// Proof: `Orderbook::AskPrices` (`max_values`: None, `max_size`: Some(16022), added: 18497, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::TradingVolume` (r:2 w:2)
+	/// Proof: `Orderbook::TradingVolume` (`max_values`: None, `max_size`: Some(649), added: 3124, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::NextTradeId` (r:1 w:1)
+	/// Proof: `Orderbook::NextTradeId` (`max_values`: Some(1), `max_size`: Some(8), added: 503, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::StopOrders` (r:1 w:0)
+	/// Proof: `Orderbook::StopOrders` (`max_values`: None, `max_size`: Some(26022), added: 28497, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::Trades` (r:0 w:1)
+	/// Proof: `Orderbook::Trades` (`max_values`: None, `max_size`: Some(197), added: 2672, mode: `MaxEncodedLen`)
+	/// The range of component `p` is `[1, 50]`.
+	fn on_finalize_auction(p: u32, ) -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `5265 + p * (150 ±0)`
+		//  Estimated: `743751 + p * (2688 ±3)`
+		// Minimum execution time: 934_360_000 picoseconds.
+		Weight::from_parts(1_231_352_932, 743751)
+			// Standard Error: 1_074_917
+			.saturating_add(Weight::from_parts(44_020_939, 0).saturating_mul(p.into()))
+			.saturating_add(RocksDbWeight::get().reads(88_u64))
+			.saturating_add(RocksDbWeight::get().reads((1_u64).saturating_mul(p.into())))
+			.saturating_add(RocksDbWeight::get().writes(76_u64))
+			.saturating_add(RocksDbWeight::get().writes((1_u64).saturating_mul(p.into())))
+			.saturating_add(Weight::from_parts(0, 2688).saturating_mul(p.into()))
+	}
+	/// Storage: `Orderbook::StopOrders` (r:1 w:1)
+	/// Proof: `Orderbook::StopOrders` (`max_values`: None, `max_size`: Some(26022), added: 28497, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::Orders` (r:50 w:50)
+	/// Proof: `Orderbook::Orders` (`max_values`: None, `max_size`: Some(213), added: 2688, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::AskPrices` (r:1 w:1)
+	/// Proof: `Orderbook::AskPrices` (`max_values`: None, `max_size`: Some(16022), added: 18497, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::PendingAsks` (r:50 w:50)
+	/// Proof: `Orderbook::PendingAsks` (`max_values`: None, `max_size`: Some(8054), added: 10529, mode: `MaxEncodedLen`)
+	/// The range of component `s` is `[1, 50]`.
+	fn trigger_stop_orders(s: u32, ) -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `255 + s * (176 ±0)`
+		//  Estimated: `29487 + s * (10529 ±0)`
+		// Minimum execution time: 44_608_000 picoseconds.
+		Weight::from_parts(16_771_697, 29487)
+			// Standard Error: 145_806
+			.saturating_add(Weight::from_parts(27_842_283, 0).saturating_mul(s.into()))
+			.saturating_add(RocksDbWeight::get().reads(2_u64))
+			.saturating_add(RocksDbWeight::get().reads((2_u64).saturating_mul(s.into())))
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
+			.saturating_add(RocksDbWeight::get().writes((2_u64).saturating_mul(s.into())))
+			.saturating_add(Weight::from_parts(0, 10529).saturating_mul(s.into()))
+	}
+	/// Storage: `Orderbook::ExpiringOrders` (r:1 w:1)
+	/// Proof: `Orderbook::ExpiringOrders` (`max_values`: None, `max_size`: Some(8022), added: 10497, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::Orders` (r:100 w:100)
+	/// Proof: `Orderbook::Orders` (`max_values`: None, `max_size`: Some(213), added: 2688, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::Markets` (r:1 w:0)
+	/// Proof: `Orderbook::Markets` (`max_values`: None, `max_size`: Some(29), added: 2504, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::Bids` (r:40 w:40)
+	/// Proof: `Orderbook::Bids` (`max_values`: None, `max_size`: Some(80054), added: 82529, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::PendingBids` (r:40 w:0)
+	/// Proof: `Orderbook::PendingBids` (`max_values`: None, `max_size`: Some(8054), added: 10529, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::BidPrices` (r:1 w:1)
+	/// Proof: `Orderbook::BidPrices` (`max_values`: None, `max_size`: Some(16022), added: 18497, mode: `MaxEncodedLen`)
+	/// Storage: `Assets::LockedBalance` (r:100 w:100)
+	/// Proof: `Assets::LockedBalance` (`max_values`: None, `max_size`: Some(84), added: 2559, mode: `MaxEncodedLen`)
+	/// Storage: `Assets::FreeBalance` (r:100 w:100)
+	/// Proof: `Assets::FreeBalance` (`max_values`: None, `max_size`: Some(84), added: 2559, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::UserOrders` (r:100 w:100)
+	/// Proof: `Orderbook::UserOrders` (`max_values`: None, `max_size`: Some(8050), added: 10525, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::OrderHistory` (r:100 w:100)
+	/// Proof: `Orderbook::OrderHistory` (`max_values`: None, `max_size`: Some(18950), added: 21425, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::BestBid` (r:0 w:1)
+	/// Proof: `Orderbook::BestBid` (`max_values`: None, `max_size`: Some(36), added: 2511, mode: `MaxEncodedLen`)
+	/// The range of component `e` is `[1, 100]`.
+	fn expire_orders(e: u32, ) -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `1550 + e * (450 ±0)`
+		//  Estimated: `1191560 + e * (28996 ±0)`
+		// Minimum execution time: 137_093_000 picoseconds.
+		Weight::from_parts(146_417_000, 1191560)
+			// Standard Error: 476_633
+			.saturating_add(Weight::from_parts(86_729_299, 0).saturating_mul(e.into()))
+			.saturating_add(RocksDbWeight::get().reads(32_u64))
+			.saturating_add(RocksDbWeight::get().reads((6_u64).saturating_mul(e.into())))
+			.saturating_add(RocksDbWeight::get().writes(17_u64))
+			.saturating_add(RocksDbWeight::get().writes((5_u64).saturating_mul(e.into())))
+			.saturating_add(Weight::from_parts(0, 28996).saturating_mul(e.into()))
+	}
+	/// Storage: `Orderbook::Markets` (r:1 w:0)
+	/// Proof: `Orderbook::Markets` (`max_values`: None, `max_size`: Some(29), added: 2504, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::MarketRules` (r:1 w:0)
+	/// Proof: `Orderbook::MarketRules` (`max_values`: None, `max_size`: Some(68), added: 2543, mode: `MaxEncodedLen`)
+	/// Storage: `Assets::FreeBalance` (r:1 w:1)
+	/// Proof: `Assets::FreeBalance` (`max_values`: None, `max_size`: Some(84), added: 2559, mode: `MaxEncodedLen`)
+	/// Storage: `Assets::LockedBalance` (r:1 w:1)
+	/// Proof: `Assets::LockedBalance` (`max_values`: None, `max_size`: Some(84), added: 2559, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::BidPrices` (r:1 w:1)
+	/// Proof: `Orderbook::BidPrices` (`max_values`: None, `max_size`: Some(16022), added: 18497, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::NextOrderId` (r:1 w:1)
+	/// Proof: `Orderbook::NextOrderId` (`max_values`: Some(1), `max_size`: Some(8), added: 503, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::AccountSelfTradePrevention` (r:1 w:0)
+	/// Proof: `Orderbook::AccountSelfTradePrevention` (`max_values`: None, `max_size`: Some(49), added: 2524, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::PendingBids` (r:40 w:40)
+	/// Proof: `Orderbook::PendingBids` (`max_values`: None, `max_size`: Some(8054), added: 10529, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::UserOrders` (r:1 w:1)
+	/// Proof: `Orderbook::UserOrders` (`max_values`: None, `max_size`: Some(8050), added: 10525, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::Orders` (r:0 w:999)
+	/// Proof: `Orderbook::Orders` (`max_values`: None, `max_size`: Some(213), added: 2688, mode: `MaxEncodedLen`)
+	/// The range of component `n` is `[1, 1000]`.
+	fn place_orders(n: u32, ) -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `394`
+		//  Estimated: `375188 + n * (69 ±9)`
+		// Minimum execution time: 100_200_000 picoseconds.
+		Weight::from_parts(102_295_000, 375188)
+			// Standard Error: 233_044
+			.saturating_add(Weight::from_parts(55_052_804, 0).saturating_mul(n.into()))
+			.saturating_add(RocksDbWeight::get().reads(44_u64))
+			.saturating_add(RocksDbWeight::get().writes(41_u64))
+			.saturating_add(RocksDbWeight::get().writes((1_u64).saturating_mul(n.into())))
+			.saturating_add(Weight::from_parts(0, 69).saturating_mul(n.into()))
+	}
+	/// Storage: `Orderbook::Orders` (r:50 w:0)
+	/// Proof: `Orderbook::Orders` (`max_values`: None, `max_size`: Some(213), added: 2688, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::PendingCancellations` (r:1 w:1)
+	/// Proof: `Orderbook::PendingCancellations` (`max_values`: None, `max_size`: Some(421), added: 2896, mode: `MaxEncodedLen`)
+	/// The range of component `n` is `[1, 50]`.
+	fn cancel_orders(n: u32, ) -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `282 + n * (133 ±0)`
+		//  Estimated: `3886 + n * (2688 ±0)`
+		// Minimum execution time: 40_373_000 picoseconds.
+		Weight::from_parts(12_592_826, 3886)
+			// Standard Error: 85_009
+			.saturating_add(Weight::from_parts(22_843_631, 0).saturating_mul(n.into()))
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().reads((1_u64).saturating_mul(n.into())))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+			.saturating_add(Weight::from_parts(0, 2688).saturating_mul(n.into()))
+	}
+	/// Storage: `Orderbook::Orders` (r:50 w:950)
+	/// Proof: `Orderbook::Orders` (`max_values`: None, `max_size`: Some(213), added: 2688, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::PendingCancellations` (r:1 w:1)
+	/// Proof: `Orderbook::PendingCancellations` (`max_values`: None, `max_size`: Some(421), added: 2896, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::Markets` (r:1 w:0)
+	/// Proof: `Orderbook::Markets` (`max_values`: None, `max_size`: Some(29), added: 2504, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::MarketRules` (r:1 w:0)
+	/// Proof: `Orderbook::MarketRules` (`max_values`: None, `max_size`: Some(68), added: 2543, mode: `MaxEncodedLen`)
+	/// Storage: `Assets::FreeBalance` (r:1 w:1)
+	/// Proof: `Assets::FreeBalance` (`max_values`: None, `max_size`: Some(84), added: 2559, mode: `MaxEncodedLen`)
+	/// Storage: `Assets::LockedBalance` (r:1 w:1)
+	/// Proof: `Assets::LockedBalance` (`max_values`: None, `max_size`: Some(84), added: 2559, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::BidPrices` (r:1 w:1)
+	/// Proof: `Orderbook::BidPrices` (`max_values`: None, `max_size`: Some(16022), added: 18497, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::NextOrderId` (r:1 w:1)
+	/// Proof: `Orderbook::NextOrderId` (`max_values`: Some(1), `max_size`: Some(8), added: 503, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::AccountSelfTradePrevention` (r:1 w:0)
+	/// Proof: `Orderbook::AccountSelfTradePrevention` (`max_values`: None, `max_size`: Some(49), added: 2524, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::PendingBids` (r:40 w:40)
+	/// Proof: `Orderbook::PendingBids` (`max_values`: None, `max_size`: Some(8054), added: 10529, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::UserOrders` (r:1 w:1)
+	/// Proof: `Orderbook::UserOrders` (`max_values`: None, `max_size`: Some(8050), added: 10525, mode: `MaxEncodedLen`)
+	/// The range of component `c` is `[0, 50]`.
+	/// The range of component `p` is `[1, 950]`.
+	fn replace_orders(c: u32, p: u32, ) -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `715 + c * (203 ±0)`
+		//  Estimated: `361409 + c * (2690 ±0) + p * (58 ±5)`
+		// Minimum execution time: 1_133_863_000 picoseconds.
+		Weight::from_parts(3_678_946_909, 361409)
+			// Standard Error: 901_814
+			.saturating_add(Weight::from_parts(46_776_930, 0).saturating_mul(p.into()))
+			.saturating_add(RocksDbWeight::get().reads(43_u64))
+			.saturating_add(RocksDbWeight::get().reads((1_u64).saturating_mul(c.into())))
+			.saturating_add(RocksDbWeight::get().writes(40_u64))
+			.saturating_add(RocksDbWeight::get().writes((1_u64).saturating_mul(p.into())))
+			.saturating_add(Weight::from_parts(0, 2690).saturating_mul(c.into()))
+			.saturating_add(Weight::from_parts(0, 58).saturating_mul(p.into()))
+	}
+	/// Storage: `Orderbook::Orders` (r:1 w:1)
+	/// Proof: `Orderbook::Orders` (`max_values`: None, `max_size`: Some(213), added: 2688, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::PendingCancellations` (r:1 w:0)
+	/// Proof: `Orderbook::PendingCancellations` (`max_values`: None, `max_size`: Some(421), added: 2896, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::Markets` (r:1 w:0)
+	/// Proof: `Orderbook::Markets` (`max_values`: None, `max_size`: Some(29), added: 2504, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::MarketRules` (r:1 w:0)
+	/// Proof: `Orderbook::MarketRules` (`max_values`: None, `max_size`: Some(68), added: 2543, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::Bids` (r:1 w:1)
+	/// Proof: `Orderbook::Bids` (`max_values`: None, `max_size`: Some(80054), added: 82529, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::PendingBids` (r:2 w:1)
+	/// Proof: `Orderbook::PendingBids` (`max_values`: None, `max_size`: Some(8054), added: 10529, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::BidPrices` (r:1 w:1)
+	/// Proof: `Orderbook::BidPrices` (`max_values`: None, `max_size`: Some(16022), added: 18497, mode: `MaxEncodedLen`)
+	/// Storage: `Assets::FreeBalance` (r:1 w:1)
+	/// Proof: `Assets::FreeBalance` (`max_values`: None, `max_size`: Some(84), added: 2559, mode: `MaxEncodedLen`)
+	/// Storage: `Assets::LockedBalance` (r:1 w:1)
+	/// Proof: `Assets::LockedBalance` (`max_values`: None, `max_size`: Some(84), added: 2559, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::BestBid` (r:0 w:1)
+	/// Proof: `Orderbook::BestBid` (`max_values`: None, `max_size`: Some(36), added: 2511, mode: `MaxEncodedLen`)
+	fn amend_order() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `906`
+		//  Estimated: `83519`
+		// Minimum execution time: 128_602_000 picoseconds.
+		Weight::from_parts(139_042_000, 83519)
+			.saturating_add(RocksDbWeight::get().reads(10_u64))
 			.saturating_add(RocksDbWeight::get().writes(7_u64))
-			.saturating_add(RocksDbWeight::get().writes((7_u64).saturating_mul(m.into())))
+	}
+	/// Storage: `Orderbook::UserOrders` (r:1 w:0)
+	/// Proof: `Orderbook::UserOrders` (`max_values`: None, `max_size`: Some(8050), added: 10525, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::Orders` (r:999 w:0)
+	/// Proof: `Orderbook::Orders` (`max_values`: None, `max_size`: Some(213), added: 2688, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::PendingCancellations` (r:1 w:1)
+	/// Proof: `Orderbook::PendingCancellations` (`max_values`: None, `max_size`: Some(421), added: 2896, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::CancelAllQueue` (r:1 w:1)
+	/// Proof: `Orderbook::CancelAllQueue` (`max_values`: Some(1), `max_size`: Some(3402), added: 3897, mode: `MaxEncodedLen`)
+	/// The range of component `n` is `[1, 1000]`.
+	fn cancel_all_orders(n: u32, ) -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `376 + n * (141 ±0)`
+		//  Estimated: `11515 + n * (2688 ±0)`
+		// Minimum execution time: 60_052_000 picoseconds.
+		Weight::from_parts(61_175_000, 11515)
+			// Standard Error: 119_798
+			.saturating_add(Weight::from_parts(19_370_222, 0).saturating_mul(n.into()))
+			.saturating_add(RocksDbWeight::get().reads(3_u64))
+			.saturating_add(RocksDbWeight::get().reads((1_u64).saturating_mul(n.into())))
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
+			.saturating_add(Weight::from_parts(0, 2688).saturating_mul(n.into()))
+	}
+	/// Storage: `Orderbook::PrunedTradesUpTo` (r:1 w:1)
+	/// Proof: `Orderbook::PrunedTradesUpTo` (`max_values`: Some(1), `max_size`: Some(8), added: 503, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::NextTradeId` (r:1 w:0)
+	/// Proof: `Orderbook::NextTradeId` (`max_values`: Some(1), `max_size`: Some(8), added: 503, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::TradeArchiveCommitment` (r:1 w:1)
+	/// Proof: `Orderbook::TradeArchiveCommitment` (`max_values`: Some(1), `max_size`: Some(32), added: 527, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::Trades` (r:100 w:100)
+	/// Proof: `Orderbook::Trades` (`max_values`: None, `max_size`: Some(197), added: 2672, mode: `MaxEncodedLen`)
+	/// The range of component `n` is `[0, 100]`.
+	fn prune_trades(n: u32, ) -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `144 + n * (204 ±0)`
+		//  Estimated: `1517 + n * (2672 ±0)`
+		// Minimum execution time: 12_334_000 picoseconds.
+		Weight::from_parts(68_196_542, 1517)
+			// Standard Error: 160_305
+			.saturating_add(Weight::from_parts(9_461_861, 0).saturating_mul(n.into()))
+			.saturating_add(RocksDbWeight::get().reads(3_u64))
+			.saturating_add(RocksDbWeight::get().reads((1_u64).saturating_mul(n.into())))
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
 			.saturating_add(RocksDbWeight::get().writes((1_u64).saturating_mul(n.into())))
-			.saturating_add(RocksDbWeight::get().writes((3_u64).saturating_mul(c.into())))
-			.saturating_add(Weight::from_parts(0, 2595).saturating_mul(c.into()))
-			.saturating_add(Weight::from_parts(0, 5190).saturating_mul(m.into()))
-			.saturating_add(Weight::from_parts(0, 2595).saturating_mul(n.into()))
+			.saturating_add(Weight::from_parts(0, 2672).saturating_mul(n.into()))
+	}
+	/// Storage: `Orderbook::AccountSelfTradePrevention` (r:0 w:1)
+	/// Proof: `Orderbook::AccountSelfTradePrevention` (`max_values`: None, `max_size`: Some(49), added: 2524, mode: `MaxEncodedLen`)
+	fn set_self_trade_prevention() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `0`
+		//  Estimated: `0`
+		// Minimum execution time: 10_602_000 picoseconds.
+		Weight::from_parts(10_907_000, 0)
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: `Orderbook::Markets` (r:1 w:0)
+	/// Proof: `Orderbook::Markets` (`max_values`: None, `max_size`: Some(29), added: 2504, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::MarketMatchingMode` (r:0 w:1)
+	/// Proof: `Orderbook::MarketMatchingMode` (`max_values`: None, `max_size`: Some(25), added: 2500, mode: `MaxEncodedLen`)
+	fn set_matching_mode() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `149`
+		//  Estimated: `3494`
+		// Minimum execution time: 19_011_000 picoseconds.
+		Weight::from_parts(30_184_000, 3494)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: `Orderbook::Markets` (r:1 w:0)
+	/// Proof: `Orderbook::Markets` (`max_values`: None, `max_size`: Some(29), added: 2504, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::MarketClearingMode` (r:0 w:1)
+	/// Proof: `Orderbook::MarketClearingMode` (`max_values`: None, `max_size`: Some(21), added: 2496, mode: `MaxEncodedLen`)
+	fn set_clearing_mode() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `149`
+		//  Estimated: `3494`
+		// Minimum execution time: 18_631_000 picoseconds.
+		Weight::from_parts(20_350_000, 3494)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: `Orderbook::Markets` (r:1 w:0)
+	/// Proof: `Orderbook::Markets` (`max_values`: None, `max_size`: Some(29), added: 2504, mode: `MaxEncodedLen`)
+	/// Storage: `Orderbook::MarketOrderingPolicy` (r:0 w:1)
+	/// Proof: `Orderbook::MarketOrderingPolicy` (`max_values`: None, `max_size`: Some(21), added: 2496, mode: `MaxEncodedLen`)
+	fn set_ordering_policy() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `149`
+		//  Estimated: `3494`
+		// Minimum execution time: 18_564_000 picoseconds.
+		Weight::from_parts(19_870_000, 3494)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
 	}
 }
@@ -197,6 +197,15 @@ impl_runtime_apis! {
         }
     }
 
+    impl pallet_orderbook::runtime_api::OrderbookApi<Block, AccountId> for Runtime {
+        fn effective_fees(
+            market_id: pallet_orderbook::types::MarketId,
+            who: AccountId,
+        ) -> pallet_orderbook::types::EffectiveFees {
+            pallet_orderbook::Pallet::<Runtime>::effective_fees(market_id, &who)
+        }
+    }
+
     impl pallet_transaction_payment_rpc_runtime_api::TransactionPaymentCallApi<Block, Balance, RuntimeCall>
         for Runtime
     {
@@ -25,7 +25,7 @@
 
 // Substrate and Polkadot dependencies
 use frame_support::{
-    derive_impl, parameter_types,
+    PalletId, derive_impl, parameter_types,
     traits::{ConstBool, ConstU8, ConstU32, ConstU64, ConstU128, VariantCountOf},
     weights::{
         IdentityFee, Weight,
@@ -35,7 +35,10 @@ use frame_support::{
 use frame_system::limits::{BlockLength, BlockWeights};
 use pallet_transaction_payment::{ConstFeeMultiplier, FungibleAdapter, Multiplier};
 use sp_consensus_aura::sr25519::AuthorityId as AuraId;
-use sp_runtime::{Perbill, traits::One};
+use sp_runtime::{
+    Perbill, Permill,
+    traits::{AccountIdConversion, One},
+};
 use sp_version::RuntimeVersion;
 
 use pallet_assets;
@@ -43,7 +46,7 @@ use pallet_assets;
 
 // Local module imports
 use super::{
-    AccountId, Aura, Balance, Balances, Block, BlockNumber, EXISTENTIAL_DEPOSIT, Hash, Nonce,
+    AccountId, Aura, Balance, Balances, Block, BlockNumber, DAYS, EXISTENTIAL_DEPOSIT, Hash, Nonce,
     PalletInfo, Runtime, RuntimeCall, RuntimeEvent, RuntimeFreezeReason, RuntimeHoldReason,
     RuntimeOrigin, RuntimeTask, SLOT_DURATION, System, VERSION,
 };
@@ -174,6 +177,21 @@ parameter_types! {
     pub const MaxCancellationOrders: u32 = 50;       // Max 50 cancellations per block in tests
     pub const MaxOrders: u32 = 10000;                 // Max 1000 orders per price level in tests
     pub const MaxUserOrders: u32 = 1000;              // Max 100 orders per user in tests
+    pub const MaxOrderHistory: u32 = 100;             // Finished orders kept per user
+    pub const MaxPriceLevels: u32 = 1000;             // Distinct prices per side of a market
+    pub const MaxStopOrders: u32 = 1000;              // Untriggered stop orders per market
+    pub const MaxExpiringOrders: u32 = 1000;          // Orders that can expire in the same block
+    pub const MaxCancelAllRequests: u32 = 100;        // Cancel-alls carried over to the next block
+    pub const TradeRetention: u64 = 100_000;          // Trades kept on chain, older ones are archived
+    pub FeeAccount: AccountId = PalletId(*b"orbx/fee").into_account_truncating(); // Trading fee treasury
+    pub const DefaultMakerFee: Permill = Permill::from_parts(1_000); // 0.1%
+    pub const DefaultTakerFee: Permill = Permill::from_parts(2_000); // 0.2%
+    pub const VolumeBucketBlocks: u32 = DAYS;          // Trading volume is kept per day
+    pub const VolumeBuckets: u32 = 30;                // Fee tiers look at 30-day volume
+    pub const MaxFeeTiers: u32 = 10;
+    pub const DefaultTickSize: u128 = 10_000;            // 0.01 quote
+    pub const DefaultLotSize: u128 = 1_000;              // 0.001 base
+    pub const DefaultMinNotional: u128 = 1_000_000_000_000; // 1 quote at 10^6 price and size scaling
 }
 
 impl pallet_orderbook::Config for Runtime {
@@ -181,5 +199,22 @@ impl pallet_orderbook::Config for Runtime {
     type MaxCancellationOrders = MaxCancellationOrders;
     type MaxOrders = MaxOrders;
     type MaxUserOrders = MaxUserOrders;
+    type MaxOrderHistory = MaxOrderHistory;
+    type MaxPriceLevels = MaxPriceLevels;
+    type MaxStopOrders = MaxStopOrders;
+    type MaxExpiringOrders = MaxExpiringOrders;
+    type MaxCancelAllRequests = MaxCancelAllRequests;
+    type TradeRetention = TradeRetention;
+    type FeeAccount = FeeAccount;
+    type DefaultMakerFee = DefaultMakerFee;
+    type DefaultTakerFee = DefaultTakerFee;
+    type VolumeBucketBlocks = VolumeBucketBlocks;
+    type VolumeBuckets = VolumeBuckets;
+    type MaxFeeTiers = MaxFeeTiers;
+    type DefaultTickSize = DefaultTickSize;
+    type DefaultLotSize = DefaultLotSize;
+    type DefaultMinNotional = DefaultMinNotional;
+    // Aura has no randomness source of its own
+    type Randomness = pallet_orderbook::ParentHashRandomness<Runtime>;
     type WeightInfo = pallet_orderbook::weights::SubstrateWeight<Runtime>;
 }
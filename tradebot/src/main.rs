@@ -27,12 +27,24 @@ struct OrderRecord {
 pub mod polkadot {}
 
 // Import the generated types for convenience
-use polkadot::runtime_types::pallet_orderbook::types::{OrderSide, OrderType};
+use polkadot::runtime_types::pallet_orderbook::types::{OrderSide, OrderType, TimeInForce};
+
+// ETH/USDT is listed as market 0 in the genesis presets
+const MARKET_ID: u32 = 0;
+
+/// Tick size, lot size and min notional of the market, orders are rounded to them
+#[derive(Debug, Clone, Copy)]
+struct MarketRules {
+    tick_size: u128,
+    lot_size: u128,
+    min_notional: u128,
+}
 
 struct TradeBot {
     client: OnlineClient<PolkadotConfig>,
     accounts: Vec<(String, Keypair)>, // (address, keypair)
     account_locks: HashMap<String, Arc<Mutex<()>>>, // Per-account locks
+    rules: MarketRules,
 }
 
 impl TradeBot {
@@ -43,6 +55,9 @@ impl TradeBot {
 
         info!("✅ Connected to chain: {:?}", client.runtime_version());
 
+        let rules = Self::fetch_market_rules(&client).await?;
+        info!("📏 Market {} rules: {:?}", MARKET_ID, rules);
+
         // Generate accounts using development keypairs
         let accounts = Self::generate_accounts(num_accounts)?;
 
@@ -58,6 +73,32 @@ impl TradeBot {
             client,
             accounts,
             account_locks,
+            rules,
+        })
+    }
+
+    /// Rules set for the market, or the runtime defaults when governance has not set any
+    async fn fetch_market_rules(client: &OnlineClient<PolkadotConfig>) -> Result<MarketRules> {
+        let stored = client
+            .storage()
+            .at_latest()
+            .await?
+            .fetch(&polkadot::storage().orderbook().market_rules(MARKET_ID))
+            .await?;
+
+        if let Some(rules) = stored {
+            return Ok(MarketRules {
+                tick_size: rules.tick_size,
+                lot_size: rules.lot_size,
+                min_notional: rules.min_notional,
+            });
+        }
+
+        let constants = polkadot::constants().orderbook();
+        Ok(MarketRules {
+            tick_size: client.constants().at(&constants.default_tick_size())?,
+            lot_size: client.constants().at(&constants.default_lot_size())?,
+            min_notional: client.constants().at(&constants.default_min_notional())?,
         })
     }
 
@@ -108,8 +149,22 @@ impl TradeBot {
         let _guard = account_lock.lock().await;
 
         // Convert f64 price and quantity to u128 (assuming 6 decimal places)
-        let price_u128 = (price * 1_000_000.0) as u128;
-        let quantity_u128 = (quantity * 1_000_000.0) as u128;
+        // and round them to the market tick and lot
+        let tick = self.rules.tick_size.max(1);
+        let lot = self.rules.lot_size.max(1);
+        let price_u128 = ((price * 1_000_000.0) as u128 + tick / 2) / tick * tick;
+        let quantity_u128 = (quantity * 1_000_000.0) as u128 / lot * lot;
+
+        if price_u128 == 0
+            || quantity_u128 == 0
+            || price_u128.saturating_mul(quantity_u128) < self.rules.min_notional
+        {
+            warn!(
+                "⚠️  Skipping order below market minimums: {} {} @ {}",
+                side, quantity, price
+            );
+            return Ok(());
+        }
 
         // Determine order side ("bid" = Buy, "ask" = Sell)
         let order_side = if side.to_lowercase() == "bid" {
@@ -121,12 +176,19 @@ impl TradeBot {
         // Use Limit order type for all orders from the synthetic data
         let order_type = OrderType::Limit;
 
-        // Build the extrinsic with correct parameter order: side, price, quantity, order_type
+        // Build the extrinsic with correct parameter order: market, side, price, quantity, order_type, time_in_force, post_only
         let tx = polkadot::tx().orderbook().place_order(
+            MARKET_ID,
             order_side,
             price_u128,
             quantity_u128,
             order_type,
+            TimeInForce::GTC,
+            None,
+            None,
+            None,
+            None,
+            None,
         );
 
         // Wait for confirmation to avoid nonce issues